@@ -9,9 +9,12 @@
 //!
 //! - `GET /api/health` - Health check with node/edge counts
 //! - `GET /api/graph` - Full SourceCodeGraph JSON
+//! - `GET /api/graph?collapse=crate` - Aggregated view (`crate`, `directory[:depth]`, `metadata:<key>`)
 //! - `GET /api/graph/nodes` - Nodes only
 //! - `GET /api/graph/edges` - Edges only
 //! - `GET /api/graph/metadata` - Graph metadata
+//! - `POST /api/graph/query` - Run a graph query (`{"query": "MATCH ..."}`)
+//! - `GET /api/graph/query?q=...` - Run a graph query from query params
 //! - `GET /api/git/changes` - Current git change snapshot
 //! - `GET /api/ws` - WebSocket for real-time updates
 //!
@@ -20,7 +23,9 @@
 //! - `POST /api/ops/sync` - Sync a codebase
 //! - `GET /api/ops/sync?source=...` - Sync with query params
 //! - `POST /api/ops/graph` - Build source code graph
-//! - `GET /api/ops/graph?path=...` - Build graph with query params
+//! - `GET /api/ops/graph?path=...&rev=...` - Build graph with query params
+//! - `GET /api/ops/graph/snapshots?path=...` - List graph snapshots
+//! - `GET /api/ops/graph/diff?path=...&from=...&to=...` - Compare graph snapshots
 //! - `GET /api/ops/status?path=...` - Get workspace status
 //! - `GET /api/ops/load?path=...` - Load project from .self
 //! - `DELETE /api/ops/clean?path=...` - Clean .self folder
@@ -72,7 +77,7 @@ use vibe_graph_core::{GitChangeSnapshot, SourceCodeGraph};
 pub fn create_api_state(graph: SourceCodeGraph) -> Arc<ApiState> {
     let (tx, _) = broadcast::channel(100);
     Arc::new(ApiState {
-        graph: Arc::new(RwLock::new(graph)),
+        graph: Arc::new(RwLock::new(Arc::new(graph))),
         git_changes: Arc::new(RwLock::new(GitChangeSnapshot::default())),
         tx,
     })
@@ -85,7 +90,7 @@ pub fn create_api_state_with_changes(
 ) -> Arc<ApiState> {
     let (tx, _) = broadcast::channel(100);
     Arc::new(ApiState {
-        graph: Arc::new(RwLock::new(graph)),
+        graph: Arc::new(RwLock::new(Arc::new(graph))),
         git_changes: Arc::new(RwLock::new(git_changes)),
         tx,
     })
@@ -3,17 +3,68 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
-use vibe_graph_core::{GraphEdge, GraphNode};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use vibe_graph_core::{CollapseBy, GraphEdge, GraphNode};
 
+use super::ops::OpsErrorResponse;
 use crate::types::{ApiResponse, ApiState};
 
-/// Handler for GET /api/graph - returns full graph.
+/// Body of POST /api/graph/query.
+#[derive(Debug, Deserialize)]
+pub struct GraphQueryRequest {
+    /// Query text (see `vibe_graph_core::query`).
+    pub query: String,
+}
+
+/// Query parameters for GET /api/graph/query.
+#[derive(Debug, Deserialize)]
+pub struct GraphQueryParams {
+    /// Query text.
+    pub q: String,
+}
+
+/// Query parameters for GET /api/graph.
+#[derive(Debug, Deserialize)]
+pub struct GraphViewParams {
+    /// Collapse files into groups: `crate`, `directory`, `directory:<depth>`
+    /// or `metadata:<key>` (see `vibe_graph_core::collapse`).
+    #[serde(default)]
+    pub collapse: Option<String>,
+}
+
+/// Handler for GET /api/graph - returns the full graph, or an aggregated
+/// view with `?collapse=...`.
 pub async fn graph_handler(
     State(state): State<Arc<ApiState>>,
-) -> Json<ApiResponse<vibe_graph_core::SourceCodeGraph>> {
+    Query(params): Query<GraphViewParams>,
+) -> axum::response::Response {
+    let by = match params.collapse.as_deref().map(str::parse::<CollapseBy>) {
+        None => None,
+        Some(Ok(by)) => Some(by),
+        Some(Err(message)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::new(OpsErrorResponse {
+                    code: "INVALID_COLLAPSE".to_string(),
+                    message,
+                })),
+            )
+                .into_response();
+        }
+    };
+
     let graph = state.graph.read().await;
-    Json(ApiResponse::new(graph.clone()))
+    let view = match by {
+        Some(by) => graph.collapse(&by),
+        None => (**graph).clone(),
+    };
+    Json(ApiResponse::new(view)).into_response()
 }
 
 /// Handler for GET /api/graph/nodes - returns nodes only.
@@ -39,3 +90,45 @@ pub async fn metadata_handler(
     let graph = state.graph.read().await;
     Json(ApiResponse::new(graph.metadata.clone()))
 }
+
+/// Handler for POST /api/graph/query - runs a graph query.
+pub async fn query_handler(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<GraphQueryRequest>,
+) -> impl IntoResponse {
+    run_query(&state, &request.query).await
+}
+
+/// Handler for GET /api/graph/query?q=... - runs a graph query.
+pub async fn query_get_handler(
+    State(state): State<Arc<ApiState>>,
+    Query(params): Query<GraphQueryParams>,
+) -> impl IntoResponse {
+    run_query(&state, &params.q).await
+}
+
+async fn run_query(state: &ApiState, query: &str) -> axum::response::Response {
+    // Queries can be expensive, so they run on a blocking thread against a
+    // cloned handle rather than holding the lock across the evaluation.
+    let graph = Arc::clone(&*state.graph.read().await);
+    let query = query.to_string();
+    match tokio::task::spawn_blocking(move || graph.query(&query)).await {
+        Ok(Ok(result)) => (StatusCode::OK, Json(ApiResponse::new(result))).into_response(),
+        Ok(Err(e)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::new(OpsErrorResponse {
+                code: "QUERY_ERROR".to_string(),
+                message: e.to_string(),
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::new(OpsErrorResponse {
+                code: "QUERY_ERROR".to_string(),
+                message: e.to_string(),
+            })),
+        )
+            .into_response(),
+    }
+}
@@ -39,6 +39,10 @@ pub fn create_api_router(state: Arc<ApiState>) -> Router {
         .route("/graph/nodes", get(graph::nodes_handler))
         .route("/graph/edges", get(graph::edges_handler))
         .route("/graph/metadata", get(graph::metadata_handler))
+        .route(
+            "/graph/query",
+            get(graph::query_get_handler).post(graph::query_handler),
+        )
         // Git endpoints
         .route("/git/changes", get(git::changes_handler))
         // WebSocket
@@ -76,6 +80,8 @@ pub fn create_ops_router(ctx: OpsContext) -> Router {
         // Graph operations
         .route("/graph", post(ops::graph_handler))
         .route("/graph", get(ops::graph_query_handler))
+        .route("/graph/snapshots", get(ops::graph_snapshots_handler))
+        .route("/graph/diff", get(ops::graph_diff_handler))
         // Status
         .route("/status", get(ops::status_handler))
         // Load
@@ -199,6 +205,10 @@ pub fn create_full_api_router(api_state: Arc<ApiState>, ops_ctx: OpsContext) ->
         .route("/graph/nodes", get(graph::nodes_handler))
         .route("/graph/edges", get(graph::edges_handler))
         .route("/graph/metadata", get(graph::metadata_handler))
+        .route(
+            "/graph/query",
+            get(graph::query_get_handler).post(graph::query_handler),
+        )
         .route("/git/changes", get(git::changes_handler))
         .route("/ws", get(ws_handler))
         .with_state(api_state);
@@ -275,6 +285,10 @@ fn build_full_api_router_with_git_router(
         .route("/graph/nodes", get(graph::nodes_handler))
         .route("/graph/edges", get(graph::edges_handler))
         .route("/graph/metadata", get(graph::metadata_handler))
+        .route(
+            "/graph/query",
+            get(graph::query_get_handler).post(graph::query_handler),
+        )
         .route("/git/changes", get(git::changes_handler))
         .route("/ws", get(ws_handler))
         .with_state(api_state);
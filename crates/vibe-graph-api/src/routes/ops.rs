@@ -14,8 +14,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use vibe_graph_ops::{
-    CleanRequest, GitChangesRequest, GraphRequest, LoadRequest, OpsContext, StatusRequest,
-    SyncRequest,
+    CleanRequest, GitChangesRequest, GraphDiffRequest, GraphRequest, GraphSnapshotsRequest,
+    LoadRequest, OpsContext, OpsError, StatusRequest, SyncRequest,
 };
 
 use crate::types::ApiResponse;
@@ -57,6 +57,31 @@ pub struct GraphQuery {
     /// Force rebuild even if cached.
     #[serde(default)]
     pub force: Option<bool>,
+    /// Keep a snapshot of the graph.
+    #[serde(default)]
+    pub snapshot: Option<bool>,
+    /// Build the graph at this git revision instead of the working tree.
+    #[serde(default)]
+    pub rev: Option<String>,
+}
+
+/// Query parameters for graph snapshots endpoint.
+#[derive(Debug, Deserialize)]
+pub struct GraphSnapshotsQuery {
+    /// Path to workspace.
+    pub path: String,
+}
+
+/// Query parameters for graph diff endpoint.
+#[derive(Debug, Deserialize)]
+pub struct GraphDiffQuery {
+    /// Path to workspace.
+    pub path: String,
+    /// Older snapshot (ID, ID prefix or git revision).
+    pub from: String,
+    /// Newer snapshot; the current graph when omitted.
+    #[serde(default)]
+    pub to: Option<String>,
 }
 
 /// Query parameters for status endpoint.
@@ -211,6 +236,12 @@ pub async fn graph_query_handler(
     if query.force.unwrap_or(false) {
         request = request.force();
     }
+    if query.snapshot.unwrap_or(false) {
+        request = request.with_snapshot();
+    }
+    if let Some(rev) = query.rev {
+        request = request.at_rev(rev);
+    }
 
     match state.ctx.graph(request).await {
         Ok(response) => (StatusCode::OK, Json(ApiResponse::new(response))).into_response(),
@@ -225,6 +256,58 @@ pub async fn graph_query_handler(
     }
 }
 
+/// GET /api/ops/graph/snapshots - List graph snapshots.
+pub async fn graph_snapshots_handler(
+    State(state): State<Arc<OpsState>>,
+    Query(query): Query<GraphSnapshotsQuery>,
+) -> impl IntoResponse {
+    let request = GraphSnapshotsRequest::new(&query.path);
+
+    match state.ctx.graph_snapshots(request).await {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::new(response))).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::new(OpsErrorResponse {
+                code: "GRAPH_SNAPSHOTS_ERROR".to_string(),
+                message: e.to_string(),
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/ops/graph/diff - Compare two graph snapshots.
+pub async fn graph_diff_handler(
+    State(state): State<Arc<OpsState>>,
+    Query(query): Query<GraphDiffQuery>,
+) -> impl IntoResponse {
+    let mut request = GraphDiffRequest::new(&query.path, query.from);
+    if let Some(to) = query.to {
+        request = request.to(to);
+    }
+
+    match state.ctx.graph_diff(request).await {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::new(response))).into_response(),
+        Err(e) => {
+            let status = match e {
+                OpsError::StoreNotFound { .. }
+                | OpsError::GraphNotFound
+                | OpsError::SnapshotNotFound { .. } => StatusCode::NOT_FOUND,
+                OpsError::AmbiguousSnapshot { .. } => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(ApiResponse::new(OpsErrorResponse {
+                    code: "GRAPH_DIFF_ERROR".to_string(),
+                    message: e.to_string(),
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// GET /api/ops/status - Get workspace status.
 pub async fn status_handler(
     State(state): State<Arc<OpsState>>,
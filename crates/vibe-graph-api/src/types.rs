@@ -8,8 +8,8 @@ use vibe_graph_core::{GitChangeSnapshot, SourceCodeGraph};
 
 /// Shared application state for the API.
 pub struct ApiState {
-    /// The source code graph.
-    pub graph: Arc<RwLock<SourceCodeGraph>>,
+    /// The source code graph, shared so queries can run off the async runtime.
+    pub graph: Arc<RwLock<Arc<SourceCodeGraph>>>,
     /// Current git change snapshot.
     pub git_changes: Arc<RwLock<GitChangeSnapshot>>,
     /// Broadcast channel for WebSocket messages.
@@ -7,6 +7,7 @@
 
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 use axum::{
     body::Body,
@@ -16,10 +17,12 @@ use axum::{
 use http_body_util::BodyExt;
 use serde_json::{json, Value};
 use tempfile::TempDir;
+use tokio::sync::{broadcast, RwLock};
 use tower::ServiceExt;
-use vibe_graph_ops::{Config, OpsContext};
+use vibe_graph_core::{GitChangeSnapshot, ReferenceKind, SourceCodeGraphBuilder};
+use vibe_graph_ops::{Config, OpsContext, Store};
 
-use vibe_graph_api::create_ops_router;
+use vibe_graph_api::{create_api_router, create_ops_router, ApiState};
 
 /// Create a test router with a fresh OpsContext.
 fn create_test_router() -> Router {
@@ -466,10 +469,10 @@ async fn test_graph_after_sync() {
     assert!(data["graph"]["nodes"].is_array(), "Graph should have nodes");
     assert!(data["graph"]["edges"].is_array(), "Graph should have edges");
 
-    // Verify graph.json was created
+    // Verify the graph was persisted
     assert!(
-        test_dir.join(".self/graph.json").exists(),
-        "graph.json should be created"
+        Store::new(test_dir).has_graph(),
+        "graph should be persisted in the store"
     );
 }
 
@@ -511,6 +514,47 @@ async fn test_graph_get_query() {
     assert!(data["graph"].is_object(), "Should have graph");
 }
 
+#[tokio::test]
+async fn test_graph_snapshot_and_diff() {
+    let temp = TempDir::new().unwrap();
+    let test_dir = temp.path();
+    create_test_repo(test_dir);
+    let path = test_dir.to_str().unwrap();
+
+    let router = create_test_router();
+    let sync = json!({
+        "source": { "type": "local", "path": path },
+        "force": true
+    });
+    post(&router, "/sync", sync.clone()).await;
+
+    let (status, json) = get(&router, &format!("/graph?path={}&snapshot=true", path)).await;
+    assert_eq!(status, StatusCode::OK, "Graph should succeed: {:?}", json);
+    let id = json["data"]["snapshot_created"]["id"]
+        .as_str()
+        .expect("snapshot id")
+        .to_string();
+
+    let (status, json) = get(&router, &format!("/graph/snapshots?path={}", path)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["snapshots"][0]["id"], id.as_str());
+
+    // Add a file, rescan and rebuild, then compare with the snapshot
+    fs::write(test_dir.join("src/extra.rs"), "pub fn extra() {}\n").unwrap();
+    post(&router, "/sync", sync).await;
+    get(&router, &format!("/graph?path={}&force=true", path)).await;
+
+    let (status, json) = get(&router, &format!("/graph/diff?path={}&from={}", path, id)).await;
+    assert_eq!(status, StatusCode::OK, "Diff should succeed: {:?}", json);
+    let diff = &json["data"]["diff"];
+    assert_eq!(diff["added_files"], json!(["src/extra.rs"]));
+    assert_eq!(diff["removed_files"], json!([]));
+    assert!(json["data"]["to"].is_null());
+
+    let (status, _) = get(&router, &format!("/graph/diff?path={}&from=missing", path)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_graph_fails_without_sync() {
     let temp = TempDir::new().unwrap();
@@ -856,7 +900,7 @@ async fn test_full_workflow_sync_graph_clean() {
     )
     .await;
     assert_eq!(status, StatusCode::OK, "Graph should succeed");
-    assert!(test_dir.join(".self/graph.json").exists());
+    assert!(Store::new(test_dir).has_graph());
 
     // 4. Load
     let uri = format!("/load?path={}", test_dir.to_str().unwrap());
@@ -944,6 +988,88 @@ async fn test_resync_updates_project() {
     );
 }
 
+// =============================================================================
+// Graph Query Endpoint Tests
+// =============================================================================
+
+/// API router over `main.rs -> lib.rs -> util.rs`.
+fn create_query_router() -> Router {
+    let mut builder = SourceCodeGraphBuilder::new().with_root("/repo");
+    let main = builder.add_file(Path::new("/repo/src/main.rs"), "src/main.rs");
+    let lib = builder.add_file(Path::new("/repo/src/lib.rs"), "src/lib.rs");
+    let util = builder.add_file(Path::new("/repo/src/util.rs"), "src/util.rs");
+    builder.add_edge(main, lib, ReferenceKind::Uses);
+    builder.add_edge(lib, util, ReferenceKind::Uses);
+
+    let (tx, _) = broadcast::channel(16);
+    create_api_router(Arc::new(ApiState {
+        graph: Arc::new(RwLock::new(Arc::new(builder.build()))),
+        git_changes: Arc::new(RwLock::new(GitChangeSnapshot::default())),
+        tx,
+    }))
+}
+
+#[tokio::test]
+async fn test_graph_query_post() {
+    let router = create_query_router();
+
+    let (status, json) = post(
+        &router,
+        "/graph/query",
+        json!({
+            "query": "MATCH (a {name: \"main.rs\"})-[:uses*]->(d) RETURN d.relative_path AS dep ORDER BY dep"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK, "{:?}", json);
+    assert_eq!(json["data"]["columns"], json!(["dep"]));
+    assert_eq!(
+        json["data"]["rows"],
+        json!([["src/lib.rs"], ["src/util.rs"]])
+    );
+}
+
+#[tokio::test]
+async fn test_graph_query_get_and_errors() {
+    let router = create_query_router();
+
+    let (status, json) = get(
+        &router,
+        "/graph/query?q=MATCH%20(f:file)%20RETURN%20count(*)%20AS%20n",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["rows"], json!([[3]]));
+
+    let (status, json) = get(&router, "/graph/query?q=MATCH%20(f").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["data"]["code"], "QUERY_ERROR");
+}
+
+#[tokio::test]
+async fn test_graph_collapsed_view() {
+    let router = create_query_router();
+
+    let (status, json) = get(&router, "/graph?collapse=directory").await;
+    assert_eq!(status, StatusCode::OK, "{:?}", json);
+    let nodes = json["data"]["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0]["name"], "src");
+    assert_eq!(nodes[0]["metadata"]["member_count"], "3");
+    assert_eq!(json["data"]["edges"], json!([]));
+
+    let (status, json) = get(&router, "/graph?collapse=metadata:relative_path").await;
+    assert_eq!(status, StatusCode::OK);
+    let edges = json["data"]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 2);
+    assert!(edges.iter().all(|e| e["metadata"]["weight"] == "1"));
+
+    let (status, json) = get(&router, "/graph?collapse=bogus").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["data"]["code"], "INVALID_COLLAPSE");
+}
+
 // =============================================================================
 // Backward Compatibility Tests
 // =============================================================================
@@ -3,17 +3,19 @@
 //! The `GraphAutomaton` is the main entry point for running rule-driven
 //! state evolution on temporal graphs.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use rayon::prelude::*;
 use tracing::{debug, info, warn};
 use vibe_graph_core::NodeId;
 
 use crate::error::{AutomatonError, AutomatonResult};
-use crate::rule::{NeighborState, Rule, RuleContext, RuleId, RuleOutcome, RuleRegistry};
+use crate::replay::{RecordedTransition, RunRecording, TickRecord};
+use crate::rule::{Locality, NeighborState, Rule, RuleContext, RuleId, RuleOutcome, RuleRegistry};
 use crate::state::StateData;
-use crate::temporal::{SourceCodeTemporalGraph, TemporalGraph, TemporalNode};
+use crate::temporal::{SourceCodeTemporalGraph, TemporalGraph};
 
 /// Configuration for the automaton.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -24,9 +26,17 @@ pub struct AutomatonConfig {
     /// History window size for nodes.
     pub history_window: usize,
 
-    /// Enable parallel evaluation (when safe).
+    /// Evaluate nodes on the rayon thread pool. Every node sees the previous
+    /// generation either way, so results are identical to sequential ticks.
     pub parallel: bool,
 
+    /// Only evaluate the frontier: nodes whose own or neighbor state changed
+    /// in the previous tick. Applies while every rule has
+    /// [`Locality::Neighborhood`]; the run is stable once the frontier is
+    /// empty.
+    #[serde(default)]
+    pub frontier: bool,
+
     /// Stability threshold for early stopping.
     pub stability_threshold: f32,
 
@@ -40,6 +50,7 @@ impl Default for AutomatonConfig {
             max_ticks: 100,
             history_window: 16,
             parallel: false,
+            frontier: false,
             stability_threshold: 0.001,
             min_ticks_before_stability: 5,
         }
@@ -87,6 +98,10 @@ pub struct TickResult {
 
     /// Average activation after this tick.
     pub avg_activation: f32,
+
+    /// Number of nodes evaluated (the frontier size in frontier mode).
+    #[serde(default)]
+    pub evaluated: usize,
 }
 
 impl TickResult {
@@ -106,6 +121,13 @@ impl TickResult {
     }
 }
 
+/// What a tick decided for one node.
+enum NodeOutcome {
+    Transition(RuleId, StateData),
+    Skip,
+    Error,
+}
+
 /// Heuristic for determining if the automaton has stabilized.
 pub trait StabilityHeuristic: Send + Sync {
     /// Check if the system is stable based on recent tick results.
@@ -205,6 +227,18 @@ pub struct GraphAutomaton {
 
     /// Stability heuristic.
     stability: Box<dyn StabilityHeuristic>,
+
+    /// Nodes the next frontier tick evaluates (`None`: every node).
+    frontier: Option<HashSet<NodeId>>,
+
+    /// Relationship index, kept across ticks until the graph is edited.
+    relationships: Option<HashMap<(NodeId, NodeId), String>>,
+
+    /// Running activation sum of frontier ticks.
+    activation_sum: Option<f64>,
+
+    /// Transitions recorded since [`start_recording`](Self::start_recording).
+    recording: Option<RunRecording>,
 }
 
 /// Stable interface for reading/writing automaton runtime state.
@@ -234,7 +268,7 @@ impl AutomatonRuntime for GraphAutomaton {
     }
 
     fn graph_mut(&mut self) -> &mut SourceCodeTemporalGraph {
-        &mut self.graph
+        GraphAutomaton::graph_mut(self)
     }
 
     fn tick_count(&self) -> u64 {
@@ -266,6 +300,10 @@ impl GraphAutomaton {
             current_tick: 0,
             tick_history: Vec::new(),
             stability: Box::new(TransitionRateHeuristic::default()),
+            frontier: None,
+            relationships: None,
+            activation_sum: None,
+            recording: None,
         }
     }
 
@@ -278,6 +316,7 @@ impl GraphAutomaton {
     /// Register a rule.
     pub fn register_rule(&mut self, rule: Arc<dyn Rule>) {
         self.rules.register(rule);
+        self.frontier = None;
     }
 
     /// Register a rule (builder pattern).
@@ -289,6 +328,7 @@ impl GraphAutomaton {
     /// Set global context value.
     pub fn set_global(&mut self, key: impl Into<String>, value: impl Into<String>) {
         self.global_context.insert(key.into(), value.into());
+        self.frontier = None;
     }
 
     /// Get global context value.
@@ -302,10 +342,49 @@ impl GraphAutomaton {
     }
 
     /// Get mutable reference to the underlying graph.
+    ///
+    /// The next tick evaluates every node again; narrow it down with
+    /// [`set_frontier`](Self::set_frontier) after seeding states.
     pub fn graph_mut(&mut self) -> &mut SourceCodeTemporalGraph {
+        self.frontier = None;
+        self.relationships = None;
+        self.activation_sum = None;
         &mut self.graph
     }
 
+    /// Nodes the next tick evaluates in frontier mode, or `None` when it
+    /// evaluates every node.
+    pub fn frontier(&self) -> Option<&HashSet<NodeId>> {
+        self.frontier.as_ref()
+    }
+
+    /// Declare the nodes whose state changed outside of a tick: in frontier
+    /// mode, the next tick evaluates them and their neighbors only.
+    pub fn set_frontier(&mut self, changed: impl IntoIterator<Item = NodeId>) {
+        let changed: Vec<NodeId> = changed.into_iter().collect();
+        self.frontier = Some(self.with_neighbors(&changed));
+    }
+
+    /// Record the transitions of the following ticks, starting from the
+    /// graph as it is now, so the run can be replayed. Replaces any current
+    /// recording.
+    ///
+    /// Edits made through [`graph_mut`](Self::graph_mut) are not recorded:
+    /// seed states before starting.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(RunRecording::new(self.graph.clone(), self.current_tick));
+    }
+
+    /// The current recording, if any.
+    pub fn recording(&self) -> Option<&RunRecording> {
+        self.recording.as_ref()
+    }
+
+    /// Stop recording and return what was recorded.
+    pub fn take_recording(&mut self) -> Option<RunRecording> {
+        self.recording.take()
+    }
+
     /// Get current tick number.
     pub fn tick_count(&self) -> u64 {
         self.current_tick
@@ -318,7 +397,10 @@ impl GraphAutomaton {
 
     /// Build a rule context for a node (public for async extensions).
     pub fn build_rule_context(&self, node_id: NodeId) -> AutomatonResult<RuleContext<'_>> {
-        self.build_context(node_id)
+        match &self.relationships {
+            Some(relationships) => self.build_context(node_id, relationships),
+            None => self.build_context(node_id, &self.index_relationships()),
+        }
     }
 
     /// Get tick history.
@@ -341,63 +423,114 @@ impl GraphAutomaton {
         let started = Instant::now();
         debug!(tick = self.current_tick, "automaton_tick_start");
 
+        let local = match rule_id {
+            Some(id) => self
+                .rules
+                .get(id)
+                .is_some_and(|rule| rule.locality() == Locality::Neighborhood),
+            None => self
+                .rules
+                .rules_by_priority()
+                .iter()
+                .all(|rule| rule.locality() == Locality::Neighborhood),
+        };
+        let frontier = self.config.frontier && local;
+
         // Collect node IDs to iterate
-        let node_ids = self.graph.node_ids();
+        let node_ids = match self.frontier.as_ref().filter(|_| frontier) {
+            Some(nodes) => {
+                let mut ids: Vec<NodeId> = nodes.iter().copied().collect();
+                ids.sort_by_key(|id| id.0);
+                ids
+            }
+            None => self.graph.node_ids(),
+        };
+        let relationships = self
+            .relationships
+            .take()
+            .unwrap_or_else(|| self.index_relationships());
         let mut transitions = 0;
         let mut skipped = 0;
         let mut errors = 0;
 
-        // Compute updates (without mutating yet)
+        // Compute updates against the current generation (without mutating
+        // yet), so evaluation order does not matter
+        let evaluate = |node_id: &NodeId| self.evaluate_node(*node_id, rule_id, &relationships);
+        let outcomes: Vec<AutomatonResult<NodeOutcome>> = if self.config.parallel {
+            node_ids.par_iter().map(evaluate).collect()
+        } else {
+            node_ids.iter().map(evaluate).collect()
+        };
+        self.relationships = Some(relationships);
+
         let mut updates: Vec<(NodeId, RuleId, StateData)> = Vec::new();
+        for (node_id, outcome) in node_ids.iter().zip(outcomes) {
+            match outcome? {
+                NodeOutcome::Transition(rule_id, new_state) => {
+                    updates.push((*node_id, rule_id, new_state));
+                    transitions += 1;
+                }
+                NodeOutcome::Skip => skipped += 1,
+                NodeOutcome::Error => errors += 1,
+            }
+        }
 
-        for node_id in &node_ids {
-            // Build rule context
-            let ctx = self.build_context(*node_id)?;
+        if let Some(recording) = &mut self.recording {
+            // Ticks counted with `increment_tick` applied nothing recorded
+            while recording.end_tick() < self.current_tick {
+                let tick = recording.end_tick();
+                recording.ticks.push(TickRecord {
+                    tick,
+                    transitions: Vec::new(),
+                });
+            }
+            recording.ticks.push(TickRecord {
+                tick: self.current_tick,
+                transitions: updates
+                    .iter()
+                    .map(|(node_id, rule_id, state)| RecordedTransition {
+                        node_id: *node_id,
+                        rule_id: rule_id.clone(),
+                        state: state.clone(),
+                    })
+                    .collect(),
+            });
+        }
 
-            // Apply rule(s)
-            let outcome = if let Some(specific_rule) = rule_id {
-                self.rules.apply_rule(specific_rule, &ctx)?
-            } else {
-                // Apply all rules by priority until one produces a transition
-                self.apply_all_rules(&ctx)?
+        if !frontier {
+            self.frontier = None;
+            self.activation_sum = None;
+            for (node_id, rule_id, new_state) in updates {
+                self.graph.apply_transition(&node_id, rule_id, new_state)?;
+            }
+        } else {
+            // The next frontier is every node that saw a state change
+            let mut sum = match self.activation_sum {
+                Some(sum) => sum,
+                None => self.activation_sum_in_order(),
             };
-
-            match outcome {
-                RuleOutcome::Transition(new_state) => {
-                    // Determine which rule caused the transition
-                    let causing_rule = rule_id
-                        .cloned()
-                        .unwrap_or_else(|| self.find_applicable_rule(&ctx));
-                    updates.push((*node_id, causing_rule, new_state));
-                    transitions += 1;
-                }
-                RuleOutcome::Skip => {
-                    skipped += 1;
-                }
-                RuleOutcome::Delegate(delegated_rule) => {
-                    // Recursively apply delegated rule
-                    match self.rules.apply_rule(&delegated_rule, &ctx) {
-                        Ok(RuleOutcome::Transition(new_state)) => {
-                            updates.push((*node_id, delegated_rule, new_state));
-                            transitions += 1;
-                        }
-                        Ok(_) => skipped += 1,
-                        Err(e) => {
-                            warn!(node = node_id.0, error = %e, "delegated_rule_error");
-                            errors += 1;
-                        }
+            let mut changed = Vec::new();
+            for (node_id, rule_id, new_state) in updates {
+                if let Some(node) = self.graph.get_node(&node_id) {
+                    let current = node.current_state();
+                    sum += f64::from(new_state.activation) - f64::from(current.activation);
+                    if *current != new_state {
+                        changed.push(node_id);
                     }
                 }
+                self.graph.apply_transition(&node_id, rule_id, new_state)?;
             }
-        }
-
-        // Apply all updates
-        for (node_id, rule_id, new_state) in updates {
-            self.graph.apply_transition(&node_id, rule_id, new_state)?;
+            self.activation_sum = Some(sum);
+            self.frontier = Some(self.with_neighbors(&changed));
         }
 
         // Compute stats
-        let avg_activation = self.compute_avg_activation();
+        let avg_activation = match self.activation_sum {
+            Some(sum) if self.graph.node_count() > 0 => {
+                (sum / self.graph.node_count() as f64) as f32
+            }
+            _ => self.compute_avg_activation(&node_ids),
+        };
         let duration = started.elapsed();
 
         let result = TickResult {
@@ -407,6 +540,7 @@ impl GraphAutomaton {
             errors,
             duration,
             avg_activation,
+            evaluated: node_ids.len(),
         };
 
         self.tick_history.push(result.clone());
@@ -433,8 +567,9 @@ impl GraphAutomaton {
             results.push(result);
 
             // Check stability
-            if self.current_tick >= self.config.min_ticks_before_stability as u64
-                && self.stability.is_stable(&self.tick_history)
+            if self.frontier_is_empty()
+                || (self.current_tick >= self.config.min_ticks_before_stability as u64
+                    && self.stability.is_stable(&self.tick_history))
             {
                 info!(tick = self.current_tick, "automaton_stabilized");
                 break;
@@ -457,18 +592,96 @@ impl GraphAutomaton {
 
     /// Check if the automaton has stabilized.
     pub fn is_stable(&self) -> bool {
-        self.stability.is_stable(&self.tick_history)
+        self.frontier_is_empty() || self.stability.is_stable(&self.tick_history)
     }
 
     /// Reset tick counter and history.
     pub fn reset(&mut self) {
         self.current_tick = 0;
         self.tick_history.clear();
+        self.frontier = None;
     }
 
     // Internal helpers
 
-    fn build_context(&self, node_id: NodeId) -> AutomatonResult<RuleContext<'_>> {
+    /// Apply the rule(s) of a tick to one node.
+    fn evaluate_node(
+        &self,
+        node_id: NodeId,
+        rule_id: Option<&RuleId>,
+        relationships: &HashMap<(NodeId, NodeId), String>,
+    ) -> AutomatonResult<NodeOutcome> {
+        let ctx = self.build_context(node_id, relationships)?;
+
+        // Apply the given rule, or all rules by priority until one produces a
+        // transition
+        let outcome = match rule_id {
+            Some(specific_rule) => self.rules.apply_rule(specific_rule, &ctx)?,
+            None => self.apply_all_rules(&ctx)?,
+        };
+
+        Ok(match outcome {
+            RuleOutcome::Transition(new_state) => {
+                // Determine which rule caused the transition
+                let causing_rule = rule_id
+                    .cloned()
+                    .unwrap_or_else(|| self.find_applicable_rule(&ctx));
+                NodeOutcome::Transition(causing_rule, new_state)
+            }
+            RuleOutcome::Skip => NodeOutcome::Skip,
+            RuleOutcome::Delegate(delegated_rule) => {
+                match self.rules.apply_rule(&delegated_rule, &ctx) {
+                    Ok(RuleOutcome::Transition(new_state)) => {
+                        NodeOutcome::Transition(delegated_rule, new_state)
+                    }
+                    Ok(_) => NodeOutcome::Skip,
+                    Err(e) => {
+                        warn!(node = node_id.0, error = %e, "delegated_rule_error");
+                        NodeOutcome::Error
+                    }
+                }
+            }
+        })
+    }
+
+    /// Relationship of the first edge between each pair of nodes, in either
+    /// direction.
+    fn index_relationships(&self) -> HashMap<(NodeId, NodeId), String> {
+        let mut relationships = HashMap::new();
+        for edge in &self.graph.source_graph.edges {
+            relationships
+                .entry((edge.from, edge.to))
+                .or_insert_with(|| edge.relationship.clone());
+            relationships
+                .entry((edge.to, edge.from))
+                .or_insert_with(|| edge.relationship.clone());
+        }
+        relationships
+    }
+
+    /// The given nodes and their neighbors: whatever may react to their
+    /// change.
+    fn with_neighbors(&self, nodes: &[NodeId]) -> HashSet<NodeId> {
+        let mut affected = HashSet::new();
+        for node_id in nodes {
+            if let Some(neighborhood) = self.graph.neighborhood(node_id) {
+                affected.insert(*node_id);
+                affected.extend(neighborhood.all_neighbors().iter().map(|n| n.id()));
+            }
+        }
+        affected
+    }
+
+    /// Whether frontier ticks have nothing left to evaluate.
+    fn frontier_is_empty(&self) -> bool {
+        self.config.frontier && self.frontier.as_ref().is_some_and(|f| f.is_empty())
+    }
+
+    fn build_context(
+        &self,
+        node_id: NodeId,
+        relationships: &HashMap<(NodeId, NodeId), String>,
+    ) -> AutomatonResult<RuleContext<'_>> {
         let neighborhood = self
             .graph
             .neighborhood(&node_id)
@@ -477,13 +690,13 @@ impl GraphAutomaton {
         let neighbors: Vec<NeighborState> = neighborhood
             .all_neighbors()
             .into_iter()
-            .map(|n| {
-                let rel = self.find_relationship(&node_id, &n.id());
-                NeighborState {
-                    node_id: n.id(),
-                    state: &n.evolution,
-                    relationship: rel,
-                }
+            .map(|n| NeighborState {
+                node_id: n.id(),
+                state: &n.evolution,
+                relationship: relationships
+                    .get(&(node_id, n.id()))
+                    .map_or("unknown", String::as_str)
+                    .to_string(),
             })
             .collect();
 
@@ -496,16 +709,6 @@ impl GraphAutomaton {
         })
     }
 
-    fn find_relationship(&self, from: &NodeId, to: &NodeId) -> String {
-        // Look for edge between from->to or to->from
-        for edge in &self.graph.source_graph.edges {
-            if (&edge.from == from && &edge.to == to) || (&edge.from == to && &edge.to == from) {
-                return edge.relationship.clone();
-            }
-        }
-        "unknown".to_string()
-    }
-
     fn apply_all_rules(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome> {
         for rule in self.rules.rules_by_priority() {
             if rule.should_apply(ctx) {
@@ -528,13 +731,28 @@ impl GraphAutomaton {
         RuleId::noop()
     }
 
-    fn compute_avg_activation(&self) -> f32 {
-        let nodes: Vec<&TemporalNode> = self.graph.nodes().collect();
-        if nodes.is_empty() {
+    /// Activation sum over all nodes, in node ID order.
+    fn activation_sum_in_order(&self) -> f64 {
+        self.graph
+            .node_ids()
+            .iter()
+            .filter_map(|id| self.graph.get_node(id))
+            .map(|n| f64::from(n.current_state().activation))
+            .sum()
+    }
+
+    /// Average activation, summed in node ID order so it does not depend on
+    /// hash map iteration order.
+    fn compute_avg_activation(&self, node_ids: &[NodeId]) -> f32 {
+        if node_ids.is_empty() {
             return 0.0;
         }
-        let sum: f32 = nodes.iter().map(|n| n.current_state().activation).sum();
-        sum / nodes.len() as f32
+        let sum: f32 = node_ids
+            .iter()
+            .filter_map(|id| self.graph.get_node(id))
+            .map(|n| n.current_state().activation)
+            .sum();
+        sum / node_ids.len() as f32
     }
 }
 
@@ -553,6 +771,7 @@ impl std::fmt::Debug for GraphAutomaton {
 mod tests {
     use super::*;
     use crate::rule::NoOpRule;
+    use crate::source_code::{ImportPropagationRule, SourceCodeAutomatonBuilder};
     use serde_json::json;
     use vibe_graph_core::{EdgeId, GraphEdge, GraphNode, GraphNodeKind, SourceCodeGraph};
 
@@ -670,6 +889,129 @@ mod tests {
         assert!(node3.has_evolved());
     }
 
+    #[test]
+    fn test_parallel_tick_matches_sequential() {
+        // A ring with chords, so nodes read neighbors updated in the same tick
+        let n = 200u64;
+        let mut graph = SourceCodeGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        for i in 0..n {
+            graph.nodes.push(GraphNode {
+                id: NodeId(i),
+                name: format!("n{}", i),
+                kind: GraphNodeKind::File,
+                metadata: HashMap::new(),
+            });
+            for (k, to) in [(i + 1) % n, (i * 7 + 3) % n].into_iter().enumerate() {
+                graph.edges.push(GraphEdge {
+                    id: EdgeId(i * 2 + k as u64),
+                    from: NodeId(i),
+                    to: NodeId(to),
+                    relationship: "uses".into(),
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+
+        let run = |parallel: bool| {
+            let mut temporal = SourceCodeTemporalGraph::from_source_graph(graph.clone());
+            for seed in [0, 57, 123] {
+                temporal
+                    .set_initial_state(&NodeId(seed), StateData::with_activation(json!(null), 1.0))
+                    .unwrap();
+            }
+            let config = AutomatonConfig {
+                parallel,
+                ..Default::default()
+            };
+            let mut automaton = GraphAutomaton::with_config(temporal, config)
+                .with_rule(Arc::new(ActivationSpreadRule));
+            let results = automaton.run_ticks(8).unwrap();
+            let mut activations: Vec<(u64, f32)> = automaton
+                .graph()
+                .nodes()
+                .map(|node| (node.id().0, node.current_state().activation))
+                .collect();
+            activations.sort_by_key(|&(id, _)| id);
+            let counts: Vec<(usize, usize, usize, f32)> = results
+                .iter()
+                .map(|r| (r.transitions, r.skipped, r.errors, r.avg_activation))
+                .collect();
+            (activations, counts)
+        };
+
+        assert_eq!(run(true), run(false));
+    }
+
+    #[test]
+    fn test_frontier_tick_matches_full_tick() {
+        // A long chain with one changed node in the middle
+        let n = 2000u64;
+        let mut graph = SourceCodeGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        for i in 0..n {
+            graph.nodes.push(GraphNode {
+                id: NodeId(i),
+                name: format!("n{}", i),
+                kind: GraphNodeKind::File,
+                metadata: HashMap::new(),
+            });
+            if i + 1 < n {
+                graph.edges.push(GraphEdge {
+                    id: EdgeId(i),
+                    from: NodeId(i),
+                    to: NodeId(i + 1),
+                    relationship: "uses".into(),
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+
+        let automaton = |frontier: bool| {
+            let config = AutomatonConfig {
+                frontier,
+                ..Default::default()
+            };
+            SourceCodeAutomatonBuilder::new(graph.clone())
+                .with_config(config)
+                .with_rule(Arc::new(ImportPropagationRule::default()))
+                .with_activation(NodeId(n / 2), 1.0)
+                .build()
+                .unwrap()
+        };
+        let activations = |automaton: &GraphAutomaton| {
+            let mut activations: Vec<(u64, f32)> = automaton
+                .graph()
+                .nodes()
+                .map(|node| (node.id().0, node.current_state().activation))
+                .collect();
+            activations.sort_by_key(|&(id, _)| id);
+            activations
+        };
+
+        let mut full = automaton(false);
+        let mut sparse = automaton(true);
+        let full_results = full.run_ticks(20).unwrap();
+        let sparse_results = sparse.run_ticks(20).unwrap();
+        assert_eq!(activations(&full), activations(&sparse));
+        assert!(full_results.iter().all(|r| r.evaluated == n as usize));
+        assert_eq!(sparse_results[0].evaluated, 3);
+        assert!(sparse_results.iter().all(|r| r.evaluated < 100));
+
+        // The run stops once nothing is left to re-evaluate
+        let mut sparse = automaton(true);
+        let results = sparse.run().unwrap();
+        assert!(results.len() < sparse.config().max_ticks);
+        assert!(sparse.frontier().unwrap().is_empty());
+        assert!(sparse.is_stable());
+    }
+
     #[test]
     fn test_stability_detection() {
         let graph = sample_graph();
@@ -6,6 +6,10 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use vibe_graph_core::NodeId;
+
+use crate::error::AutomatonResult;
+use crate::expression::ExpressionRule;
 
 /// Root configuration for an automaton.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -180,6 +184,9 @@ pub struct RuleConfig {
     /// System prompt for LLM rules.
     #[serde(default)]
     pub system_prompt: Option<String>,
+    /// Source of expression rules (see [`ExpressionRule`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
     /// Additional parameters for the rule.
     #[serde(default)]
     pub params: Option<HashMap<String, serde_json::Value>>,
@@ -195,6 +202,8 @@ pub enum RuleType {
     Llm,
     /// Composite rule combining multiple rules.
     Composite,
+    /// Deterministic rule written as an expression.
+    Expression,
 }
 
 impl AutomatonDescription {
@@ -213,6 +222,17 @@ impl AutomatonDescription {
         }
     }
 
+    /// Rewrite node IDs through an old → new mapping, e.g. after the source
+    /// graph moved to path-derived IDs. IDs missing from the mapping are
+    /// left unchanged.
+    pub fn remap_node_ids(&mut self, mapping: &HashMap<NodeId, NodeId>) {
+        for node in &mut self.nodes {
+            if let Some(id) = mapping.get(&NodeId(node.id)) {
+                node.id = id.0;
+            }
+        }
+    }
+
     /// Add a node configuration.
     pub fn add_node(&mut self, node: NodeConfig) {
         self.nodes.push(node);
@@ -238,6 +258,25 @@ impl AutomatonDescription {
         self.rules.iter().find(|r| r.name == name)
     }
 
+    /// Add rules, replacing the ones with the same name.
+    pub fn merge_rules(&mut self, rules: impl IntoIterator<Item = RuleConfig>) {
+        for rule in rules {
+            match self.rules.iter_mut().find(|r| r.name == rule.name) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+    }
+
+    /// Compile the expression rules, failing on the first invalid one.
+    pub fn expression_rules(&self) -> AutomatonResult<Vec<ExpressionRule>> {
+        self.rules
+            .iter()
+            .filter(|r| r.rule_type == RuleType::Expression)
+            .map(ExpressionRule::from_config)
+            .collect()
+    }
+
     /// Get the effective stability for a node (falls back to defaults).
     pub fn effective_stability(&self, node_id: u64) -> f32 {
         self.get_node(node_id)
@@ -469,6 +508,7 @@ impl RuleConfig {
             name: name.into(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         }
     }
@@ -479,6 +519,18 @@ impl RuleConfig {
             name: name.into(),
             rule_type: RuleType::Llm,
             system_prompt: Some(system_prompt.into()),
+            expression: None,
+            params: None,
+        }
+    }
+
+    /// Create an expression rule.
+    pub fn expression(name: impl Into<String>, expression: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rule_type: RuleType::Expression,
+            system_prompt: None,
+            expression: Some(expression.into()),
             params: None,
         }
     }
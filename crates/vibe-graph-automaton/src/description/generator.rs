@@ -289,10 +289,13 @@ impl DescriptionGenerator {
         stability_calc: &StabilityCalculator,
     ) -> NodeConfig {
         let kind = match node.kind {
-            GraphNodeKind::Directory | GraphNodeKind::Module => NodeKind::Directory,
+            GraphNodeKind::Directory | GraphNodeKind::Module | GraphNodeKind::Repository => {
+                NodeKind::Directory
+            }
             GraphNodeKind::File => NodeKind::File,
             GraphNodeKind::Service => NodeKind::Other, // Service is a special kind
             GraphNodeKind::Test => NodeKind::Other,    // Test files
+            GraphNodeKind::Symbol => NodeKind::Other,  // Definitions inside files
             GraphNodeKind::Other => NodeKind::Other,
         };
 
@@ -375,6 +378,7 @@ impl DescriptionGenerator {
             name: "identity".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -397,6 +401,7 @@ impl DescriptionGenerator {
             } else {
                 None
             },
+            expression: None,
             params: None,
         });
 
@@ -419,6 +424,7 @@ impl DescriptionGenerator {
             } else {
                 None
             },
+            expression: None,
             params: None,
         });
 
@@ -441,6 +447,7 @@ impl DescriptionGenerator {
             } else {
                 None
             },
+            expression: None,
             params: None,
         });
 
@@ -449,6 +456,7 @@ impl DescriptionGenerator {
             name: "sink".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -457,6 +465,7 @@ impl DescriptionGenerator {
             name: "directory_container".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -465,6 +474,7 @@ impl DescriptionGenerator {
             name: "validate_child".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -472,6 +482,7 @@ impl DescriptionGenerator {
             name: "check_dependents".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -479,6 +490,7 @@ impl DescriptionGenerator {
             name: "propagate_change".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
 
@@ -486,6 +498,7 @@ impl DescriptionGenerator {
             name: "aggregate_activation".to_string(),
             rule_type: RuleType::Builtin,
             system_prompt: None,
+            expression: None,
             params: None,
         });
     }
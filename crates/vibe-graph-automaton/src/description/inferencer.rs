@@ -255,6 +255,7 @@ impl DescriptionInferencer {
                 name: rule_name,
                 rule_type: RuleType::Llm,
                 system_prompt: Some(response),
+                expression: None,
                 params: None,
             };
 
@@ -14,6 +14,10 @@ pub enum AutomatonError {
     #[error("rule not found: {rule_id:?}")]
     RuleNotFound { rule_id: RuleId },
 
+    /// A rule definition could not be compiled.
+    #[error("invalid rule {name}: {message}")]
+    InvalidRule { name: String, message: String },
+
     /// A node referenced by ID was not found in the graph.
     #[error("node not found: {node_id:?}")]
     NodeNotFound { node_id: NodeId },
@@ -46,6 +50,10 @@ pub enum AutomatonError {
     #[error("i/o error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// A WebAssembly plugin could not be loaded or failed at runtime.
+    #[error("plugin {plugin}: {message}")]
+    Plugin { plugin: String, message: String },
+
     /// LLM operation error.
     #[error("llm error: {0}")]
     LlmError(String),
@@ -0,0 +1,1172 @@
+//! Expression rules: deterministic rules written as text.
+//!
+//! An expression rule assigns new values to the node state, optionally
+//! guarded by a condition:
+//!
+//! ```text
+//! activation = max(self.activation * 0.9, avg(neighbors['uses'].activation) * damping)
+//!     when self.role == 'hub'
+//! ```
+//!
+//! Rules are declared in `description.json` or `vg.toml` with
+//! `type = "expression"` (see [`RuleConfig::expression`]); the rule's
+//! `params` are the named constants of the expression (`damping` above) and
+//! a numeric `priority` param sets its priority. The source is parsed, type
+//! checked against the fields of [`RuleContext`] and compiled once, so a
+//! broken rule is reported when it is loaded, not in the middle of a run.
+//!
+//! | Expression | Type |
+//! |------------|------|
+//! | `self.activation`, `self.id` | number |
+//! | `self.role`, `self.annotations['key']` | string (empty when missing) |
+//! | `neighbors`, `neighbors['relationship']` | neighbor list |
+//! | `<list>.activation`, `<list>.id` | list of numbers |
+//! | `<list>.role`, `<list>.relationship`, `<list>.annotations['key']` | list of strings |
+//! | `global['key']` | string |
+//! | `tick` | number |
+//!
+//! Lists are reduced with `avg`, `sum`, `min` and `max` (numbers, `0` when
+//! empty) or `count`; `min` and `max` also take two or more numbers, next to
+//! `abs(x)` and `clamp(x, lo, hi)`. Operators are `+ - * /` (dividing by
+//! zero gives `0`), comparisons, `and`, `or` and `not`.
+//!
+//! Several assignments are separated by `;`; the targets are `activation`
+//! (clamped to `[0, 1]`) and `annotations['key']`. Every right-hand side
+//! sees the state before the rule applied.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config::{RuleConfig, RuleType};
+use crate::error::{AutomatonError, AutomatonResult};
+use crate::rule::{Locality, NeighborState, Rule, RuleContext, RuleId, RuleOutcome};
+
+/// A syntax or type error in an expression rule.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{message} (column {column})")]
+pub struct ExpressionError {
+    /// 1-based column of the offending token.
+    pub column: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl ExpressionError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            column: position + 1,
+            message: message.into(),
+        }
+    }
+}
+
+type ExpressionResult<T> = Result<T, ExpressionError>;
+
+/// A rule compiled from an expression (see the [module docs](self)).
+#[derive(Debug, Clone)]
+pub struct ExpressionRule {
+    id: RuleId,
+    source: String,
+    assignments: Vec<(Target, Expr)>,
+    condition: Option<Expr>,
+    priority: i32,
+    locality: Locality,
+}
+
+impl ExpressionRule {
+    /// Compile `source`, resolving names against `params`.
+    pub fn parse(
+        name: &str,
+        source: &str,
+        params: &HashMap<String, Value>,
+    ) -> Result<Self, ExpressionError> {
+        let tokens = lex(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            next: 0,
+            end: source.len(),
+        };
+        let (assignments, condition) = parser.rule()?;
+
+        let mut checker = Checker {
+            params,
+            reads_tick: false,
+        };
+        let assignments = assignments
+            .into_iter()
+            .map(|(target, value)| {
+                let expected = match target {
+                    Target::Activation => Type::Number,
+                    Target::Annotation(_) => Type::Str,
+                };
+                Ok((target, checker.expect(&value, expected)?))
+            })
+            .collect::<ExpressionResult<Vec<_>>>()?;
+        let condition = condition
+            .map(|condition| checker.expect(&condition, Type::Bool))
+            .transpose()?;
+
+        let priority = match params.get("priority") {
+            Some(value) => value
+                .as_i64()
+                .ok_or_else(|| ExpressionError::new(0, "the `priority` param must be an integer"))?
+                as i32,
+            None => 0,
+        };
+
+        Ok(Self {
+            id: RuleId::new(format!("expression::{}", name)),
+            source: source.to_string(),
+            assignments,
+            condition,
+            priority,
+            // The tick number is the only input outside the neighborhood
+            locality: if checker.reads_tick {
+                Locality::Global
+            } else {
+                Locality::Neighborhood
+            },
+        })
+    }
+
+    /// Compile an `expression` rule of a description or `vg.toml`.
+    pub fn from_config(config: &RuleConfig) -> AutomatonResult<Self> {
+        let invalid = |message: String| AutomatonError::InvalidRule {
+            name: config.name.clone(),
+            message,
+        };
+        if config.rule_type != RuleType::Expression {
+            return Err(invalid("not an expression rule".to_string()));
+        }
+        let source = config
+            .expression
+            .as_deref()
+            .ok_or_else(|| invalid("missing `expression`".to_string()))?;
+        Self::parse(
+            &config.name,
+            source,
+            config.params.as_ref().unwrap_or(&HashMap::new()),
+        )
+        .map_err(|e| invalid(e.to_string()))
+    }
+
+    /// The expression this rule was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Rule for ExpressionRule {
+    fn id(&self) -> RuleId {
+        self.id.clone()
+    }
+
+    fn description(&self) -> &str {
+        &self.source
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        self.condition
+            .as_ref()
+            .is_none_or(|condition| condition.eval(ctx).as_bool())
+    }
+
+    fn apply(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome> {
+        let current = ctx.current_state();
+        let mut next = current.clone();
+        for (target, value) in &self.assignments {
+            match target {
+                Target::Activation => {
+                    next.activation = (value.eval(ctx).as_number() as f32).clamp(0.0, 1.0);
+                }
+                Target::Annotation(key) => {
+                    next.annotations
+                        .insert(key.clone(), value.eval(ctx).as_str().to_string());
+                }
+            }
+        }
+
+        // Same threshold as the builtin rules, so runs settle
+        if (next.activation - current.activation).abs() < 0.001
+            && next.annotations == current.annotations
+        {
+            return Ok(RuleOutcome::Skip);
+        }
+        Ok(RuleOutcome::Transition(next))
+    }
+
+    fn locality(&self) -> Locality {
+        self.locality
+    }
+}
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "`{}`", n),
+            Token::Str(s) => write!(f, "'{}'", s),
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "=", "!", "+", "-", "*", "/", "(", ")", "[", "]",
+    ",",
+];
+
+/// Split the source into tokens and their byte offsets.
+fn lex(source: &str) -> ExpressionResult<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit()
+            || (c == '.' && source[start + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let number = source[start..end]
+                .parse()
+                .map_err(|_| ExpressionError::new(start, "invalid number"))?;
+            tokens.push((Token::Number(number), start));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push((Token::Ident(source[start..end].to_string()), start));
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, q)) if q == c => break,
+                    Some((_, ch)) => value.push(ch),
+                    None => return Err(ExpressionError::new(start, "unterminated string")),
+                }
+            }
+            tokens.push((Token::Str(value), start));
+        } else if c == '.' || c == ';' {
+            chars.next();
+            tokens.push((Token::Symbol(if c == '.' { "." } else { ";" }), start));
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| source[start..].starts_with(*symbol))
+                .ok_or_else(|| ExpressionError::new(start, format!("unexpected `{}`", c)))?;
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push((Token::Symbol(symbol), start));
+        }
+    }
+    Ok(tokens)
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// Parsed expression, before name resolution.
+#[derive(Debug, Clone)]
+enum Ast {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Name(String),
+    Field(Box<Node>, String),
+    Index(Box<Node>, String),
+    Call(String, Vec<Node>),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+}
+
+/// An [`Ast`] and the byte offset it starts at.
+#[derive(Debug, Clone)]
+struct Node {
+    ast: Ast,
+    position: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Activation,
+    Annotation(String),
+}
+
+/// Assignments and `when` condition of a rule.
+type ParsedRule = (Vec<(Target, Node)>, Option<Node>);
+
+const KEYWORDS: [&str; 6] = ["and", "or", "not", "when", "true", "false"];
+
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    next: usize,
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |&(_, position)| position)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Ident(name)) if name == keyword);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> ExpressionResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", symbol)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ExpressionError {
+        let found = match self.peek() {
+            Some(token) => token.to_string(),
+            None => "end of expression".to_string(),
+        };
+        ExpressionError::new(
+            self.position(),
+            format!("expected {}, found {}", expected, found),
+        )
+    }
+
+    /// `assignment (';' assignment)* [';'] ['when' expr]`
+    fn rule(&mut self) -> ExpressionResult<ParsedRule> {
+        let mut assignments = vec![self.assignment()?];
+        while self.eat_symbol(";") {
+            if self.peek().is_none() || matches!(self.peek(), Some(Token::Ident(k)) if k == "when")
+            {
+                break;
+            }
+            assignments.push(self.assignment()?);
+        }
+        let condition = if self.eat_keyword("when") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        if self.peek().is_some() {
+            return Err(self.unexpected("`;`, `when` or end of expression"));
+        }
+        Ok((assignments, condition))
+    }
+
+    fn assignment(&mut self) -> ExpressionResult<(Target, Node)> {
+        let target = self.postfix()?;
+        let target = match &target.ast {
+            Ast::Name(name) if name == "activation" => Target::Activation,
+            Ast::Field(base, field)
+                if field == "activation" && matches!(&base.ast, Ast::Name(n) if n == "self") =>
+            {
+                Target::Activation
+            }
+            Ast::Index(base, key) => match &base.ast {
+                Ast::Name(name) if name == "annotations" => Target::Annotation(key.clone()),
+                Ast::Field(node, field)
+                    if field == "annotations"
+                        && matches!(&node.ast, Ast::Name(n) if n == "self") =>
+                {
+                    Target::Annotation(key.clone())
+                }
+                _ => return Err(invalid_target(&target)),
+            },
+            _ => return Err(invalid_target(&target)),
+        };
+        self.expect_symbol("=")?;
+        Ok((target, self.expr()?))
+    }
+
+    fn expr(&mut self) -> ExpressionResult<Node> {
+        self.binary(0)
+    }
+
+    /// Binary operators by increasing precedence level.
+    fn binary(&mut self, level: usize) -> ExpressionResult<Node> {
+        const LEVELS: [&[(&str, BinaryOp)]; 5] = [
+            &[("or", BinaryOp::Or), ("||", BinaryOp::Or)],
+            &[("and", BinaryOp::And), ("&&", BinaryOp::And)],
+            &[
+                ("==", BinaryOp::Eq),
+                ("!=", BinaryOp::Ne),
+                ("<=", BinaryOp::Le),
+                (">=", BinaryOp::Ge),
+                ("<", BinaryOp::Lt),
+                (">", BinaryOp::Gt),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[("*", BinaryOp::Mul), ("/", BinaryOp::Div)],
+        ];
+        let Some(operators) = LEVELS.get(level) else {
+            return self.unary();
+        };
+
+        let mut left = self.binary(level + 1)?;
+        'operators: loop {
+            for &(symbol, op) in operators.iter() {
+                if self.eat_symbol(symbol) || self.eat_keyword(symbol) {
+                    let right = self.binary(level + 1)?;
+                    let position = left.position;
+                    left = Node {
+                        ast: Ast::Binary(op, Box::new(left), Box::new(right)),
+                        position,
+                    };
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn unary(&mut self) -> ExpressionResult<Node> {
+        let position = self.position();
+        if self.eat_symbol("-") {
+            let operand = self.unary()?;
+            return Ok(Node {
+                ast: Ast::Neg(Box::new(operand)),
+                position,
+            });
+        }
+        if self.eat_symbol("!") || self.eat_keyword("not") {
+            let operand = self.unary()?;
+            return Ok(Node {
+                ast: Ast::Not(Box::new(operand)),
+                position,
+            });
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> ExpressionResult<Node> {
+        let mut node = self.primary()?;
+        // Paths are reported at their start
+        let position = node.position;
+        loop {
+            if self.eat_symbol(".") {
+                match self.tokens.get(self.next) {
+                    Some((Token::Ident(field), _)) => {
+                        self.next += 1;
+                        node = Node {
+                            ast: Ast::Field(Box::new(node), field.clone()),
+                            position,
+                        };
+                    }
+                    _ => return Err(self.unexpected("a field name")),
+                }
+            } else if self.eat_symbol("[") {
+                let key = match self.tokens.get(self.next) {
+                    Some((Token::Str(key), _)) => key.clone(),
+                    _ => return Err(self.unexpected("a quoted key")),
+                };
+                self.next += 1;
+                self.expect_symbol("]")?;
+                node = Node {
+                    ast: Ast::Index(Box::new(node), key),
+                    position,
+                };
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> ExpressionResult<Node> {
+        let position = self.position();
+        let ast = match self.tokens.get(self.next).map(|(token, _)| token.clone()) {
+            Some(Token::Number(n)) => Ast::Number(n),
+            Some(Token::Str(s)) => Ast::Str(s),
+            Some(Token::Ident(name)) if name == "true" || name == "false" => {
+                Ast::Bool(name == "true")
+            }
+            Some(Token::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                self.next += 1;
+                if self.eat_symbol("(") {
+                    let mut args = Vec::new();
+                    if !self.eat_symbol(")") {
+                        loop {
+                            args.push(self.expr()?);
+                            if self.eat_symbol(")") {
+                                break;
+                            }
+                            self.expect_symbol(",")?;
+                        }
+                    }
+                    return Ok(Node {
+                        ast: Ast::Call(name, args),
+                        position,
+                    });
+                }
+                return Ok(Node {
+                    ast: Ast::Name(name),
+                    position,
+                });
+            }
+            Some(Token::Symbol("(")) => {
+                self.next += 1;
+                let inner = self.expr()?;
+                self.expect_symbol(")")?;
+                return Ok(inner);
+            }
+            _ => return Err(self.unexpected("a value")),
+        };
+        self.next += 1;
+        Ok(Node { ast, position })
+    }
+}
+
+fn invalid_target(target: &Node) -> ExpressionError {
+    ExpressionError::new(
+        target.position,
+        "can only assign to `activation` or `annotations['key']`",
+    )
+}
+
+// =============================================================================
+// Type checker
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Number,
+    Bool,
+    Str,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Number => "number",
+            Type::Bool => "boolean",
+            Type::Str => "string",
+        })
+    }
+}
+
+/// A node or neighbor attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Activation,
+    Id,
+    Role,
+    Relationship,
+    Annotation(String),
+}
+
+impl Field {
+    fn value_type(&self) -> Type {
+        match self {
+            Field::Activation | Field::Id => Type::Number,
+            _ => Type::Str,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reducer {
+    Avg,
+    Sum,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Min,
+    Max,
+    Abs,
+    Clamp,
+}
+
+/// Type checked expression.
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Own(Field),
+    Global(String),
+    Tick,
+    /// Reduce a numeric field over the neighbors with a relationship (or all).
+    Reduce(Reducer, Option<String>, Field),
+    Count(Option<String>),
+    Call(Function, Vec<Expr>),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+/// What a (partial) path resolves to.
+enum Checked {
+    Value(Expr, Type),
+    SelfNode,
+    SelfAnnotations,
+    Global,
+    Neighbors(Option<String>),
+    NeighborAnnotations(Option<String>),
+    List(Option<String>, Field),
+}
+
+impl Checked {
+    fn describe(&self) -> String {
+        match self {
+            Checked::Value(_, ty) => format!("a {}", ty),
+            Checked::SelfNode => "`self`".to_string(),
+            Checked::SelfAnnotations | Checked::NeighborAnnotations(_) => {
+                "`annotations`".to_string()
+            }
+            Checked::Global => "`global`".to_string(),
+            Checked::Neighbors(_) => "a neighbor list".to_string(),
+            Checked::List(_, field) => format!("a list of {}s", field.value_type()),
+        }
+    }
+}
+
+struct Checker<'a> {
+    params: &'a HashMap<String, Value>,
+    reads_tick: bool,
+}
+
+impl Checker<'_> {
+    fn expect(&mut self, node: &Node, expected: Type) -> ExpressionResult<Expr> {
+        let (expr, ty) = self.value(node)?;
+        if ty != expected {
+            return Err(ExpressionError::new(
+                node.position,
+                format!("expected a {}, found a {}", expected, ty),
+            ));
+        }
+        Ok(expr)
+    }
+
+    fn value(&mut self, node: &Node) -> ExpressionResult<(Expr, Type)> {
+        match self.check(node)? {
+            Checked::Value(expr, ty) => Ok((expr, ty)),
+            Checked::Neighbors(_) | Checked::List(..) => Err(ExpressionError::new(
+                node.position,
+                "lists must be reduced with avg(), sum(), min(), max() or count()",
+            )),
+            other => Err(ExpressionError::new(
+                node.position,
+                format!("{} is not a value", other.describe()),
+            )),
+        }
+    }
+
+    fn check(&mut self, node: &Node) -> ExpressionResult<Checked> {
+        let error = |message: String| ExpressionError::new(node.position, message);
+        Ok(match &node.ast {
+            Ast::Number(n) => Checked::Value(Expr::Number(*n), Type::Number),
+            Ast::Str(s) => Checked::Value(Expr::Str(s.clone()), Type::Str),
+            Ast::Bool(b) => Checked::Value(Expr::Bool(*b), Type::Bool),
+            Ast::Name(name) => match name.as_str() {
+                "self" => Checked::SelfNode,
+                "neighbors" => Checked::Neighbors(None),
+                "global" => Checked::Global,
+                "tick" => {
+                    self.reads_tick = true;
+                    Checked::Value(Expr::Tick, Type::Number)
+                }
+                _ => match self.params.get(name) {
+                    Some(Value::Number(n)) => {
+                        Checked::Value(Expr::Number(n.as_f64().unwrap_or(0.0)), Type::Number)
+                    }
+                    Some(Value::String(s)) => Checked::Value(Expr::Str(s.clone()), Type::Str),
+                    Some(Value::Bool(b)) => Checked::Value(Expr::Bool(*b), Type::Bool),
+                    Some(_) => {
+                        return Err(error(format!(
+                            "param `{}` must be a number, string or boolean",
+                            name
+                        )))
+                    }
+                    None if name == "activation" => {
+                        return Err(error(
+                            "unknown name `activation` (use `self.activation`)".into(),
+                        ))
+                    }
+                    None => return Err(error(format!("unknown name `{}`", name))),
+                },
+            },
+            Ast::Field(base, field) => {
+                let base = self.check(base)?;
+                let resolved = match field.as_str() {
+                    "activation" => Some(Field::Activation),
+                    "id" => Some(Field::Id),
+                    "role" => Some(Field::Role),
+                    "relationship" => Some(Field::Relationship),
+                    _ => None,
+                };
+                match (base, resolved) {
+                    (Checked::SelfNode, _) if field == "annotations" => Checked::SelfAnnotations,
+                    (Checked::Neighbors(rel), _) if field == "annotations" => {
+                        Checked::NeighborAnnotations(rel)
+                    }
+                    (Checked::SelfNode, Some(Field::Relationship)) => {
+                        return Err(error(
+                            "`self` has no relationship; read it on `neighbors`".into(),
+                        ))
+                    }
+                    (Checked::SelfNode, Some(field)) => {
+                        let ty = field.value_type();
+                        Checked::Value(Expr::Own(field), ty)
+                    }
+                    (Checked::Neighbors(rel), Some(field)) => Checked::List(rel, field),
+                    (Checked::SelfNode | Checked::Neighbors(_), None) => {
+                        return Err(error(format!(
+                            "unknown field `{}` (expected activation, id, role, relationship or annotations)",
+                            field
+                        )))
+                    }
+                    (other, _) => {
+                        return Err(error(format!("{} has no fields", other.describe())))
+                    }
+                }
+            }
+            Ast::Index(base, key) => match self.check(base)? {
+                Checked::SelfAnnotations => {
+                    Checked::Value(Expr::Own(Field::Annotation(key.clone())), Type::Str)
+                }
+                Checked::NeighborAnnotations(rel) => {
+                    Checked::List(rel, Field::Annotation(key.clone()))
+                }
+                Checked::Global => Checked::Value(Expr::Global(key.clone()), Type::Str),
+                Checked::Neighbors(None) => Checked::Neighbors(Some(key.clone())),
+                Checked::Neighbors(Some(_)) => {
+                    return Err(error(
+                        "neighbors are already filtered by relationship".into(),
+                    ))
+                }
+                other => return Err(error(format!("{} cannot be indexed", other.describe()))),
+            },
+            Ast::Call(name, args) => Checked::Value(self.call(node, name, args)?, Type::Number),
+            Ast::Neg(operand) => Checked::Value(
+                Expr::Neg(Box::new(self.expect(operand, Type::Number)?)),
+                Type::Number,
+            ),
+            Ast::Not(operand) => Checked::Value(
+                Expr::Not(Box::new(self.expect(operand, Type::Bool)?)),
+                Type::Bool,
+            ),
+            Ast::Binary(op, left, right) => {
+                let (left_expr, left_type) = self.value(left)?;
+                let (right_expr, right_type) = self.value(right)?;
+                let (operand, result) = match op {
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                        (Some(Type::Number), Type::Number)
+                    }
+                    BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                        (Some(Type::Number), Type::Bool)
+                    }
+                    BinaryOp::And | BinaryOp::Or => (Some(Type::Bool), Type::Bool),
+                    BinaryOp::Eq | BinaryOp::Ne => (None, Type::Bool),
+                };
+                let operand = operand.unwrap_or(left_type);
+                for (side, ty) in [(left, left_type), (right, right_type)] {
+                    if ty != operand {
+                        return Err(ExpressionError::new(
+                            side.position,
+                            format!("expected a {}, found a {}", operand, ty),
+                        ));
+                    }
+                }
+                Checked::Value(
+                    Expr::Binary(*op, Box::new(left_expr), Box::new(right_expr)),
+                    result,
+                )
+            }
+        })
+    }
+
+    fn call(&mut self, node: &Node, name: &str, args: &[Node]) -> ExpressionResult<Expr> {
+        let error = |message: String| ExpressionError::new(node.position, message);
+        let reducer = match name {
+            "avg" => Some(Reducer::Avg),
+            "sum" => Some(Reducer::Sum),
+            "min" => Some(Reducer::Min),
+            "max" => Some(Reducer::Max),
+            _ => None,
+        };
+
+        // Reductions of a neighbor list
+        if let ([arg], true) = (args, reducer.is_some() || name == "count") {
+            return match (self.check(arg)?, reducer) {
+                (Checked::Neighbors(rel), None) | (Checked::List(rel, _), None) => {
+                    Ok(Expr::Count(rel))
+                }
+                (Checked::List(rel, field), Some(reducer))
+                    if field.value_type() == Type::Number =>
+                {
+                    Ok(Expr::Reduce(reducer, rel, field))
+                }
+                (other, _) => Err(error(format!(
+                    "{}() takes a list of {}, found {}",
+                    name,
+                    if reducer.is_some() {
+                        "numbers"
+                    } else {
+                        "neighbors or values"
+                    },
+                    other.describe()
+                ))),
+            };
+        }
+
+        let (function, arity) = match name {
+            "min" => (Function::Min, None),
+            "max" => (Function::Max, None),
+            "abs" => (Function::Abs, Some(1)),
+            "clamp" => (Function::Clamp, Some(3)),
+            "count" | "avg" | "sum" => {
+                return Err(error(format!("{}() takes a single list", name)))
+            }
+            _ => {
+                return Err(error(format!(
+                    "unknown function `{}` (expected avg, sum, min, max, count, abs or clamp)",
+                    name
+                )))
+            }
+        };
+        match arity {
+            Some(arity) if args.len() != arity => {
+                return Err(error(format!("{}() takes {} arguments", name, arity)))
+            }
+            None if args.len() < 2 => {
+                return Err(error(format!("{}() takes a list or several numbers", name)))
+            }
+            _ => {}
+        }
+        let args = args
+            .iter()
+            .map(|arg| self.expect(arg, Type::Number))
+            .collect::<ExpressionResult<_>>()?;
+        Ok(Expr::Call(function, args))
+    }
+}
+
+// =============================================================================
+// Interpreter
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Scalar {
+    Number(f64),
+    Bool(bool),
+    Str(String),
+}
+
+impl Scalar {
+    // Types are checked at compile time, the fallbacks are unreachable
+    fn as_number(&self) -> f64 {
+        match self {
+            Scalar::Number(n) => *n,
+            _ => 0.0,
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        matches!(self, Scalar::Bool(true))
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Scalar::Str(s) => s,
+            _ => "",
+        }
+    }
+}
+
+fn annotation<'a>(annotations: &'a HashMap<String, String>, key: &str) -> &'a str {
+    annotations.get(key).map_or("", String::as_str)
+}
+
+fn neighbor_number(neighbor: &NeighborState, field: &Field) -> f64 {
+    match field {
+        Field::Activation => f64::from(neighbor.state.current_state().activation),
+        Field::Id => neighbor.node_id.0 as f64,
+        _ => 0.0,
+    }
+}
+
+impl Expr {
+    fn eval(&self, ctx: &RuleContext) -> Scalar {
+        match self {
+            Expr::Number(n) => Scalar::Number(*n),
+            Expr::Str(s) => Scalar::Str(s.clone()),
+            Expr::Bool(b) => Scalar::Bool(*b),
+            Expr::Own(field) => {
+                let state = ctx.current_state();
+                match field {
+                    Field::Activation => Scalar::Number(f64::from(state.activation)),
+                    Field::Id => Scalar::Number(ctx.node_id.0 as f64),
+                    Field::Role => Scalar::Str(annotation(&state.annotations, "role").into()),
+                    Field::Annotation(key) => {
+                        Scalar::Str(annotation(&state.annotations, key).into())
+                    }
+                    Field::Relationship => Scalar::Str(String::new()),
+                }
+            }
+            Expr::Global(key) => Scalar::Str(ctx.global_value(key).unwrap_or("").to_string()),
+            Expr::Tick => Scalar::Number(ctx.tick as f64),
+            Expr::Reduce(reducer, rel, field) => {
+                let values: Vec<f64> = selected(ctx, rel)
+                    .map(|n| neighbor_number(n, field))
+                    .collect();
+                if values.is_empty() {
+                    return Scalar::Number(0.0);
+                }
+                let sum: f64 = values.iter().sum();
+                Scalar::Number(match reducer {
+                    Reducer::Avg => sum / values.len() as f64,
+                    Reducer::Sum => sum,
+                    Reducer::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                    Reducer::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                })
+            }
+            Expr::Count(rel) => Scalar::Number(selected(ctx, rel).count() as f64),
+            Expr::Call(function, args) => {
+                let args: Vec<f64> = args.iter().map(|a| a.eval(ctx).as_number()).collect();
+                Scalar::Number(match function {
+                    Function::Min => args.iter().copied().fold(f64::INFINITY, f64::min),
+                    Function::Max => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Function::Abs => args[0].abs(),
+                    Function::Clamp => args[0].max(args[1]).min(args[2]),
+                })
+            }
+            Expr::Neg(operand) => Scalar::Number(-operand.eval(ctx).as_number()),
+            Expr::Not(operand) => Scalar::Bool(!operand.eval(ctx).as_bool()),
+            Expr::Binary(op, left, right) => {
+                // Short-circuit boolean operators
+                match op {
+                    BinaryOp::And => {
+                        return Scalar::Bool(left.eval(ctx).as_bool() && right.eval(ctx).as_bool())
+                    }
+                    BinaryOp::Or => {
+                        return Scalar::Bool(left.eval(ctx).as_bool() || right.eval(ctx).as_bool())
+                    }
+                    _ => {}
+                }
+                let (left, right) = (left.eval(ctx), right.eval(ctx));
+                let (a, b) = (left.as_number(), right.as_number());
+                match op {
+                    BinaryOp::Add => Scalar::Number(a + b),
+                    BinaryOp::Sub => Scalar::Number(a - b),
+                    BinaryOp::Mul => Scalar::Number(a * b),
+                    BinaryOp::Div => Scalar::Number(if b == 0.0 { 0.0 } else { a / b }),
+                    BinaryOp::Lt => Scalar::Bool(a < b),
+                    BinaryOp::Le => Scalar::Bool(a <= b),
+                    BinaryOp::Gt => Scalar::Bool(a > b),
+                    BinaryOp::Ge => Scalar::Bool(a >= b),
+                    BinaryOp::Eq => Scalar::Bool(left == right),
+                    BinaryOp::Ne => Scalar::Bool(left != right),
+                    BinaryOp::And | BinaryOp::Or => unreachable!("handled above"),
+                }
+            }
+        }
+    }
+}
+
+/// Neighbors with the given relationship, or all of them.
+fn selected<'c, 'a>(
+    ctx: &'c RuleContext<'a>,
+    relationship: &'c Option<String>,
+) -> impl Iterator<Item = &'c NeighborState<'a>> {
+    ctx.neighbors.iter().filter(move |n| {
+        relationship
+            .as_ref()
+            .is_none_or(|relationship| n.relationship == *relationship)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::GraphAutomaton;
+    use crate::state::StateData;
+    use crate::temporal::SourceCodeTemporalGraph;
+    use serde_json::json;
+    use vibe_graph_core::{EdgeId, GraphEdge, GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+    /// A hub using two files and contained in a directory.
+    fn automaton() -> GraphAutomaton {
+        let node = |id: u64| GraphNode {
+            id: NodeId(id),
+            name: format!("n{}", id),
+            kind: GraphNodeKind::File,
+            metadata: HashMap::new(),
+        };
+        let edge = |id: u64, from: u64, to: u64, relationship: &str| GraphEdge {
+            id: EdgeId(id),
+            from: NodeId(from),
+            to: NodeId(to),
+            relationship: relationship.into(),
+            metadata: HashMap::new(),
+        };
+        let graph = SourceCodeGraph {
+            nodes: (1..=4).map(node).collect(),
+            edges: vec![
+                edge(1, 1, 2, "uses"),
+                edge(2, 1, 3, "uses"),
+                edge(3, 4, 1, "contains"),
+            ],
+            metadata: HashMap::new(),
+        };
+        let mut temporal = SourceCodeTemporalGraph::from_source_graph(graph);
+        for (id, activation, role) in [(1, 0.5, "hub"), (2, 0.8, "sink"), (3, 0.4, "sink")] {
+            let state = StateData::with_activation(json!(null), activation).annotate("role", role);
+            temporal.set_initial_state(&NodeId(id), state).unwrap();
+        }
+        GraphAutomaton::new(temporal)
+    }
+
+    fn compile(source: &str) -> ExpressionRule {
+        let params = HashMap::from([("damping".to_string(), json!(0.75))]);
+        ExpressionRule::parse("test", source, &params).unwrap()
+    }
+
+    fn next_state(rule: &ExpressionRule, node: u64) -> Option<StateData> {
+        let automaton = automaton();
+        let ctx = automaton.build_rule_context(NodeId(node)).unwrap();
+        if !rule.should_apply(&ctx) {
+            return None;
+        }
+        match rule.apply(&ctx).unwrap() {
+            RuleOutcome::Transition(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expression_rule_evaluates_against_context() {
+        let rule = compile(
+            "activation = max(self.activation * 0.9, avg(neighbors['uses'].activation) * damping) \
+             when self.role == 'hub'",
+        );
+        // avg(0.8, 0.4) * 0.75 = 0.45 < 0.5 * 0.9
+        let state = next_state(&rule, 1).unwrap();
+        assert!((state.activation - 0.45).abs() < 1e-6);
+        assert_eq!(next_state(&rule, 2), None);
+        assert_eq!(rule.locality(), Locality::Neighborhood);
+
+        let rule = compile(
+            "activation = clamp(sum(neighbors.activation), 0, 1); \
+             annotations['size'] = 'large' when count(neighbors['uses']) >= 2 and not (self.id == 2)",
+        );
+        let state = next_state(&rule, 1).unwrap();
+        assert_eq!(state.activation, 1.0);
+        assert_eq!(state.annotations["size"], "large");
+        assert_eq!(state.annotations["role"], "hub");
+
+        // Reading the tick makes the rule global
+        let rule = compile("activation = tick / 10");
+        assert_eq!(rule.locality(), Locality::Global);
+    }
+
+    #[test]
+    fn test_expression_rule_reports_errors() {
+        let error = |source: &str| {
+            ExpressionRule::parse("test", source, &HashMap::new())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("activation = self.activation +"),
+            "expected a value, found end of expression (column 31)"
+        );
+        assert_eq!(
+            error("activation = self.role"),
+            "expected a number, found a string (column 14)"
+        );
+        assert_eq!(
+            error("activation = neighbors.activation"),
+            "lists must be reduced with avg(), sum(), min(), max() or count() (column 14)"
+        );
+        assert_eq!(
+            error("activation = avg(neighbors.role)"),
+            "avg() takes a list of numbers, found a list of strings (column 14)"
+        );
+        assert_eq!(
+            error("activation = self.weight"),
+            "unknown field `weight` (expected activation, id, role, relationship or annotations) (column 14)"
+        );
+        assert_eq!(
+            error("activation = damping"),
+            "unknown name `damping` (column 14)"
+        );
+        assert_eq!(
+            error("self.id = 1"),
+            "can only assign to `activation` or `annotations['key']` (column 1)"
+        );
+        assert_eq!(
+            error("activation = 1 when self.activation"),
+            "expected a boolean, found a number (column 21)"
+        );
+    }
+}
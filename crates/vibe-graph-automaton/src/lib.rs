@@ -32,14 +32,17 @@
 //! ## Features
 //!
 //! - `llm` - Enable LLM-powered rules using the [Rig](https://github.com/0xPlaygrounds/rig) library
+//! - `plugins` - Load rules and samplers from sandboxed WebAssembly modules (see [`plugin`])
 
 mod automaton;
 pub mod config;
 pub mod description;
 mod error;
+mod expression;
 pub mod inference;
 pub mod persistence;
 pub mod project_config;
+mod replay;
 mod rule;
 pub mod script;
 mod source_code;
@@ -50,6 +53,10 @@ mod temporal;
 #[cfg(feature = "llm")]
 pub mod llm_runner;
 
+// WebAssembly plugin host (optional feature)
+#[cfg(feature = "plugins")]
+pub mod plugin;
+
 // Test fixtures (only for tests)
 #[cfg(test)]
 pub mod test_fixtures;
@@ -59,9 +66,10 @@ pub use automaton::{
     StabilityHeuristic, TickResult, TransitionRateHeuristic,
 };
 pub use error::{AutomatonError, AutomatonResult};
+pub use expression::{ExpressionError, ExpressionRule};
 pub use rule::{
-    CompositeRule, IdentityRule, NeighborState, NoOpRule, Rule, RuleContext, RuleId, RuleOutcome,
-    RuleRegistry,
+    CompositeRule, IdentityRule, Locality, NeighborState, NoOpRule, Rule, RuleContext, RuleId,
+    RuleOutcome, RuleRegistry,
 };
 pub use state::{EvolutionaryState, StateData, Transition, TransitionBuilder};
 pub use temporal::{Neighborhood as TemporalNeighborhood, TemporalGraph, TemporalNode};
@@ -95,14 +103,16 @@ pub use source_code::{
 
 // Description → Runtime bridge (impact analysis)
 pub use source_code::{
-    apply_description, format_behavioral_contracts, format_impact_report, run_impact_analysis,
-    DampedPropagationRule, ImpactLevel, ImpactNode, ImpactReport, ImpactStats,
+    apply_description, apply_description_with_config, apply_description_with_rules,
+    description_config, format_behavioral_contracts, format_impact_report, run_impact,
+    run_impact_analysis, run_impact_analysis_with_rules, DampedPropagationRule, ImpactLevel,
+    ImpactNode, ImpactReport, ImpactStats,
 };
 
 // Evolution planning (objective-driven development)
 pub use source_code::{
-    format_evolution_plan, run_evolution_plan, EvolutionItem, EvolutionPlan, EvolutionSummary,
-    Perturbation,
+    format_evolution_plan, run_evolution_plan, run_evolution_plan_with_rules, EvolutionItem,
+    EvolutionPlan, EvolutionSummary, Perturbation,
 };
 
 // Canonical next-task object (benchmark-ready)
@@ -111,12 +121,19 @@ pub use source_code::{
     TaskNeighbor, TaskPriority, TaskScope,
 };
 
+// Replay and diff of recorded runs
+pub use replay::{
+    AnnotationDelta, GraphDiff, NodeDelta, RecordedTransition, Replay, RunRecording, TickRecord,
+};
+
 // Re-export TemporalGraph implementation
 pub use temporal::SourceCodeTemporalGraph;
 
 // Project config (vg.toml)
 pub use inference::{detect_project_type, generate_toml, infer_config, infer_process, ProjectType};
-pub use project_config::{ProcessSection, ProjectConfig, RestartPolicy, CONFIG_FILENAME};
+pub use project_config::{
+    PluginConfig, ProcessSection, ProjectConfig, RestartPolicy, CONFIG_FILENAME, PLUGINS_DIR,
+};
 pub use script::{
     parse_errors, run_script, run_script_with_timeout, run_watch_scripts,
     run_watch_scripts_with_timeout, ProcessFeedback, ScriptError, ScriptFeedback, ScriptResult,
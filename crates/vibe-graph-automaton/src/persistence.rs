@@ -11,20 +11,24 @@
 //! │   ├── state.json         # Current temporal graph state
 //! │   ├── config.json        # Automaton configuration
 //! │   ├── tick_history.json  # History of tick results
+//! │   ├── recording.json     # Transitions of the last recorded run
 //! │   └── snapshots/         # Timestamped snapshots
 //! │       ├── 1703800000.json
 //! │       └── 1703800100.json
 //! ```
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
+use vibe_graph_core::{NodeId, ID_SCHEME_KEY, PATH_ID_SCHEME};
 
 use crate::automaton::{AutomatonConfig, AutomatonRuntime, TickResult};
 use crate::config::AutomatonDescription;
 use crate::error::AutomatonResult;
+use crate::replay::RunRecording;
 use crate::temporal::SourceCodeTemporalGraph;
 use crate::GraphAutomaton;
 
@@ -39,6 +43,7 @@ const STATE_FILE: &str = "state.json";
 const CONFIG_FILE: &str = "config.json";
 const DESCRIPTION_FILE: &str = "description.json";
 const TICK_HISTORY_FILE: &str = "tick_history.json";
+const RECORDING_FILE: &str = "recording.json";
 const SNAPSHOTS_DIR: &str = "snapshots";
 const PERTURBATION_FILE: &str = "perturbation.json";
 
@@ -342,6 +347,45 @@ impl AutomatonStore {
         Ok(Some(history))
     }
 
+    // =========================================================================
+    // Recording
+    // =========================================================================
+
+    /// Save the recording of a run, replacing the previous one.
+    pub fn save_recording(&self, recording: &RunRecording) -> AutomatonResult<PathBuf> {
+        self.init()?;
+
+        let recording_path = self.automaton_dir.join(RECORDING_FILE);
+        let json = serde_json::to_string(recording)?;
+        std::fs::write(&recording_path, &json)?;
+
+        debug!(
+            path = %recording_path.display(),
+            ticks = recording.ticks.len(),
+            "Saved run recording"
+        );
+        Ok(recording_path)
+    }
+
+    /// Load the recording of the last recorded run.
+    pub fn load_recording(&self) -> AutomatonResult<Option<RunRecording>> {
+        let recording_path = self.automaton_dir.join(RECORDING_FILE);
+
+        if !recording_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&recording_path)?;
+        let recording: RunRecording = serde_json::from_str(&json)?;
+
+        debug!(
+            path = %recording_path.display(),
+            ticks = recording.ticks.len(),
+            "Loaded run recording"
+        );
+        Ok(Some(recording))
+    }
+
     // =========================================================================
     // Snapshots
     // =========================================================================
@@ -470,6 +514,68 @@ impl AutomatonStore {
         Ok(deleted)
     }
 
+    // =========================================================================
+    // Node ID Migration
+    // =========================================================================
+
+    /// Rewrite persisted node IDs after the source graph moved to
+    /// path-derived IDs.
+    ///
+    /// The state, snapshots and recording are only rewritten while their
+    /// embedded graph still uses the old IDs, the description while one of
+    /// its nodes maps to a new ID, so applying a mapping twice is a no-op.
+    /// Returns the number of files rewritten.
+    pub fn migrate_node_ids(&self, mapping: &HashMap<NodeId, NodeId>) -> AutomatonResult<usize> {
+        let mut state_paths = vec![self.automaton_dir.join(STATE_FILE)];
+        state_paths.extend(self.list_snapshots()?.into_iter().map(|s| s.path));
+
+        let mut rewritten = 0;
+        for path in state_paths.iter().filter(|p| p.exists()) {
+            let mut state: PersistedState = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            if state.graph.source_graph.has_stable_ids() {
+                continue;
+            }
+            state.graph.remap_node_ids(mapping);
+            state
+                .graph
+                .source_graph
+                .metadata
+                .insert(ID_SCHEME_KEY.to_string(), PATH_ID_SCHEME.to_string());
+            std::fs::write(path, serde_json::to_string_pretty(&state)?)?;
+            rewritten += 1;
+        }
+
+        if let Some(mut recording) = self.load_recording()? {
+            if !recording.initial.source_graph.has_stable_ids() {
+                recording.initial.remap_node_ids(mapping);
+                recording
+                    .initial
+                    .source_graph
+                    .metadata
+                    .insert(ID_SCHEME_KEY.to_string(), PATH_ID_SCHEME.to_string());
+                for transition in recording.ticks.iter_mut().flat_map(|t| &mut t.transitions) {
+                    if let Some(id) = mapping.get(&transition.node_id) {
+                        transition.node_id = *id;
+                    }
+                }
+                self.save_recording(&recording)?;
+                rewritten += 1;
+            }
+        }
+
+        if let Some(mut description) = self.load_description()? {
+            let maps = |id: u64| mapping.get(&NodeId(id)).is_some_and(|new| new.0 != id);
+            if description.nodes.iter().any(|node| maps(node.id)) {
+                description.remap_node_ids(mapping);
+                self.save_description(&description)?;
+                rewritten += 1;
+            }
+        }
+
+        info!(files = rewritten, "Migrated automaton node IDs");
+        Ok(rewritten)
+    }
+
     // =========================================================================
     // Cleanup
     // =========================================================================
@@ -882,4 +988,75 @@ mod tests {
             assert_eq!(loaded.goal, "persistent goal");
         }
     }
+
+    #[test]
+    fn test_migrate_node_ids_rewrites_state_once() {
+        use crate::temporal::TemporalGraph;
+
+        let temp_dir = TempDir::new().unwrap();
+        let store = AutomatonStore::new(temp_dir.path());
+
+        let temporal = SourceCodeTemporalGraph::from_source_graph(create_test_graph());
+        store
+            .save_state(&GraphAutomaton::new(temporal), None)
+            .unwrap();
+        let mut description = AutomatonDescription::new("test-project");
+        description.add_node(crate::config::NodeConfig::file(1, "test.rs"));
+        store.save_description(&description).unwrap();
+
+        let mapping: HashMap<NodeId, NodeId> =
+            [(NodeId(1), NodeId(101)), (NodeId(2), NodeId(102))].into();
+        assert_eq!(store.migrate_node_ids(&mapping).unwrap(), 2);
+        let description = store.load_description().unwrap().unwrap();
+        assert_eq!(description.nodes[0].id, 101);
+
+        let loaded = store.load_state().unwrap().unwrap();
+        let mut ids = loaded.graph.node_ids();
+        ids.sort_by_key(|id| id.0);
+        assert_eq!(ids, vec![NodeId(101), NodeId(102)]);
+        assert!(loaded.graph.source_graph.has_stable_ids());
+
+        // Already migrated: a second pass leaves every file alone
+        assert_eq!(store.migrate_node_ids(&mapping).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_save_and_load_recording() {
+        use crate::state::StateData;
+        use crate::temporal::TemporalGraph;
+
+        let temp_dir = TempDir::new().unwrap();
+        let store = AutomatonStore::new(temp_dir.path());
+        assert!(store.load_recording().unwrap().is_none());
+
+        let temporal = SourceCodeTemporalGraph::from_source_graph(create_test_graph());
+        let mut automaton =
+            GraphAutomaton::new(temporal).with_rule(std::sync::Arc::new(crate::rule::IdentityRule));
+        automaton
+            .graph_mut()
+            .set_initial_state(
+                &NodeId(1),
+                StateData::with_activation(serde_json::json!(null), 0.5),
+            )
+            .unwrap();
+        automaton.start_recording();
+        automaton.run_ticks(2).unwrap();
+        store
+            .save_recording(automaton.recording().unwrap())
+            .unwrap();
+
+        let loaded = store.load_recording().unwrap().unwrap();
+        assert_eq!(loaded.ticks.len(), 2);
+        assert_eq!(loaded.ticks[0].transitions.len(), 2);
+
+        // Migration rewrites the recorded node IDs along with the graph
+        let mapping: HashMap<NodeId, NodeId> =
+            [(NodeId(1), NodeId(101)), (NodeId(2), NodeId(102))].into();
+        store.migrate_node_ids(&mapping).unwrap();
+        let migrated = store.load_recording().unwrap().unwrap();
+        let replayed = migrated.state_at(2).unwrap();
+        let node = replayed.get_node(&NodeId(101)).unwrap();
+        assert_eq!(node.current_state().activation, 0.5);
+        assert_eq!(node.evolution.transition_count(), 3);
+    }
 }
@@ -0,0 +1,811 @@
+//! WebAssembly plugins: [`Rule`]s and [`Sampler`]s loaded at runtime.
+//!
+//! A plugin is a core WebAssembly module. Plugins are discovered from
+//! `.self/plugins/*.wasm` and declared in the `[[plugins]]` tables of
+//! `vg.toml` (see [`PluginConfig`]), so org-specific rules can be shipped
+//! to every repository without rebuilding `vg`.
+//!
+//! Guests run sandboxed: they get no host imports (no filesystem, network
+//! or clock), each call is granted a fuel budget, and their linear memory
+//! is capped ([`PluginLimits`]). Every call runs in a fresh instance, so
+//! plugins are stateless: globals and memory written by one call are gone
+//! by the next.
+//!
+//! ## ABI (version 1)
+//!
+//! Values cross the boundary as UTF-8 JSON in the guest's linear memory.
+//! Buffers returned by the guest are packed in an `i64` as `ptr << 32 | len`.
+//!
+//! ```text
+//! memory                                    the guest's linear memory
+//! vg_abi_version() -> i32                   must return 1
+//! vg_alloc(len: i32) -> i32                 buffer the host writes an input to
+//! vg_manifest() -> i64                      PluginManifest
+//! vg_rule_apply(ptr: i32, len: i32) -> i64  rule input → outcome    (with rules)
+//! vg_sample(ptr: i32, len: i32) -> i64      sample input → artifact (with samplers)
+//! ```
+//!
+//! The manifest lists what the plugin provides:
+//!
+//! ```json
+//! {"rules": [{"id": "doc_coverage", "description": "...", "priority": 10, "locality": "neighborhood"}],
+//!  "samplers": [{"id": "todo_count", "kind": "File"}]}
+//! ```
+//!
+//! A rule input carries the [`RuleContext`]: `rule` (the rule's ID in the
+//! manifest), `node_id`, `tick`, `state`, `neighbors` (`node_id`,
+//! `relationship`, `state`) and `global`, states being [`StateData`]. The
+//! guest answers `{"outcome": "transition", "state": {...}}`,
+//! `{"outcome": "skip"}` or `{"outcome": "delegate", "rule": "<rule id>"}`.
+//!
+//! A sample input carries the [`SampleContext`]: `sampler`, `node`,
+//! `neighbors` (`node`, `edge`), `content`, `annotations` and
+//! `graph_metadata`. The guest answers the artifact, or `null` to skip the
+//! node.
+//!
+//! Rules and samplers are registered as `<plugin>::<id>`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use vibe_graph_core::{
+    GraphEdge, GraphNode, GraphNodeKind, NodeId, NodeSelector, SampleContext, Sampler, SamplerError,
+};
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    TypedFunc,
+};
+
+use crate::error::{AutomatonError, AutomatonResult};
+use crate::project_config::PluginConfig;
+use crate::rule::{Locality, Rule, RuleContext, RuleId, RuleOutcome};
+use crate::state::StateData;
+
+/// Version of the guest ABI implemented by this host.
+pub const ABI_VERSION: i32 = 1;
+
+/// Resources granted to a plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginLimits {
+    /// Fuel per call (roughly one unit per WebAssembly instruction).
+    pub fuel: u64,
+    /// Maximum size of the linear memory, in bytes.
+    pub memory_bytes: usize,
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            memory_bytes: 64 << 20,
+        }
+    }
+}
+
+impl TryFrom<&PluginConfig> for PluginLimits {
+    type Error = AutomatonError;
+
+    /// Rejects a zero fuel budget, and memory caps that are zero or do not
+    /// fit in `usize` bytes.
+    fn try_from(config: &PluginConfig) -> AutomatonResult<Self> {
+        let invalid = |message: String| AutomatonError::Plugin {
+            plugin: config.name.clone(),
+            message,
+        };
+        let defaults = Self::default();
+        let fuel = match config.fuel {
+            Some(0) => return Err(invalid("fuel must be positive".to_string())),
+            fuel => fuel.unwrap_or(defaults.fuel),
+        };
+        let memory_bytes = match config.memory_mb {
+            Some(mb) => mb
+                .checked_mul(1 << 20)
+                .filter(|&bytes| bytes > 0)
+                .ok_or_else(|| invalid(format!("memory_mb = {} is out of range", mb)))?,
+            None => defaults.memory_bytes,
+        };
+        Ok(Self { fuel, memory_bytes })
+    }
+}
+
+/// What a plugin provides, as returned by `vg_manifest`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginManifest {
+    #[serde(default)]
+    pub rules: Vec<RuleManifest>,
+    #[serde(default)]
+    pub samplers: Vec<SamplerManifest>,
+}
+
+/// A rule declared by a plugin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleManifest {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub priority: i32,
+    /// Declare `neighborhood` to keep frontier ticks enabled.
+    #[serde(default)]
+    pub locality: Locality,
+}
+
+/// A sampler declared by a plugin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplerManifest {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    /// Only sample nodes of this kind (all nodes when absent).
+    #[serde(default)]
+    pub kind: Option<GraphNodeKind>,
+}
+
+/// Compiles and instantiates plugins.
+#[derive(Clone)]
+pub struct PluginHost {
+    engine: Engine,
+}
+
+impl PluginHost {
+    /// Create a host with fuel metering enabled.
+    pub fn new() -> AutomatonResult<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| AutomatonError::Plugin {
+            plugin: "host".to_string(),
+            message: message(e),
+        })?;
+        Ok(Self { engine })
+    }
+
+    /// Load the plugins of a project: every `*.wasm` module in
+    /// `.self/plugins/`, plus the `[[plugins]]` of its `vg.toml`. A declared
+    /// plugin replaces the discovered module of the same name.
+    pub fn load_project(
+        &self,
+        root: &Path,
+        configs: &[PluginConfig],
+    ) -> AutomatonResult<Vec<Plugin>> {
+        let dir = PluginConfig::discovery_dir(root);
+        let mut found: BTreeMap<String, (PathBuf, PluginLimits)> = BTreeMap::new();
+        if dir.is_dir() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "wasm") {
+                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                        found.insert(name.to_string(), (path.clone(), PluginLimits::default()));
+                    }
+                }
+            }
+        }
+        for config in configs {
+            if !config.enabled {
+                found.remove(&config.name);
+                continue;
+            }
+            let path = match &config.path {
+                Some(path) => root.join(path),
+                None => dir.join(format!("{}.wasm", config.name)),
+            };
+            found.insert(config.name.clone(), (path, PluginLimits::try_from(config)?));
+        }
+
+        found
+            .into_iter()
+            .map(|(name, (path, limits))| self.load(&name, &path, limits))
+            .collect()
+    }
+
+    /// Load a plugin from a `.wasm` (or `.wat`) file.
+    pub fn load(&self, name: &str, path: &Path, limits: PluginLimits) -> AutomatonResult<Plugin> {
+        let bytes = std::fs::read(path).map_err(|e| AutomatonError::Plugin {
+            plugin: name.to_string(),
+            message: format!("cannot read {}: {}", path.display(), e),
+        })?;
+        let mut plugin = self.load_bytes(name, &bytes, limits)?;
+        plugin.path = Some(path.to_path_buf());
+        Ok(plugin)
+    }
+
+    /// Load a plugin from module bytes (binary or text format).
+    pub fn load_bytes(
+        &self,
+        name: &str,
+        bytes: &[u8],
+        limits: PluginLimits,
+    ) -> AutomatonResult<Plugin> {
+        let error = |message: String| AutomatonError::Plugin {
+            plugin: name.to_string(),
+            message,
+        };
+        let module = Module::new(&self.engine, bytes).map_err(|e| error(message(e)))?;
+        if let Some(import) = module.imports().next() {
+            return Err(error(format!(
+                "imports {}::{}, but plugins run without host functions",
+                import.module(),
+                import.name()
+            )));
+        }
+
+        let guest = Arc::new(Guest {
+            engine: self.engine.clone(),
+            module,
+            limits,
+        });
+        let manifest = guest.call(Export::Manifest, &[]).map_err(&error)?;
+        let manifest: PluginManifest = serde_json::from_slice(&manifest)
+            .map_err(|e| error(format!("invalid manifest: {}", e)))?;
+        let exports = guest.instantiate().map_err(&error)?;
+        if !manifest.rules.is_empty() && exports.rule_apply.is_none() {
+            return Err(error(
+                "declares rules but does not export vg_rule_apply".into(),
+            ));
+        }
+        if !manifest.samplers.is_empty() && exports.sample.is_none() {
+            return Err(error(
+                "declares samplers but does not export vg_sample".into(),
+            ));
+        }
+
+        Ok(Plugin {
+            name: name.to_string(),
+            path: None,
+            manifest,
+            guest,
+        })
+    }
+}
+
+/// A loaded plugin.
+pub struct Plugin {
+    name: String,
+    path: Option<PathBuf>,
+    manifest: PluginManifest,
+    guest: Arc<Guest>,
+}
+
+impl Plugin {
+    /// Name of the plugin, prefixing its rule and sampler IDs.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// File the plugin was loaded from.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// What the plugin provides.
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    /// The plugin's rules, ready to register on an automaton.
+    pub fn rules(&self) -> Vec<Arc<dyn Rule>> {
+        self.manifest
+            .rules
+            .iter()
+            .map(|rule| {
+                Arc::new(PluginRule {
+                    id: RuleId::new(format!("{}::{}", self.name, rule.id)),
+                    manifest: rule.clone(),
+                    guest: self.guest.clone(),
+                }) as Arc<dyn Rule>
+            })
+            .collect()
+    }
+
+    /// The plugin's samplers.
+    pub fn samplers(&self) -> Vec<Box<dyn Sampler>> {
+        self.manifest
+            .samplers
+            .iter()
+            .map(|sampler| {
+                Box::new(PluginSampler {
+                    id: format!("{}::{}", self.name, sampler.id),
+                    manifest: sampler.clone(),
+                    guest: self.guest.clone(),
+                }) as Box<dyn Sampler>
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Plugin")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("manifest", &self.manifest)
+            .finish()
+    }
+}
+
+/// A rule implemented by a plugin.
+pub struct PluginRule {
+    id: RuleId,
+    manifest: RuleManifest,
+    guest: Arc<Guest>,
+}
+
+#[derive(Serialize)]
+struct RuleInput<'a> {
+    rule: &'a str,
+    node_id: NodeId,
+    tick: u64,
+    state: &'a StateData,
+    neighbors: Vec<RuleNeighbor<'a>>,
+    global: &'a HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct RuleNeighbor<'a> {
+    node_id: NodeId,
+    relationship: &'a str,
+    state: &'a StateData,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum RuleOutput {
+    Transition { state: StateData },
+    Skip,
+    Delegate { rule: String },
+}
+
+impl Rule for PluginRule {
+    fn id(&self) -> RuleId {
+        self.id.clone()
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn priority(&self) -> i32 {
+        self.manifest.priority
+    }
+
+    fn locality(&self) -> Locality {
+        self.manifest.locality
+    }
+
+    fn apply(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome> {
+        let input = RuleInput {
+            rule: &self.manifest.id,
+            node_id: ctx.node_id,
+            tick: ctx.tick,
+            state: ctx.current_state(),
+            neighbors: ctx
+                .neighbors
+                .iter()
+                .map(|n| RuleNeighbor {
+                    node_id: n.node_id,
+                    relationship: &n.relationship,
+                    state: n.state.current_state(),
+                })
+                .collect(),
+            global: ctx.global,
+        };
+        let failed = |message: String| AutomatonError::RuleExecutionFailed {
+            rule_id: self.id.clone(),
+            node_id: ctx.node_id,
+            message,
+        };
+        let output = self
+            .guest
+            .call(Export::RuleApply, &serde_json::to_vec(&input)?)
+            .map_err(failed)?;
+        let output: RuleOutput = serde_json::from_slice(&output)
+            .map_err(|e| failed(format!("invalid outcome: {}", e)))?;
+        Ok(match output {
+            RuleOutput::Transition { state } => RuleOutcome::Transition(state),
+            RuleOutput::Skip => RuleOutcome::Skip,
+            RuleOutput::Delegate { rule } => RuleOutcome::Delegate(RuleId::new(rule)),
+        })
+    }
+}
+
+/// A sampler implemented by a plugin.
+pub struct PluginSampler {
+    id: String,
+    manifest: SamplerManifest,
+    guest: Arc<Guest>,
+}
+
+#[derive(Serialize)]
+struct SampleInput<'a> {
+    sampler: &'a str,
+    node: &'a GraphNode,
+    neighbors: Vec<SampleNeighbor<'a>>,
+    content: Option<&'a str>,
+    annotations: &'a HashMap<String, Value>,
+    graph_metadata: &'a HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct SampleNeighbor<'a> {
+    node: &'a GraphNode,
+    edge: &'a GraphEdge,
+}
+
+impl PluginSampler {
+    /// What the sampler computes, as declared by the plugin.
+    pub fn description(&self) -> &str {
+        &self.manifest.description
+    }
+}
+
+impl Sampler for PluginSampler {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn selector(&self) -> NodeSelector {
+        match self.manifest.kind {
+            Some(kind) => NodeSelector::ByKind(kind),
+            None => NodeSelector::All,
+        }
+    }
+
+    fn compute(&self, ctx: &SampleContext<'_>) -> Result<Option<Value>, SamplerError> {
+        let input = SampleInput {
+            sampler: &self.manifest.id,
+            node: ctx.node,
+            neighbors: ctx
+                .neighbors
+                .iter()
+                .map(|n| SampleNeighbor {
+                    node: n.node,
+                    edge: n.edge,
+                })
+                .collect(),
+            content: ctx.content,
+            annotations: ctx.annotations,
+            graph_metadata: ctx.graph_metadata,
+        };
+        let failed = |message: String| SamplerError::new(&self.id, message);
+        let input = serde_json::to_vec(&input).map_err(|e| failed(e.to_string()))?;
+        let output = self.guest.call(Export::Sample, &input).map_err(failed)?;
+        let value: Value = serde_json::from_slice(&output)
+            .map_err(|e| failed(format!("invalid artifact: {}", e)))?;
+        Ok((!value.is_null()).then_some(value))
+    }
+}
+
+// =============================================================================
+// Guest instances
+// =============================================================================
+
+/// Guest functions taking and returning a JSON buffer.
+#[derive(Debug, Clone, Copy)]
+enum Export {
+    Manifest,
+    RuleApply,
+    Sample,
+}
+
+impl Export {
+    fn name(self) -> &'static str {
+        match self {
+            Export::Manifest => "vg_manifest",
+            Export::RuleApply => "vg_rule_apply",
+            Export::Sample => "vg_sample",
+        }
+    }
+}
+
+/// A compiled module.
+///
+/// Each call instantiates the module afresh, so parallel ticks never share
+/// an instance, a trap cannot leave state behind, and a guest cannot carry
+/// state from one node to the next.
+struct Guest {
+    engine: Engine,
+    module: Module,
+    limits: PluginLimits,
+}
+
+struct GuestInstance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    manifest: TypedFunc<(), i64>,
+    rule_apply: Option<TypedFunc<(i32, i32), i64>>,
+    sample: Option<TypedFunc<(i32, i32), i64>>,
+}
+
+impl Guest {
+    fn call(&self, export: Export, input: &[u8]) -> Result<Vec<u8>, String> {
+        self.instantiate()?.call(export, input, self.limits.fuel)
+    }
+
+    fn instantiate(&self) -> Result<GuestInstance, String> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.limits.memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.limits.fuel).map_err(message)?;
+
+        let instance = Instance::new(&mut store, &self.module, &[]).map_err(message)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("missing export memory")?;
+        let version = instance
+            .get_typed_func::<(), i32>(&mut store, "vg_abi_version")
+            .and_then(|f| f.call(&mut store, ()))
+            .map_err(message)?;
+        if version != ABI_VERSION {
+            return Err(format!(
+                "ABI version {} is not supported (expected {})",
+                version, ABI_VERSION
+            ));
+        }
+
+        Ok(GuestInstance {
+            alloc: instance
+                .get_typed_func(&mut store, "vg_alloc")
+                .map_err(message)?,
+            manifest: instance
+                .get_typed_func(&mut store, Export::Manifest.name())
+                .map_err(message)?,
+            rule_apply: instance
+                .get_typed_func(&mut store, Export::RuleApply.name())
+                .ok(),
+            sample: instance
+                .get_typed_func(&mut store, Export::Sample.name())
+                .ok(),
+            memory,
+            store,
+        })
+    }
+}
+
+impl GuestInstance {
+    fn call(&mut self, export: Export, input: &[u8], fuel: u64) -> Result<Vec<u8>, String> {
+        self.store.set_fuel(fuel).map_err(message)?;
+        let packed = match export {
+            Export::Manifest => self.manifest.call(&mut self.store, ()),
+            Export::RuleApply | Export::Sample => {
+                let func = match export {
+                    Export::RuleApply => self.rule_apply.as_ref(),
+                    _ => self.sample.as_ref(),
+                }
+                .ok_or_else(|| format!("missing export {}", export.name()))?;
+                let len = i32::try_from(input.len()).map_err(|_| "input too large")?;
+                let ptr = self.alloc.call(&mut self.store, len).map_err(message)?;
+                self.memory
+                    .write(&mut self.store, ptr as u32 as usize, input)
+                    .map_err(|_| "vg_alloc returned a buffer out of bounds")?;
+                func.call(&mut self.store, (ptr, len))
+            }
+        }
+        .map_err(message)?;
+
+        // Check the buffer before allocating: the memory is capped by
+        // `PluginLimits::memory_bytes`, the returned length is not
+        let (ptr, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+        let end = ptr
+            .checked_add(len)
+            .filter(|&end| end <= self.memory.data_size(&self.store));
+        let Some(end) = end else {
+            return Err(format!("{} returned a buffer out of bounds", export.name()));
+        };
+        Ok(self.memory.data(&self.store)[ptr..end].to_vec())
+    }
+}
+
+/// Message of a wasmtime error, without the wasm backtrace of traps.
+fn message(error: wasmtime::Error) -> String {
+    match error.downcast_ref::<Trap>() {
+        Some(trap) => trap.to_string(),
+        None => format!("{:#}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::GraphAutomaton;
+    use crate::temporal::{SourceCodeTemporalGraph, TemporalGraph};
+    use vibe_graph_core::{EdgeId, SourceCodeGraph};
+
+    /// A guest answering every call with a constant JSON document.
+    fn guest(manifest: &str, outcome: &str, artifact: &str) -> String {
+        let data = |offset: usize, json: &str| {
+            format!(
+                "(data (i32.const {}) \"{}\")",
+                offset,
+                json.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        };
+        let packed = |offset: usize, json: &str| ((offset as i64) << 32) | json.len() as i64;
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                {}
+                {}
+                {}
+                (func (export "vg_abi_version") (result i32) (i32.const 1))
+                (func (export "vg_alloc") (param i32) (result i32) (i32.const 32768))
+                (func (export "vg_manifest") (result i64) (i64.const {}))
+                (func (export "vg_rule_apply") (param i32 i32) (result i64) (i64.const {}))
+                (func (export "vg_sample") (param i32 i32) (result i64) (i64.const {})))"#,
+            data(0, manifest),
+            data(4096, outcome),
+            data(8192, artifact),
+            packed(0, manifest),
+            packed(4096, outcome),
+            packed(8192, artifact),
+        )
+    }
+
+    const MANIFEST: &str = r#"{"rules": [{"id": "flag", "priority": 5, "locality": "neighborhood"}], "samplers": [{"id": "size", "kind": "File"}]}"#;
+
+    fn load(source: &str) -> AutomatonResult<Plugin> {
+        PluginHost::new()?.load_bytes("org", source.as_bytes(), PluginLimits::default())
+    }
+
+    fn graph() -> SourceCodeGraph {
+        let node = |id: u64, kind| GraphNode {
+            id: NodeId(id),
+            name: format!("n{}", id),
+            kind,
+            metadata: HashMap::new(),
+        };
+        SourceCodeGraph {
+            nodes: vec![
+                node(1, GraphNodeKind::File),
+                node(2, GraphNodeKind::Directory),
+            ],
+            edges: vec![GraphEdge {
+                id: EdgeId(1),
+                from: NodeId(2),
+                to: NodeId(1),
+                relationship: "contains".into(),
+                metadata: HashMap::new(),
+            }],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_plugin_rules_and_samplers() {
+        let plugin = load(&guest(
+            MANIFEST,
+            r#"{"outcome": "transition", "state": {"payload": null, "activation": 0.5, "annotations": {"checked": "yes"}}}"#,
+            r#"{"lines": 1}"#,
+        ))
+        .unwrap();
+
+        let rules = plugin.rules();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id(), RuleId::new("org::flag"));
+        assert_eq!(rules[0].priority(), 5);
+        assert_eq!(rules[0].locality(), Locality::Neighborhood);
+
+        let mut automaton =
+            GraphAutomaton::new(SourceCodeTemporalGraph::from_source_graph(graph()));
+        automaton.register_rule(rules[0].clone());
+        automaton.tick().unwrap();
+        let state = automaton
+            .graph()
+            .get_node(&NodeId(1))
+            .unwrap()
+            .current_state()
+            .clone();
+        assert_eq!(state.activation, 0.5);
+        assert_eq!(state.annotations["checked"], "yes");
+
+        let samplers = plugin.samplers();
+        let result = samplers[0].sample(&graph(), &HashMap::new()).unwrap();
+        assert_eq!(result.sampler_id, "org::size");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.get(NodeId(1)).unwrap().value["lines"], 1);
+    }
+
+    #[test]
+    fn test_plugin_sandbox() {
+        let imports = r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#;
+        let error = load(imports).unwrap_err().to_string();
+        assert!(
+            error.contains("imports wasi_snapshot_preview1::fd_write"),
+            "{}",
+            error
+        );
+
+        // A rule that never returns runs out of fuel
+        let spinning = guest(MANIFEST, "", "null").replace(
+            "(func (export \"vg_rule_apply\") (param i32 i32) (result i64)",
+            "(func (export \"vg_rule_apply\") (param i32 i32) (result i64) (loop (br 0))",
+        );
+        let plugin = load(&spinning).unwrap();
+        let automaton = GraphAutomaton::new(SourceCodeTemporalGraph::from_source_graph(graph()));
+        let ctx = automaton.build_rule_context(NodeId(1)).unwrap();
+        let error = plugin.rules()[0].apply(&ctx).unwrap_err().to_string();
+        assert!(error.contains("fuel"), "{}", error);
+        // The plugin still answers after a trap
+        assert!(plugin.samplers()[0]
+            .sample(&graph(), &HashMap::new())
+            .unwrap()
+            .is_empty());
+
+        // A length past the end of memory is rejected before any allocation
+        let oversized = guest(MANIFEST, "", "null").replace(
+            &format!("(i64.const {})", (8192i64 << 32) | 4),
+            &format!("(i64.const {})", (8192i64 << 32) | 0xFFFF_FFFF),
+        );
+        let plugin = load(&oversized).unwrap();
+        let error = plugin.samplers()[0]
+            .sample(&graph(), &HashMap::new())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("vg_sample returned a buffer out of bounds"),
+            "{}",
+            error
+        );
+
+        let missing = guest(MANIFEST, "", "").replace("vg_sample", "other");
+        let error = load(&missing).unwrap_err().to_string();
+        assert!(error.contains("does not export vg_sample"), "{}", error);
+    }
+
+    #[test]
+    fn test_plugin_calls_do_not_share_state() {
+        // Answers only its first call, counted in a global
+        let artifact = r#"{"lines": 1}"#;
+        let packed = (8192i64 << 32) | artifact.len() as i64;
+        let counting = guest(MANIFEST, "", artifact).replace(
+            &format!(
+                "(func (export \"vg_sample\") (param i32 i32) (result i64) (i64.const {}))",
+                packed
+            ),
+            &format!(
+                r#"(global $calls (mut i32) (i32.const 0))
+                (func (export "vg_sample") (param i32 i32) (result i64)
+                    (global.set $calls (i32.add (global.get $calls) (i32.const 1)))
+                    (if (result i64) (i32.eq (global.get $calls) (i32.const 1))
+                        (then (i64.const {}))
+                        (else (i64.const -1))))"#,
+                packed
+            ),
+        );
+        let plugin = load(&counting).unwrap();
+        let sampler = &plugin.samplers()[0];
+        for _ in 0..3 {
+            let result = sampler.sample(&graph(), &HashMap::new()).unwrap();
+            assert_eq!(result.get(NodeId(1)).unwrap().value["lines"], 1);
+        }
+    }
+
+    #[test]
+    fn test_plugin_limits_reject_invalid_config() {
+        let config = |fuel: Option<u64>, memory_mb: Option<usize>| PluginConfig {
+            name: "org".to_string(),
+            path: None,
+            enabled: true,
+            fuel,
+            memory_mb,
+        };
+        let limits = PluginLimits::try_from(&config(None, Some(16))).unwrap();
+        assert_eq!(limits.memory_bytes, 16 << 20);
+        assert_eq!(limits.fuel, PluginLimits::default().fuel);
+
+        for invalid in [
+            config(Some(0), None),
+            config(None, Some(0)),
+            config(None, Some(usize::MAX)),
+        ] {
+            let error = PluginLimits::try_from(&invalid).unwrap_err().to_string();
+            assert!(error.contains("org"), "{}", error);
+        }
+    }
+}
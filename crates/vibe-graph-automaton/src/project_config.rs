@@ -8,16 +8,20 @@
 //! 3. Auto-inferred from project markers (Cargo.toml, package.json, etc.)
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
-use crate::config::StabilityObjective;
+use crate::config::{RuleConfig, StabilityObjective};
+use crate::persistence::SELF_DIR;
 
 /// Name of the project config file.
 pub const CONFIG_FILENAME: &str = "vg.toml";
 
+/// Directory under `.self/` holding plugin modules (`*.wasm`).
+pub const PLUGINS_DIR: &str = "plugins";
+
 // =============================================================================
 // Top-level config
 // =============================================================================
@@ -53,6 +57,16 @@ pub struct ProjectConfig {
     #[serde(default)]
     pub automaton: AutomatonSection,
 
+    /// Expression rules (`[[rules]]`), added to the description's rules by
+    /// `vg run`.
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+
+    /// WebAssembly plugins (`[[plugins]]`), on top of those found in
+    /// `.self/plugins/`.
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+
     /// Workspace config (only in root vg.toml for multi-repo).
     #[serde(default)]
     pub workspace: Option<WorkspaceSection>,
@@ -168,6 +182,11 @@ pub struct AutomatonSection {
     /// Poll interval in seconds for change detection.
     #[serde(default = "AutomatonSection::default_interval")]
     pub interval: u64,
+
+    /// Only re-evaluate the surroundings of changed files (see
+    /// [`AutomatonConfig::frontier`](crate::AutomatonConfig)).
+    #[serde(default)]
+    pub frontier: bool,
 }
 
 impl AutomatonSection {
@@ -184,10 +203,50 @@ impl Default for AutomatonSection {
         Self {
             max_ticks: Self::default_max_ticks(),
             interval: Self::default_interval(),
+            frontier: false,
         }
     }
 }
 
+/// `[[plugins]]` entry — a WebAssembly module providing rules and samplers.
+///
+/// Plugins are only loaded by builds with the `plugins` feature. An entry
+/// named like a module in `.self/plugins/` overrides its settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    /// Plugin name, prefixing the IDs of its rules and samplers.
+    pub name: String,
+
+    /// Path to the `.wasm` module, relative to the project root.
+    /// Defaults to `.self/plugins/<name>.wasm`.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+
+    /// Set to `false` to skip the plugin.
+    #[serde(default = "PluginConfig::default_enabled")]
+    pub enabled: bool,
+
+    /// Fuel granted to each call into the plugin (roughly one unit per
+    /// WebAssembly instruction).
+    #[serde(default)]
+    pub fuel: Option<u64>,
+
+    /// Maximum linear memory of the plugin, in MiB.
+    #[serde(default)]
+    pub memory_mb: Option<usize>,
+}
+
+impl PluginConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+
+    /// Directory scanned for plugin modules: `.self/plugins/`.
+    pub fn discovery_dir(root: &Path) -> PathBuf {
+        root.join(SELF_DIR).join(PLUGINS_DIR)
+    }
+}
+
 /// `[workspace]` section — multi-repo defaults.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WorkspaceSection {
@@ -398,6 +457,7 @@ interval = 5
         assert_eq!(config.stability["entry_point"], 0.95);
         assert_eq!(config.ignore.directories, vec!["node_modules", "target"]);
         assert_eq!(config.automaton.max_ticks, 30);
+        assert!(!config.automaton.frontier);
         assert!(config.workspace.is_none());
     }
 
@@ -438,6 +498,53 @@ test = "pytest"
         assert!(config.stability.is_empty());
     }
 
+    #[test]
+    fn test_parse_expression_rules() {
+        let toml_str = r#"
+[[rules]]
+name = "hub_decay"
+type = "expression"
+expression = "activation = self.activation * damping when self.role == 'hub'"
+params = { damping = 0.8, priority = 20 }
+"#;
+        let config: ProjectConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.rules.len(), 1);
+
+        let mut description = crate::AutomatonDescription::new("test");
+        description.add_rule(RuleConfig::expression("hub_decay", "activation = 0"));
+        description.merge_rules(config.rules);
+        let rules = description.expression_rules().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules[0].source(),
+            "activation = self.activation * damping when self.role == 'hub'"
+        );
+    }
+
+    #[test]
+    fn test_parse_plugins() {
+        let toml_str = r#"
+[[plugins]]
+name = "org-quality"
+path = "../shared/org-quality.wasm"
+fuel = 5000000
+
+[[plugins]]
+name = "legacy"
+enabled = false
+"#;
+        let config: ProjectConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.plugins.len(), 2);
+        assert_eq!(
+            config.plugins[0].path.as_deref(),
+            Some(Path::new("../shared/org-quality.wasm"))
+        );
+        assert_eq!(config.plugins[0].fuel, Some(5_000_000));
+        assert!(config.plugins[0].enabled);
+        assert!(!config.plugins[1].enabled);
+        assert_eq!(config.plugins[1].memory_mb, None);
+    }
+
     #[test]
     fn test_stability_objective_defaults() {
         let config = ProjectConfig::default();
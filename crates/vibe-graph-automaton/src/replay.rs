@@ -0,0 +1,429 @@
+//! Time travel over automaton runs: replay and diff.
+//!
+//! Temporal nodes only keep a bounded window of transitions, so a run
+//! cannot be rebuilt from its final state. [`GraphAutomaton::start_recording`]
+//! keeps the graph as it was and every transition applied by the following
+//! ticks in a [`RunRecording`], from which [`Replay`] reconstructs the
+//! [`SourceCodeTemporalGraph`] at any tick.
+//!
+//! [`SourceCodeTemporalGraph::diff`] compares two states — two ticks of a
+//! replay, or two snapshots — node by node.
+//!
+//! [`GraphAutomaton::start_recording`]: crate::GraphAutomaton::start_recording
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use vibe_graph_core::NodeId;
+
+use crate::error::{AutomatonError, AutomatonResult};
+use crate::rule::RuleId;
+use crate::state::StateData;
+use crate::temporal::{SourceCodeTemporalGraph, TemporalGraph};
+
+/// A transition applied by a tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedTransition {
+    pub node_id: NodeId,
+    pub rule_id: RuleId,
+    pub state: StateData,
+}
+
+/// The transitions applied by one tick, in application order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// Tick number, as in [`TickResult::tick`](crate::TickResult::tick).
+    pub tick: u64,
+    pub transitions: Vec<RecordedTransition>,
+}
+
+/// A recorded run: the graph before its first tick and the transitions of
+/// every tick since.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecording {
+    /// Tick number of the first recorded tick.
+    pub start_tick: u64,
+    /// The graph before `start_tick`.
+    pub initial: SourceCodeTemporalGraph,
+    /// Recorded ticks, in order.
+    pub ticks: Vec<TickRecord>,
+}
+
+impl RunRecording {
+    /// Start a recording from the graph as it is before tick `start_tick`.
+    pub fn new(initial: SourceCodeTemporalGraph, start_tick: u64) -> Self {
+        Self {
+            start_tick,
+            initial,
+            ticks: Vec::new(),
+        }
+    }
+
+    /// Tick number after the last recorded tick.
+    pub fn end_tick(&self) -> u64 {
+        self.start_tick + self.ticks.len() as u64
+    }
+
+    /// The transitions of a tick, if it was recorded.
+    pub fn tick(&self, tick: u64) -> Option<&TickRecord> {
+        let index = tick.checked_sub(self.start_tick)?;
+        self.ticks.get(index as usize)
+    }
+
+    /// A replay positioned before the first recorded tick.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {
+            recording: self,
+            graph: self.initial.clone(),
+            tick: self.start_tick,
+        }
+    }
+
+    /// The graph before `tick` ran, i.e. after the ticks preceding it;
+    /// [`end_tick`](Self::end_tick) gives the final state.
+    pub fn state_at(&self, tick: u64) -> AutomatonResult<SourceCodeTemporalGraph> {
+        let mut replay = self.replay();
+        replay.seek(tick)?;
+        Ok(replay.graph)
+    }
+}
+
+/// A cursor stepping through a [`RunRecording`].
+///
+/// Replayed transitions get fresh timestamps; states, rules and history
+/// windows match the recorded run.
+pub struct Replay<'a> {
+    recording: &'a RunRecording,
+    graph: SourceCodeTemporalGraph,
+    tick: u64,
+}
+
+impl<'a> Replay<'a> {
+    /// Tick the next [`step`](Self::step) applies.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// The graph before [`tick`](Self::tick).
+    pub fn graph(&self) -> &SourceCodeTemporalGraph {
+        &self.graph
+    }
+
+    /// Apply the next recorded tick, returning its transitions, or `None`
+    /// at the end of the recording.
+    pub fn step(&mut self) -> AutomatonResult<Option<&'a TickRecord>> {
+        let Some(record) = self.recording.tick(self.tick) else {
+            return Ok(None);
+        };
+        for transition in &record.transitions {
+            self.graph.apply_transition(
+                &transition.node_id,
+                transition.rule_id.clone(),
+                transition.state.clone(),
+            )?;
+        }
+        self.tick += 1;
+        Ok(Some(record))
+    }
+
+    /// Move to the state before `tick`, rewinding to the start if needed.
+    pub fn seek(&mut self, tick: u64) -> AutomatonResult<()> {
+        if tick < self.recording.start_tick || tick > self.recording.end_tick() {
+            return Err(AutomatonError::InconsistentState {
+                message: format!(
+                    "tick {} is outside the recording (ticks {}..={})",
+                    tick,
+                    self.recording.start_tick,
+                    self.recording.end_tick()
+                ),
+            });
+        }
+        if tick < self.tick {
+            *self = self.recording.replay();
+        }
+        while self.tick < tick {
+            self.step()?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Diff
+// =============================================================================
+
+/// How a node's state differs between two graphs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDelta {
+    pub node_id: NodeId,
+    pub name: String,
+    /// Workspace-relative path, when the node has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub activation_before: f32,
+    pub activation_after: f32,
+    /// Rule of the current transition, before and after.
+    pub rule_before: RuleId,
+    pub rule_after: RuleId,
+    /// Annotations that were added, removed or changed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationDelta>,
+}
+
+impl NodeDelta {
+    /// Change in activation.
+    pub fn activation_delta(&self) -> f32 {
+        self.activation_after - self.activation_before
+    }
+
+    /// Whether the current rule differs.
+    pub fn rule_changed(&self) -> bool {
+        self.rule_before != self.rule_after
+    }
+}
+
+/// An annotation change: `before` is `None` when it was added, `after` when
+/// it was removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnotationDelta {
+    pub key: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Node-by-node comparison of two graph states.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    /// Nodes present in both states whose activation, rule or annotations
+    /// differ, largest activation change first.
+    pub changed: Vec<NodeDelta>,
+    /// Nodes only in the second state.
+    pub added: Vec<NodeId>,
+    /// Nodes only in the first state.
+    pub removed: Vec<NodeId>,
+    /// Nodes present in both states.
+    pub compared: usize,
+}
+
+impl GraphDiff {
+    /// Whether both states are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// The delta of a node, if it changed.
+    pub fn get(&self, node_id: NodeId) -> Option<&NodeDelta> {
+        self.changed.iter().find(|d| d.node_id == node_id)
+    }
+}
+
+impl SourceCodeTemporalGraph {
+    /// Compare the current state of every node with `other`'s. Activation
+    /// changes below `epsilon` are ignored.
+    pub fn diff(&self, other: &SourceCodeTemporalGraph, epsilon: f32) -> GraphDiff {
+        let before: HashMap<NodeId, _> = self.nodes().map(|n| (n.id(), n)).collect();
+        let after: HashMap<NodeId, _> = other.nodes().map(|n| (n.id(), n)).collect();
+
+        let mut diff = GraphDiff::default();
+        for (id, old) in &before {
+            let Some(new) = after.get(id) else {
+                diff.removed.push(*id);
+                continue;
+            };
+            diff.compared += 1;
+            let (old_state, new_state) = (old.current_state(), new.current_state());
+            let keys: BTreeSet<&String> = old_state
+                .annotations
+                .keys()
+                .chain(new_state.annotations.keys())
+                .collect();
+            let annotations: Vec<AnnotationDelta> = keys
+                .into_iter()
+                .filter_map(|key| {
+                    let before = old_state.annotations.get(key);
+                    let after = new_state.annotations.get(key);
+                    (before != after).then(|| AnnotationDelta {
+                        key: key.clone(),
+                        before: before.cloned(),
+                        after: after.cloned(),
+                    })
+                })
+                .collect();
+            let delta = NodeDelta {
+                node_id: *id,
+                name: new.name().to_string(),
+                path: new.metadata("relative_path").map(str::to_string),
+                activation_before: old_state.activation,
+                activation_after: new_state.activation,
+                rule_before: old.current_rule().clone(),
+                rule_after: new.current_rule().clone(),
+                annotations,
+            };
+            if delta.activation_delta().abs() >= epsilon
+                || delta.rule_changed()
+                || !delta.annotations.is_empty()
+            {
+                diff.changed.push(delta);
+            }
+        }
+        diff.added = after
+            .keys()
+            .filter(|id| !before.contains_key(id))
+            .copied()
+            .collect();
+
+        diff.changed.sort_by(|a, b| {
+            b.activation_delta()
+                .abs()
+                .total_cmp(&a.activation_delta().abs())
+                .then(a.node_id.0.cmp(&b.node_id.0))
+        });
+        diff.added.sort_by_key(|id| id.0);
+        diff.removed.sort_by_key(|id| id.0);
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{AutomatonConfig, GraphAutomaton};
+    use crate::source_code::ImportPropagationRule;
+    use serde_json::json;
+    use std::sync::Arc;
+    use vibe_graph_core::{EdgeId, GraphEdge, GraphNode, GraphNodeKind, SourceCodeGraph};
+
+    /// A chain 0 → 1 → … → 5 of imports, activated at its head.
+    fn automaton() -> GraphAutomaton {
+        let graph = SourceCodeGraph {
+            nodes: (0..6)
+                .map(|i| GraphNode {
+                    id: NodeId(i),
+                    name: format!("f{}.rs", i),
+                    kind: GraphNodeKind::File,
+                    metadata: HashMap::new(),
+                })
+                .collect(),
+            edges: (0..5)
+                .map(|i| GraphEdge {
+                    id: EdgeId(i),
+                    from: NodeId(i + 1),
+                    to: NodeId(i),
+                    relationship: "uses".into(),
+                    metadata: HashMap::new(),
+                })
+                .collect(),
+            metadata: HashMap::new(),
+        };
+        let mut temporal = SourceCodeTemporalGraph::from_source_graph_with_config(graph, 2);
+        temporal
+            .set_initial_state(&NodeId(0), StateData::with_activation(json!(null), 1.0))
+            .unwrap();
+        let config = AutomatonConfig {
+            history_window: 2,
+            ..Default::default()
+        };
+        GraphAutomaton::with_config(temporal, config)
+            .with_rule(Arc::new(ImportPropagationRule::default()))
+    }
+
+    fn activations(graph: &SourceCodeTemporalGraph) -> Vec<(u64, f32)> {
+        let mut activations: Vec<_> = graph
+            .nodes()
+            .map(|n| (n.id().0, n.current_state().activation))
+            .collect();
+        activations.sort_by_key(|(id, _)| *id);
+        activations
+    }
+
+    #[test]
+    fn test_replay_reconstructs_every_tick() {
+        let mut recorded = automaton();
+        recorded.start_recording();
+        recorded.run_ticks(6).unwrap();
+        let recording = recorded.take_recording().unwrap();
+        assert_eq!((recording.start_tick, recording.end_tick()), (0, 6));
+
+        // Replaying past the history window matches a fresh run of the
+        // same length, history included
+        let mut reference = automaton();
+        let mut replay = recording.replay();
+        for tick in 0..=6 {
+            assert_eq!(replay.tick(), tick);
+            assert_eq!(activations(replay.graph()), activations(reference.graph()));
+            let node = replay.graph().get_node(&NodeId(1)).unwrap();
+            let expected = reference.graph().get_node(&NodeId(1)).unwrap();
+            assert_eq!(
+                node.evolution.transition_count(),
+                expected.evolution.transition_count()
+            );
+            assert_eq!(
+                node.evolution.history().len(),
+                expected.evolution.history().len()
+            );
+            replay.step().unwrap();
+            reference.tick().unwrap();
+        }
+        assert!(replay.step().unwrap().is_none());
+
+        let end = recording.state_at(6).unwrap();
+        assert_eq!(activations(&end), activations(recorded.graph()));
+        let early = recording.state_at(2).unwrap();
+        assert_eq!(activations(&early), {
+            let mut partial = automaton();
+            partial.run_ticks(2).unwrap();
+            activations(partial.graph())
+        });
+        assert!(recording.state_at(7).is_err());
+
+        // Recordings survive a JSON round trip
+        let json = serde_json::to_string(&recording).unwrap();
+        let restored: RunRecording = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            activations(&restored.state_at(6).unwrap()),
+            activations(&end)
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_activation_rule_and_annotations() {
+        let recorded = {
+            let mut automaton = automaton();
+            automaton.start_recording();
+            automaton.run_ticks(3).unwrap();
+            automaton.take_recording().unwrap()
+        };
+        let before = recorded.state_at(0).unwrap();
+        let mut after = recorded.state_at(3).unwrap();
+        after
+            .apply_transition(
+                &NodeId(5),
+                RuleId::new("manual"),
+                StateData::default().annotate("owner", "core"),
+            )
+            .unwrap();
+
+        let diff = before.diff(&after, 0.001);
+        assert_eq!(diff.compared, 6);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        // The head decays the most, activation reached f1 and f2
+        let ids: Vec<u64> = diff.changed.iter().map(|d| d.node_id.0).collect();
+        assert_eq!(ids, vec![0, 1, 2, 5]);
+        assert!(diff.changed[0].activation_delta() < 0.0);
+        let reached = diff.get(NodeId(1)).unwrap();
+        assert!(reached.activation_delta() > 0.0);
+        assert!(reached.rule_changed());
+
+        let manual = diff.get(NodeId(5)).unwrap();
+        assert_eq!(manual.rule_after, RuleId::new("manual"));
+        assert_eq!(
+            manual.annotations,
+            vec![AnnotationDelta {
+                key: "owner".into(),
+                before: None,
+                after: Some("core".into()),
+            }]
+        );
+        assert!(before.diff(&before, 0.001).is_empty());
+    }
+}
@@ -174,6 +174,24 @@ pub enum RuleOutcome {
     Delegate(RuleId),
 }
 
+/// What the outcome of a rule depends on.
+///
+/// Frontier ticks (see [`AutomatonConfig::frontier`](crate::AutomatonConfig))
+/// only re-evaluate nodes next to a change, which is sound for
+/// [`Locality::Neighborhood`] rules alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locality {
+    /// Anything, e.g. the tick number or state outside the neighborhood.
+    #[default]
+    Global,
+
+    /// The node's own state, its direct neighbors' states and relationships,
+    /// and the global context. Given the same inputs the rule returns the
+    /// same outcome.
+    Neighborhood,
+}
+
 /// A rule that can evolve node state based on context.
 ///
 /// Rules are the fundamental unit of state evolution in the automaton.
@@ -201,6 +219,12 @@ pub trait Rule: Send + Sync {
 
     /// Compute the next state based on context.
     fn apply(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome>;
+
+    /// What [`apply`](Rule::apply) and [`should_apply`](Rule::should_apply)
+    /// depend on. Defaults to [`Locality::Global`], which never skips nodes.
+    fn locality(&self) -> Locality {
+        Locality::Global
+    }
 }
 
 /// A no-op rule that leaves state unchanged.
@@ -219,6 +243,10 @@ impl Rule for NoOpRule {
     fn apply(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome> {
         Ok(RuleOutcome::Transition(ctx.current_state().clone()))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// A rule that echoes the previous state (identity).
@@ -237,6 +265,10 @@ impl Rule for IdentityRule {
     fn apply(&self, ctx: &RuleContext) -> AutomatonResult<RuleOutcome> {
         Ok(RuleOutcome::Transition(ctx.current_state().clone()))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// A composite rule that tries multiple rules in order.
@@ -295,6 +327,18 @@ impl Rule for CompositeRule {
         // No rule produced a transition
         Ok(RuleOutcome::Skip)
     }
+
+    fn locality(&self) -> Locality {
+        if self
+            .rules
+            .iter()
+            .all(|rule| rule.locality() == Locality::Neighborhood)
+        {
+            Locality::Neighborhood
+        } else {
+            Locality::Global
+        }
+    }
 }
 
 /// Registry for managing rules by ID.
@@ -342,11 +386,16 @@ impl RuleRegistry {
         self.rules.keys().collect()
     }
 
-    /// Get all rules sorted by priority.
+    /// Get all rules sorted by priority, ties broken by rule name so the
+    /// order does not depend on hash map iteration.
     pub fn rules_by_priority(&self) -> Vec<&Arc<dyn Rule>> {
-        let mut rules: Vec<_> = self.rules.values().collect();
-        rules.sort_by_key(|b| std::cmp::Reverse(b.priority()));
-        rules
+        let mut rules: Vec<_> = self.rules.iter().collect();
+        rules.sort_by(|(a_id, a), (b_id, b)| {
+            b.priority()
+                .cmp(&a.priority())
+                .then_with(|| a_id.name().cmp(b_id.name()))
+        });
+        rules.into_iter().map(|(_, rule)| rule).collect()
     }
 
     /// Apply a rule by ID to the given context.
@@ -460,6 +509,28 @@ mod tests {
         assert!(!registry.contains(&RuleId::new("unknown")));
     }
 
+    #[test]
+    fn test_rules_by_priority_breaks_ties_by_name() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Arc::new(ThresholdSkipRule { threshold: 0.3 }));
+        registry.register(Arc::new(DoubleActivationRule));
+        registry.register(Arc::new(NoOpRule));
+
+        let ids: Vec<_> = registry
+            .rules_by_priority()
+            .iter()
+            .map(|rule| rule.id())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                RuleId::new("double_activation"),
+                RuleId::new("noop"),
+                RuleId::new("threshold_skip"),
+            ]
+        );
+    }
+
     #[test]
     fn test_composite_rule() {
         let composite = CompositeRule::new("composite", "Test composite")
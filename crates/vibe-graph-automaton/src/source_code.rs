@@ -10,7 +10,7 @@ use vibe_graph_core::{NodeId, SourceCodeGraph};
 
 use crate::automaton::{AutomatonConfig, AutomatonRuntime, GraphAutomaton};
 use crate::error::AutomatonResult;
-use crate::rule::{Rule, RuleContext, RuleId, RuleOutcome};
+use crate::rule::{Locality, Rule, RuleContext, RuleId, RuleOutcome};
 use crate::state::StateData;
 use crate::temporal::{SourceCodeTemporalGraph, TemporalGraph};
 
@@ -72,7 +72,8 @@ impl SourceCodeAutomatonBuilder {
         self
     }
 
-    /// Build the automaton.
+    /// Build the automaton. In frontier mode, the first tick evaluates the
+    /// activated nodes and their neighbors only.
     pub fn build(self) -> AutomatonResult<GraphAutomaton> {
         let temporal = SourceCodeTemporalGraph::from_source_graph_with_config(
             self.graph,
@@ -87,12 +88,15 @@ impl SourceCodeAutomatonBuilder {
         }
 
         // Set initial activations
-        for (node_id, activation) in self.initial_activations {
+        for &(node_id, activation) in &self.initial_activations {
             automaton.graph_mut().set_initial_state(
                 &node_id,
                 StateData::with_activation(Value::Null, activation),
             )?;
         }
+        if !self.initial_activations.is_empty() {
+            automaton.set_frontier(self.initial_activations.iter().map(|(id, _)| *id));
+        }
 
         Ok(automaton)
     }
@@ -179,6 +183,10 @@ impl Rule for ImportPropagationRule {
 
         Ok(RuleOutcome::Transition(new_state))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// Rule that activates module/index files based on their children.
@@ -257,6 +265,10 @@ impl Rule for ModuleActivationRule {
 
         Ok(RuleOutcome::Transition(new_state))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// Rule that increases activation for nodes near recent changes.
@@ -341,6 +353,10 @@ impl Rule for ChangeProximityRule {
 
         Ok(RuleOutcome::Transition(new_state))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// Rule that tracks code complexity signals.
@@ -387,6 +403,10 @@ impl Rule for ComplexityTrackingRule {
 
         Ok(RuleOutcome::Transition(new_state))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 // =============================================================================
@@ -686,6 +706,10 @@ impl Rule for DampedPropagationRule {
 
         Ok(RuleOutcome::Transition(new_state))
     }
+
+    fn locality(&self) -> Locality {
+        Locality::Neighborhood
+    }
 }
 
 /// Create a fully-configured automaton from a description and source graph.
@@ -697,21 +721,58 @@ impl Rule for DampedPropagationRule {
 /// - `ImportPropagationRule` for dependency edge propagation
 /// - `ModuleActivationRule` for directory/module aggregation
 /// - `ChangeProximityRule` for git-change boosting
+/// - the description's expression rules (see [`ExpressionRule`](crate::ExpressionRule))
 ///
-/// Optionally seeds activation from a list of changed file paths.
+/// Optionally seeds activation from a list of changed file paths. Every
+/// tick evaluates every node; see [`apply_description_with_config`] for
+/// frontier mode.
 pub fn apply_description(
     graph: SourceCodeGraph,
     description: &AutomatonDescription,
     changed_files: &[PathBuf],
 ) -> AutomatonResult<GraphAutomaton> {
-    let config = AutomatonConfig {
+    apply_description_with_rules(graph, description, changed_files, &[])
+}
+
+/// [`apply_description`] with additional rules, e.g. from plugins.
+pub fn apply_description_with_rules(
+    graph: SourceCodeGraph,
+    description: &AutomatonDescription,
+    changed_files: &[PathBuf],
+    rules: &[Arc<dyn Rule>],
+) -> AutomatonResult<GraphAutomaton> {
+    apply_description_with_config(
+        graph,
+        description,
+        changed_files,
+        rules,
+        description_config(),
+    )
+}
+
+/// Runtime settings of [`apply_description`].
+pub fn description_config() -> AutomatonConfig {
+    AutomatonConfig {
         max_ticks: 30,
         history_window: 8,
         stability_threshold: 0.005,
         min_ticks_before_stability: 5,
-        ..Default::default()
-    };
+        parallel: true,
+        frontier: false,
+    }
+}
 
+/// [`apply_description_with_rules`] with explicit runtime settings.
+///
+/// With `config.frontier`, only the surroundings of the changed files are
+/// evaluated and the baseline elsewhere is left as is.
+pub fn apply_description_with_config(
+    graph: SourceCodeGraph,
+    description: &AutomatonDescription,
+    changed_files: &[PathBuf],
+    rules: &[Arc<dyn Rule>],
+    config: AutomatonConfig,
+) -> AutomatonResult<GraphAutomaton> {
     let temporal = SourceCodeTemporalGraph::from_source_graph_with_config(
         graph.clone(),
         config.history_window,
@@ -727,6 +788,12 @@ pub fn apply_description(
     automaton.register_rule(Arc::new(ModuleActivationRule::default()));
     automaton.register_rule(Arc::new(ChangeProximityRule::default()));
     automaton.register_rule(Arc::new(ComplexityTrackingRule));
+    for rule in description.expression_rules()? {
+        automaton.register_rule(Arc::new(rule));
+    }
+    for rule in rules {
+        automaton.register_rule(rule.clone());
+    }
 
     // Build a path-to-node-id index for matching changed files
     let path_index: HashMap<String, NodeId> = graph
@@ -795,6 +862,9 @@ pub fn apply_description(
 
         let _ = automaton.graph_mut().set_initial_state(&node_id, state);
     }
+    if !changed_node_ids.is_empty() {
+        automaton.set_frontier(changed_node_ids);
+    }
 
     Ok(automaton)
 }
@@ -808,8 +878,30 @@ pub fn run_impact_analysis(
     changed_files: &[PathBuf],
     max_ticks: Option<usize>,
 ) -> AutomatonResult<ImpactReport> {
-    let mut automaton = apply_description(graph, description, changed_files)?;
+    run_impact_analysis_with_rules(graph, description, changed_files, max_ticks, &[])
+}
 
+/// [`run_impact_analysis`] with additional rules, e.g. from plugins.
+pub fn run_impact_analysis_with_rules(
+    graph: SourceCodeGraph,
+    description: &AutomatonDescription,
+    changed_files: &[PathBuf],
+    max_ticks: Option<usize>,
+    rules: &[Arc<dyn Rule>],
+) -> AutomatonResult<ImpactReport> {
+    let mut automaton = apply_description_with_rules(graph, description, changed_files, rules)?;
+    run_impact(&mut automaton, description, changed_files, max_ticks)
+}
+
+/// Run an automaton prepared by [`apply_description`] to stability (or for
+/// at most `max_ticks`) and report the impact, keeping the automaton for
+/// inspection, e.g. of its [recording](GraphAutomaton::start_recording).
+pub fn run_impact(
+    automaton: &mut GraphAutomaton,
+    description: &AutomatonDescription,
+    changed_files: &[PathBuf],
+    max_ticks: Option<usize>,
+) -> AutomatonResult<ImpactReport> {
     // Override max_ticks if specified
     if let Some(mt) = max_ticks {
         // We need to run manually since config is immutable after construction
@@ -823,7 +915,7 @@ pub fn run_impact_analysis(
         }
         let stabilized = ticks < mt as u64;
 
-        return build_report(&automaton, description, changed_files, ticks, stabilized);
+        return build_report(automaton, description, changed_files, ticks, stabilized);
     }
 
     // Run to stability
@@ -832,7 +924,7 @@ pub fn run_impact_analysis(
     let stabilized =
         automaton.is_stable() || results.last().map(|r| r.transitions == 0).unwrap_or(true);
 
-    build_report(&automaton, description, changed_files, ticks, stabilized)
+    build_report(automaton, description, changed_files, ticks, stabilized)
 }
 
 fn build_report(
@@ -1325,13 +1417,35 @@ pub fn run_evolution_plan(
     perturbation: Option<&Perturbation>,
     script_feedback: Option<&crate::script::ScriptFeedback>,
     semantic_scores: Option<&HashMap<NodeId, f32>>,
+) -> AutomatonResult<EvolutionPlan> {
+    run_evolution_plan_with_rules(
+        graph,
+        description,
+        objective,
+        perturbation,
+        script_feedback,
+        semantic_scores,
+        &[],
+    )
+}
+
+/// [`run_evolution_plan`] with additional rules, e.g. from plugins.
+pub fn run_evolution_plan_with_rules(
+    graph: SourceCodeGraph,
+    description: &AutomatonDescription,
+    objective: &StabilityObjective,
+    perturbation: Option<&Perturbation>,
+    script_feedback: Option<&crate::script::ScriptFeedback>,
+    semantic_scores: Option<&HashMap<NodeId, f32>>,
+    rules: &[Arc<dyn Rule>],
 ) -> AutomatonResult<EvolutionPlan> {
     let config = AutomatonConfig {
         max_ticks: 30,
         history_window: 8,
         stability_threshold: 0.005,
         min_ticks_before_stability: 3,
-        ..Default::default()
+        parallel: true,
+        frontier: false,
     };
 
     let temporal = SourceCodeTemporalGraph::from_source_graph_with_config(
@@ -1348,6 +1462,12 @@ pub fn run_evolution_plan(
     )));
     automaton.register_rule(Arc::new(ImportPropagationRule::default()));
     automaton.register_rule(Arc::new(ModuleActivationRule::default()));
+    for rule in description.expression_rules()? {
+        automaton.register_rule(Arc::new(rule));
+    }
+    for rule in rules {
+        automaton.register_rule(rule.clone());
+    }
 
     // Build per-node context: in-degree and test adjacency
     let mut in_degrees: HashMap<NodeId, usize> = HashMap::new();
@@ -2470,6 +2590,39 @@ mod tests {
         assert_eq!(node1.current_state().activation, 0.8);
     }
 
+    #[test]
+    fn test_apply_description_runs_full_ticks_by_default() {
+        let graph = sample_source_graph();
+        let description = crate::DescriptionGenerator::new().generate(&graph, "sample");
+        let changed = [PathBuf::from("main.rs")];
+
+        let mut automaton = apply_description(graph.clone(), &description, &changed).unwrap();
+        assert!(!automaton.config().frontier);
+        for _ in 0..3 {
+            assert_eq!(automaton.tick().unwrap().evaluated, graph.nodes.len());
+        }
+
+        // Same results as an explicitly full run
+        let activations = |report: ImpactReport| {
+            let mut ranking: Vec<_> = report
+                .impact_ranking
+                .into_iter()
+                .map(|n| (n.node_id, n.activation))
+                .collect();
+            ranking.sort_by_key(|(id, _)| *id);
+            ranking
+        };
+        let report = run_impact_analysis(graph.clone(), &description, &changed, None).unwrap();
+        let full = AutomatonConfig {
+            frontier: false,
+            ..description_config()
+        };
+        let mut explicit =
+            apply_description_with_config(graph, &description, &changed, &[], full).unwrap();
+        let expected = run_impact(&mut explicit, &description, &changed, None).unwrap();
+        assert_eq!(activations(report), activations(expected));
+    }
+
     #[test]
     fn test_import_propagation_rule() {
         let graph = sample_source_graph();
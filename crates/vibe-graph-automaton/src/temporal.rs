@@ -4,7 +4,7 @@
 //! tracking. The core type is `TemporalNode` which pairs structural graph data
 //! with `EvolutionaryState`.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use vibe_graph_core::{GraphEdge, GraphNode, NodeId, SourceCodeGraph};
@@ -103,18 +103,16 @@ pub struct Neighborhood<'a> {
 }
 
 impl<'a> Neighborhood<'a> {
-    /// Get all neighbors (both directions), without duplicates.
+    /// Get all neighbors (both directions), without duplicates: incoming
+    /// ones first, in edge order, so rules see them in a stable order.
     pub fn all_neighbors(&self) -> Vec<&'a TemporalNode> {
-        let mut seen: HashMap<NodeId, &'a TemporalNode> = HashMap::new();
-
-        for (node, _) in &self.incoming {
-            seen.insert(node.id(), node);
-        }
-        for (node, _) in &self.outgoing {
-            seen.insert(node.id(), node);
-        }
-
-        seen.into_values().collect()
+        let mut seen: HashSet<NodeId> = HashSet::new();
+        self.incoming
+            .iter()
+            .chain(&self.outgoing)
+            .map(|(node, _)| *node)
+            .filter(|node| seen.insert(node.id()))
+            .collect()
     }
 
     /// Count total unique neighbors.
@@ -165,7 +163,7 @@ pub trait TemporalGraph {
     /// Iterate over all nodes.
     fn nodes(&self) -> Box<dyn Iterator<Item = &TemporalNode> + '_>;
 
-    /// Iterate over all node IDs.
+    /// All node IDs, in ascending order.
     fn node_ids(&self) -> Vec<NodeId>;
 
     /// Get the neighborhood for a node.
@@ -240,6 +238,22 @@ impl SourceCodeTemporalGraph {
         }
     }
 
+    /// Rewrite node IDs through `mapping` (see
+    /// [`SourceCodeGraph::remap_node_ids`]), keeping every node's history.
+    pub fn remap_node_ids(&mut self, mapping: &HashMap<NodeId, NodeId>) {
+        let mut graph = std::mem::take(&mut self.source_graph);
+        graph.remap_node_ids(mapping);
+        let mut remapped = Self::from_source_graph_with_config(graph, self.history_window);
+
+        for (_, mut node) in std::mem::take(&mut self.nodes) {
+            if let Some(&id) = mapping.get(&node.node.id) {
+                node.node.id = id;
+            }
+            remapped.nodes.insert(node.node.id, node);
+        }
+        *self = remapped;
+    }
+
     /// Get the underlying source code graph.
     pub fn source_graph(&self) -> &SourceCodeGraph {
         &self.source_graph
@@ -322,7 +336,9 @@ impl TemporalGraph for SourceCodeTemporalGraph {
     }
 
     fn node_ids(&self) -> Vec<NodeId> {
-        self.nodes.keys().copied().collect()
+        let mut ids: Vec<NodeId> = self.nodes.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        ids
     }
 
     fn neighborhood(&self, id: &NodeId) -> Option<Neighborhood<'_>> {
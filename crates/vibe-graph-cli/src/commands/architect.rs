@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::PathBuf;
-use vibe_graph_core::{LayoutStrategy, SourceCodeGraph};
+use vibe_graph_core::{LayoutStrategy, ModuleClustering, SourceCodeGraph};
 use vibe_graph_ops::ArchitectFactory;
 
 pub fn execute(
@@ -41,10 +41,14 @@ pub fn execute(
         },
         "flat" => LayoutStrategy::Flat,
         "preserve" | "direct" => LayoutStrategy::Preserve,
+        "modular" => LayoutStrategy::Modular,
         _ => LayoutStrategy::Flat,
     };
 
     println!("🏗️  Applying strategy: {:?}", layout_strategy);
+    if layout_strategy == LayoutStrategy::Modular {
+        print_layout_comparison(&ModuleClustering::detect(&logical_graph));
+    }
 
     // 3. Run Architect
     let architect = ArchitectFactory::create(layout_strategy, &output_path);
@@ -117,6 +121,54 @@ pub fn execute(
     Ok(())
 }
 
+/// How the inferred modules compare with the current directories.
+fn print_layout_comparison(clustering: &ModuleClustering) {
+    const SHOWN: usize = 20;
+
+    println!(
+        "🧩 Inferred {} modules (modularity {:.3}; current directories {:.3})",
+        clustering.modules.len(),
+        clustering.modularity,
+        clustering.directory_modularity
+    );
+    for module in clustering.modules.iter().take(SHOWN) {
+        println!(
+            "   {:<24} {:>4} files  from {:<32} cohesion {:.0}%",
+            module.name,
+            module.files.len(),
+            module.directory,
+            module.cohesion * 100.0
+        );
+    }
+    if clustering.modules.len() > SHOWN {
+        println!("   ... and {} more", clustering.modules.len() - SHOWN);
+    }
+    if !clustering.unclustered.is_empty() {
+        println!(
+            "   {} files without dependencies keep their place",
+            clustering.unclustered.len()
+        );
+    }
+
+    if !clustering.misplaced.is_empty() {
+        println!();
+        println!(
+            "📦 Files whose directory disagrees with their dependencies ({}):",
+            clustering.misplaced.len()
+        );
+        for file in clustering.misplaced.iter().take(SHOWN) {
+            println!(
+                "   {} → {} (module {})",
+                file.file, file.module_directory, file.module
+            );
+        }
+        if clustering.misplaced.len() > SHOWN {
+            println!("   ... and {} more", clustering.misplaced.len() - SHOWN);
+        }
+    }
+    println!();
+}
+
 fn print_tree(graph: &SourceCodeGraph, root_path: &PathBuf) {
     // A simple tree printer
     // 1. Find all nodes that are contained by root (or effectively top level)
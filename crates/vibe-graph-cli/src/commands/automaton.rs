@@ -1,15 +1,17 @@
 //! Automaton description commands.
 //!
 //! Commands for generating, inferring, managing automaton descriptions,
-//! running impact analysis, and exporting behavioral contracts.
+//! running impact analysis, comparing recorded runs, and exporting
+//! behavioral contracts.
 
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
 use vibe_graph_automaton::{
-    format_behavioral_contracts, format_evolution_plan, format_impact_report, run_evolution_plan,
-    run_impact_analysis, AutomatonStore, DescriptionGenerator, GeneratorConfig,
+    apply_description_with_config, description_config, format_behavioral_contracts,
+    format_evolution_plan, format_impact_report, run_evolution_plan, run_impact, AutomatonConfig,
+    AutomatonStore, DescriptionGenerator, GeneratorConfig, GraphDiff, SourceCodeTemporalGraph,
 };
 use vibe_graph_ops::{GraphRequest, OpsContext, Store};
 
@@ -40,7 +42,24 @@ pub async fn execute(ctx: &OpsContext, cmd: AutomatonCommands) -> Result<()> {
             json,
             output,
             top,
-        } => run(ctx, &path, from_git, files, max_ticks, json, output, top).await,
+            record,
+            label,
+            frontier,
+        } => {
+            let record = record.then_some(label);
+            run(
+                ctx, &path, from_git, files, max_ticks, json, output, top, record, frontier,
+            )
+            .await
+        }
+
+        AutomatonCommands::Diff {
+            from,
+            to,
+            path,
+            json,
+            top,
+        } => diff(&path, &from, &to, json, top),
 
         AutomatonCommands::Describe {
             path,
@@ -70,7 +89,7 @@ async fn generate(
     // Ensure graph exists
     let ops_store = Store::new(&path);
     let graph = if ops_store.has_graph() {
-        println!("📊 Loading graph from .self/graph.json");
+        println!("📊 Loading graph from .self");
         ops_store
             .load_graph()
             .context("Failed to load graph")?
@@ -86,6 +105,7 @@ async fn generate(
         );
         response.graph
     };
+    super::apply_id_migration(&path)?;
 
     // Create generator with config
     let config = GeneratorConfig {
@@ -186,7 +206,7 @@ async fn infer(
         // Ensure graph exists
         let ops_store = Store::new(&path);
         let graph = if ops_store.has_graph() {
-            println!("📊 Loading graph from .self/graph.json");
+            println!("📊 Loading graph from .self");
             ops_store
                 .load_graph()
                 .context("Failed to load graph")?
@@ -197,6 +217,7 @@ async fn infer(
             let response = ctx.graph(request).await.context("Failed to build graph")?;
             response.graph
         };
+        super::apply_id_migration(&path)?;
 
         let name = path
             .file_name()
@@ -315,6 +336,8 @@ async fn run(
     json_output: bool,
     output: Option<PathBuf>,
     top: usize,
+    record: Option<Option<String>>,
+    frontier: bool,
 ) -> Result<()> {
     let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
@@ -391,8 +414,29 @@ async fn run(
 
     // 4. Run impact analysis
     println!("🚀 Running impact analysis...");
-    let report = run_impact_analysis(graph, &description, &changed_files, max_ticks)
+    let config = AutomatonConfig {
+        frontier: frontier || super::run::load_project_config(&path)?.automaton.frontier,
+        ..description_config()
+    };
+    let mut automaton =
+        apply_description_with_config(graph, &description, &changed_files, &[], config)
+            .map_err(|e| anyhow::anyhow!("Automaton error: {}", e))?;
+    if record.is_some() {
+        automaton.start_recording();
+    }
+    let report = run_impact(&mut automaton, &description, &changed_files, max_ticks)
         .map_err(|e| anyhow::anyhow!("Automaton error: {}", e))?;
+    if let Some(label) = record {
+        let snapshot = automaton_store.snapshot(&automaton, label)?;
+        if let Some(recording) = automaton.take_recording() {
+            automaton_store.save_recording(&recording)?;
+        }
+        println!(
+            "📼 Recorded {} ticks, snapshot: {}",
+            report.ticks_executed,
+            snapshot.file_stem().and_then(|s| s.to_str()).unwrap_or("?")
+        );
+    }
 
     // 5. Output results
     if json_output {
@@ -655,6 +699,112 @@ async fn plan(
     Ok(())
 }
 
+/// Compare two automaton states.
+fn diff(path: &Path, from: &str, to: &str, json_output: bool, top: usize) -> Result<()> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let store = AutomatonStore::new(&path);
+    let before = resolve_state(&store, from)?;
+    let after = resolve_state(&store, to)?;
+    let diff = before.diff(&after, 0.001);
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!("🔀 Automaton diff: {} → {}", from, to);
+    if diff.is_empty() {
+        println!("   No changes across {} nodes", diff.compared);
+        return Ok(());
+    }
+    print_diff(&diff, top);
+    Ok(())
+}
+
+/// Resolve a state reference of `vg automaton diff`.
+fn resolve_state(store: &AutomatonStore, reference: &str) -> Result<SourceCodeTemporalGraph> {
+    if reference == "state" {
+        return Ok(store
+            .load_state()?
+            .context("No saved automaton state")?
+            .graph);
+    }
+    if let Some(tick) = reference.strip_prefix("tick:") {
+        let tick: u64 = tick
+            .parse()
+            .with_context(|| format!("Invalid tick in '{}'", reference))?;
+        let recording = store
+            .load_recording()?
+            .context("No recorded run. Record one with: vg automaton run --record")?;
+        return Ok(recording.state_at(tick)?);
+    }
+    let file = Path::new(reference);
+    if file.is_file() {
+        return Ok(store.load_snapshot(file)?.graph);
+    }
+
+    // `vg run --snapshot` writes impact reports next to the automaton
+    // snapshots: skip what does not parse as a state
+    let snapshots = store.list_snapshots()?.into_iter().filter_map(|info| {
+        let id = info.timestamp.to_string();
+        store
+            .load_snapshot(&info.path)
+            .ok()
+            .map(|state| (id, state))
+    });
+    for (id, state) in snapshots {
+        if reference == "latest"
+            || id.starts_with(reference)
+            || state.metadata.label.as_deref() == Some(reference)
+        {
+            return Ok(state.graph);
+        }
+    }
+    anyhow::bail!(
+        "No automaton snapshot '{}'. Record one with: vg automaton run --record",
+        reference
+    )
+}
+
+fn print_diff(diff: &GraphDiff, top: usize) {
+    println!(
+        "   Nodes: {} compared, {} changed, +{} / -{}",
+        diff.compared,
+        diff.changed.len(),
+        diff.added.len(),
+        diff.removed.len()
+    );
+    println!();
+    println!("📈 Changed nodes (largest activation change first):");
+    for delta in diff.changed.iter().take(top) {
+        let name = delta.path.as_deref().unwrap_or(&delta.name);
+        println!(
+            "   {:+.3}  {:.3} → {:.3}  {}",
+            delta.activation_delta(),
+            delta.activation_before,
+            delta.activation_after,
+            name
+        );
+        if delta.rule_changed() {
+            println!(
+                "            rule: {} → {}",
+                delta.rule_before, delta.rule_after
+            );
+        }
+        for annotation in &delta.annotations {
+            println!(
+                "            {}: {} → {}",
+                annotation.key,
+                annotation.before.as_deref().unwrap_or("-"),
+                annotation.after.as_deref().unwrap_or("-")
+            );
+        }
+    }
+    if diff.changed.len() > top {
+        println!("   ... and {} more", diff.changed.len() - top);
+    }
+}
+
 /// Helper: load the source code graph, building it if needed.
 async fn load_or_build_graph(
     ctx: &OpsContext,
@@ -662,14 +812,17 @@ async fn load_or_build_graph(
 ) -> Result<vibe_graph_core::SourceCodeGraph> {
     let ops_store = Store::new(path);
     if ops_store.has_graph() {
-        ops_store
+        let graph = ops_store
             .load_graph()
             .context("Failed to load graph")?
-            .context("Graph should exist")
+            .context("Graph should exist")?;
+        super::apply_id_migration(path)?;
+        Ok(graph)
     } else {
         println!("📊 Building SourceCodeGraph...");
         let request = GraphRequest::new(path);
         let response = ctx.graph(request).await.context("Failed to build graph")?;
+        super::apply_id_migration(path)?;
         println!(
             "   Built: {} nodes, {} edges",
             response.graph.node_count(),
@@ -758,12 +911,14 @@ mod tests {
                     name: "identity".to_string(),
                     rule_type: RuleType::Builtin,
                     system_prompt: None,
+                    expression: None,
                     params: None,
                 },
                 RuleConfig {
                     name: "entry_point".to_string(),
                     rule_type: RuleType::Builtin,
                     system_prompt: None,
+                    expression: None,
                     params: None,
                 },
             ],
@@ -0,0 +1,107 @@
+//! `vg check-architecture` — enforce the architecture rules of a constitution.
+//!
+//! Rules come from the `[architecture]` table of `constitution.toml` or
+//! `vg.toml` (see `vibe_graph_constitution::architecture`). The command
+//! exits non-zero when any dependency breaks them, so it can gate CI.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use vibe_graph_constitution::{ArchitectureCheck, ArchitectureEvaluator, ArchitectureRules};
+use vibe_graph_ops::OpsContext;
+
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    config: PathBuf,
+    passed: bool,
+    #[serde(flatten)]
+    check: ArchitectureCheck,
+}
+
+/// Execute `vg check-architecture`.
+pub async fn execute(
+    ctx: &OpsContext,
+    path: &Path,
+    config: Option<PathBuf>,
+    json_output: bool,
+    force: bool,
+) -> Result<()> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let (config, rules) = load_rules(&path, config)?;
+    let evaluator = ArchitectureEvaluator::new(&rules)
+        .with_context(|| format!("Invalid architecture rules in {}", config.display()))?;
+
+    let graph = super::query::load_or_build_graph(ctx, &path, force).await?;
+    let check = evaluator.evaluate(&graph);
+    let passed = check.passed();
+
+    if json_output {
+        let report = CheckReport {
+            config,
+            passed,
+            check,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_check(&path, &config, &check);
+    }
+
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn load_rules(path: &Path, config: Option<PathBuf>) -> Result<(PathBuf, ArchitectureRules)> {
+    let loaded = match config {
+        Some(file) => ArchitectureRules::load_file(&file)?.map(|rules| (file, rules)),
+        None => ArchitectureRules::load(path)?,
+    };
+    let (config, rules) = loaded.context(
+        "No architecture rules found. Add an [architecture] table to constitution.toml or vg.toml",
+    )?;
+    if rules.is_empty() {
+        anyhow::bail!(
+            "{} declares no architecture rules (forbid, only or no_layer_cycles)",
+            config.display()
+        );
+    }
+    Ok((config, rules))
+}
+
+fn print_check(root: &Path, config: &Path, check: &ArchitectureCheck) {
+    println!(
+        "🏛️  Architecture rules from {}: {} rules, {} dependencies checked",
+        config.strip_prefix(root).unwrap_or(config).display(),
+        check.rules,
+        check.dependencies
+    );
+    if check.passed() {
+        println!("✅ All rules hold");
+        return;
+    }
+
+    println!("❌ {} violations", check.violations.len());
+    // Violations are sorted by rule, so each rule's edges are contiguous
+    let mut current: Option<&str> = None;
+    for violation in &check.violations {
+        if current != Some(violation.rule.as_str()) {
+            current = Some(violation.rule.as_str());
+            let count = check
+                .violations
+                .iter()
+                .filter(|v| v.rule == violation.rule)
+                .count();
+            println!();
+            println!("   {} ({}):", violation.rule, count);
+            if let Some(reason) = &violation.reason {
+                println!("   {}", reason);
+            }
+        }
+        println!(
+            "      {} → {}  ({})",
+            violation.from, violation.to, violation.relationship
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! `vg export` — write the graph in an interchange format.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use vibe_graph_core::{export_graph, ExportFormat, ExportOptions, GraphNodeKind, NodeSelector};
+use vibe_graph_ops::{OpsContext, Store};
+
+/// Options of `vg export`.
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    pub output: Option<PathBuf>,
+    pub kind: Option<String>,
+    pub has_metadata: Option<String>,
+    pub collapse_dirs: bool,
+    pub force: bool,
+}
+
+/// Execute `vg export`.
+pub async fn execute(ctx: &OpsContext, path: &Path, args: ExportArgs) -> Result<()> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let graph = super::query::load_or_build_graph(ctx, &path, args.force).await?;
+
+    let selector = match (args.kind, args.has_metadata) {
+        (Some(_), Some(_)) => anyhow::bail!("Use either --kind or --has-metadata, not both"),
+        (Some(kind), None) => NodeSelector::ByKind(
+            kind.parse::<GraphNodeKind>()
+                .map_err(|e| anyhow::anyhow!(e))?,
+        ),
+        (None, Some(key)) => NodeSelector::HasMetadata(key),
+        (None, None) => NodeSelector::All,
+    };
+    let files = export_graph(
+        &graph,
+        args.format,
+        &ExportOptions {
+            selector,
+            collapse_directories: args.collapse_dirs,
+        },
+    );
+
+    if args.format.is_multi_file() {
+        let dir = args
+            .output
+            .unwrap_or_else(|| Store::new(&path).self_dir().join("export"));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        for file in &files {
+            let target = dir.join(&file.file_name);
+            std::fs::write(&target, &file.contents)
+                .with_context(|| format!("Failed to write {}", target.display()))?;
+            eprintln!("Wrote {}", target.display());
+        }
+        return Ok(());
+    }
+
+    let contents = files.into_iter().map(|f| f.contents).collect::<String>();
+    match args.output {
+        Some(target) => {
+            std::fs::write(&target, contents)
+                .with_context(|| format!("Failed to write {}", target.display()))?;
+            eprintln!("Exported {} to {}", args.format, target.display());
+        }
+        None => print!("{}", contents),
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! `vg graph diff` and `vg graph snapshots` — compare graph snapshots;
+//! `vg graph repos` — dependencies between the repositories of a workspace.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use vibe_graph_core::{Coupling, Dependency};
+use vibe_graph_ops::{
+    DependencyKind, GraphDiffRequest, GraphSnapshotInfo, GraphSnapshotsRequest, OpsContext,
+    OpsError, RepoDependenciesRequest,
+};
+
+/// Execute `vg graph diff`.
+pub async fn diff(
+    ctx: &OpsContext,
+    path: &Path,
+    from: &str,
+    to: Option<&str>,
+    json_output: bool,
+    top: usize,
+) -> Result<()> {
+    let mut request = GraphDiffRequest::new(path, from);
+    if let Some(to) = to {
+        request = request.to(to);
+    }
+    let response = ctx.graph_diff(request).await?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(());
+    }
+
+    let diff = &response.diff;
+    let to_label = response
+        .to
+        .as_ref()
+        .map_or("current".to_string(), |info| info.id.clone());
+    println!("📐 Graph diff: {} → {}", response.from.id, to_label);
+    if diff.is_empty() {
+        println!("   No structural changes");
+        return Ok(());
+    }
+
+    let cross_added: Vec<&Dependency> = diff.added_cross_component().collect();
+    let cross_removed: Vec<&Dependency> = diff.removed_cross_component().collect();
+    println!(
+        "   Files:        +{} / -{}",
+        diff.added_files.len(),
+        diff.removed_files.len()
+    );
+    println!(
+        "   Dependencies: +{} / -{} (cross-component: +{} / -{})",
+        diff.added_dependencies.len(),
+        diff.removed_dependencies.len(),
+        cross_added.len(),
+        cross_removed.len()
+    );
+
+    print_dependencies("➕ New cross-component dependencies", &cross_added, top);
+    print_dependencies(
+        "➖ Removed cross-component dependencies",
+        &cross_removed,
+        top,
+    );
+    print_list("➕ Added files", &diff.added_files, top);
+    print_list("➖ Removed files", &diff.removed_files, top);
+
+    if !diff.coupling_changes.is_empty() {
+        println!();
+        println!("🔗 Coupling changes ({}):", diff.coupling_changes.len());
+        for change in diff.coupling_changes.iter().take(top) {
+            println!(
+                "   {:<32} {} → {}",
+                change.component,
+                format_coupling(change.before),
+                format_coupling(change.after)
+            );
+        }
+        print_more(diff.coupling_changes.len(), top);
+    }
+
+    Ok(())
+}
+
+/// Execute `vg graph snapshots`.
+pub async fn snapshots(ctx: &OpsContext, path: &Path, json_output: bool) -> Result<()> {
+    let response = ctx
+        .graph_snapshots(GraphSnapshotsRequest::new(path))
+        .await?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(());
+    }
+
+    if response.snapshots.is_empty() {
+        println!("No graph snapshots. Run `vg graph --snapshot` to take one.");
+        return Ok(());
+    }
+
+    println!("📸 Graph snapshots ({}):", response.snapshots.len());
+    for info in &response.snapshots {
+        println!(
+            "   {:<14} {:>7} nodes {:>7} edges   {}",
+            info.id,
+            info.node_count,
+            info.edge_count,
+            format_age(info)
+        );
+    }
+    Ok(())
+}
+
+/// Execute `vg graph repos`.
+pub async fn repos(ctx: &OpsContext, path: &Path, json_output: bool) -> Result<()> {
+    let response = ctx
+        .repo_dependencies(RepoDependenciesRequest::new(path))
+        .await
+        .map_err(|e| match e {
+            OpsError::StoreNotFound { .. } => anyhow::anyhow!(
+                "No .self folder found at {}. Run `vg sync` first.",
+                path.display()
+            ),
+            e => e.into(),
+        })?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(());
+    }
+
+    println!(
+        "📦 {} repositories, {} cross-repo dependencies",
+        response.repositories.len(),
+        response.dependencies.len()
+    );
+    for repo in &response.repositories {
+        let packages: Vec<String> = repo
+            .packages
+            .iter()
+            .map(|p| format!("{} ({})", p.name, p.ecosystem.as_str()))
+            .collect();
+        if packages.is_empty() {
+            println!("   {}", repo.repository);
+        } else {
+            println!(
+                "   {:<24} publishes {}",
+                repo.repository,
+                packages.join(", ")
+            );
+        }
+    }
+
+    if response.dependencies.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!("🔗 Cross-repo dependencies:");
+    for dep in &response.dependencies {
+        let mut details = vec![dep.ecosystem.as_str(), dep.manifest.as_str()];
+        if dep.kind != DependencyKind::Normal {
+            details.push(dep.kind.as_str());
+        }
+        println!(
+            "   {} → {}  {} {}  ({})",
+            dep.from,
+            dep.to,
+            dep.package,
+            dep.requirement.as_deref().unwrap_or("*"),
+            details.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn print_dependencies(title: &str, dependencies: &[&Dependency], top: usize) {
+    if dependencies.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} ({}):", title, dependencies.len());
+    for dep in dependencies.iter().take(top) {
+        println!(
+            "   {} → {}  ({} → {}, {})",
+            dep.from_component, dep.to_component, dep.from, dep.to, dep.relationship
+        );
+    }
+    print_more(dependencies.len(), top);
+}
+
+fn print_list(title: &str, items: &[String], top: usize) {
+    if items.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} ({}):", title, items.len());
+    for item in items.iter().take(top) {
+        println!("   {}", item);
+    }
+    print_more(items.len(), top);
+}
+
+fn print_more(total: usize, shown: usize) {
+    if total > shown {
+        println!("   ... and {} more", total - shown);
+    }
+}
+
+fn format_coupling(coupling: Option<Coupling>) -> String {
+    match coupling {
+        Some(c) => format!("Ca {} Ce {} I {:.2}", c.afferent, c.efferent, c.instability),
+        None => "—".to_string(),
+    }
+}
+
+fn format_age(info: &GraphSnapshotInfo) -> String {
+    let created = SystemTime::UNIX_EPOCH + Duration::from_secs(info.created);
+    created
+        .elapsed()
+        .ok()
+        .filter(|d| d.as_secs() > 0)
+        .map(|d| format!("{:?} ago", Duration::from_secs(d.as_secs())))
+        .unwrap_or_else(|| "just now".to_string())
+}
@@ -2,10 +2,15 @@
 
 pub mod architect;
 pub mod automaton;
+pub mod check_architecture;
 pub mod compose;
 pub mod config;
+pub mod export;
+pub mod graph;
+pub mod plugins;
 pub mod process;
 pub mod quality;
+pub mod query;
 pub mod remote;
 pub mod run;
 pub mod rustify;
@@ -14,3 +19,40 @@ pub mod serve;
 
 #[cfg(feature = "native-viz")]
 pub mod viz;
+
+use std::path::Path;
+
+use anyhow::Result;
+use vibe_graph_automaton::AutomatonStore;
+use vibe_graph_ops::Store;
+use vibe_graph_semantic::SemanticStore;
+
+/// Carry a pending node ID migration over to the stores keyed by node ID.
+///
+/// A graph saved with sequential IDs is migrated to path-derived ones, and
+/// replacing it (e.g. a forced rebuild) records the old → new mapping; call
+/// this after loading or building a graph so automaton state and the
+/// semantic index follow it.
+pub(crate) fn apply_id_migration(path: &Path) -> Result<()> {
+    let store = Store::new(path);
+    store.migrate_graph()?;
+    let Some(mapping) = store.load_id_migration()? else {
+        return Ok(());
+    };
+
+    AutomatonStore::new(path).migrate_node_ids(&mapping)?;
+
+    let semantic = SemanticStore::new(store.self_dir());
+    if let Some((mut index, meta)) = semantic
+        .load()
+        .map_err(|e| anyhow::anyhow!("Failed to load index: {}", e))?
+    {
+        index.remap_node_ids(&mapping);
+        semantic
+            .save_with_extra(&index, &meta.model_name, meta.extra)
+            .map_err(|e| anyhow::anyhow!("Failed to save index: {}", e))?;
+    }
+
+    store.clear_id_migration()?;
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! `vg plugins` — rules and samplers from WebAssembly modules.
+//!
+//! Plugins are loaded from `.self/plugins/*.wasm` and the `[[plugins]]` of
+//! `vg.toml` (see `vibe_graph_automaton::plugin`) when `vg` is built with
+//! the `plugins` feature. `vg run` registers their rules next to the
+//! built-in ones.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use vibe_graph_automaton::{ProjectConfig, Rule};
+use vibe_graph_ops::OpsContext;
+
+#[cfg(feature = "plugins")]
+use vibe_graph_automaton::plugin::{Plugin, PluginHost};
+
+/// Rules of the project's plugins, for `vg run`.
+pub fn load_rules(path: &Path, project_config: &ProjectConfig) -> Result<Vec<Arc<dyn Rule>>> {
+    #[cfg(feature = "plugins")]
+    {
+        let plugins = load(path, project_config)?;
+        let rules: Vec<_> = plugins.iter().flat_map(Plugin::rules).collect();
+        if !plugins.is_empty() {
+            eprintln!("   🧩 {} plugins ({} rules)", plugins.len(), rules.len());
+        }
+        Ok(rules)
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    {
+        if has_plugins(path, project_config) {
+            eprintln!("   ⚠ Plugins are ignored: built without the `plugins` feature.");
+            eprintln!("   Rebuild with: cargo build --features plugins");
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Execute `vg plugins list`.
+pub fn list(path: &Path, json_output: bool) -> Result<()> {
+    #[cfg(feature = "plugins")]
+    {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let plugins = load(&path, &ProjectConfig::resolve(&path, None))?;
+
+        if json_output {
+            let list: Vec<_> = plugins
+                .iter()
+                .map(|plugin| {
+                    serde_json::json!({
+                        "name": plugin.name(),
+                        "path": plugin.path(),
+                        "manifest": plugin.manifest(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&list)?);
+            return Ok(());
+        }
+
+        if plugins.is_empty() {
+            println!("No plugins found.");
+            println!("   Add .wasm modules to .self/plugins/ or [[plugins]] entries to vg.toml");
+            return Ok(());
+        }
+        for plugin in &plugins {
+            let file = plugin
+                .path()
+                .map(|p| p.strip_prefix(&path).unwrap_or(p).display().to_string())
+                .unwrap_or_default();
+            println!("🧩 {}  ({})", plugin.name(), file);
+            for rule in &plugin.manifest().rules {
+                println!(
+                    "   rule     {}::{}  (priority {}, {})  {}",
+                    plugin.name(),
+                    rule.id,
+                    rule.priority,
+                    format!("{:?}", rule.locality).to_lowercase(),
+                    rule.description
+                );
+            }
+            for sampler in &plugin.manifest().samplers {
+                let kind = sampler
+                    .kind
+                    .map(|k| k.as_str().to_string())
+                    .unwrap_or_else(|| "all".to_string());
+                println!(
+                    "   sampler  {}::{}  ({})  {}",
+                    plugin.name(),
+                    sampler.id,
+                    kind,
+                    sampler.description
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    {
+        let _ = (path, json_output);
+        unsupported()
+    }
+}
+
+/// Execute `vg plugins sample`: run a plugin sampler over the graph.
+pub async fn sample(
+    ctx: &OpsContext,
+    path: &Path,
+    sampler_id: &str,
+    json_output: bool,
+    force: bool,
+) -> Result<()> {
+    #[cfg(feature = "plugins")]
+    {
+        use std::collections::HashMap;
+
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let plugins = load(&path, &ProjectConfig::resolve(&path, None))?;
+        let mut samplers: Vec<_> = plugins
+            .iter()
+            .flat_map(Plugin::samplers)
+            .filter(|s| s.id() == sampler_id || s.id().ends_with(&format!("::{}", sampler_id)))
+            .collect();
+        let sampler = match samplers.len() {
+            0 => anyhow::bail!("No plugin sampler named '{}'", sampler_id),
+            1 => samplers.remove(0),
+            _ => anyhow::bail!(
+                "Several plugin samplers are named '{}': {}",
+                sampler_id,
+                samplers
+                    .iter()
+                    .map(|s| s.id())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let graph = super::query::load_or_build_graph(ctx, &path, force).await?;
+        let result = sampler.sample(&graph, &HashMap::new())?;
+
+        if json_output {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            return Ok(());
+        }
+
+        println!(
+            "🧩 {}: {} artifacts",
+            result.sampler_id,
+            result.artifacts.len()
+        );
+        let names: HashMap<_, _> = graph
+            .nodes
+            .iter()
+            .map(|n| {
+                let name = n.metadata.get("relative_path").unwrap_or(&n.name);
+                (n.id, name.as_str())
+            })
+            .collect();
+        for (node_id, value) in result.iter() {
+            let name = names.get(&node_id).copied().unwrap_or("?");
+            println!("   {}  {}", name, value);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    {
+        let _ = (ctx, path, sampler_id, json_output, force);
+        unsupported()
+    }
+}
+
+#[cfg(feature = "plugins")]
+fn load(path: &Path, project_config: &ProjectConfig) -> Result<Vec<Plugin>> {
+    let host = PluginHost::new()?;
+    Ok(host.load_project(path, &project_config.plugins)?)
+}
+
+/// Whether the project declares or ships plugins.
+#[cfg(not(feature = "plugins"))]
+fn has_plugins(path: &Path, project_config: &ProjectConfig) -> bool {
+    let discovered = std::fs::read_dir(vibe_graph_automaton::PluginConfig::discovery_dir(path))
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().extension().is_some_and(|ext| ext == "wasm"))
+        })
+        .unwrap_or(false);
+    discovered || project_config.plugins.iter().any(|p| p.enabled)
+}
+
+#[cfg(not(feature = "plugins"))]
+fn unsupported() -> Result<()> {
+    anyhow::bail!(
+        "Plugins require the `plugins` feature.\n   Rebuild with: cargo build --features plugins"
+    )
+}
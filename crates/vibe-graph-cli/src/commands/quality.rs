@@ -2,6 +2,8 @@
 //!
 //! This command is the CLI surface for `QUALITY_STANDARD.md`: it reports the
 //! graph-based stability score, validation feedback, and merge/readiness gates.
+//! It also runs the architecture analysis (dependency cycles, centrality,
+//! Martin's package metrics) and stores its results in the graph.
 
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -12,6 +14,9 @@ use vibe_graph_automaton::{
     parse_errors, run_evolution_plan, run_script_with_timeout, AutomatonStore,
     DescriptionGenerator, GeneratorConfig, ProjectConfig, ScriptError, ScriptFeedback, Severity,
 };
+use vibe_graph_core::{
+    ArchitectureAnalysis, DependencyCycle, FileCentrality, LayeringViolation, ModuleMetrics,
+};
 use vibe_graph_ops::{GraphRequest, OpsContext, Store};
 
 #[derive(Debug, Serialize)]
@@ -32,6 +37,54 @@ pub struct QualityReport {
     pub status: QualityStatus,
     pub gates: QualityGates,
     pub top_risks: Vec<QualityRisk>,
+    pub architecture: ArchitectureReport,
+}
+
+/// Architecture findings, trimmed to the top entries where lists are long.
+#[derive(Debug, Serialize)]
+pub struct ArchitectureReport {
+    pub component_cycles: Vec<DependencyCycle>,
+    pub file_cycle_count: usize,
+    pub file_cycles: Vec<DependencyCycle>,
+    pub modules: Vec<ModuleReport>,
+    pub layering_violations: Vec<LayeringViolation>,
+    pub central_files: Vec<FileCentrality>,
+    pub betweenness_sampled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleReport {
+    pub name: String,
+    #[serde(flatten)]
+    pub metrics: ModuleMetrics,
+}
+
+impl ArchitectureReport {
+    fn new(analysis: ArchitectureAnalysis, top: usize) -> Self {
+        let mut modules: Vec<ModuleReport> = analysis
+            .modules
+            .into_iter()
+            .map(|(name, metrics)| ModuleReport { name, metrics })
+            .collect();
+        // Furthest from the main sequence first, then most unstable
+        modules.sort_by(|a, b| {
+            let distance = |m: &ModuleReport| m.metrics.distance.unwrap_or(-1.0);
+            distance(b)
+                .total_cmp(&distance(a))
+                .then_with(|| b.metrics.instability.total_cmp(&a.metrics.instability))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Self {
+            component_cycles: analysis.component_cycles,
+            file_cycle_count: analysis.file_cycles.len(),
+            file_cycles: analysis.file_cycles.into_iter().take(top).collect(),
+            modules,
+            layering_violations: analysis.layering_violations.into_iter().take(top).collect(),
+            central_files: analysis.centrality.into_iter().take(top).collect(),
+            betweenness_sampled: analysis.betweenness_sampled,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -125,7 +178,14 @@ async fn calculate(
     force: bool,
     script_timeout: Duration,
 ) -> Result<QualityReport> {
-    let graph = load_or_build_graph(ctx, path, force).await?;
+    let mut graph = load_or_build_graph(ctx, path, force).await?;
+    let analysis = ArchitectureAnalysis::of(&graph);
+    analysis.annotate(&mut graph);
+    Store::new(path)
+        .save_graph(&graph)
+        .context("Failed to store architecture metrics")?;
+    let architecture = ArchitectureReport::new(analysis, top);
+
     let description = load_or_generate_description(path, &graph, force)?;
     let project_config = ProjectConfig::resolve(path, None);
     let objective = project_config.stability_objective();
@@ -219,6 +279,7 @@ async fn calculate(
         status,
         gates,
         top_risks,
+        architecture,
     })
 }
 
@@ -281,10 +342,12 @@ async fn load_or_build_graph(
 ) -> Result<vibe_graph_core::SourceCodeGraph> {
     let store = Store::new(path);
     if !force && store.has_graph() {
-        return store
+        let graph = store
             .load_graph()
             .context("Failed to load graph")?
-            .context("Graph should exist");
+            .context("Graph should exist")?;
+        super::apply_id_migration(path)?;
+        return Ok(graph);
     }
 
     eprintln!("Building SourceCodeGraph...");
@@ -292,6 +355,7 @@ async fn load_or_build_graph(
         .graph(GraphRequest::new(path))
         .await
         .context("Failed to build graph")?;
+    super::apply_id_migration(path)?;
     eprintln!(
         "Built graph: {} nodes, {} edges",
         response.graph.node_count(),
@@ -408,9 +472,94 @@ fn format_report(report: &QualityReport) -> String {
         }
     }
 
+    out.push('\n');
+    format_architecture(&mut out, &report.architecture);
+
     out
 }
 
+fn format_architecture(out: &mut String, architecture: &ArchitectureReport) {
+    out.push_str("Architecture:\n");
+    if architecture.component_cycles.is_empty() {
+        out.push_str("- component cycles: none\n");
+    } else {
+        out.push_str(&format!(
+            "- component cycles: {}\n",
+            architecture.component_cycles.len()
+        ));
+        for cycle in &architecture.component_cycles {
+            out.push_str(&format!(
+                "    {} ({} dependencies)\n",
+                cycle.members.join(" <-> "),
+                cycle.dependencies.len()
+            ));
+        }
+    }
+    out.push_str(&format!(
+        "- file cycles: {}\n",
+        architecture.file_cycle_count
+    ));
+    for cycle in &architecture.file_cycles {
+        out.push_str(&format!("    {}\n", cycle.members.join(" <-> ")));
+    }
+
+    if !architecture.modules.is_empty() {
+        out.push_str("- modules (Ca Ce I A D):\n");
+        for module in &architecture.modules {
+            let m = &module.metrics;
+            let optional =
+                |value: Option<f64>| value.map_or("  -  ".to_string(), |v| format!("{:.2}", v));
+            out.push_str(&format!(
+                "    {:>3} {:>3} {:.2} {} {}  {}\n",
+                m.afferent,
+                m.efferent,
+                m.instability,
+                optional(m.abstractness),
+                optional(m.distance),
+                module.name
+            ));
+        }
+        if architecture
+            .modules
+            .iter()
+            .all(|m| m.metrics.abstractness.is_none())
+        {
+            out.push_str("    (abstractness needs a graph built with `vg graph --symbols`)\n");
+        }
+    }
+
+    if !architecture.layering_violations.is_empty() {
+        out.push_str("- layering violations (depends on a less stable module):\n");
+        for violation in &architecture.layering_violations {
+            out.push_str(&format!(
+                "    {} ({:.2}) -> {} ({:.2}), {} dependencies\n",
+                violation.from,
+                violation.from_instability,
+                violation.to,
+                violation.to_instability,
+                violation.dependencies
+            ));
+        }
+    }
+
+    if !architecture.central_files.is_empty() {
+        out.push_str(&format!(
+            "- central files (pagerank, betweenness{}):\n",
+            if architecture.betweenness_sampled {
+                " sampled"
+            } else {
+                ""
+            }
+        ));
+        for file in &architecture.central_files {
+            out.push_str(&format!(
+                "    {:.4} {:.4} {}\n",
+                file.pagerank, file.betweenness, file.file
+            ));
+        }
+    }
+}
+
 fn pass_fail(pass: bool) -> &'static str {
     if pass {
         "PASS"
@@ -0,0 +1,96 @@
+//! `vg query` — run a graph query against the SourceCodeGraph.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use vibe_graph_core::{QueryResult, SourceCodeGraph};
+use vibe_graph_ops::{GraphRequest, OpsContext, Store};
+
+/// Execute `vg query`.
+pub async fn execute(
+    ctx: &OpsContext,
+    path: &Path,
+    query: &str,
+    json_output: bool,
+    force: bool,
+) -> Result<()> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let graph = load_or_build_graph(ctx, &path, force).await?;
+    let result = graph.query(query)?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        print!("{}", format_table(&result));
+    }
+    Ok(())
+}
+
+/// Load the stored graph, building it when missing or when `force` is set.
+pub(crate) async fn load_or_build_graph(
+    ctx: &OpsContext,
+    path: &Path,
+    force: bool,
+) -> Result<SourceCodeGraph> {
+    let store = Store::new(path);
+    if !force && store.has_graph() {
+        let graph = store
+            .load_graph()
+            .context("Failed to load graph")?
+            .context("Graph should exist")?;
+        super::apply_id_migration(path)?;
+        return Ok(graph);
+    }
+
+    eprintln!("Building SourceCodeGraph...");
+    let response = ctx
+        .graph(GraphRequest::new(path))
+        .await
+        .context("Failed to build graph")?;
+    super::apply_id_migration(path)?;
+    Ok(response.graph)
+}
+
+/// Render rows as an aligned text table followed by a row count.
+fn format_table(result: &QueryResult) -> String {
+    let cells: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|v| v.to_string()).collect())
+        .collect();
+    let widths: Vec<usize> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |values: &[String]| {
+        let padded: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut out = line(&result.columns);
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.push_str(&line(&rule));
+    for row in &cells {
+        out.push_str(&line(row));
+    }
+    out.push_str(&format!(
+        "({} row{})\n",
+        cells.len(),
+        if cells.len() == 1 { "" } else { "s" }
+    ));
+    out
+}
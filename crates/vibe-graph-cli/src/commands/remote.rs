@@ -10,9 +10,9 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use git2::{Cred, RemoteCallbacks, Repository as GitRepo};
 use octocrab::Octocrab;
+use tokio::task::JoinSet;
 use tracing::info;
-use vibe_graph_ops::{Project, ProjectSource, Repository, Source, Store};
-use walkdir::WalkDir;
+use vibe_graph_ops::{scan_directory, Project, ProjectSource, Repository, Store};
 
 use crate::config::Config;
 
@@ -249,6 +249,9 @@ pub async fn list(config: &Config, store: &Store) -> Result<()> {
     Ok(())
 }
 
+/// How many repositories are cloned and scanned at once.
+const CLONE_CONCURRENCY: usize = 8;
+
 /// Clone all repositories from the configured remote organization.
 pub async fn clone(config: &Config, store: &Store, ignore_list: &[String]) -> Result<Project> {
     config.validate_github()?;
@@ -300,58 +303,64 @@ pub async fn clone(config: &Config, store: &Store, ignore_list: &[String]) -> Re
     println!("📥 Cloning to: {}", cache_dir.display());
     println!();
 
+    let total = repos.items.len();
+    let mut selected = Vec::new();
     for (i, repo) in repos.items.iter().enumerate() {
-        let repo_name = &repo.name;
-
         // Skip if in ignore list
-        if ignore_list.iter().any(|s| s == repo_name) {
-            println!(
-                "[{}/{}] ⏭️  Skipping {} (ignored)",
-                i + 1,
-                repos.items.len(),
-                repo_name
-            );
+        if ignore_list.iter().any(|s| s == &repo.name) {
+            println!("[{}/{}] ⏭️  Skipping {} (ignored)", i + 1, total, repo.name);
             continue;
         }
-
         let clone_url = repo
             .clone_url
             .as_ref()
             .map(|u| u.to_string())
-            .unwrap_or_else(|| format!("https://github.com/{}/{}.git", org, repo_name));
-
-        let repo_path = cache_dir.join(repo_name);
-
-        // Clone or update repository
-        if needs_clone(&repo_path) {
-            println!(
-                "[{}/{}] 📦 Cloning {}...",
-                i + 1,
-                repos.items.len(),
-                repo_name
-            );
-
-            // Clean up incomplete clone if exists
-            if repo_path.exists() {
-                std::fs::remove_dir_all(&repo_path)?;
-            }
+            .unwrap_or_else(|| format!("https://github.com/{}/{}.git", org, repo.name));
+        selected.push((i, repo.name.clone(), clone_url));
+    }
 
-            clone_repository(&clone_url, &repo_path, &username, &token)?;
-        } else {
-            println!(
-                "[{}/{}] ✓  Using cached {}",
-                i + 1,
-                repos.items.len(),
-                repo_name
-            );
-        }
+    // Clone and scan on blocking threads, a bounded number at a time
+    let mut pending = selected.into_iter();
+    let mut tasks = JoinSet::new();
+    let mut cloned = Vec::new();
+    loop {
+        while tasks.len() < CLONE_CONCURRENCY {
+            let Some((i, repo_name, clone_url)) = pending.next() else {
+                break;
+            };
+            let repo_path = cache_dir.join(&repo_name);
+            let (username, token) = (username.clone(), token.clone());
+            tasks.spawn_blocking(move || -> Result<(usize, Repository)> {
+                // Clone or update repository
+                if needs_clone(&repo_path) {
+                    println!("[{}/{}] 📦 Cloning {}...", i + 1, total, repo_name);
+
+                    // Clean up incomplete clone if exists
+                    if repo_path.exists() {
+                        std::fs::remove_dir_all(&repo_path)?;
+                    }
+
+                    clone_repository(&clone_url, &repo_path, &username, &token)?;
+                } else {
+                    println!("[{}/{}] ✓  Using cached {}", i + 1, total, repo_name);
+                }
 
-        // Scan the repository
-        let mut repository = Repository::new(repo_name, &clone_url, repo_path.clone());
-        scan_repository(&mut repository)?;
-        project.repositories.push(repository);
+                // Scan the repository
+                let mut repository = Repository::new(&repo_name, &clone_url, repo_path.clone());
+                scan_directory(&mut repository, &repo_path)?;
+                Ok((i, repository))
+            });
+        }
+        match tasks.join_next().await {
+            Some(joined) => cloned.push(joined.context("Clone task failed")??),
+            None => break,
+        }
     }
 
+    // Results keep the listing order
+    cloned.sort_by_key(|(i, _)| *i);
+    project.repositories = cloned.into_iter().map(|(_, repo)| repo).collect();
+
     println!();
     println!(
         "✅ Cloned {} repositories ({} files total)",
@@ -388,49 +397,3 @@ fn clone_repository(url: &str, path: &Path, username: &str, token: &str) -> Resu
 
     Ok(())
 }
-
-/// Scan a repository and populate its sources.
-fn scan_repository(repo: &mut Repository) -> Result<()> {
-    for entry in WalkDir::new(&repo.local_path)
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e) && !is_blacklisted(e))
-        .filter_map(|e| e.ok())
-    {
-        if entry.path().is_file() {
-            if let Ok(source) = Source::from_path(entry.path().to_path_buf(), &repo.local_path) {
-                repo.sources.push(source);
-            }
-        }
-    }
-    Ok(())
-}
-
-/// Check if entry is hidden.
-fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with('.'))
-        .unwrap_or(false)
-}
-
-/// Check if entry is in a blacklisted directory.
-fn is_blacklisted(entry: &walkdir::DirEntry) -> bool {
-    const BLACKLIST: &[&str] = &[
-        "node_modules",
-        "target",
-        "dist",
-        "build",
-        "__pycache__",
-        ".venv",
-        "venv",
-        ".git",
-        "vendor",
-    ];
-
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| BLACKLIST.contains(&s))
-        .unwrap_or(false)
-}
@@ -2,13 +2,16 @@
 //!
 //! Bootstraps the full pipeline (sync → graph → description) if needed,
 //! then starts the automaton runtime seeded from current git changes.
-//! In watch mode, the process stays alive, polling for changes and
-//! re-running impact analysis on each detected delta.
+//! In watch mode, the process stays alive, polling for changes, updating the
+//! graph for the files involved and re-running impact analysis on each
+//! detected delta. Expression rules from
+//! `description.json` and `vg.toml` are reloaded when either file changes;
+//! rules from plugins (see `vg plugins`) are loaded once at startup.
 
 use std::collections::HashSet;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result};
 
@@ -17,13 +20,14 @@ use std::sync::Arc;
 
 use super::process::ManagedProcess;
 use vibe_graph_automaton::{
-    build_next_task, format_behavioral_contracts, format_evolution_plan, format_next_task_markdown,
-    run_evolution_plan, run_impact_analysis, run_watch_scripts, AutomatonDescription,
-    AutomatonStore, DescriptionGenerator, GeneratorConfig, ImpactReport, Perturbation,
-    ProjectConfig, ScriptFeedback,
+    apply_description_with_config, build_next_task, description_config,
+    format_behavioral_contracts, format_evolution_plan, format_next_task_markdown,
+    run_evolution_plan_with_rules, run_impact, run_watch_scripts, AutomatonConfig,
+    AutomatonDescription, AutomatonStore, DescriptionGenerator, GeneratorConfig, ImpactReport,
+    Perturbation, ProjectConfig, Rule, ScriptFeedback, CONFIG_FILENAME,
 };
-use vibe_graph_core::{NodeId, SourceCodeGraph};
-use vibe_graph_ops::{GraphRequest, OpsContext, Store, SyncRequest};
+use vibe_graph_core::{GraphChangeSet, NodeId, SourceCodeGraph};
+use vibe_graph_ops::{GraphRequest, GraphUpdateRequest, OpsContext, Store, SyncRequest};
 use vibe_graph_semantic::VectorIndex;
 
 // ─── Public entry point ──────────────────────────────────────────────────────
@@ -43,6 +47,7 @@ pub async fn execute(
     goal: Option<String>,
     targets: Vec<String>,
     run_scripts: bool,
+    frontier: bool,
 ) -> Result<()> {
     let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
@@ -55,6 +60,10 @@ pub async fn execute(
 
     // ── Phase 1: Bootstrap ──────────────────────────────────────────────
     let (graph, description, semantic_index) = bootstrap(ctx, &path, force).await?;
+    let project_config = load_project_config(&path)?;
+    let description = with_project_rules(description, &project_config)?;
+    let plugin_rules = super::plugins::load_rules(&path, &project_config)?;
+    let frontier = frontier || project_config.automaton.frontier;
 
     // Load semantic context once (embedder + index) for reuse across the session.
     let semantic_ctx: Option<(VectorIndex, Arc<dyn vibe_graph_semantic::Embedder>)> =
@@ -95,21 +104,28 @@ pub async fn execute(
 
     // ── Phase 2: Initial run ────────────────────────────────────────────
     let changed_files = detect_git_changes(ctx, &path).await;
-    let report = run_analysis(&graph, &description, &changed_files, max_ticks)?;
+    let report = run_analysis(
+        &graph,
+        &description,
+        &plugin_rules,
+        &changed_files,
+        max_ticks,
+        frontier,
+    )?;
 
     if json_output {
         // JSON mode: output the canonical NextTask object, not the raw ImpactReport.
         // Skip watch scripts — JSON mode should be fast (CI-friendly).
-        let project_config = ProjectConfig::resolve(&path, None);
         let objective = project_config.stability_objective();
         let goal_scores = goal_semantic_scores(&semantic_ctx, perturbation.as_ref());
-        match run_evolution_plan(
+        match run_evolution_plan_with_rules(
             graph.clone(),
             &description,
             &objective,
             perturbation.as_ref(),
             None,
             goal_scores.as_ref(),
+            &plugin_rules,
         ) {
             Ok(plan) if !plan.items.is_empty() => {
                 let commit = git_head_sha(&path);
@@ -159,7 +175,6 @@ pub async fn execute(
         // Write a fresh next-task.md so it's never stale.
         // Scripts are skipped by default in --once mode for speed;
         // pass --scripts to opt in (runs cargo check/test before planning).
-        let project_config = ProjectConfig::resolve(&path, None);
         let script_fb = if run_scripts && project_config.has_watch_scripts() {
             eprintln!("   🔧 Running watch scripts...");
             let fb = run_watch_scripts(&project_config, &path);
@@ -173,13 +188,14 @@ pub async fn execute(
 
         let objective = project_config.stability_objective();
         let goal_scores = goal_semantic_scores(&semantic_ctx, perturbation.as_ref());
-        match run_evolution_plan(
+        match run_evolution_plan_with_rules(
             graph.clone(),
             &description,
             &objective,
             perturbation.as_ref(),
             script_fb.as_ref(),
             goal_scores.as_ref(),
+            &plugin_rules,
         ) {
             Ok(plan) if !plan.items.is_empty() => {
                 let commit = git_head_sha(&path);
@@ -214,7 +230,6 @@ pub async fn execute(
 
     // ── Phase 3: Watch loop ─────────────────────────────────────────────
     // (managed children never reach here — they exit in the `once` branch above)
-    let project_config = ProjectConfig::resolve(&path, None);
     if project_config.has_scripts() {
         eprintln!(
             "   📄 Loaded vg.toml ({} scripts)",
@@ -228,13 +243,15 @@ pub async fn execute(
     watch_loop(
         ctx,
         &path,
-        &graph,
-        &description,
+        graph,
+        description,
+        &plugin_rules,
         &changed_files,
         interval,
         top,
         max_ticks,
         snapshot,
+        frontier,
         perturbation,
         &project_config,
         &semantic_ctx,
@@ -260,7 +277,7 @@ async fn bootstrap(
     let ops_store = Store::new(path);
     let automaton_store = AutomatonStore::new(path);
 
-    // 1. Sync (project.json)
+    // 1. Sync (project)
     if force || !ops_store.exists() {
         eprint!("   📦 Syncing codebase...");
         let request = SyncRequest::local(path);
@@ -271,12 +288,12 @@ async fn bootstrap(
             response.project.repositories.len()
         );
     } else {
-        eprintln!("   ✅ project.json (cached)");
+        eprintln!("   ✅ project (cached)");
     }
 
-    // 2. Graph (graph.json)
+    // 2. Graph
     let graph = if !force && ops_store.has_graph() {
-        eprintln!("   ✅ graph.json (cached)");
+        eprintln!("   ✅ graph (cached)");
         ops_store
             .load_graph()
             .context("Failed to load graph")?
@@ -292,6 +309,7 @@ async fn bootstrap(
         );
         response.graph
     };
+    super::apply_id_migration(path)?;
 
     // 3. Description (description.json)
     let description = if !force && automaton_store.has_description() {
@@ -322,6 +340,74 @@ async fn bootstrap(
     Ok((graph, description, semantic_index))
 }
 
+// ─── Expression rules ────────────────────────────────────────────────────────
+
+/// Add the `[[rules]]` of `vg.toml` to the description, checking that every
+/// expression rule compiles.
+fn with_project_rules(
+    mut description: AutomatonDescription,
+    project_config: &ProjectConfig,
+) -> Result<AutomatonDescription> {
+    description.merge_rules(project_config.rules.iter().cloned());
+    let rules = description.expression_rules()?;
+    if !rules.is_empty() {
+        eprintln!("   📐 {} expression rules", rules.len());
+    }
+    Ok(description)
+}
+
+/// The project's `vg.toml`, or a config inferred from its markers when
+/// there is none. A malformed `vg.toml` is reported, not replaced.
+pub(super) fn load_project_config(path: &Path) -> Result<ProjectConfig> {
+    match ProjectConfig::load(path).map_err(anyhow::Error::msg)? {
+        Some(config) => Ok(config),
+        None => Ok(ProjectConfig::resolve(path, None)),
+    }
+}
+
+/// Reload `description.json` and the rules of `vg.toml`.
+fn reload_rules(path: &Path) -> Result<AutomatonDescription> {
+    let description = AutomatonStore::new(path)
+        .load_description()?
+        .context("description.json is missing")?;
+    let project_config = ProjectConfig::load(path)
+        .map_err(anyhow::Error::msg)?
+        .unwrap_or_default();
+    with_project_rules(description, &project_config)
+}
+
+/// Files the rules come from, and their last seen modification times.
+struct RuleSources {
+    files: Vec<PathBuf>,
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl RuleSources {
+    fn new(path: &Path) -> Self {
+        let files = vec![
+            AutomatonStore::new(path).description_path(),
+            path.join(CONFIG_FILENAME),
+        ];
+        let modified = Self::modified(&files);
+        Self { files, modified }
+    }
+
+    fn modified(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+        files
+            .iter()
+            .map(|file| std::fs::metadata(file).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    /// Whether any file changed since the last call.
+    fn changed(&mut self) -> bool {
+        let modified = Self::modified(&self.files);
+        let changed = modified != self.modified;
+        self.modified = modified;
+        changed
+    }
+}
+
 // ─── Change detection ────────────────────────────────────────────────────────
 
 /// Detect current git changes, returning changed file paths.
@@ -338,6 +424,41 @@ async fn detect_git_changes(ctx: &OpsContext, path: &Path) -> Vec<PathBuf> {
     }
 }
 
+/// Update the stored graph for files that may have changed: paths that
+/// still exist are re-read if their content changed, missing ones dropped.
+async fn refresh_graph(
+    ctx: &OpsContext,
+    path: &Path,
+    files: impl IntoIterator<Item = PathBuf>,
+) -> Result<SourceCodeGraph> {
+    let mut changes = GraphChangeSet::default();
+    for file in files {
+        let list = if file.exists() {
+            &mut changes.added
+        } else {
+            &mut changes.deleted
+        };
+        if !list.contains(&file) {
+            list.push(file);
+        }
+    }
+
+    let response = ctx
+        .update_graph(GraphUpdateRequest::new(path, changes))
+        .await?;
+    let diff = &response.diff;
+    if !diff.is_empty() {
+        eprintln!(
+            "   📊 Graph updated: +{} -{} nodes, +{} -{} edges",
+            diff.added_nodes.len(),
+            diff.removed_nodes.len(),
+            diff.added_edges.len(),
+            diff.removed_edges.len()
+        );
+    }
+    Ok(response.graph)
+}
+
 /// Get a fingerprint of the current change set for diffing.
 fn change_fingerprint(files: &[PathBuf]) -> HashSet<String> {
     files
@@ -352,11 +473,24 @@ fn change_fingerprint(files: &[PathBuf]) -> HashSet<String> {
 fn run_analysis(
     graph: &SourceCodeGraph,
     description: &vibe_graph_automaton::AutomatonDescription,
+    plugin_rules: &[Arc<dyn Rule>],
     changed_files: &[PathBuf],
     max_ticks: Option<usize>,
+    frontier: bool,
 ) -> Result<ImpactReport> {
-    run_impact_analysis(graph.clone(), description, changed_files, max_ticks)
-        .map_err(|e| anyhow::anyhow!("Automaton error: {}", e))
+    let config = AutomatonConfig {
+        frontier,
+        ..description_config()
+    };
+    apply_description_with_config(
+        graph.clone(),
+        description,
+        changed_files,
+        plugin_rules,
+        config,
+    )
+    .and_then(|mut automaton| run_impact(&mut automaton, description, changed_files, max_ticks))
+    .map_err(|e| anyhow::anyhow!("Automaton error: {}", e))
 }
 
 // ─── Display ─────────────────────────────────────────────────────────────────
@@ -503,13 +637,15 @@ fn print_controls(has_process: bool) {
 async fn watch_loop(
     ctx: &OpsContext,
     path: &Path,
-    graph: &SourceCodeGraph,
-    description: &vibe_graph_automaton::AutomatonDescription,
+    mut graph: SourceCodeGraph,
+    mut description: AutomatonDescription,
+    plugin_rules: &[Arc<dyn Rule>],
     initial_changes: &[PathBuf],
     interval: u64,
     top: usize,
     max_ticks: Option<usize>,
     snapshot: bool,
+    frontier: bool,
     initial_perturbation: Option<Perturbation>,
     project_config: &ProjectConfig,
     semantic_ctx: &Option<(VectorIndex, Arc<dyn vibe_graph_semantic::Embedder>)>,
@@ -519,6 +655,7 @@ async fn watch_loop(
     let store = AutomatonStore::new(path);
     let objective = project_config.stability_objective();
     let mut last_script_feedback: Option<ScriptFeedback> = None;
+    let mut rule_sources = RuleSources::new(path);
 
     // Spawn managed process if configured.
     // Note: managed children never reach watch_loop — they exit in the `once`
@@ -565,7 +702,14 @@ async fn watch_loop(
                         // Enter: force re-analyze
                         eprintln!("   ↻ Re-analyzing...");
                         let changed_files = detect_git_changes(ctx, path).await;
-                        let report = run_analysis(graph, description, &changed_files, max_ticks)?;
+                        let report = run_analysis(
+                            &graph,
+                            &description,
+                            plugin_rules,
+                            &changed_files,
+                            max_ticks,
+                            frontier,
+                        )?;
                         print_report(&report, top, path);
                         last_fingerprint = change_fingerprint(&changed_files);
                         if snapshot {
@@ -577,13 +721,14 @@ async fn watch_loop(
                         // Plan
                         eprintln!("   📋 Computing evolution plan...\n");
                         let goal_scores = goal_semantic_scores(semantic_ctx, perturbation.as_ref());
-                        match run_evolution_plan(
+                        match run_evolution_plan_with_rules(
                             graph.clone(),
-                            description,
+                            &description,
                             &objective,
                             perturbation.as_ref(),
                             last_script_feedback.as_ref(),
                             goal_scores.as_ref(),
+                            plugin_rules,
                         ) {
                             Ok(plan) => {
                                 let md = format_evolution_plan(&plan);
@@ -597,25 +742,26 @@ async fn watch_loop(
                     b'n' => {
                         eprintln!("   🎯 Computing next task...\n");
                         let goal_scores = goal_semantic_scores(semantic_ctx, perturbation.as_ref());
-                        match run_evolution_plan(
+                        match run_evolution_plan_with_rules(
                             graph.clone(),
-                            description,
+                            &description,
                             &objective,
                             perturbation.as_ref(),
                             last_script_feedback.as_ref(),
                             goal_scores.as_ref(),
+                            plugin_rules,
                         ) {
                             Ok(plan) if !plan.items.is_empty() => {
                                 let commit = git_head_sha(path);
                                 let total = plan.items.len();
                                 let sem_neighbors = semantic_neighbors_for(
                                     semantic_ctx,
-                                    graph,
+                                    &graph,
                                     plan.items[0].node_id,
                                 );
                                 let task = build_next_task(
                                     &plan.items[0],
-                                    graph,
+                                    &graph,
                                     &plan.project_name,
                                     perturbation.as_ref(),
                                     1,
@@ -647,7 +793,7 @@ async fn watch_loop(
                     b'd' => {
                         // Update .cursor/rules with behavioral contracts
                         eprintln!("   📝 Updating behavioral contracts...");
-                        match update_cursor_rules(path, description) {
+                        match update_cursor_rules(path, &description) {
                             Ok(rule_path) => {
                                 eprintln!("   ✅ Updated: {}", rule_path.display());
                                 eprintln!("      Cursor will auto-load these contracts.\n");
@@ -659,7 +805,14 @@ async fn watch_loop(
                     b's' => {
                         // Snapshot
                         let changed_files = detect_git_changes(ctx, path).await;
-                        let report = run_analysis(graph, description, &changed_files, max_ticks)?;
+                        let report = run_analysis(
+                            &graph,
+                            &description,
+                            plugin_rules,
+                            &changed_files,
+                            max_ticks,
+                            frontier,
+                        )?;
                         save_snapshot(path, &report)?;
                         print_watching();
                     }
@@ -751,13 +904,51 @@ async fn watch_loop(
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
+        // Hot-reload rules, keeping the previous ones when the new ones are invalid
+        if rule_sources.changed() {
+            match reload_rules(path) {
+                Ok(reloaded) => {
+                    description = reloaded;
+                    eprintln!("   🔁 Rules reloaded");
+                    let changed_files = detect_git_changes(ctx, path).await;
+                    let report = run_analysis(
+                        &graph,
+                        &description,
+                        plugin_rules,
+                        &changed_files,
+                        max_ticks,
+                        frontier,
+                    )?;
+                    print_delta(&report, top, &chrono_now_short());
+                }
+                Err(e) => eprintln!("   ❌ Rules not reloaded: {:#}", e),
+            }
+        }
+
         // Check for new git changes
         let changed_files = detect_git_changes(ctx, path).await;
         let new_fingerprint = change_fingerprint(&changed_files);
 
         if new_fingerprint != last_fingerprint {
+            // Files that changed, or were reverted, since the last pass
+            let touched = last_fingerprint
+                .iter()
+                .map(PathBuf::from)
+                .chain(changed_files.iter().cloned());
+            match refresh_graph(ctx, path, touched).await {
+                Ok(updated) => graph = updated,
+                Err(e) => eprintln!("   ⚠ Graph not updated: {:#}", e),
+            }
+
             let now = chrono_now_short();
-            let report = run_analysis(graph, description, &changed_files, max_ticks)?;
+            let report = run_analysis(
+                &graph,
+                &description,
+                plugin_rules,
+                &changed_files,
+                max_ticks,
+                frontier,
+            )?;
             print_delta(&report, top, &now);
 
             // Run watch scripts on change
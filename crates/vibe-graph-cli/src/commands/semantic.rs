@@ -48,10 +48,12 @@ pub(crate) fn make_embedder(workspace: &Path) -> (Arc<dyn vibe_graph_semantic::E
 /// Load the graph from .self, or fail with a helpful message.
 fn load_graph(path: &Path) -> Result<SourceCodeGraph> {
     let store = Store::new(path);
-    store
+    let graph = store
         .load_graph()
         .context("Failed to load graph")?
-        .context("No graph.json found. Run `vg sync && vg graph` first.")
+        .context("No graph found. Run `vg sync && vg graph` first.")?;
+    super::apply_id_migration(path)?;
+    Ok(graph)
 }
 
 /// Resolve the SemanticStore from a workspace path.
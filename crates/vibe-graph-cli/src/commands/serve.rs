@@ -52,7 +52,7 @@ static EMBEDDED_JS: &[u8] = include_bytes!("../../assets/vibe_graph_bevy.js");
 /// Shared state for the semantic search endpoint.
 struct SemanticApiState {
     index: RwLock<VectorIndex>,
-    graph: Arc<RwLock<vibe_graph_core::SourceCodeGraph>>,
+    graph: Arc<RwLock<Arc<vibe_graph_core::SourceCodeGraph>>>,
     embedder: Arc<dyn vibe_graph_semantic::Embedder>,
 }
 
@@ -136,7 +136,7 @@ async fn semantic_search_handler(
 /// Build a semantic search subrouter. Returns `None` if no index exists.
 fn build_semantic_router(
     workspace_path: &Path,
-    graph: Arc<RwLock<vibe_graph_core::SourceCodeGraph>>,
+    graph: Arc<RwLock<Arc<vibe_graph_core::SourceCodeGraph>>>,
 ) -> Option<Router> {
     let self_dir = workspace_path.join(".self");
     let store = SemanticStore::new(&self_dir);
@@ -288,6 +288,7 @@ pub async fn execute(
         println!("📊 Loading graph...");
         let request = GraphRequest::new(&path);
         let response = ctx.graph(request).await?;
+        super::apply_id_migration(&path)?;
         response.graph
     };
 
@@ -731,6 +732,7 @@ async fn ensure_graph_loaded(
     let mut request = GraphRequest::new(path);
     request.force = needs_sync;
     let response = ctx.graph(request).await?;
+    super::apply_id_migration(path)?;
     println!(
         "✅ Graph: {} nodes, {} edges",
         response.graph.node_count(),
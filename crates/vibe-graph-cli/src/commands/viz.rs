@@ -17,7 +17,7 @@ pub fn execute(path: &Path, automaton: bool) -> Result<()> {
     let store = Store::new(&path);
 
     let source_graph = if store.has_graph() {
-        println!("📊 Loading graph from .self/graph.json");
+        println!("📊 Loading graph from .self");
         store
             .load_graph()
             .context("Failed to load graph")?
@@ -16,8 +16,8 @@ mod config;
 
 // Import ops types
 use vibe_graph_ops::{
-    CleanRequest, Config as OpsConfig, GraphRequest, LoadRequest, OpsContext, Store, SyncRequest,
-    SyncSource, WorkspaceInfo, WorkspaceKind,
+    CleanRequest, Config as OpsConfig, GraphRequest, LoadRequest, OpsContext, OpsError, Store,
+    SyncRequest, SyncSource, WorkspaceInfo, WorkspaceKind,
 };
 
 use commands::compose::OutputFormat;
@@ -138,6 +138,15 @@ enum Commands {
     ///
     /// Creates a graph representation of the codebase with nodes for files/directories
     /// and edges for references (imports, uses) and hierarchy.
+    ///
+    /// Examples:
+    ///   vg graph                       # build (or load) the graph
+    ///   vg graph --force --snapshot    # rescan, rebuild and keep a snapshot at HEAD
+    ///   vg graph --rev main            # graph of main, read from git objects
+    ///   vg graph snapshots             # list graph snapshots
+    ///   vg graph diff main HEAD        # compare two snapshots or revisions
+    ///   vg graph repos                 # dependencies between repositories
+    #[command(args_conflicts_with_subcommands = true)]
     Graph {
         /// Path to workspace (defaults to current directory).
         #[arg(default_value = ".")]
@@ -146,6 +155,29 @@ enum Commands {
         /// Output graph to JSON file.
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Also emit symbol nodes (functions, structs, enums, traits, classes)
+        /// with `calls`/`implements` edges between them.
+        #[arg(long)]
+        symbols: bool,
+
+        /// Rescan the workspace and rebuild instead of using the stored graph.
+        #[arg(long)]
+        force: bool,
+
+        /// Keep a snapshot of the graph, keyed by the HEAD commit (or a
+        /// timestamp outside git).
+        #[arg(long)]
+        snapshot: bool,
+
+        /// Build the graph at a git revision (branch, tag, commit) straight
+        /// from the repository, without checking it out. Graphs are cached
+        /// per tree; --force rebuilds.
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
+
+        #[command(subcommand)]
+        command: Option<GraphCommands>,
     },
 
     /// Serve an interactive visualization of the codebase graph.
@@ -280,6 +312,11 @@ enum Commands {
         /// Target specific files/modules for the goal (repeatable).
         #[arg(long = "target", short = 't')]
         targets: Vec<String>,
+
+        /// Only re-evaluate nodes next to a change on each tick (also
+        /// `frontier = true` under `[automaton]` in vg.toml).
+        #[arg(long)]
+        frontier: bool,
     },
 
     /// Initialize a vg.toml project config.
@@ -361,6 +398,101 @@ enum Commands {
         force: bool,
     },
 
+    /// Check the graph against the architecture rules of the constitution.
+    ///
+    /// Rules are read from the [architecture] table of constitution.toml,
+    /// else vg.toml: forbidden dependencies, targets only some paths may
+    /// depend on, and no cycles between layers. Exits non-zero and lists
+    /// every offending dependency when a rule is broken.
+    ///
+    /// Examples:
+    ///   vg check-architecture                       # current directory
+    ///   vg check-architecture --json                # machine-readable report
+    ///   vg check-architecture --config rules.toml   # rules from another file
+    CheckArchitecture {
+        /// Path to project (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Read rules from this TOML file instead.
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Rebuild the graph before checking.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Export the graph to GraphML, DOT, GEXF, Neo4j CSV or Cypher.
+    ///
+    /// Single-file formats are written to --output, or stdout. neo4j-csv
+    /// writes nodes.csv and relationships.csv into the --output directory
+    /// (default: .self/export).
+    ///
+    /// Examples:
+    ///   vg export --format graphml -o graph.graphml
+    ///   vg export --format dot --kind file --collapse-dirs | dot -Tsvg > deps.svg
+    ///   vg export --format neo4j-csv -o import/
+    Export {
+        /// Path to project (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format: graphml, dot, gexf, neo4j-csv or cypher.
+        #[arg(short, long, default_value = "graphml")]
+        format: String,
+
+        /// Output file (directory for neo4j-csv).
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only export nodes of this kind (file, directory, module, test, symbol, ...).
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Only export nodes that have this metadata key.
+        #[arg(long)]
+        has_metadata: Option<String>,
+
+        /// Collapse files and symbols into their directories.
+        #[arg(long)]
+        collapse_dirs: bool,
+
+        /// Rebuild the graph before exporting.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Run a graph query.
+    ///
+    /// Cypher-like patterns over the SourceCodeGraph with WHERE filters,
+    /// aggregation, HAVING, ORDER BY and LIMIT.
+    ///
+    /// Examples:
+    ///   vg query 'MATCH (f:file) WHERE f.language = "rust" RETURN count(*)'
+    ///   vg query 'MATCH (f:file)<-[:uses]-(u) RETURN f.relative_path AS file, count(u) AS users ORDER BY users DESC LIMIT 10'
+    ///   vg query 'MATCH (a {name: "main.rs"})-[:uses*1..3]->(dep) RETURN DISTINCT dep' --json
+    Query {
+        /// Query text.
+        query: String,
+
+        /// Path to project (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Rebuild the graph before querying.
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Semantic search and embedding management.
     ///
     /// Build a local embedding index over your codebase and search by meaning.
@@ -378,6 +510,18 @@ enum Commands {
     #[command(subcommand)]
     Semantic(SemanticCommands),
 
+    /// Rules and samplers from WebAssembly plugins.
+    ///
+    /// Plugins are `.wasm` modules in .self/plugins/ or declared with
+    /// [[plugins]] in vg.toml; `vg run` registers their rules. Requires
+    /// `--features plugins`.
+    ///
+    /// Examples:
+    ///   vg plugins list                  # plugins with their rules and samplers
+    ///   vg plugins sample todo_count     # run a plugin sampler over the graph
+    #[command(subcommand)]
+    Plugins(PluginCommands),
+
     /// Work with automaton descriptions (generate, infer, run).
     #[command(subcommand)]
     Automaton(AutomatonCommands),
@@ -418,7 +562,11 @@ enum Commands {
         #[arg(long, conflicts_with = "output")]
         temp: bool,
 
-        /// Strategy to use.
+        /// Strategy to use: flat, lattice, preserve or modular.
+        ///
+        /// modular groups files into directories by inferred module
+        /// (communities of the dependency graph) and reports where the
+        /// current directories disagree with them.
         #[arg(long, default_value = "flat")]
         strategy: String,
 
@@ -436,6 +584,73 @@ enum Commands {
     },
 }
 
+/// Graph snapshot commands.
+#[derive(Subcommand, Debug)]
+enum GraphCommands {
+    /// Compare two graph snapshots.
+    ///
+    /// Reports added and removed files, added and removed dependencies
+    /// (highlighting those crossing crate/package boundaries) and components
+    /// whose coupling changed. Snapshots are named by ID, ID prefix or git
+    /// revision; a revision without a snapshot is built from its git tree.
+    ///
+    /// Examples:
+    ///   vg graph diff main              # main vs current graph
+    ///   vg graph diff main feature      # two revisions, no checkout needed
+    ///   vg graph diff 1718000000 --json
+    Diff {
+        /// Older snapshot or revision.
+        from: String,
+
+        /// Newer snapshot or revision (defaults to the current graph).
+        to: Option<String>,
+
+        /// Path to workspace (defaults to current directory).
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Show at most N entries per section.
+        #[arg(long, default_value = "20")]
+        top: usize,
+    },
+
+    /// List graph snapshots, newest first.
+    Snapshots {
+        /// Path to workspace (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show which repositories of a multi-repo workspace depend on which.
+    ///
+    /// Reads the Cargo.toml, package.json, pyproject.toml and go.mod files
+    /// of every repository and matches declared dependencies against the
+    /// packages sibling repositories publish. The same dependencies appear
+    /// in the graph as `depends_on` edges between repository nodes.
+    ///
+    /// Examples:
+    ///   vg remote add my-org && vg remote clone && vg sync
+    ///   vg graph repos
+    ///   vg graph repos --json
+    Repos {
+        /// Path to workspace (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 /// Automaton description commands.
 #[derive(Subcommand, Debug)]
 enum AutomatonCommands {
@@ -501,6 +716,7 @@ enum AutomatonCommands {
     ///   vg automaton run --file src/lib.rs      # seed specific file
     ///   vg automaton run --max-ticks 30         # limit ticks
     ///   vg automaton run --json                 # JSON output
+    ///   vg automaton run --record --label v1     # keep a snapshot to diff later
     Run {
         /// Path to workspace (defaults to current directory).
         #[arg(default_value = ".")]
@@ -529,6 +745,52 @@ enum AutomatonCommands {
         /// Show top N impacted files (default 20).
         #[arg(long, default_value = "20")]
         top: usize,
+
+        /// Record the run: save a snapshot of the final state and the
+        /// transitions of every tick, for `vg automaton diff`.
+        #[arg(long)]
+        record: bool,
+
+        /// Label of the recorded snapshot.
+        #[arg(long, requires = "record")]
+        label: Option<String>,
+
+        /// Only re-evaluate nodes next to a change on each tick (also
+        /// `frontier = true` under `[automaton]` in vg.toml).
+        #[arg(long)]
+        frontier: bool,
+    },
+
+    /// Compare two automaton states node by node.
+    ///
+    /// Reports per-node activation, rule and annotation changes. States are
+    /// named by snapshot timestamp (or prefix), snapshot label, `latest`,
+    /// `state` (the saved state), `tick:N` (tick N of the last recorded run,
+    /// replayed) or a snapshot file.
+    ///
+    /// Examples:
+    ///   vg automaton run --record --label before
+    ///   vg automaton diff before latest
+    ///   vg automaton diff tick:0 tick:5        # how the recorded run evolved
+    ///   vg automaton diff 1718000000 state --json
+    Diff {
+        /// Older state.
+        from: String,
+
+        /// Newer state.
+        to: String,
+
+        /// Path to workspace (defaults to current directory).
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Show at most N changed nodes.
+        #[arg(long, default_value = "20")]
+        top: usize,
     },
 
     /// Export behavioral contracts from the automaton description.
@@ -589,6 +851,39 @@ enum AutomatonCommands {
     },
 }
 
+/// Plugin commands.
+#[derive(Subcommand, Debug)]
+enum PluginCommands {
+    /// List plugins with the rules and samplers they provide.
+    List {
+        /// Path to project (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Run a plugin sampler over the graph and print its artifacts.
+    Sample {
+        /// Sampler ID, with or without the `<plugin>::` prefix.
+        sampler: String,
+
+        /// Path to project (defaults to current directory).
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Rebuild the graph before sampling.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 /// Semantic search commands.
 #[derive(Subcommand, Debug)]
 enum SemanticCommands {
@@ -888,6 +1183,7 @@ async fn main() -> Result<()> {
         goal: None,
         scripts: false,
         targets: Vec::new(),
+        frontier: false,
     });
 
     match command {
@@ -939,11 +1235,30 @@ async fn main() -> Result<()> {
             println!("   Repositories: {}", response.project.repositories.len());
             println!("   Total files:  {}", response.project.total_sources());
             println!("   Total size:   {}", response.project.human_total_size());
+            let excluded: usize = response
+                .project
+                .repositories
+                .iter()
+                .map(|r| r.excluded.len())
+                .sum();
+            if excluded > 0 && !cli.verbose {
+                println!("   Excluded:     {} paths (use -v for reasons)", excluded);
+            } else if excluded > 0 {
+                println!("   Excluded:     {} paths", excluded);
+            }
 
             if cli.verbose {
                 println!();
                 for repo in &response.project.repositories {
                     println!("   📦 {} ({} files)", repo.name, repo.sources.len());
+                    for exclusion in &repo.excluded {
+                        println!(
+                            "      ⊘ {}{} — {}",
+                            exclusion.path.display(),
+                            if exclusion.is_dir { "/" } else { "" },
+                            exclusion.reason
+                        );
+                    }
                 }
             }
 
@@ -1035,22 +1350,90 @@ async fn main() -> Result<()> {
             commands::compose::execute(&cli_config, &mut project, output, format)?;
         }
 
-        Commands::Graph { path, output } => {
+        Commands::Graph {
+            command:
+                Some(GraphCommands::Diff {
+                    from,
+                    to,
+                    path,
+                    json,
+                    top,
+                }),
+            ..
+        } => {
+            commands::graph::diff(&ctx, &path, &from, to.as_deref(), json, top).await?;
+        }
+
+        Commands::Graph {
+            command: Some(GraphCommands::Snapshots { path, json }),
+            ..
+        } => {
+            commands::graph::snapshots(&ctx, &path, json).await?;
+        }
+
+        Commands::Graph {
+            command: Some(GraphCommands::Repos { path, json }),
+            ..
+        } => {
+            commands::graph::repos(&ctx, &path, json).await?;
+        }
+
+        Commands::Graph {
+            path,
+            output,
+            symbols,
+            force,
+            snapshot,
+            rev,
+            command: None,
+        } => {
+            if force && rev.is_none() {
+                println!("📦 Rescanning: {}", path.display());
+                let response = ctx.sync(SyncRequest::local(&path).force()).await?;
+                println!("   Files: {}", response.file_count());
+            }
+
             let mut request = GraphRequest::new(&path);
+            if force {
+                request = request.force();
+            }
             if let Some(output_path) = output.clone() {
                 request = request.with_output(output_path);
             }
+            if symbols {
+                request = request.with_symbols();
+            }
+            if snapshot {
+                request = request.with_snapshot();
+            }
+            match &rev {
+                Some(rev) => {
+                    println!(
+                        "📊 Building SourceCodeGraph for: {} at {}",
+                        path.display(),
+                        rev
+                    );
+                    request = request.at_rev(rev);
+                }
+                None => println!("📊 Building SourceCodeGraph for: {}", path.display()),
+            }
 
-            println!("📊 Building SourceCodeGraph for: {}", path.display());
-
-            let response = ctx.graph(request).await.map_err(|_| {
-                anyhow::anyhow!(
+            let response = ctx.graph(request).await.map_err(|e| match e {
+                OpsError::StoreNotFound { .. } => anyhow::anyhow!(
                     "No .self folder found at {}. Run `vg sync` first.",
                     path.display()
-                )
+                ),
+                e => e.into(),
             })?;
+            if rev.is_none() {
+                commands::apply_id_migration(&path)?;
+            }
 
-            println!("✅ Graph built:");
+            if response.from_cache && rev.is_some() {
+                println!("✅ Graph loaded from tree cache:");
+            } else {
+                println!("✅ Graph built:");
+            }
             println!("   Nodes: {}", response.graph.node_count());
             println!("   Edges: {}", response.graph.edge_count());
             println!("💾 Saved to: {}", response.saved_path.display());
@@ -1058,6 +1441,9 @@ async fn main() -> Result<()> {
             if let Some(output_path) = response.output_path {
                 println!("💾 Also saved to: {}", output_path.display());
             }
+            if let Some(info) = response.snapshot_created {
+                println!("📸 Snapshot: {}", info.id);
+            }
         }
 
         Commands::Serve {
@@ -1104,10 +1490,11 @@ async fn main() -> Result<()> {
             goal,
             scripts,
             targets,
+            frontier,
         } => {
             commands::run::execute(
                 &ctx, &path, force, once, interval, json, snapshot, top, max_ticks, goal, targets,
-                scripts,
+                scripts, frontier,
             )
             .await?;
         }
@@ -1229,6 +1616,48 @@ async fn main() -> Result<()> {
             .await?;
         }
 
+        Commands::CheckArchitecture {
+            path,
+            config,
+            json,
+            force,
+        } => {
+            commands::check_architecture::execute(&ctx, &path, config, json, force).await?;
+        }
+
+        Commands::Export {
+            path,
+            format,
+            output,
+            kind,
+            has_metadata,
+            collapse_dirs,
+            force,
+        } => {
+            commands::export::execute(
+                &ctx,
+                &path,
+                commands::export::ExportArgs {
+                    format: format.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+                    output,
+                    kind,
+                    has_metadata,
+                    collapse_dirs,
+                    force,
+                },
+            )
+            .await?;
+        }
+
+        Commands::Query {
+            query,
+            path,
+            json,
+            force,
+        } => {
+            commands::query::execute(&ctx, &path, &query, json, force).await?;
+        }
+
         Commands::Semantic(semantic_cmd) => match semantic_cmd {
             SemanticCommands::Index { path, force, deep } => {
                 if deep && std::env::var("VG_SEMANTIC_INDEX_WORKER").is_ok() {
@@ -1257,6 +1686,20 @@ async fn main() -> Result<()> {
             }
         },
 
+        Commands::Plugins(plugin_cmd) => match plugin_cmd {
+            PluginCommands::List { path, json } => {
+                commands::plugins::list(&path, json)?;
+            }
+            PluginCommands::Sample {
+                sampler,
+                path,
+                json,
+                force,
+            } => {
+                commands::plugins::sample(&ctx, &path, &sampler, json, force).await?;
+            }
+        },
+
         Commands::Automaton(automaton_cmd) => {
             commands::automaton::execute(&ctx, automaton_cmd).await?;
         }
@@ -1408,9 +1851,13 @@ async fn main() -> Result<()> {
                 if stats.snapshot_count > 0 {
                     println!("   Snapshots:  {}", stats.snapshot_count);
                 }
+                if stats.graph_snapshot_count > 0 {
+                    println!("   Graph snapshots: {}", stats.graph_snapshot_count);
+                }
                 println!(
-                    "   Store size: {}",
-                    humansize::format_size(stats.total_size, humansize::DECIMAL)
+                    "   Store size: {} ({})",
+                    humansize::format_size(stats.total_size, humansize::DECIMAL),
+                    stats.backend
                 );
             } else {
                 println!("💾 .self:      not initialized (run `vg sync`)");
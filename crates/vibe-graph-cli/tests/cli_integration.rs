@@ -28,6 +28,17 @@ fn run_vg_in_dir(dir: &Path, args: &[&str]) -> Output {
         .expect("Failed to execute vg command")
 }
 
+/// Helper to run vg in a directory with the JSON store backend, whose
+/// files the structure tests read directly.
+fn run_vg_json_store(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vg"))
+        .current_dir(dir)
+        .env("VG_STORE_BACKEND", "json")
+        .args(args)
+        .output()
+        .expect("Failed to execute vg command")
+}
+
 /// Create a minimal git repository for testing.
 fn create_test_repo(dir: &Path) {
     // Create .git directory (minimal git repo marker)
@@ -99,8 +110,8 @@ fn test_sync_local_creates_self_folder() {
         ".self folder should be created"
     );
     assert!(
-        test_dir.join(".self/project.json").exists(),
-        "project.json should be created"
+        test_dir.join(".self/store.db").exists(),
+        "store.db should be created"
     );
     assert!(
         test_dir.join(".self/manifest.json").exists(),
@@ -204,13 +215,13 @@ fn test_sync_with_snapshot() {
 
     create_test_repo(test_dir);
 
-    let output = run_vg_in_dir(test_dir, &["sync", "--snapshot", "."]);
+    let output = run_vg_json_store(test_dir, &["sync", "--snapshot", "."]);
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     assert!(output.status.success(), "vg sync --snapshot should succeed");
     assert!(stdout.contains("📸 Snapshot:"), "Should create snapshot");
 
-    // Verify snapshot directory exists
+    // Verify snapshot directory exists (JSON layout)
     let snapshots_dir = test_dir.join(".self/snapshots");
     assert!(snapshots_dir.exists(), "Snapshots directory should exist");
 
@@ -294,8 +305,8 @@ fn test_graph_creates_graph_json() {
 
     assert!(output.status.success(), "vg graph should succeed");
     assert!(
-        test_dir.join(".self/graph.json").exists(),
-        "graph.json should be created"
+        test_dir.join(".self/store.db").exists(),
+        "store.db should be created"
     );
     assert!(
         stdout.contains("Building SourceCodeGraph"),
@@ -355,6 +366,89 @@ fn test_graph_output_path() {
     let _: serde_json::Value = serde_json::from_str(&content).expect("Should be valid JSON");
 }
 
+#[test]
+fn test_graph_snapshot_and_diff() {
+    let temp = TempDir::new().unwrap();
+    let test_dir = temp.path();
+
+    create_test_repo(test_dir);
+    run_vg_in_dir(test_dir, &["sync", "."]);
+
+    let output = run_vg_in_dir(test_dir, &["graph", "--snapshot", "."]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "vg graph --snapshot should succeed"
+    );
+    let id = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("📸 Snapshot: "))
+        .expect("Should report the snapshot")
+        .trim()
+        .to_string();
+
+    let output = run_vg_in_dir(test_dir, &["graph", "snapshots", "."]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&id));
+
+    // A new file shows up once the workspace is rescanned
+    fs::write(test_dir.join("src/extra.rs"), "pub fn extra() {}\n").unwrap();
+    let output = run_vg_in_dir(test_dir, &["graph", "--force", "."]);
+    assert!(output.status.success(), "vg graph --force should succeed");
+
+    let output = run_vg_in_dir(test_dir, &["graph", "diff", &id, "--json"]);
+    assert!(output.status.success(), "vg graph diff should succeed");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["from"]["id"], id.as_str());
+    assert_eq!(
+        json["diff"]["added_files"],
+        serde_json::json!(["src/extra.rs"])
+    );
+
+    let output = run_vg_in_dir(test_dir, &["graph", "diff", "missing"]);
+    assert!(!output.status.success(), "Unknown snapshots should fail");
+}
+
+// =============================================================================
+// Architecture Check Tests
+// =============================================================================
+
+#[test]
+fn test_check_architecture_reports_violations() {
+    let temp = TempDir::new().unwrap();
+    let test_dir = temp.path();
+
+    fs::create_dir_all(test_dir.join(".git")).unwrap();
+    fs::create_dir_all(test_dir.join("src")).unwrap();
+    fs::write(test_dir.join("src/main.rs"), "mod db;\nfn main() {}\n").unwrap();
+    fs::write(test_dir.join("src/db.rs"), "pub fn connect() {}\n").unwrap();
+    run_vg_in_dir(test_dir, &["sync", "."]);
+
+    let output = run_vg_in_dir(test_dir, &["check-architecture", "."]);
+    assert!(!output.status.success(), "Missing rules should fail");
+
+    fs::write(
+        test_dir.join("constitution.toml"),
+        "[[architecture.only]]\nfrom = \"src/api/**\"\nto = \"src/db.rs\"\n",
+    )
+    .unwrap();
+    let output = run_vg_in_dir(test_dir, &["check-architecture", ".", "--json"]);
+    assert!(!output.status.success(), "Violations should fail the check");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["passed"], false);
+    assert_eq!(json["violations"][0]["from"], "src/main.rs");
+    assert_eq!(json["violations"][0]["to"], "src/db.rs");
+
+    fs::write(
+        test_dir.join("constitution.toml"),
+        "[[architecture.forbid]]\nfrom = \"src/db.rs\"\nto = \"src/main.rs\"\n",
+    )
+    .unwrap();
+    let output = run_vg_in_dir(test_dir, &["check-architecture", "."]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Rules that hold should pass");
+    assert!(stdout.contains("All rules hold"));
+}
+
 // =============================================================================
 // Load Command Tests
 // =============================================================================
@@ -482,7 +576,7 @@ fn test_project_json_structure() {
     let test_dir = temp.path();
 
     create_test_repo(test_dir);
-    run_vg_in_dir(test_dir, &["sync", "."]);
+    run_vg_json_store(test_dir, &["sync", "."]);
 
     // Read and parse project.json
     let project_json = fs::read_to_string(test_dir.join(".self/project.json")).unwrap();
@@ -547,8 +641,8 @@ fn test_graph_json_structure() {
     let test_dir = temp.path();
 
     create_test_repo(test_dir);
-    run_vg_in_dir(test_dir, &["sync", "."]);
-    run_vg_in_dir(test_dir, &["graph", "."]);
+    run_vg_json_store(test_dir, &["sync", "."]);
+    run_vg_json_store(test_dir, &["graph", "."]);
 
     // Read and parse graph.json
     let graph_json = fs::read_to_string(test_dir.join(".self/graph.json")).unwrap();
@@ -642,7 +736,7 @@ fn test_multi_repo_project_json_format() {
     let test_dir = temp.path();
 
     create_multi_repo_workspace(test_dir);
-    run_vg_in_dir(test_dir, &["sync", "."]);
+    run_vg_json_store(test_dir, &["sync", "."]);
 
     // Read and parse project.json
     let project_json = fs::read_to_string(test_dir.join(".self/project.json")).unwrap();
@@ -711,6 +805,37 @@ fn test_multi_repo_status_shows_repos() {
     );
 }
 
+#[test]
+fn test_multi_repo_graph_repos_links_published_packages() {
+    let temp = TempDir::new().unwrap();
+    let test_dir = temp.path();
+
+    create_multi_repo_workspace(test_dir);
+    fs::write(
+        test_dir.join("repo1/Cargo.toml"),
+        "[package]\nname = \"shared-model\"\nversion = \"1.2.0\"\n",
+    )
+    .unwrap();
+    fs::write(
+        test_dir.join("repo2/Cargo.toml"),
+        "[package]\nname = \"service\"\n\n[dependencies]\nshared_model = \"^1.2\"\n",
+    )
+    .unwrap();
+
+    run_vg_in_dir(test_dir, &["sync", "."]);
+    let output = run_vg_in_dir(test_dir, &["graph", "repos", ".", "--json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "vg graph repos should succeed");
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    let dependencies = json["dependencies"].as_array().unwrap();
+    assert_eq!(dependencies.len(), 1, "{}", stdout);
+    assert_eq!(dependencies[0]["from"], "repo2");
+    assert_eq!(dependencies[0]["to"], "repo1");
+    assert_eq!(dependencies[0]["package"], "shared-model");
+    assert_eq!(dependencies[0]["requirement"], "^1.2");
+}
+
 #[test]
 fn test_backward_compatible_project_json_load() {
     // Test that we can load a project.json with the old format
@@ -771,4 +896,8 @@ fn test_backward_compatible_project_json_load() {
         output.status.success(),
         "vg load should succeed with old project.json format"
     );
+
+    // ...and, being a read, left the JSON store in place
+    assert!(test_dir.join(".self/project.json").exists());
+    assert!(!test_dir.join(".self/store.db").exists());
 }
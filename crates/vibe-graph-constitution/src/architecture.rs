@@ -0,0 +1,689 @@
+//! Declarative architecture rules, checked against the dependency graph.
+//!
+//! Rules live in an `[architecture]` table of `constitution.toml` or, if
+//! there is none, of `vg.toml`:
+//!
+//! ```toml
+//! [architecture]
+//! no_layer_cycles = true
+//!
+//! [[architecture.layers]]
+//! name = "core"
+//! paths = ["crates/*-core"]
+//!
+//! [[architecture.layers]]
+//! name = "cli"
+//! paths = ["crates/*-cli"]
+//!
+//! [[architecture.forbid]]
+//! from = "core"
+//! to = "cli"
+//! reason = "the domain model must not know about its frontends"
+//!
+//! [[architecture.only]]
+//! from = "src/api/**"
+//! to = "src/db/**"
+//! ```
+//!
+//! A pattern is either the name of a layer or a glob over workspace-relative
+//! paths (`*` stays within a path segment, `**` crosses them). A glob that
+//! matches a directory matches every file below it. Files belong to the
+//! first layer that matches them.
+//!
+//! Rules are evaluated against file-level dependencies: the
+//! non-`contains` edges of the graph, lifted from symbols to their files
+//! (see [`Structure`]). Every dependency that breaks a rule is reported as
+//! its own [`RuleViolation`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use vibe_graph_core::{Dependency, SourceCodeGraph, Structure};
+
+/// File holding the constitution of a workspace.
+pub const CONSTITUTION_FILENAME: &str = "constitution.toml";
+
+/// Project configuration file, read when there is no constitution.
+pub const PROJECT_FILENAME: &str = "vg.toml";
+
+/// Errors raised while loading or compiling architecture rules.
+#[derive(Debug, Error)]
+pub enum RulesError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Invalid pattern '{pattern}': {source}")]
+    Pattern {
+        pattern: String,
+        source: globset::Error,
+    },
+
+    #[error("Invalid architecture rule: {0}")]
+    Invalid(String),
+}
+
+/// The `[architecture]` table of a constitution.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchitectureRules {
+    /// Named groups of files, in precedence order.
+    pub layers: Vec<Layer>,
+    /// Dependencies that must not exist.
+    pub forbid: Vec<ForbidRule>,
+    /// Targets that only some sources may depend on.
+    pub only: Vec<OnlyRule>,
+    /// Forbid dependency cycles between layers.
+    pub no_layer_cycles: bool,
+}
+
+/// A named group of files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    pub paths: Vec<String>,
+}
+
+/// Files matching `from` must not depend on files matching `to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForbidRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(with = "patterns")]
+    pub from: Vec<String>,
+    #[serde(with = "patterns")]
+    pub to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Only files matching `from` (or `to` itself) may depend on files
+/// matching `to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnlyRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(with = "patterns")]
+    pub from: Vec<String>,
+    #[serde(with = "patterns")]
+    pub to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Accepts a single pattern or a list of them.
+mod patterns {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(patterns: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(patterns)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        Ok(match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(pattern) => vec![pattern],
+            OneOrMany::Many(patterns) => patterns,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+    architecture: Option<ArchitectureRules>,
+}
+
+impl ArchitectureRules {
+    /// Load the rules of the workspace at `root`: the `[architecture]` table
+    /// of `constitution.toml`, else of `vg.toml`. Returns the file they came
+    /// from, or `None` if neither declares any.
+    pub fn load(root: &Path) -> Result<Option<(PathBuf, Self)>, RulesError> {
+        for name in [CONSTITUTION_FILENAME, PROJECT_FILENAME] {
+            let path = root.join(name);
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(rules) = Self::load_file(&path)? {
+                return Ok(Some((path, rules)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load the `[architecture]` table of a TOML file.
+    pub fn load_file(path: &Path) -> Result<Option<Self>, RulesError> {
+        let content = std::fs::read_to_string(path).map_err(|source| RulesError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: RulesFile = toml::from_str(&content).map_err(|source| RulesError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(file.architecture)
+    }
+
+    /// Number of rules, counting the layer cycle check as one.
+    pub fn rule_count(&self) -> usize {
+        self.forbid.len() + self.only.len() + usize::from(self.no_layer_cycles)
+    }
+
+    /// Whether no rule is declared (layers alone enforce nothing).
+    pub fn is_empty(&self) -> bool {
+        self.rule_count() == 0
+    }
+}
+
+/// A dependency that breaks an architecture rule.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RuleViolation {
+    /// The rule's name, or a description of it.
+    pub rule: String,
+    /// Workspace-relative path of the depending file.
+    pub from: String,
+    /// Workspace-relative path of the file depended on.
+    pub to: String,
+    /// Edge relationship, e.g. `uses` or `imports`.
+    pub relationship: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Outcome of checking a graph against architecture rules.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchitectureCheck {
+    /// Number of rules checked.
+    pub rules: usize,
+    /// Number of file-level dependencies checked.
+    pub dependencies: usize,
+    /// Violations, sorted by rule, then by edge.
+    pub violations: Vec<RuleViolation>,
+}
+
+impl ArchitectureCheck {
+    /// Whether every rule holds.
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Globs compiled into one set.
+struct Globs(GlobSet);
+
+impl Globs {
+    fn new(patterns: &[&str]) -> Result<Self, RulesError> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(glob(pattern)?);
+        }
+        let set = builder.build().map_err(|source| RulesError::Pattern {
+            pattern: patterns.join(", "),
+            source,
+        })?;
+        Ok(Self(set))
+    }
+
+    fn is_match(&self, file: &str) -> bool {
+        // A match on any ancestor directory covers the file
+        let mut path = Some(Path::new(file));
+        while let Some(current) = path.filter(|p| !p.as_os_str().is_empty()) {
+            if self.0.is_match(current) {
+                return true;
+            }
+            path = current.parent();
+        }
+        false
+    }
+}
+
+fn glob(pattern: &str) -> Result<Glob, RulesError> {
+    GlobBuilder::new(pattern.trim_start_matches("./"))
+        .literal_separator(true)
+        .build()
+        .map_err(|source| RulesError::Pattern {
+            pattern: pattern.to_string(),
+            source,
+        })
+}
+
+/// The patterns of one side of a rule: layer names match the files of
+/// that layer, anything else is a glob.
+struct Matcher {
+    layers: Vec<usize>,
+    globs: Globs,
+}
+
+impl Matcher {
+    fn new(patterns: &[String], layers: &[Layer]) -> Result<Self, RulesError> {
+        let mut indices = Vec::new();
+        let mut globs = Vec::new();
+        for pattern in patterns {
+            match layers.iter().position(|layer| layer.name == *pattern) {
+                Some(index) => indices.push(index),
+                None => globs.push(pattern.as_str()),
+            }
+        }
+        Ok(Self {
+            layers: indices,
+            globs: Globs::new(&globs)?,
+        })
+    }
+
+    fn is_match(&self, file: &FileRef<'_>) -> bool {
+        file.layer.is_some_and(|layer| self.layers.contains(&layer))
+            || self.globs.is_match(file.path)
+    }
+}
+
+/// A file with the layer it belongs to.
+struct FileRef<'a> {
+    path: &'a str,
+    layer: Option<usize>,
+}
+
+struct CompiledRule {
+    label: String,
+    reason: Option<String>,
+    kind: RuleKind,
+}
+
+enum RuleKind {
+    Forbid { from: Matcher, to: Matcher },
+    Only { from: Matcher, to: Matcher },
+}
+
+impl CompiledRule {
+    fn is_violated_by(&self, from_file: &FileRef<'_>, to_file: &FileRef<'_>) -> bool {
+        match &self.kind {
+            RuleKind::Forbid { from, to } => from.is_match(from_file) && to.is_match(to_file),
+            RuleKind::Only { from, to } => {
+                to.is_match(to_file) && !to.is_match(from_file) && !from.is_match(from_file)
+            }
+        }
+    }
+}
+
+/// Checks the dependencies of a graph against [`ArchitectureRules`].
+pub struct ArchitectureEvaluator {
+    layers: Vec<(String, Globs)>,
+    rules: Vec<CompiledRule>,
+    no_layer_cycles: bool,
+}
+
+impl ArchitectureEvaluator {
+    /// Compile `rules`, validating their layers and patterns.
+    pub fn new(rules: &ArchitectureRules) -> Result<Self, RulesError> {
+        let mut names = BTreeSet::new();
+        let mut layers = Vec::with_capacity(rules.layers.len());
+        for layer in &rules.layers {
+            if layer.paths.is_empty() {
+                return Err(RulesError::Invalid(format!(
+                    "layer '{}' has no paths",
+                    layer.name
+                )));
+            }
+            if !names.insert(layer.name.as_str()) {
+                return Err(RulesError::Invalid(format!(
+                    "layer '{}' is declared twice",
+                    layer.name
+                )));
+            }
+            let paths: Vec<&str> = layer.paths.iter().map(String::as_str).collect();
+            layers.push((layer.name.clone(), Globs::new(&paths)?));
+        }
+        if rules.no_layer_cycles && layers.len() < 2 {
+            return Err(RulesError::Invalid(
+                "no_layer_cycles needs at least two layers".to_string(),
+            ));
+        }
+
+        let mut compiled = Vec::new();
+        for rule in &rules.forbid {
+            check_patterns("forbid", &rule.from, &rule.to)?;
+            compiled.push(CompiledRule {
+                label: rule.name.clone().unwrap_or_else(|| {
+                    format!(
+                        "{} must not depend on {}",
+                        rule.from.join(", "),
+                        rule.to.join(", ")
+                    )
+                }),
+                reason: rule.reason.clone(),
+                kind: RuleKind::Forbid {
+                    from: Matcher::new(&rule.from, &rules.layers)?,
+                    to: Matcher::new(&rule.to, &rules.layers)?,
+                },
+            });
+        }
+        for rule in &rules.only {
+            check_patterns("only", &rule.from, &rule.to)?;
+            compiled.push(CompiledRule {
+                label: rule.name.clone().unwrap_or_else(|| {
+                    format!(
+                        "only {} may depend on {}",
+                        rule.from.join(", "),
+                        rule.to.join(", ")
+                    )
+                }),
+                reason: rule.reason.clone(),
+                kind: RuleKind::Only {
+                    from: Matcher::new(&rule.from, &rules.layers)?,
+                    to: Matcher::new(&rule.to, &rules.layers)?,
+                },
+            });
+        }
+
+        Ok(Self {
+            layers,
+            rules: compiled,
+            no_layer_cycles: rules.no_layer_cycles,
+        })
+    }
+
+    /// Check the file-level dependencies of `graph`.
+    pub fn evaluate(&self, graph: &SourceCodeGraph) -> ArchitectureCheck {
+        self.evaluate_structure(&Structure::of(graph))
+    }
+
+    /// Check already extracted file-level dependencies.
+    pub fn evaluate_structure(&self, structure: &Structure) -> ArchitectureCheck {
+        let mut violations = Vec::new();
+        for dependency in &structure.dependencies {
+            let from = self.file(&dependency.from);
+            let to = self.file(&dependency.to);
+            for rule in &self.rules {
+                if rule.is_violated_by(&from, &to) {
+                    violations.push(violation(&rule.label, rule.reason.clone(), dependency));
+                }
+            }
+        }
+        if self.no_layer_cycles {
+            violations.extend(self.layer_cycle_violations(structure));
+        }
+        violations.sort();
+
+        ArchitectureCheck {
+            rules: self.rules.len() + usize::from(self.no_layer_cycles),
+            dependencies: structure.dependencies.len(),
+            violations,
+        }
+    }
+
+    fn file<'a>(&self, path: &'a str) -> FileRef<'a> {
+        FileRef {
+            path,
+            layer: self
+                .layers
+                .iter()
+                .position(|(_, globs)| globs.is_match(path)),
+        }
+    }
+
+    fn layer_of(&self, file: &str) -> Option<&str> {
+        self.file(file)
+            .layer
+            .map(|index| self.layers[index].0.as_str())
+    }
+
+    /// Dependencies along a layer edge that closes a cycle between layers.
+    fn layer_cycle_violations(&self, structure: &Structure) -> Vec<RuleViolation> {
+        let mut crossing: BTreeMap<(&str, &str), Vec<&Dependency>> = BTreeMap::new();
+        for dependency in &structure.dependencies {
+            let (Some(from), Some(to)) = (
+                self.layer_of(&dependency.from),
+                self.layer_of(&dependency.to),
+            ) else {
+                continue;
+            };
+            if from != to {
+                crossing.entry((from, to)).or_default().push(dependency);
+            }
+        }
+
+        let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for &(from, to) in crossing.keys() {
+            successors.entry(from).or_default().push(to);
+        }
+        let reaches = |start: &str, target: &str| {
+            let mut seen = BTreeSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(layer) = stack.pop() {
+                if layer == target {
+                    return true;
+                }
+                for &next in successors.get(layer).into_iter().flatten() {
+                    if seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            false
+        };
+
+        let mut violations = Vec::new();
+        for (&(from, to), dependencies) in &crossing {
+            if !reaches(to, from) {
+                continue;
+            }
+            let label = format!("no cycles between layers ({} → {})", from, to);
+            violations.extend(
+                dependencies
+                    .iter()
+                    .map(|dependency| violation(&label, None, dependency)),
+            );
+        }
+        violations
+    }
+}
+
+fn check_patterns(kind: &str, from: &[String], to: &[String]) -> Result<(), RulesError> {
+    if from.is_empty() || to.is_empty() {
+        return Err(RulesError::Invalid(format!(
+            "{} rules need both `from` and `to` patterns",
+            kind
+        )));
+    }
+    Ok(())
+}
+
+fn violation(rule: &str, reason: Option<String>, dependency: &Dependency) -> RuleViolation {
+    RuleViolation {
+        rule: rule.to_string(),
+        from: dependency.from.clone(),
+        to: dependency.to.clone(),
+        relationship: dependency.relationship.clone(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(from: &str, to: &str) -> Dependency {
+        Dependency {
+            from: from.to_string(),
+            to: to.to_string(),
+            relationship: "uses".to_string(),
+            from_component: String::new(),
+            to_component: String::new(),
+        }
+    }
+
+    fn structure(edges: &[(&str, &str)]) -> Structure {
+        Structure {
+            files: BTreeSet::new(),
+            dependencies: edges
+                .iter()
+                .map(|(from, to)| dependency(from, to))
+                .collect(),
+            coupling: BTreeMap::new(),
+        }
+    }
+
+    fn parse(toml: &str) -> ArchitectureRules {
+        toml::from_str::<RulesFile>(toml)
+            .unwrap()
+            .architecture
+            .unwrap()
+    }
+
+    #[test]
+    fn test_forbid_and_only_rules() {
+        let rules = parse(
+            r#"
+            [[architecture.forbid]]
+            from = "crates/*-core"
+            to = "crates/*-cli/**"
+            reason = "core stays frontend-agnostic"
+
+            [[architecture.only]]
+            from = ["src/api/**"]
+            to = "src/db/**"
+            "#,
+        );
+        let evaluator = ArchitectureEvaluator::new(&rules).unwrap();
+        let check = evaluator.evaluate_structure(&structure(&[
+            ("crates/vg-core/src/lib.rs", "crates/vg-cli/src/main.rs"),
+            ("crates/vg-cli/src/main.rs", "crates/vg-core/src/lib.rs"),
+            ("src/api/users.rs", "src/db/mod.rs"),
+            ("src/db/mod.rs", "src/db/pool.rs"),
+            ("src/web/page.rs", "src/db/pool.rs"),
+        ]));
+
+        assert_eq!(check.rules, 2);
+        assert_eq!(check.dependencies, 5);
+        assert_eq!(check.violations.len(), 2);
+        let forbid = &check.violations[0];
+        assert_eq!(
+            forbid.rule,
+            "crates/*-core must not depend on crates/*-cli/**"
+        );
+        assert_eq!(forbid.from, "crates/vg-core/src/lib.rs");
+        assert_eq!(
+            forbid.reason.as_deref(),
+            Some("core stays frontend-agnostic")
+        );
+        let only = &check.violations[1];
+        assert_eq!(only.rule, "only src/api/** may depend on src/db/**");
+        assert_eq!(
+            (only.from.as_str(), only.to.as_str()),
+            ("src/web/page.rs", "src/db/pool.rs")
+        );
+    }
+
+    #[test]
+    fn test_layer_cycles() {
+        let rules = parse(
+            r#"
+            [architecture]
+            no_layer_cycles = true
+
+            [[architecture.layers]]
+            name = "domain"
+            paths = ["src/domain"]
+
+            [[architecture.layers]]
+            name = "app"
+            paths = ["src/app"]
+
+            [[architecture.layers]]
+            name = "infra"
+            paths = ["src/**"]
+
+            [[architecture.forbid]]
+            name = "domain is pure"
+            from = "domain"
+            to = ["infra"]
+            "#,
+        );
+        let evaluator = ArchitectureEvaluator::new(&rules).unwrap();
+        let check = evaluator.evaluate_structure(&structure(&[
+            ("src/app/run.rs", "src/domain/model.rs"),
+            ("src/domain/model.rs", "src/app/run.rs"),
+            ("src/infra/db.rs", "src/domain/model.rs"),
+        ]));
+
+        let rules: Vec<&str> = check.violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                "no cycles between layers (app → domain)",
+                "no cycles between layers (domain → app)",
+            ]
+        );
+        // src/domain/** matches infra's glob too, but the first layer wins
+        assert!(check.violations.iter().all(|v| v.rule != "domain is pure"));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let bad_glob = parse(
+            r#"
+            [[architecture.forbid]]
+            from = "src/[a"
+            to = "src/b"
+            "#,
+        );
+        assert!(matches!(
+            ArchitectureEvaluator::new(&bad_glob),
+            Err(RulesError::Pattern { .. })
+        ));
+
+        let lonely_layer = parse(
+            r#"
+            [architecture]
+            no_layer_cycles = true
+
+            [[architecture.layers]]
+            name = "core"
+            paths = ["core"]
+            "#,
+        );
+        assert!(matches!(
+            ArchitectureEvaluator::new(&lonely_layer),
+            Err(RulesError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_prefers_constitution() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_FILENAME),
+            "[project]\nname = \"demo\"\n\n[[architecture.forbid]]\nfrom = \"a\"\nto = \"b\"\n",
+        )
+        .unwrap();
+        let (path, rules) = ArchitectureRules::load(dir.path()).unwrap().unwrap();
+        assert_eq!(path, dir.path().join(PROJECT_FILENAME));
+        assert_eq!(rules.forbid.len(), 1);
+
+        std::fs::write(
+            dir.path().join(CONSTITUTION_FILENAME),
+            "[architecture]\nno_layer_cycles = false\n",
+        )
+        .unwrap();
+        let (path, rules) = ArchitectureRules::load(dir.path()).unwrap().unwrap();
+        assert_eq!(path, dir.path().join(CONSTITUTION_FILENAME));
+        assert!(rules.is_empty());
+    }
+}
@@ -1,4 +1,14 @@
 //! Governance and planning utilities for the Vibe-Graph runtime.
+//!
+//! Besides per-change evaluators, a constitution can declare architecture
+//! rules (see [`architecture`]) that the dependency graph must satisfy.
+
+pub mod architecture;
+
+pub use architecture::{
+    ArchitectureCheck, ArchitectureEvaluator, ArchitectureRules, ForbidRule, Layer, OnlyRule,
+    RuleViolation, RulesError,
+};
 
 use vibe_graph_core::{CellState, Constitution, NodeId};
 
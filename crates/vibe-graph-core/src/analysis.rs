@@ -0,0 +1,638 @@
+//! Architecture analysis of a [`SourceCodeGraph`].
+//!
+//! Works on the file-level dependencies and components of [`Structure`]:
+//!
+//! - Dependency cycles: strongly connected components of the component
+//!   graph (crates depending on each other in a loop) and of the file graph
+//! - Centrality: PageRank and betweenness of every file in the dependency
+//!   graph; betweenness is estimated from a sample of source files on large
+//!   graphs
+//! - Martin's package metrics per component: afferent and efferent
+//!   coupling, instability, abstractness and the distance from the main
+//!   sequence, `|A + I - 1|`. Abstractness counts traits and interfaces
+//!   among the component's types, so it needs a graph built with symbols
+//! - Layering violations: dependencies of a component on a less stable one,
+//!   against the Stable Dependencies Principle
+//!
+//! [`ArchitectureAnalysis::annotate`] records the results as node metadata.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::Path;
+
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::structure::{file_key, Components, Dependency, Structure};
+use crate::{node_key, GraphNodeKind, SourceCodeGraph};
+
+/// Damping factor of the PageRank random walk.
+pub const PAGERANK_DAMPING: f64 = 0.85;
+
+/// Maximum PageRank iterations; the walk usually converges much sooner.
+const PAGERANK_ITERATIONS: usize = 100;
+
+/// Total rank change below which PageRank has converged.
+const PAGERANK_TOLERANCE: f64 = 1e-9;
+
+/// Above this many files, betweenness is estimated from this many sources.
+pub const BETWEENNESS_EXACT_LIMIT: usize = 2_000;
+
+/// Instability difference below which a dependency is not a violation.
+const INSTABILITY_EPSILON: f64 = 1e-9;
+
+/// Martin's metrics of one component.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleMetrics {
+    /// Number of files in the component.
+    pub files: usize,
+    /// Afferent coupling: components depending on this one.
+    pub afferent: usize,
+    /// Efferent coupling: components this one depends on.
+    pub efferent: usize,
+    /// `efferent / (afferent + efferent)`, 0 for an isolated component.
+    pub instability: f64,
+    /// Share of abstract types (traits, interfaces) among the component's
+    /// types. `None` when the graph has no symbol nodes for it.
+    pub abstractness: Option<f64>,
+    /// Distance from the main sequence, `|A + I - 1|`. `None` when the
+    /// abstractness is unknown.
+    pub distance: Option<f64>,
+    /// Index of the component cycle this component is part of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<usize>,
+}
+
+/// A strongly connected set of components or files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyCycle {
+    /// Components or files in the cycle, sorted.
+    pub members: Vec<String>,
+    /// File-level dependencies between members of the cycle.
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A component depending on a less stable one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayeringViolation {
+    /// Depending component.
+    pub from: String,
+    /// Component depended upon.
+    pub to: String,
+    /// Instability of `from`.
+    pub from_instability: f64,
+    /// Instability of `to`, higher than that of `from`.
+    pub to_instability: f64,
+    /// Number of file-level dependencies from `from` to `to`.
+    pub dependencies: usize,
+}
+
+/// Centrality of one file in the dependency graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileCentrality {
+    /// Workspace-relative path.
+    pub file: String,
+    /// Component of the file.
+    pub component: String,
+    /// PageRank, with rank flowing from dependents to dependencies.
+    pub pagerank: f64,
+    /// Normalized betweenness: share of shortest dependency paths between
+    /// other files that pass through this one.
+    pub betweenness: f64,
+}
+
+/// Cycles, centrality, package metrics and layering violations of a graph.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchitectureAnalysis {
+    /// Cycles between components, largest first.
+    pub component_cycles: Vec<DependencyCycle>,
+    /// Cycles between files, largest first.
+    pub file_cycles: Vec<DependencyCycle>,
+    /// Metrics of every component.
+    pub modules: BTreeMap<String, ModuleMetrics>,
+    /// Dependencies on less stable components, worst first.
+    pub layering_violations: Vec<LayeringViolation>,
+    /// Every file, highest PageRank first.
+    pub centrality: Vec<FileCentrality>,
+    /// Whether betweenness was estimated from a sample of files.
+    pub betweenness_sampled: bool,
+}
+
+impl ArchitectureAnalysis {
+    /// Analyze a graph.
+    pub fn of(graph: &SourceCodeGraph) -> Self {
+        let components = Components::detect(graph);
+        let structure = Structure::of(graph);
+
+        let component_cycles = component_cycles(&structure);
+        let file_cycles = file_cycles(&structure);
+        let modules = module_metrics(graph, &components, &structure, &component_cycles);
+        let layering_violations = layering_violations(&structure, &modules);
+        let (centrality, betweenness_sampled) = centrality(&components, &structure);
+
+        Self {
+            component_cycles,
+            file_cycles,
+            modules,
+            layering_violations,
+            centrality,
+            betweenness_sampled,
+        }
+    }
+
+    /// Record the results as node metadata: `pagerank`, `betweenness` and
+    /// `file_cycle` on files; coupling, `instability`, `abstractness`,
+    /// `main_sequence_distance` and `component_cycle` on the directory of
+    /// each component.
+    pub fn annotate(&self, graph: &mut SourceCodeGraph) {
+        let centrality: HashMap<&str, &FileCentrality> = self
+            .centrality
+            .iter()
+            .map(|c| (c.file.as_str(), c))
+            .collect();
+        let file_cycles: HashMap<&str, usize> = self
+            .file_cycles
+            .iter()
+            .enumerate()
+            .flat_map(|(i, cycle)| cycle.members.iter().map(move |m| (m.as_str(), i)))
+            .collect();
+
+        let root = graph
+            .metadata
+            .get("root")
+            .map(|r| Path::new(r).to_path_buf());
+        let keys: Vec<Option<String>> = graph
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                GraphNodeKind::Symbol => None,
+                GraphNodeKind::Directory => node
+                    .metadata
+                    .get("path")
+                    .map(|path| node_key(Path::new(path), root.as_deref())),
+                _ => file_key(graph, node),
+            })
+            .collect();
+
+        for (node, key) in graph.nodes.iter_mut().zip(keys) {
+            let Some(key) = key else {
+                continue;
+            };
+            let metadata = &mut node.metadata;
+            if node.kind == GraphNodeKind::Directory {
+                let Some(module) = self.modules.get(&key) else {
+                    continue;
+                };
+                metadata.insert("afferent_coupling".into(), module.afferent.to_string());
+                metadata.insert("efferent_coupling".into(), module.efferent.to_string());
+                metadata.insert("instability".into(), format!("{:.4}", module.instability));
+                if let Some(abstractness) = module.abstractness {
+                    metadata.insert("abstractness".into(), format!("{:.4}", abstractness));
+                }
+                if let Some(distance) = module.distance {
+                    metadata.insert("main_sequence_distance".into(), format!("{:.4}", distance));
+                }
+                if let Some(cycle) = module.cycle {
+                    metadata.insert("component_cycle".into(), cycle.to_string());
+                }
+                continue;
+            }
+
+            if let Some(c) = centrality.get(key.as_str()) {
+                metadata.insert("pagerank".into(), format!("{:.6}", c.pagerank));
+                metadata.insert("betweenness".into(), format!("{:.6}", c.betweenness));
+            }
+            if let Some(cycle) = file_cycles.get(key.as_str()) {
+                metadata.insert("file_cycle".into(), cycle.to_string());
+            }
+        }
+    }
+}
+
+/// Distinct `(from, to)` pairs of a set of dependencies.
+fn edge_pairs<'a>(
+    dependencies: impl Iterator<Item = (&'a str, &'a str)>,
+) -> BTreeSet<(&'a str, &'a str)> {
+    dependencies.filter(|(from, to)| from != to).collect()
+}
+
+/// Strongly connected components with more than one member.
+fn cycles<'a>(
+    nodes: impl IntoIterator<Item = &'a str>,
+    edges: &BTreeSet<(&'a str, &'a str)>,
+) -> Vec<BTreeSet<&'a str>> {
+    let mut graph: DiGraph<&str, ()> = DiGraph::new();
+    let index: HashMap<&str, NodeIndex> = nodes
+        .into_iter()
+        .map(|name| (name, graph.add_node(name)))
+        .collect();
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(from), index.get(to)) {
+            graph.add_edge(a, b, ());
+        }
+    }
+
+    let mut cycles: Vec<BTreeSet<&str>> = tarjan_scc(&graph)
+        .into_iter()
+        .filter(|scc| scc.len() > 1)
+        .map(|scc| scc.into_iter().map(|i| graph[i]).collect())
+        .collect();
+    cycles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    cycles
+}
+
+fn component_cycles(structure: &Structure) -> Vec<DependencyCycle> {
+    let edges = edge_pairs(
+        structure
+            .dependencies
+            .iter()
+            .map(|d| (d.from_component.as_str(), d.to_component.as_str())),
+    );
+    cycles(structure.coupling.keys().map(String::as_str), &edges)
+        .into_iter()
+        .map(|members| DependencyCycle {
+            dependencies: structure
+                .dependencies
+                .iter()
+                .filter(|d| {
+                    d.is_cross_component()
+                        && members.contains(d.from_component.as_str())
+                        && members.contains(d.to_component.as_str())
+                })
+                .cloned()
+                .collect(),
+            members: members.into_iter().map(String::from).collect(),
+        })
+        .collect()
+}
+
+fn file_cycles(structure: &Structure) -> Vec<DependencyCycle> {
+    let edges = edge_pairs(
+        structure
+            .dependencies
+            .iter()
+            .map(|d| (d.from.as_str(), d.to.as_str())),
+    );
+    cycles(structure.files.iter().map(String::as_str), &edges)
+        .into_iter()
+        .map(|members| DependencyCycle {
+            dependencies: structure
+                .dependencies
+                .iter()
+                .filter(|d| members.contains(d.from.as_str()) && members.contains(d.to.as_str()))
+                .cloned()
+                .collect(),
+            members: members.into_iter().map(String::from).collect(),
+        })
+        .collect()
+}
+
+fn module_metrics(
+    graph: &SourceCodeGraph,
+    components: &Components,
+    structure: &Structure,
+    component_cycles: &[DependencyCycle],
+) -> BTreeMap<String, ModuleMetrics> {
+    // (abstract types, all types) per component
+    let mut types: HashMap<String, (usize, usize)> = HashMap::new();
+    for node in graph
+        .nodes
+        .iter()
+        .filter(|n| n.kind == GraphNodeKind::Symbol)
+    {
+        let is_abstract = match node.metadata.get("symbol_kind").map(String::as_str) {
+            Some("trait") => true,
+            Some("struct" | "enum" | "class") => false,
+            _ => continue,
+        };
+        let Some(file) = file_key(graph, node) else {
+            continue;
+        };
+        let counts = types.entry(components.component_of(&file)).or_default();
+        counts.0 += usize::from(is_abstract);
+        counts.1 += 1;
+    }
+
+    let mut files: HashMap<String, usize> = HashMap::new();
+    for file in &structure.files {
+        *files.entry(components.component_of(file)).or_default() += 1;
+    }
+
+    structure
+        .coupling
+        .iter()
+        .map(|(component, coupling)| {
+            let abstractness = types
+                .get(component)
+                .filter(|(_, total)| *total > 0)
+                .map(|(abstract_types, total)| *abstract_types as f64 / *total as f64);
+            let metrics = ModuleMetrics {
+                files: files.get(component).copied().unwrap_or(0),
+                afferent: coupling.afferent,
+                efferent: coupling.efferent,
+                instability: coupling.instability,
+                abstractness,
+                distance: abstractness.map(|a| (a + coupling.instability - 1.0).abs()),
+                cycle: component_cycles
+                    .iter()
+                    .position(|cycle| cycle.members.contains(component)),
+            };
+            (component.clone(), metrics)
+        })
+        .collect()
+}
+
+fn layering_violations(
+    structure: &Structure,
+    modules: &BTreeMap<String, ModuleMetrics>,
+) -> Vec<LayeringViolation> {
+    let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for dep in structure
+        .dependencies
+        .iter()
+        .filter(|d| d.is_cross_component())
+    {
+        *counts
+            .entry((dep.from_component.as_str(), dep.to_component.as_str()))
+            .or_default() += 1;
+    }
+
+    let mut violations: Vec<LayeringViolation> = counts
+        .into_iter()
+        .filter_map(|((from, to), dependencies)| {
+            let from_instability = modules.get(from)?.instability;
+            let to_instability = modules.get(to)?.instability;
+            (to_instability > from_instability + INSTABILITY_EPSILON).then(|| LayeringViolation {
+                from: from.to_string(),
+                to: to.to_string(),
+                from_instability,
+                to_instability,
+                dependencies,
+            })
+        })
+        .collect();
+    violations.sort_by(|a, b| {
+        let gap = |v: &LayeringViolation| v.to_instability - v.from_instability;
+        gap(b)
+            .total_cmp(&gap(a))
+            .then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to)))
+    });
+    violations
+}
+
+/// PageRank and betweenness of every file, and whether betweenness was
+/// sampled.
+fn centrality(components: &Components, structure: &Structure) -> (Vec<FileCentrality>, bool) {
+    let files: Vec<&str> = structure.files.iter().map(String::as_str).collect();
+    let index: HashMap<&str, usize> = files.iter().enumerate().map(|(i, f)| (*f, i)).collect();
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+    let edges = edge_pairs(
+        structure
+            .dependencies
+            .iter()
+            .map(|d| (d.from.as_str(), d.to.as_str())),
+    );
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(from), index.get(to)) {
+            adjacency[a].push(b);
+        }
+    }
+
+    let ranks = pagerank(&adjacency);
+    let sampled = files.len() > BETWEENNESS_EXACT_LIMIT;
+    let betweenness = betweenness(&adjacency, BETWEENNESS_EXACT_LIMIT);
+
+    let mut centrality: Vec<FileCentrality> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| FileCentrality {
+            file: file.to_string(),
+            component: components.component_of(file),
+            pagerank: ranks[i],
+            betweenness: betweenness[i],
+        })
+        .collect();
+    centrality.sort_by(|a, b| {
+        b.pagerank
+            .total_cmp(&a.pagerank)
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    (centrality, sampled)
+}
+
+/// PageRank by power iteration. Rank of nodes without outgoing edges is
+/// spread evenly.
+fn pagerank(adjacency: &[Vec<usize>]) -> Vec<f64> {
+    let n = adjacency.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let uniform = 1.0 / n as f64;
+    let mut ranks = vec![uniform; n];
+    for _ in 0..PAGERANK_ITERATIONS {
+        let dangling: f64 = adjacency
+            .iter()
+            .zip(&ranks)
+            .filter(|(out, _)| out.is_empty())
+            .map(|(_, rank)| rank)
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING) * uniform + PAGERANK_DAMPING * dangling * uniform;
+        let mut next = vec![base; n];
+        for (node, out) in adjacency.iter().enumerate() {
+            if out.is_empty() {
+                continue;
+            }
+            let share = PAGERANK_DAMPING * ranks[node] / out.len() as f64;
+            for &target in out {
+                next[target] += share;
+            }
+        }
+        let change: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if change < PAGERANK_TOLERANCE {
+            break;
+        }
+    }
+    ranks
+}
+
+/// Normalized betweenness (Brandes). With more than `max_sources` nodes,
+/// shortest paths are only counted from an evenly spaced sample of sources
+/// and scaled up.
+fn betweenness(adjacency: &[Vec<usize>], max_sources: usize) -> Vec<f64> {
+    let n = adjacency.len();
+    let mut scores = vec![0.0; n];
+    if n < 3 {
+        return scores;
+    }
+    let step = n.div_ceil(max_sources.max(1));
+    let sources: Vec<usize> = (0..n).step_by(step).collect();
+
+    let mut order = Vec::with_capacity(n);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut paths = vec![0.0f64; n];
+    let mut distance = vec![usize::MAX; n];
+    let mut dependency = vec![0.0f64; n];
+    let mut queue = VecDeque::new();
+    for &source in &sources {
+        order.clear();
+        for i in 0..n {
+            predecessors[i].clear();
+            paths[i] = 0.0;
+            distance[i] = usize::MAX;
+            dependency[i] = 0.0;
+        }
+        paths[source] = 1.0;
+        distance[source] = 0;
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &adjacency[node] {
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[node] + 1;
+                    queue.push_back(next);
+                }
+                if distance[next] == distance[node] + 1 {
+                    paths[next] += paths[node];
+                    predecessors[next].push(node);
+                }
+            }
+        }
+        while let Some(node) = order.pop() {
+            for &previous in &predecessors[node] {
+                dependency[previous] += paths[previous] / paths[node] * (1.0 + dependency[node]);
+            }
+            if node != source {
+                scores[node] += dependency[node];
+            }
+        }
+    }
+
+    let scale = n as f64 / sources.len() as f64 / ((n - 1) * (n - 2)) as f64;
+    for score in &mut scores {
+        *score *= scale;
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SourceCodeGraphBuilder, SymbolDefinition, SymbolKind};
+    use std::path::PathBuf;
+
+    const FILES: &[&str] = &[
+        "app/Cargo.toml",
+        "app/src/main.rs",
+        "core/Cargo.toml",
+        "core/src/lib.rs",
+        "core/src/model.rs",
+        "util/Cargo.toml",
+        "util/src/lib.rs",
+    ];
+
+    /// Three crates with their directories, and a trait and a struct in
+    /// `core/src/model.rs`.
+    fn workspace(edges: &[(&str, &str)]) -> SourceCodeGraph {
+        let root = PathBuf::from("/ws");
+        let graph = crate::structure::workspace(FILES, edges);
+        let mut builder = SourceCodeGraphBuilder::from_graph(graph);
+        builder.add_directory(&root);
+        for dir in ["app", "core", "util"] {
+            builder.add_directory(&root.join(dir));
+        }
+        let trait_def = |name: &str, kind| SymbolDefinition {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind,
+            line: 1,
+        };
+        let model = root.join("core/src/model.rs");
+        builder.add_symbol(&model, &trait_def("Repository", SymbolKind::Trait));
+        builder.add_symbol(&model, &trait_def("User", SymbolKind::Struct));
+        builder.build()
+    }
+
+    #[test]
+    fn test_cycles_and_module_metrics() {
+        let graph = workspace(&[
+            ("app/src/main.rs", "core/src/lib.rs"),
+            ("core/src/lib.rs", "core/src/model.rs"),
+            ("core/src/model.rs", "util/src/lib.rs"),
+            ("util/src/lib.rs", "core/src/lib.rs"),
+        ]);
+        let analysis = ArchitectureAnalysis::of(&graph);
+
+        assert_eq!(analysis.component_cycles.len(), 1);
+        assert_eq!(analysis.component_cycles[0].members, vec!["core", "util"]);
+        assert_eq!(analysis.component_cycles[0].dependencies.len(), 2);
+        assert_eq!(analysis.file_cycles.len(), 1);
+        assert_eq!(analysis.file_cycles[0].members.len(), 3);
+
+        let core = &analysis.modules["core"];
+        assert_eq!((core.afferent, core.efferent), (2, 1));
+        assert!((core.instability - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(core.abstractness, Some(0.5));
+        assert!((core.distance.unwrap() - (0.5 + 1.0 / 3.0 - 1.0_f64).abs()).abs() < 1e-9);
+        assert_eq!(core.cycle, Some(0));
+        assert_eq!(analysis.modules["app"].abstractness, None);
+        assert_eq!(analysis.modules["app"].cycle, None);
+
+        // core (I = 1/3) depends on util (I = 1/2)
+        assert_eq!(analysis.layering_violations.len(), 1);
+        assert_eq!(analysis.layering_violations[0].from, "core");
+        assert_eq!(analysis.layering_violations[0].to, "util");
+    }
+
+    #[test]
+    fn test_centrality_ranks_hubs_and_bridges() {
+        let graph = workspace(&[
+            ("app/src/main.rs", "core/src/lib.rs"),
+            ("core/src/lib.rs", "util/src/lib.rs"),
+            ("core/src/model.rs", "util/src/lib.rs"),
+        ]);
+        let analysis = ArchitectureAnalysis::of(&graph);
+        assert!(!analysis.betweenness_sampled);
+
+        let by_file: HashMap<&str, &FileCentrality> = analysis
+            .centrality
+            .iter()
+            .map(|c| (c.file.as_str(), c))
+            .collect();
+        assert_eq!(analysis.centrality[0].file, "util/src/lib.rs");
+        assert!(by_file["core/src/lib.rs"].betweenness > 0.0);
+        assert_eq!(by_file["app/src/main.rs"].betweenness, 0.0);
+        let total: f64 = analysis.centrality.iter().map(|c| c.pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        // A chain: each inner node lies on two of the six ordered pairs
+        let adjacency = vec![vec![1], vec![2], vec![3], vec![]];
+        let third = 1.0 / 3.0;
+        assert_eq!(betweenness(&adjacency, 4), vec![0.0, third, third, 0.0]);
+    }
+
+    #[test]
+    fn test_annotate_records_metrics_as_metadata() {
+        let mut graph = workspace(&[
+            ("core/src/lib.rs", "util/src/lib.rs"),
+            ("util/src/lib.rs", "core/src/lib.rs"),
+        ]);
+        let analysis = ArchitectureAnalysis::of(&graph);
+        analysis.annotate(&mut graph);
+
+        let node = |path: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.metadata.get("path").map(String::as_str) == Some(path))
+                .unwrap()
+        };
+        let lib = node("/ws/core/src/lib.rs");
+        assert!(lib.metadata.contains_key("pagerank"));
+        assert_eq!(lib.metadata["file_cycle"], "0");
+        let core = node("/ws/core");
+        assert_eq!(core.metadata["instability"], "0.5000");
+        assert_eq!(core.metadata["component_cycle"], "0");
+        assert_eq!(core.metadata["abstractness"], "0.5000");
+    }
+}
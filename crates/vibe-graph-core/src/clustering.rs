@@ -0,0 +1,561 @@
+//! Inferred modules: communities of files in the dependency graph.
+//!
+//! [`ModuleClustering::detect`] runs Louvain modularity optimization over
+//! the file-level dependencies of [`Structure`], treated as an undirected
+//! graph weighted by the number of dependencies between two files. Each
+//! community is an inferred module. Files without dependencies are left
+//! unclustered: nothing says where they belong.
+//!
+//! Every module is compared with the current layout: its home directory is
+//! the one holding most of its files, and files elsewhere are reported as
+//! misplaced. The modularity of the directory layout itself is computed
+//! over the same graph, so the two partitions can be compared directly.
+//!
+//! [`ModuleClustering::annotate`] adds the modules to a graph as
+//! [`GraphNodeKind::Module`] nodes.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::structure::{file_key, Structure};
+use crate::{GraphEdge, GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+/// Maximum local-moving passes per Louvain level.
+const MAX_PASSES: usize = 100;
+
+/// Modularity gain below which a move is not an improvement.
+const MIN_GAIN: f64 = 1e-12;
+
+/// Metadata marking module nodes added by [`ModuleClustering::annotate`].
+pub const INFERRED_MODULE_KEY: &str = "inferred_module";
+
+/// A community of files that depend mostly on each other.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferredModule {
+    /// Unique name: the home directory's, or the most connected file's
+    /// when another module already has that.
+    pub name: String,
+    /// Directory holding most of the module's files (`.` for the root).
+    pub directory: String,
+    /// Workspace-relative paths of the member files, sorted.
+    pub files: Vec<String>,
+    /// Share of the files that live in `directory`.
+    pub cohesion: f64,
+    /// Dependencies between member files.
+    pub internal_dependencies: usize,
+    /// Dependencies between a member and a file outside the module.
+    pub external_dependencies: usize,
+}
+
+/// A file whose directory differs from its inferred module's home.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MisplacedFile {
+    pub file: String,
+    /// Current directory of the file.
+    pub directory: String,
+    /// Inferred module the file belongs to.
+    pub module: String,
+    /// Home directory of that module.
+    pub module_directory: String,
+}
+
+/// Inferred modules of a graph, compared with its directory layout.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleClustering {
+    /// Modules, largest first, then by their first file.
+    pub modules: Vec<InferredModule>,
+    /// Files without dependencies, sorted.
+    pub unclustered: Vec<String>,
+    /// Files placed outside their module's home directory, sorted.
+    pub misplaced: Vec<MisplacedFile>,
+    /// Modularity of the inferred modules.
+    pub modularity: f64,
+    /// Modularity of grouping the same files by directory.
+    pub directory_modularity: f64,
+}
+
+/// Undirected weighted graph over dense node indices.
+struct WeightedGraph {
+    /// Neighbors and edge weights; a self-loop appears once with twice
+    /// the weight of the edges it stands for.
+    adjacency: Vec<Vec<(usize, f64)>>,
+    /// Weighted degree of each node, self-loops included.
+    degree: Vec<f64>,
+    /// Sum of all degrees, `2m`.
+    total: f64,
+}
+
+impl WeightedGraph {
+    fn new(size: usize, weights: &BTreeMap<(usize, usize), f64>) -> Self {
+        let mut adjacency = vec![Vec::new(); size];
+        let mut degree = vec![0.0; size];
+        for (&(a, b), &weight) in weights {
+            if a == b {
+                adjacency[a].push((a, weight));
+                degree[a] += weight;
+            } else {
+                adjacency[a].push((b, weight));
+                adjacency[b].push((a, weight));
+                degree[a] += weight;
+                degree[b] += weight;
+            }
+        }
+        let total = degree.iter().sum();
+        Self {
+            adjacency,
+            degree,
+            total,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.degree.len()
+    }
+
+    /// Modularity of `community`, one community index per node.
+    fn modularity(&self, community: &[usize]) -> f64 {
+        if self.total == 0.0 {
+            return 0.0;
+        }
+        let mut internal: HashMap<usize, f64> = HashMap::new();
+        let mut totals: HashMap<usize, f64> = HashMap::new();
+        for node in 0..self.len() {
+            *totals.entry(community[node]).or_default() += self.degree[node];
+            for &(neighbor, weight) in &self.adjacency[node] {
+                if community[neighbor] == community[node] {
+                    *internal.entry(community[node]).or_default() += weight;
+                }
+            }
+        }
+        totals
+            .iter()
+            .map(|(c, total)| {
+                internal.get(c).copied().unwrap_or(0.0) / self.total - (total / self.total).powi(2)
+            })
+            .sum()
+    }
+
+    /// One Louvain level: move nodes between communities while modularity
+    /// improves. Returns dense community indices and whether any node moved.
+    fn local_moving(&self) -> (Vec<usize>, bool) {
+        let mut community: Vec<usize> = (0..self.len()).collect();
+        let mut totals = self.degree.clone();
+        let mut moved = false;
+
+        for _ in 0..MAX_PASSES {
+            let mut improved = false;
+            for node in 0..self.len() {
+                let current = community[node];
+                let degree = self.degree[node];
+                let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+                for &(neighbor, weight) in &self.adjacency[node] {
+                    if neighbor != node {
+                        *links.entry(community[neighbor]).or_default() += weight;
+                    }
+                }
+
+                totals[current] -= degree;
+                let gain = |c: usize, links: f64| links - totals[c] * degree / self.total;
+                let mut best = current;
+                let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+                for (&candidate, &weight) in &links {
+                    let candidate_gain = gain(candidate, weight);
+                    if candidate_gain > best_gain + MIN_GAIN {
+                        best = candidate;
+                        best_gain = candidate_gain;
+                    }
+                }
+                totals[best] += degree;
+
+                if best != current {
+                    community[node] = best;
+                    improved = true;
+                    moved = true;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        (renumber(&community), moved)
+    }
+
+    /// Collapse each community into one node.
+    fn aggregate(&self, community: &[usize]) -> Self {
+        let size = community.iter().max().map_or(0, |max| max + 1);
+        let mut weights: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        for node in 0..self.len() {
+            for &(neighbor, weight) in &self.adjacency[node] {
+                let (a, b) = (community[node], community[neighbor]);
+                if node == neighbor {
+                    *weights.entry((a, a)).or_default() += weight;
+                } else if a == b {
+                    // Seen from both ends: together they form the self-loop
+                    *weights.entry((a, a)).or_default() += weight;
+                } else if a < b {
+                    *weights.entry((a, b)).or_default() += weight;
+                }
+            }
+        }
+        Self::new(size, &weights)
+    }
+}
+
+/// Map community labels to `0..n` in order of first appearance.
+fn renumber(community: &[usize]) -> Vec<usize> {
+    let mut labels: HashMap<usize, usize> = HashMap::new();
+    community
+        .iter()
+        .map(|c| {
+            let next = labels.len();
+            *labels.entry(*c).or_insert(next)
+        })
+        .collect()
+}
+
+/// Louvain communities of `graph`, one index per node.
+fn louvain(graph: &WeightedGraph) -> Vec<usize> {
+    let mut membership: Vec<usize> = (0..graph.len()).collect();
+    let mut level = None;
+    loop {
+        let current = level.as_ref().unwrap_or(graph);
+        let (community, moved) = current.local_moving();
+        if !moved {
+            break;
+        }
+        for c in membership.iter_mut() {
+            *c = community[*c];
+        }
+        level = Some(current.aggregate(&community));
+    }
+    renumber(&membership)
+}
+
+/// Directory of a workspace-relative file path, `.` for the root.
+fn directory_of(file: &str) -> &str {
+    file.rsplit_once('/').map_or(".", |(dir, _)| dir)
+}
+
+impl ModuleClustering {
+    /// Infer the modules of `graph` from its file-level dependencies.
+    pub fn detect(graph: &SourceCodeGraph) -> Self {
+        let structure = Structure::of(graph);
+
+        let mut pairs: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+        for dependency in &structure.dependencies {
+            let (a, b) = (dependency.from.as_str(), dependency.to.as_str());
+            let key = if a < b { (a, b) } else { (b, a) };
+            *pairs.entry(key).or_default() += 1.0;
+        }
+        let connected: BTreeSet<&str> = pairs.keys().flat_map(|&(a, b)| [a, b]).collect();
+        let files: Vec<&str> = connected.into_iter().collect();
+        let index: HashMap<&str, usize> = files.iter().enumerate().map(|(i, f)| (*f, i)).collect();
+        let weights: BTreeMap<(usize, usize), f64> = pairs
+            .iter()
+            .map(|(&(a, b), &weight)| ((index[a], index[b]), weight))
+            .collect();
+        let weighted = WeightedGraph::new(files.len(), &weights);
+
+        let community = louvain(&weighted);
+        let directories: Vec<usize> = {
+            let mut ids: HashMap<&str, usize> = HashMap::new();
+            files
+                .iter()
+                .map(|file| {
+                    let next = ids.len();
+                    *ids.entry(directory_of(file)).or_insert(next)
+                })
+                .collect()
+        };
+
+        let mut members: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for (file, &c) in files.iter().zip(&community) {
+            members.entry(c).or_default().push(file);
+        }
+        let mut groups: Vec<Vec<&str>> = members.into_values().collect();
+        groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let mut degree: HashMap<&str, usize> = HashMap::new();
+        for dependency in &structure.dependencies {
+            *degree.entry(dependency.from.as_str()).or_default() += 1;
+            *degree.entry(dependency.to.as_str()).or_default() += 1;
+        }
+
+        // Internal and external dependency counts per group, in one pass
+        let group_of: HashMap<&str, usize> = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(i, group)| group.iter().map(move |file| (*file, i)))
+            .collect();
+        let mut tallies = vec![(0, 0); groups.len()];
+        for dependency in &structure.dependencies {
+            let from = group_of.get(dependency.from.as_str());
+            let to = group_of.get(dependency.to.as_str());
+            match (from, to) {
+                (Some(a), Some(b)) if a == b => tallies[*a].0 += 1,
+                _ => {
+                    for &i in from.into_iter().chain(to) {
+                        tallies[i].1 += 1;
+                    }
+                }
+            }
+        }
+
+        let mut modules = Vec::with_capacity(groups.len());
+        let mut names = BTreeSet::new();
+        for (group, (internal, external)) in groups.into_iter().zip(tallies) {
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for file in &group {
+                *counts.entry(directory_of(file)).or_default() += 1;
+            }
+            // Most files first, then the shallowest, then alphabetical
+            let (directory, home) = counts
+                .iter()
+                .max_by(|(a, x), (b, y)| {
+                    x.cmp(y)
+                        .then_with(|| b.matches('/').count().cmp(&a.matches('/').count()))
+                        .then_with(|| b.cmp(a))
+                })
+                .map(|(dir, count)| (dir.to_string(), *count))
+                .unwrap_or_default();
+
+            // A second module from the same directory is named after its
+            // most connected file instead
+            let mut base = match directory.rsplit('/').next() {
+                Some(".") | None => "root".to_string(),
+                Some(segment) => segment.to_string(),
+            };
+            if names.contains(&base) {
+                if let Some(stem) = group
+                    .iter()
+                    .max_by(|a, b| degree[*a].cmp(&degree[*b]).then_with(|| b.cmp(a)))
+                    .and_then(|file| Path::new(file).file_stem())
+                    .and_then(|stem| stem.to_str())
+                {
+                    base = stem.to_string();
+                }
+            }
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !names.insert(name.clone()) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+
+            modules.push(InferredModule {
+                name,
+                cohesion: home as f64 / group.len() as f64,
+                directory,
+                files: group.iter().map(|f| f.to_string()).collect(),
+                internal_dependencies: internal,
+                external_dependencies: external,
+            });
+        }
+
+        let mut misplaced: Vec<MisplacedFile> = modules
+            .iter()
+            .flat_map(|module| {
+                module
+                    .files
+                    .iter()
+                    .filter(|file| directory_of(file) != module.directory)
+                    .map(|file| MisplacedFile {
+                        file: file.clone(),
+                        directory: directory_of(file).to_string(),
+                        module: module.name.clone(),
+                        module_directory: module.directory.clone(),
+                    })
+            })
+            .collect();
+        misplaced.sort();
+
+        let unclustered = structure
+            .files
+            .iter()
+            .filter(|file| !index.contains_key(file.as_str()))
+            .cloned()
+            .collect();
+
+        Self {
+            modules,
+            unclustered,
+            misplaced,
+            modularity: weighted.modularity(&community),
+            directory_modularity: weighted.modularity(&directories),
+        }
+    }
+
+    /// Inferred module of every clustered file.
+    pub fn module_of(&self) -> HashMap<&str, &InferredModule> {
+        self.modules
+            .iter()
+            .flat_map(|module| module.files.iter().map(move |f| (f.as_str(), module)))
+            .collect()
+    }
+
+    /// Add a [`GraphNodeKind::Module`] node per inferred module, with a
+    /// `contains` edge to each member file, and tag the files with
+    /// `inferred_module`. Module nodes from an earlier call are replaced.
+    pub fn annotate(&self, graph: &mut SourceCodeGraph) {
+        let previous: HashSet<NodeId> = graph
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.kind == GraphNodeKind::Module
+                    && node.metadata.contains_key(INFERRED_MODULE_KEY)
+            })
+            .map(|node| node.id)
+            .collect();
+        graph.nodes.retain(|node| !previous.contains(&node.id));
+        graph
+            .edges
+            .retain(|edge| !previous.contains(&edge.from) && !previous.contains(&edge.to));
+
+        let module_of = self.module_of();
+        let keys: Vec<Option<String>> = graph
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                GraphNodeKind::Symbol | GraphNodeKind::Directory => None,
+                _ => file_key(graph, node),
+            })
+            .collect();
+        let mut members: HashMap<&str, Vec<NodeId>> = HashMap::new();
+        for (node, key) in graph.nodes.iter_mut().zip(keys) {
+            let Some(module) = key.and_then(|key| module_of.get(key.as_str()).copied()) else {
+                node.metadata.remove(INFERRED_MODULE_KEY);
+                continue;
+            };
+            node.metadata
+                .insert(INFERRED_MODULE_KEY.into(), module.name.clone());
+            members
+                .entry(module.name.as_str())
+                .or_default()
+                .push(node.id);
+        }
+
+        let mut next_edge = graph.edges.iter().map(|e| e.id.0 + 1).max().unwrap_or(0);
+        for module in &self.modules {
+            let id = NodeId::from_key(&format!("module:{}", module.name));
+            let mut metadata = HashMap::new();
+            metadata.insert(INFERRED_MODULE_KEY.into(), module.name.clone());
+            metadata.insert("directory".into(), module.directory.clone());
+            metadata.insert("files".into(), module.files.len().to_string());
+            metadata.insert("cohesion".into(), format!("{:.4}", module.cohesion));
+            graph.nodes.push(GraphNode {
+                id,
+                name: module.name.clone(),
+                kind: GraphNodeKind::Module,
+                metadata,
+            });
+            for &file in members.get(module.name.as_str()).into_iter().flatten() {
+                graph.edges.push(GraphEdge {
+                    id: crate::EdgeId(next_edge),
+                    from: id,
+                    to: file,
+                    relationship: "contains".to_string(),
+                    metadata: HashMap::new(),
+                });
+                next_edge += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReferenceKind, SourceCodeGraphBuilder};
+    use std::path::PathBuf;
+
+    /// Two tight triangles joined by one edge, with `parse/lexer.rs`
+    /// living in the wrong directory.
+    fn two_clusters() -> SourceCodeGraph {
+        let root = PathBuf::from("/ws");
+        let mut builder = SourceCodeGraphBuilder::new().with_root(&root);
+        let files = [
+            "parse/ast.rs",
+            "parse/token.rs",
+            "render/lexer.rs",
+            "render/html.rs",
+            "render/css.rs",
+            "render/layout.rs",
+            "README.md",
+        ];
+        for file in files {
+            builder.add_workspace_file(file);
+        }
+        let edges = [
+            ("parse/ast.rs", "parse/token.rs"),
+            ("parse/token.rs", "render/lexer.rs"),
+            ("render/lexer.rs", "parse/ast.rs"),
+            ("render/html.rs", "render/css.rs"),
+            ("render/css.rs", "render/layout.rs"),
+            ("render/layout.rs", "render/html.rs"),
+            ("render/html.rs", "parse/ast.rs"),
+        ];
+        for (from, to) in edges {
+            let from = builder.get_node_id(&root.join(from)).unwrap();
+            let to = builder.get_node_id(&root.join(to)).unwrap();
+            builder.add_edge(from, to, ReferenceKind::Uses);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_detects_communities_and_misplaced_files() {
+        let clustering = ModuleClustering::detect(&two_clusters());
+
+        let modules: Vec<(&str, &str, usize)> = clustering
+            .modules
+            .iter()
+            .map(|m| (m.name.as_str(), m.directory.as_str(), m.files.len()))
+            .collect();
+        assert_eq!(
+            modules,
+            vec![("parse", "parse", 3), ("render", "render", 3)]
+        );
+        assert_eq!(clustering.modules[0].internal_dependencies, 3);
+        assert_eq!(clustering.modules[0].external_dependencies, 1);
+        assert_eq!(clustering.unclustered, vec!["README.md"]);
+        assert_eq!(
+            clustering.misplaced,
+            vec![MisplacedFile {
+                file: "render/lexer.rs".into(),
+                directory: "render".into(),
+                module: "parse".into(),
+                module_directory: "parse".into(),
+            }]
+        );
+        // 7 edges, 6 inside the two triangles
+        let expected = 6.0 / 7.0 - 2.0 * (7.0_f64 / 14.0).powi(2);
+        assert!((clustering.modularity - expected).abs() < 1e-9);
+        assert!(clustering.directory_modularity < clustering.modularity);
+    }
+
+    #[test]
+    fn test_annotate_adds_module_nodes() {
+        let mut graph = two_clusters();
+        let clustering = ModuleClustering::detect(&graph);
+        clustering.annotate(&mut graph);
+        clustering.annotate(&mut graph);
+
+        let modules: Vec<&GraphNode> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.kind == GraphNodeKind::Module)
+            .collect();
+        assert_eq!(modules.len(), 2);
+        let parse = modules.iter().find(|n| n.name == "parse").unwrap();
+        let members = graph.edges.iter().filter(|e| e.from == parse.id).count();
+        assert_eq!(members, 3);
+
+        let lexer = graph.nodes.iter().find(|n| n.name == "lexer.rs").unwrap();
+        assert_eq!(lexer.metadata[INFERRED_MODULE_KEY], "parse");
+        // Module nodes have no path, so they stay out of the file structure
+        assert_eq!(Structure::of(&graph).dependencies.len(), 7);
+    }
+}
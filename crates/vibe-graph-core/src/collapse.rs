@@ -0,0 +1,289 @@
+//! Aggregated views of a [`SourceCodeGraph`].
+//!
+//! [`SourceCodeGraph::collapse`] replaces files (and the symbols inside
+//! them) by groups: their directory cut at some depth, their component
+//! (crate or package, see [`Components`]), or the value of a metadata key.
+//! Each group becomes one node listing its member files; the references
+//! between members of different groups are merged into one edge per group
+//! pair and relationship, weighted by how many references it stands for.
+//! `contains` edges and directory nodes are dropped: the groups replace
+//! the hierarchy.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::structure::{file_key, Components};
+use crate::{EdgeId, GraphEdge, GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+/// Metadata key listing the member files of a collapsed node, one
+/// workspace-relative path per line.
+pub const MEMBERS_KEY: &str = "members";
+
+/// Metadata key counting the member files of a collapsed node.
+pub const MEMBER_COUNT_KEY: &str = "member_count";
+
+/// Metadata key counting the references a collapsed edge stands for.
+pub const WEIGHT_KEY: &str = "weight";
+
+/// How files are grouped by [`SourceCodeGraph::collapse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollapseBy {
+    /// Directory of the file, keeping at most `depth` path segments
+    /// (`0` puts everything in the root).
+    Directory { depth: usize },
+    /// Crate or package containing the file.
+    Component,
+    /// Value of a node metadata key. Files without the key are left out.
+    Metadata(String),
+}
+
+impl std::str::FromStr for CollapseBy {
+    type Err = String;
+
+    /// Parse `crate` / `package` / `component`, `directory` (full depth),
+    /// `directory:<depth>` (or `dir:<depth>`) or `metadata:<key>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, argument) = match s.split_once(':') {
+            Some((kind, argument)) => (kind, Some(argument)),
+            None => (s, None),
+        };
+        match (kind.to_ascii_lowercase().as_str(), argument) {
+            ("crate" | "package" | "component", None) => Ok(CollapseBy::Component),
+            ("directory" | "dir", None) => Ok(CollapseBy::Directory { depth: usize::MAX }),
+            ("directory" | "dir", Some(depth)) => depth
+                .parse()
+                .map(|depth| CollapseBy::Directory { depth })
+                .map_err(|_| format!("invalid directory depth '{}'", depth)),
+            ("metadata", Some(key)) if !key.is_empty() => Ok(CollapseBy::Metadata(key.to_string())),
+            _ => Err(format!(
+                "unknown grouping '{}' (expected crate, directory, directory:<depth> or metadata:<key>)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CollapseBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollapseBy::Directory { depth: usize::MAX } => write!(f, "directory"),
+            CollapseBy::Directory { depth } => write!(f, "directory:{}", depth),
+            CollapseBy::Component => write!(f, "crate"),
+            CollapseBy::Metadata(key) => write!(f, "metadata:{}", key),
+        }
+    }
+}
+
+/// Directory of a workspace-relative file path cut at `depth` segments.
+fn directory_prefix(file: &str, depth: usize) -> String {
+    let Some((dir, _)) = file.rsplit_once('/') else {
+        return ".".to_string();
+    };
+    if depth == 0 {
+        return ".".to_string();
+    }
+    dir.split('/').take(depth).collect::<Vec<_>>().join("/")
+}
+
+impl SourceCodeGraph {
+    /// Collapse files and symbols into groups (see the [module docs](self)).
+    pub fn collapse(&self, by: &CollapseBy) -> SourceCodeGraph {
+        let components = matches!(by, CollapseBy::Component).then(|| Components::detect(self));
+        let files: HashMap<NodeId, String> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind != GraphNodeKind::Directory)
+            .filter_map(|node| Some((node.id, file_key(self, node)?)))
+            .collect();
+        // Metadata groups come from the file node, also for its symbols
+        let file_metadata: HashMap<&str, &GraphNode> = self
+            .nodes
+            .iter()
+            .filter(|node| !matches!(node.kind, GraphNodeKind::Directory | GraphNodeKind::Symbol))
+            .filter_map(|node| Some((files.get(&node.id)?.as_str(), node)))
+            .collect();
+
+        let group_of = |file: &str| -> Option<String> {
+            match by {
+                CollapseBy::Directory { depth } => Some(directory_prefix(file, *depth)),
+                CollapseBy::Component => components.as_ref().map(|c| c.component_of(file)),
+                CollapseBy::Metadata(key) => file_metadata.get(file)?.metadata.get(key).cloned(),
+            }
+        };
+
+        let mut groups: HashMap<NodeId, String> = HashMap::new();
+        let mut members: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+        for (&id, file) in &files {
+            let Some(group) = group_of(file) else {
+                continue;
+            };
+            members.entry(group.clone()).or_default().insert(file);
+            groups.insert(id, group);
+        }
+
+        let group_id = |group: &str| match by {
+            // Same ID as the directory node of that path
+            CollapseBy::Directory { .. } | CollapseBy::Component => NodeId::from_key(group),
+            CollapseBy::Metadata(key) => NodeId::from_key(&format!("{}={}", key, group)),
+        };
+        let kind = match by {
+            CollapseBy::Metadata(_) => GraphNodeKind::Module,
+            _ => GraphNodeKind::Directory,
+        };
+        let nodes = members
+            .iter()
+            .map(|(group, files)| {
+                let mut metadata = HashMap::new();
+                metadata.insert(
+                    MEMBERS_KEY.to_string(),
+                    files.iter().copied().collect::<Vec<_>>().join("\n"),
+                );
+                metadata.insert(MEMBER_COUNT_KEY.to_string(), files.len().to_string());
+                if kind == GraphNodeKind::Directory {
+                    metadata.insert("relative_path".to_string(), group.clone());
+                }
+                GraphNode {
+                    id: group_id(group),
+                    name: group.clone(),
+                    kind,
+                    metadata,
+                }
+            })
+            .collect();
+
+        let mut weights: BTreeMap<(&str, &str, &str), usize> = BTreeMap::new();
+        for edge in self.edges.iter().filter(|e| e.relationship != "contains") {
+            let (Some(from), Some(to)) = (groups.get(&edge.from), groups.get(&edge.to)) else {
+                continue;
+            };
+            if from != to {
+                *weights
+                    .entry((from.as_str(), to.as_str(), edge.relationship.as_str()))
+                    .or_default() += 1;
+            }
+        }
+        let edges = weights
+            .into_iter()
+            .enumerate()
+            .map(|(i, ((from, to, relationship), weight))| {
+                let mut metadata = HashMap::new();
+                metadata.insert(WEIGHT_KEY.to_string(), weight.to_string());
+                GraphEdge {
+                    id: EdgeId(i as u64),
+                    from: group_id(from),
+                    to: group_id(to),
+                    relationship: relationship.to_string(),
+                    metadata,
+                }
+            })
+            .collect();
+
+        let mut metadata = self.metadata.clone();
+        metadata.insert("collapsed_by".to_string(), by.to_string());
+        SourceCodeGraph {
+            nodes,
+            edges,
+            metadata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReferenceKind, SourceCodeGraphBuilder, SymbolDefinition, SymbolKind};
+    use std::path::PathBuf;
+
+    fn workspace() -> SourceCodeGraph {
+        let root = PathBuf::from("/ws");
+        let mut builder = SourceCodeGraphBuilder::new().with_root(&root);
+        for file in [
+            "app/Cargo.toml",
+            "app/src/main.rs",
+            "app/src/cli/args.rs",
+            "core/Cargo.toml",
+            "core/src/lib.rs",
+        ] {
+            builder.add_workspace_file(file);
+        }
+        let model = SymbolDefinition {
+            name: "Model".to_string(),
+            qualified_name: "Model".to_string(),
+            kind: SymbolKind::Struct,
+            line: 1,
+        };
+        let symbol = builder
+            .add_symbol(&root.join("core/src/lib.rs"), &model)
+            .unwrap();
+        let id = |builder: &SourceCodeGraphBuilder, file: &str| {
+            builder.get_node_id(&root.join(file)).unwrap()
+        };
+        let (main, args, lib) = (
+            id(&builder, "app/src/main.rs"),
+            id(&builder, "app/src/cli/args.rs"),
+            id(&builder, "core/src/lib.rs"),
+        );
+        builder.add_edge(main, lib, ReferenceKind::Uses);
+        builder.add_edge(args, symbol, ReferenceKind::Uses);
+        builder.add_edge(main, args, ReferenceKind::Uses);
+        builder.set_node_metadata(main, "team", "cli");
+        builder.set_node_metadata(args, "team", "cli");
+        builder.set_node_metadata(lib, "team", "domain");
+        builder.build()
+    }
+
+    fn edge_weights(view: &SourceCodeGraph) -> Vec<(String, String, String)> {
+        let names: HashMap<NodeId, &str> =
+            view.nodes.iter().map(|n| (n.id, n.name.as_str())).collect();
+        view.edges
+            .iter()
+            .map(|e| {
+                (
+                    names[&e.from].to_string(),
+                    names[&e.to].to_string(),
+                    e.metadata[WEIGHT_KEY].clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapse_by_component_weights_references() {
+        let view = workspace().collapse(&CollapseBy::Component);
+
+        let names: Vec<&str> = view.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "core"]);
+        assert_eq!(
+            view.nodes[0].metadata[MEMBERS_KEY],
+            "app/Cargo.toml\napp/src/cli/args.rs\napp/src/main.rs"
+        );
+        assert_eq!(view.nodes[0].metadata[MEMBER_COUNT_KEY], "3");
+        // main.rs → lib.rs and args.rs → Model both cross the crate boundary
+        assert_eq!(
+            edge_weights(&view),
+            vec![("app".to_string(), "core".to_string(), "2".to_string())]
+        );
+        assert_eq!(view.metadata["collapsed_by"], "crate");
+    }
+
+    #[test]
+    fn test_collapse_by_directory_depth_and_metadata() {
+        let graph = workspace();
+
+        let view = graph.collapse(&"directory:2".parse().unwrap());
+        let names: Vec<&str> = view.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "app/src", "core", "core/src"]);
+        assert_eq!(view.nodes[1].metadata[MEMBER_COUNT_KEY], "2");
+        assert_eq!(view.edges.len(), 1);
+
+        let view = graph.collapse(&"metadata:team".parse().unwrap());
+        assert!(view.nodes.iter().all(|n| n.kind == GraphNodeKind::Module));
+        assert_eq!(
+            edge_weights(&view),
+            vec![("cli".to_string(), "domain".to_string(), "2".to_string())]
+        );
+
+        assert!("directory:x".parse::<CollapseBy>().is_err());
+        assert!("metadata".parse::<CollapseBy>().is_err());
+    }
+}
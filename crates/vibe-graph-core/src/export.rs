@@ -0,0 +1,744 @@
+//! Export a [`SourceCodeGraph`] to graph interchange formats.
+//!
+//! Supported targets are GraphML, Graphviz DOT, GEXF (Gephi), the
+//! `neo4j-admin import` CSV layout and a Cypher script. Node and edge
+//! metadata become typed attributes: a key whose values all parse as
+//! booleans, integers or floats is declared with that type, anything else
+//! is a string.
+//!
+//! [`ExportOptions`] narrows the export to nodes matching a
+//! [`NodeSelector`] and can collapse files and symbols into their
+//! directories, aggregating the edges between them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write};
+use std::path::Path;
+
+use crate::collapse::CollapseBy;
+use crate::{GraphNodeKind, NodeId, NodeSelector, SourceCodeGraph};
+
+/// Target format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    GraphMl,
+    Dot,
+    Gexf,
+    /// `nodes.csv` + `relationships.csv` for `neo4j-admin database import`.
+    Neo4jCsv,
+    Cypher,
+}
+
+impl ExportFormat {
+    /// Whether the format produces several files.
+    pub fn is_multi_file(&self) -> bool {
+        matches!(self, ExportFormat::Neo4jCsv)
+    }
+
+    /// Conventional file extension of a single-file format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::GraphMl => "graphml",
+            ExportFormat::Dot => "dot",
+            ExportFormat::Gexf => "gexf",
+            ExportFormat::Neo4jCsv => "csv",
+            ExportFormat::Cypher => "cypher",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "graphml" => Ok(ExportFormat::GraphMl),
+            "dot" | "graphviz" => Ok(ExportFormat::Dot),
+            "gexf" => Ok(ExportFormat::Gexf),
+            "neo4j-csv" | "csv" => Ok(ExportFormat::Neo4jCsv),
+            "cypher" => Ok(ExportFormat::Cypher),
+            _ => Err(format!(
+                "unknown export format '{}' (expected graphml, dot, gexf, neo4j-csv or cypher)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExportFormat::GraphMl => "graphml",
+            ExportFormat::Dot => "dot",
+            ExportFormat::Gexf => "gexf",
+            ExportFormat::Neo4jCsv => "neo4j-csv",
+            ExportFormat::Cypher => "cypher",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What part of the graph to export.
+#[derive(Debug, Default)]
+pub struct ExportOptions {
+    /// Only nodes passing the selector (and edges between them) are exported.
+    pub selector: NodeSelector,
+    /// Replace files and symbols by their directory (see
+    /// [`SourceCodeGraph::collapse`]). Parallel edges between two
+    /// directories are merged and counted in a `weight` attribute.
+    pub collapse_directories: bool,
+}
+
+/// One file produced by an export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedFile {
+    /// Suggested file name, e.g. `graph.graphml` or `nodes.csv`.
+    pub file_name: String,
+    pub contents: String,
+}
+
+/// Export `graph` in `format`.
+pub fn export_graph(
+    graph: &SourceCodeGraph,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Vec<ExportedFile> {
+    let view = export_view(graph, options);
+    let schema = Schema::infer(&view);
+    let single = |contents: String| {
+        vec![ExportedFile {
+            file_name: format!("graph.{}", format.extension()),
+            contents,
+        }]
+    };
+
+    match format {
+        ExportFormat::GraphMl => single(to_graphml(&view, &schema)),
+        ExportFormat::Dot => single(to_dot(&view)),
+        ExportFormat::Gexf => single(to_gexf(&view, &schema)),
+        ExportFormat::Cypher => single(to_cypher(&view, &schema)),
+        ExportFormat::Neo4jCsv => {
+            let (nodes, relationships) = to_neo4j_csv(&view, &schema);
+            vec![
+                ExportedFile {
+                    file_name: "nodes.csv".to_string(),
+                    contents: nodes,
+                },
+                ExportedFile {
+                    file_name: "relationships.csv".to_string(),
+                    contents: relationships,
+                },
+            ]
+        }
+    }
+}
+
+/// The graph that will actually be written: selected nodes, optionally
+/// collapsed to directories.
+pub fn export_view(graph: &SourceCodeGraph, options: &ExportOptions) -> SourceCodeGraph {
+    let selected: HashSet<NodeId> = graph
+        .nodes
+        .iter()
+        .filter(|n| options.selector.matches(n))
+        .map(|n| n.id)
+        .collect();
+    let view = SourceCodeGraph {
+        nodes: graph
+            .nodes
+            .iter()
+            .filter(|n| selected.contains(&n.id))
+            .cloned()
+            .collect(),
+        edges: graph
+            .edges
+            .iter()
+            .filter(|e| selected.contains(&e.from) && selected.contains(&e.to))
+            .cloned()
+            .collect(),
+        metadata: graph.metadata.clone(),
+    };
+
+    if options.collapse_directories {
+        view.collapse(&CollapseBy::Directory { depth: usize::MAX })
+    } else {
+        view
+    }
+}
+
+// =============================================================================
+// Attribute typing
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum AttrType {
+    Boolean,
+    Long,
+    Double,
+    String,
+}
+
+impl AttrType {
+    fn of(value: &str) -> Self {
+        if value == "true" || value == "false" {
+            AttrType::Boolean
+        } else if value.parse::<i64>().is_ok() {
+            AttrType::Long
+        } else if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            AttrType::Double
+        } else {
+            AttrType::String
+        }
+    }
+
+    /// Narrowest type that holds both. Booleans only mix with booleans.
+    fn widen(self, other: AttrType) -> AttrType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (AttrType::Long, AttrType::Double) | (AttrType::Double, AttrType::Long) => {
+                AttrType::Double
+            }
+            _ => AttrType::String,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AttrType::Boolean => "boolean",
+            AttrType::Long => "long",
+            AttrType::Double => "double",
+            AttrType::String => "string",
+        }
+    }
+}
+
+/// Metadata keys of nodes and edges with their inferred types, sorted by
+/// key so exports are deterministic.
+struct Schema {
+    node: BTreeMap<String, AttrType>,
+    edge: BTreeMap<String, AttrType>,
+}
+
+impl Schema {
+    fn infer(graph: &SourceCodeGraph) -> Self {
+        fn collect<'a>(
+            maps: impl Iterator<Item = &'a HashMap<String, String>>,
+        ) -> BTreeMap<String, AttrType> {
+            let mut types: BTreeMap<String, AttrType> = BTreeMap::new();
+            for map in maps {
+                for (key, value) in map {
+                    let ty = AttrType::of(value);
+                    types
+                        .entry(key.clone())
+                        .and_modify(|t| *t = t.widen(ty))
+                        .or_insert(ty);
+                }
+            }
+            types
+        }
+
+        Self {
+            node: collect(graph.nodes.iter().map(|n| &n.metadata)),
+            edge: collect(graph.edges.iter().map(|e| &e.metadata)),
+        }
+    }
+}
+
+/// Sorted metadata entries, so attribute order is stable.
+fn sorted(metadata: &HashMap<String, String>) -> BTreeMap<&str, &str> {
+    metadata
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+// =============================================================================
+// Writers
+// =============================================================================
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Double-quoted string with backslash escapes (DOT and Cypher).
+fn quoted(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn graph_name(graph: &SourceCodeGraph) -> String {
+    graph
+        .metadata
+        .get("name")
+        .cloned()
+        .or_else(|| {
+            graph
+                .metadata
+                .get("root")
+                .and_then(|root| Path::new(root).file_name())
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "vibe-graph".to_string())
+}
+
+fn to_graphml(graph: &SourceCodeGraph, schema: &Schema) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+
+    let key = |out: &mut String, id: &str, domain: &str, name: &str, ty: AttrType| {
+        let _ = writeln!(
+            out,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            xml_escape(id),
+            domain,
+            xml_escape(name),
+            ty.name()
+        );
+    };
+    key(&mut out, "n:name", "node", "name", AttrType::String);
+    key(&mut out, "n:kind", "node", "kind", AttrType::String);
+    for (name, ty) in &schema.node {
+        key(&mut out, &format!("n:m:{}", name), "node", name, *ty);
+    }
+    key(
+        &mut out,
+        "e:relationship",
+        "edge",
+        "relationship",
+        AttrType::String,
+    );
+    for (name, ty) in &schema.edge {
+        key(&mut out, &format!("e:m:{}", name), "edge", name, *ty);
+    }
+
+    let _ = writeln!(
+        out,
+        "  <graph id=\"{}\" edgedefault=\"directed\">",
+        xml_escape(&graph_name(graph))
+    );
+    let data = |out: &mut String, key: &str, value: &str| {
+        let _ = writeln!(
+            out,
+            "      <data key=\"{}\">{}</data>",
+            xml_escape(key),
+            xml_escape(value)
+        );
+    };
+    for node in &graph.nodes {
+        let _ = writeln!(out, "    <node id=\"n{}\">", node.id.0);
+        data(&mut out, "n:name", &node.name);
+        data(&mut out, "n:kind", node.kind.as_str());
+        for (name, value) in sorted(&node.metadata) {
+            data(&mut out, &format!("n:m:{}", name), value);
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">",
+            edge.id.0, edge.from.0, edge.to.0
+        );
+        data(&mut out, "e:relationship", &edge.relationship);
+        for (name, value) in sorted(&edge.metadata) {
+            data(&mut out, &format!("e:m:{}", name), value);
+        }
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn to_dot(graph: &SourceCodeGraph) -> String {
+    // DOT attributes are untyped; numbers and booleans are written bare
+    let value = |v: &str| match AttrType::of(v) {
+        AttrType::String => quoted(v),
+        _ => v.to_string(),
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph {} {{", quoted(&graph_name(graph)));
+    out.push_str("  node [shape=box];\n");
+    for node in &graph.nodes {
+        let _ = write!(
+            out,
+            "  n{} [label={}, kind={}",
+            node.id.0,
+            quoted(&node.name),
+            quoted(node.kind.as_str())
+        );
+        for (name, v) in sorted(&node.metadata) {
+            let _ = write!(out, ", {}={}", quoted(name), value(v));
+        }
+        out.push_str("];\n");
+    }
+    for edge in &graph.edges {
+        let _ = write!(
+            out,
+            "  n{} -> n{} [label={}",
+            edge.from.0,
+            edge.to.0,
+            quoted(&edge.relationship)
+        );
+        for (name, v) in sorted(&edge.metadata) {
+            let _ = write!(out, ", {}={}", quoted(name), value(v));
+        }
+        out.push_str("];\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn to_gexf(graph: &SourceCodeGraph, schema: &Schema) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    let _ = writeln!(
+        out,
+        "  <meta>\n    <creator>vibe-graph</creator>\n    <description>{}</description>\n  </meta>",
+        xml_escape(&graph_name(graph))
+    );
+    out.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+
+    // Attribute ids are positions in the schema, with `kind` first for nodes
+    out.push_str("    <attributes class=\"node\">\n");
+    out.push_str("      <attribute id=\"0\" title=\"kind\" type=\"string\"/>\n");
+    let node_ids: HashMap<&str, usize> = schema
+        .node
+        .keys()
+        .enumerate()
+        .map(|(i, k)| (k.as_str(), i + 1))
+        .collect();
+    for (name, ty) in &schema.node {
+        let _ = writeln!(
+            out,
+            "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>",
+            node_ids[name.as_str()],
+            xml_escape(name),
+            ty.name()
+        );
+    }
+    out.push_str("    </attributes>\n");
+    out.push_str("    <attributes class=\"edge\">\n");
+    let edge_ids: HashMap<&str, usize> = schema
+        .edge
+        .keys()
+        .enumerate()
+        .map(|(i, k)| (k.as_str(), i))
+        .collect();
+    for (name, ty) in &schema.edge {
+        let _ = writeln!(
+            out,
+            "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>",
+            edge_ids[name.as_str()],
+            xml_escape(name),
+            ty.name()
+        );
+    }
+    out.push_str("    </attributes>\n");
+
+    let attvalue = |out: &mut String, id: usize, value: &str| {
+        let _ = writeln!(
+            out,
+            "          <attvalue for=\"{}\" value=\"{}\"/>",
+            id,
+            xml_escape(value)
+        );
+    };
+    out.push_str("    <nodes>\n");
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "      <node id=\"n{}\" label=\"{}\">\n        <attvalues>",
+            node.id.0,
+            xml_escape(&node.name)
+        );
+        attvalue(&mut out, 0, node.kind.as_str());
+        for (name, value) in sorted(&node.metadata) {
+            attvalue(&mut out, node_ids[name], value);
+        }
+        out.push_str("        </attvalues>\n      </node>\n");
+    }
+    out.push_str("    </nodes>\n    <edges>\n");
+    for edge in &graph.edges {
+        let weight = edge
+            .metadata
+            .get("weight")
+            .filter(|w| w.parse::<f64>().is_ok())
+            .map(|w| format!(" weight=\"{}\"", w))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "      <edge id=\"e{}\" source=\"n{}\" target=\"n{}\" label=\"{}\"{}>\n        <attvalues>",
+            edge.id.0,
+            edge.from.0,
+            edge.to.0,
+            xml_escape(&edge.relationship),
+            weight
+        );
+        for (name, value) in sorted(&edge.metadata) {
+            attvalue(&mut out, edge_ids[name], value);
+        }
+        out.push_str("        </attvalues>\n      </edge>\n");
+    }
+    out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    out
+}
+
+/// Neo4j label for a node kind (`File`, `Directory`, ...).
+fn label(kind: GraphNodeKind) -> String {
+    let name = kind.as_str();
+    name[..1].to_ascii_uppercase() + &name[1..]
+}
+
+/// Neo4j relationship type (`USES`, `CONTAINS`, ...).
+fn relationship_type(relationship: &str) -> String {
+    relationship
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_header(name: &str, ty: AttrType) -> String {
+    match ty {
+        AttrType::String => csv_field(name),
+        ty => csv_field(&format!("{}:{}", name, ty.name())),
+    }
+}
+
+fn to_neo4j_csv(graph: &SourceCodeGraph, schema: &Schema) -> (String, String) {
+    let mut nodes = String::new();
+    let mut header = vec![
+        "id:ID".to_string(),
+        ":LABEL".to_string(),
+        "name".to_string(),
+        "kind".to_string(),
+    ];
+    header.extend(schema.node.iter().map(|(k, t)| csv_header(k, *t)));
+    let _ = writeln!(nodes, "{}", header.join(","));
+    for node in &graph.nodes {
+        let mut row = vec![
+            node.id.0.to_string(),
+            format!("Node;{}", label(node.kind)),
+            csv_field(&node.name),
+            node.kind.as_str().to_string(),
+        ];
+        row.extend(
+            schema
+                .node
+                .keys()
+                .map(|k| csv_field(node.metadata.get(k).map(String::as_str).unwrap_or(""))),
+        );
+        let _ = writeln!(nodes, "{}", row.join(","));
+    }
+
+    let mut relationships = String::new();
+    let mut header = vec![
+        ":START_ID".to_string(),
+        ":END_ID".to_string(),
+        ":TYPE".to_string(),
+    ];
+    header.extend(schema.edge.iter().map(|(k, t)| csv_header(k, *t)));
+    let _ = writeln!(relationships, "{}", header.join(","));
+    for edge in &graph.edges {
+        let mut row = vec![
+            edge.from.0.to_string(),
+            edge.to.0.to_string(),
+            relationship_type(&edge.relationship),
+        ];
+        row.extend(
+            schema
+                .edge
+                .keys()
+                .map(|k| csv_field(edge.metadata.get(k).map(String::as_str).unwrap_or(""))),
+        );
+        let _ = writeln!(relationships, "{}", row.join(","));
+    }
+
+    (nodes, relationships)
+}
+
+fn to_cypher(graph: &SourceCodeGraph, schema: &Schema) -> String {
+    let literal = |value: &str, ty: AttrType| match ty {
+        AttrType::String => quoted(value),
+        _ => value.to_string(),
+    };
+    let properties = |metadata: &HashMap<String, String>, types: &BTreeMap<String, AttrType>| {
+        sorted(metadata)
+            .into_iter()
+            .map(|(k, v)| format!("`{}`: {}", k.replace('`', "``"), literal(v, types[k])))
+            .collect::<Vec<_>>()
+    };
+
+    let mut out = String::new();
+    out.push_str(
+        "CREATE CONSTRAINT vibe_node_id IF NOT EXISTS FOR (n:Node) REQUIRE n.id IS UNIQUE;\n",
+    );
+    for node in &graph.nodes {
+        let mut props = vec![
+            format!("id: {}", node.id.0),
+            format!("name: {}", quoted(&node.name)),
+            format!("kind: {}", quoted(node.kind.as_str())),
+        ];
+        props.extend(properties(&node.metadata, &schema.node));
+        let _ = writeln!(
+            out,
+            "CREATE (:Node:{} {{{}}});",
+            label(node.kind),
+            props.join(", ")
+        );
+    }
+    for edge in &graph.edges {
+        let props = properties(&edge.metadata, &schema.edge);
+        let props = if props.is_empty() {
+            String::new()
+        } else {
+            format!(" {{{}}}", props.join(", "))
+        };
+        let _ = writeln!(
+            out,
+            "MATCH (a:Node {{id: {}}}), (b:Node {{id: {}}}) CREATE (a)-[:{}{}]->(b);",
+            edge.from.0,
+            edge.to.0,
+            relationship_type(&edge.relationship),
+            props
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collapse::MEMBER_COUNT_KEY;
+    use crate::{ReferenceKind, SourceCodeGraphBuilder};
+
+    /// `/ws/src/{a,b}.rs` both use `/ws/lib/c.rs`.
+    fn sample_graph() -> SourceCodeGraph {
+        let mut builder = SourceCodeGraphBuilder::new().with_root("/ws");
+        let a = builder.add_workspace_file("src/a.rs");
+        let b = builder.add_workspace_file("src/b.rs");
+        let c = builder.add_workspace_file("lib/c.rs");
+        builder.set_node_metadata(a, "lines", "12");
+        builder.set_node_metadata(b, "lines", "7.5");
+        builder.set_node_metadata(c, "note", "a \"quoted\" <value>");
+        builder.add_edge(a, c, ReferenceKind::Uses);
+        builder.add_edge(b, c, ReferenceKind::Uses);
+        builder.build()
+    }
+
+    #[test]
+    fn test_attribute_types_are_widened() {
+        let schema = Schema::infer(&sample_graph());
+        assert_eq!(schema.node["lines"], AttrType::Double);
+        assert_eq!(schema.node["note"], AttrType::String);
+        assert_eq!(AttrType::Boolean.widen(AttrType::Long), AttrType::String);
+    }
+
+    #[test]
+    fn test_collapse_merges_edges_between_directories() {
+        let graph = sample_graph();
+        let view = export_view(
+            &graph,
+            &ExportOptions {
+                selector: NodeSelector::ByKind(GraphNodeKind::File),
+                collapse_directories: true,
+            },
+        );
+
+        let names: Vec<&str> = view.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["lib", "src"]);
+        assert_eq!(view.nodes[1].metadata[MEMBER_COUNT_KEY], "2");
+        assert_eq!(view.edges.len(), 1);
+        assert_eq!(view.edges[0].relationship, "uses");
+        assert_eq!(view.edges[0].metadata["weight"], "2");
+    }
+
+    #[test]
+    fn test_exports_escape_and_type_attributes() {
+        let graph = sample_graph();
+        let options = ExportOptions::default();
+
+        let graphml = &export_graph(&graph, ExportFormat::GraphMl, &options)[0];
+        assert_eq!(graphml.file_name, "graph.graphml");
+        assert!(graphml
+            .contents
+            .contains("attr.name=\"lines\" attr.type=\"double\""));
+        assert!(graphml
+            .contents
+            .contains("a &quot;quoted&quot; &lt;value&gt;"));
+
+        let dot = &export_graph(&graph, ExportFormat::Dot, &options)[0].contents;
+        assert!(dot.contains("\"lines\"=12"));
+        assert!(dot.contains("\"note\"=\"a \\\"quoted\\\" <value>\""));
+
+        let gexf = &export_graph(&graph, ExportFormat::Gexf, &options)[0].contents;
+        assert!(gexf.contains("title=\"lines\" type=\"double\""));
+
+        let csv = export_graph(&graph, ExportFormat::Neo4jCsv, &options);
+        assert_eq!(csv[0].file_name, "nodes.csv");
+        assert!(csv[0].contents.starts_with("id:ID,:LABEL,name,kind,"));
+        assert!(csv[0]
+            .contents
+            .lines()
+            .next()
+            .unwrap()
+            .contains("lines:double"));
+        assert!(csv[0].contents.contains("\"a \"\"quoted\"\" <value>\""));
+        assert_eq!(csv[1].contents.lines().count(), 1 + graph.edges.len());
+        assert!(csv[1].contents.contains(",USES"));
+
+        let cypher = &export_graph(&graph, ExportFormat::Cypher, &options)[0].contents;
+        assert!(cypher.contains("CREATE (:Node:File {id: "));
+        assert!(cypher.contains("`lines`: 12"));
+        assert!(cypher.contains("-[:USES]->(b);"));
+    }
+
+    #[test]
+    fn test_selector_drops_dangling_edges() {
+        let graph = sample_graph();
+        let view = export_view(
+            &graph,
+            &ExportOptions {
+                selector: NodeSelector::HasMetadata("lines".to_string()),
+                collapse_directories: false,
+            },
+        );
+        assert_eq!(view.nodes.len(), 2);
+        assert!(view.edges.is_empty());
+    }
+}
@@ -3,7 +3,7 @@
 use petgraph::stable_graph::{NodeIndex, StableDiGraph};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
@@ -13,6 +13,24 @@ use std::time::Instant;
 #[cfg(target_arch = "wasm32")]
 use web_time::Instant;
 
+pub mod analysis;
+pub mod clustering;
+pub mod collapse;
+pub mod export;
+pub mod query;
+pub mod structure;
+#[cfg(feature = "tree-sitter")]
+pub mod syntax;
+
+pub use analysis::{
+    ArchitectureAnalysis, DependencyCycle, FileCentrality, LayeringViolation, ModuleMetrics,
+};
+pub use clustering::{InferredModule, MisplacedFile, ModuleClustering};
+pub use collapse::CollapseBy;
+pub use export::{export_graph, ExportFormat, ExportOptions, ExportedFile};
+pub use query::{Query, QueryError, QueryNode, QueryResult, QueryValue};
+pub use structure::{Components, Coupling, CouplingChange, Dependency, StructuralDiff, Structure};
+
 // =============================================================================
 // Git Change Tracking Types
 // =============================================================================
@@ -124,6 +142,55 @@ impl GitChangeSnapshot {
     }
 }
 
+/// Files added, modified or deleted since a graph was built.
+///
+/// Input to incremental graph rebuilds. Paths must use the same form as the
+/// graph's node `path` metadata (absolute for graphs built by the ops layer).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphChangeSet {
+    /// Files that did not exist before.
+    #[serde(default)]
+    pub added: Vec<PathBuf>,
+    /// Files whose content changed.
+    #[serde(default)]
+    pub modified: Vec<PathBuf>,
+    /// Files that no longer exist.
+    #[serde(default)]
+    pub deleted: Vec<PathBuf>,
+}
+
+impl GraphChangeSet {
+    /// Build a change set from a git snapshot. Renames count as a deletion of
+    /// the old path plus an addition of the new one.
+    pub fn from_git_changes(snapshot: &GitChangeSnapshot) -> Self {
+        let mut changes = Self::default();
+        for change in &snapshot.changes {
+            let list = match change.kind {
+                GitChangeKind::Modified => &mut changes.modified,
+                GitChangeKind::Added | GitChangeKind::Untracked | GitChangeKind::RenamedTo => {
+                    &mut changes.added
+                }
+                GitChangeKind::Deleted | GitChangeKind::RenamedFrom => &mut changes.deleted,
+            };
+            // Staged and unstaged entries can repeat a path
+            if !list.contains(&change.path) {
+                list.push(change.path.clone());
+            }
+        }
+        changes
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    /// Total number of changed paths.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.modified.len() + self.deleted.len()
+    }
+}
+
 /// State for animating change indicators.
 #[derive(Debug, Clone)]
 pub struct ChangeIndicatorState {
@@ -171,6 +238,57 @@ impl ChangeIndicatorState {
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NodeId(pub u64);
 
+impl NodeId {
+    /// Derive an ID from a node key (see [`node_key`]).
+    ///
+    /// FNV-1a, truncated to 53 bits so IDs survive a round trip through
+    /// JavaScript numbers in JSON consumers.
+    pub fn from_key(key: &str) -> Self {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in key.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        NodeId(hash & ((1 << 53) - 1))
+    }
+}
+
+/// Normalized key a node ID is derived from.
+///
+/// The path relative to `root` with `/` separators, `.` for the root itself,
+/// or the whole path when it lies outside `root`. Symbol nodes use
+/// `<file key>::<qualified name>`.
+pub fn node_key(path: &Path, root: Option<&Path>) -> String {
+    use std::path::Component;
+
+    let relative = root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let mut key = String::new();
+    for component in relative.components() {
+        match component {
+            Component::CurDir => {}
+            Component::RootDir => key.push('/'),
+            other => {
+                if !key.is_empty() && !key.ends_with('/') {
+                    key.push('/');
+                }
+                key.push_str(&other.as_os_str().to_string_lossy());
+            }
+        }
+    }
+    if key.is_empty() {
+        key.push('.');
+    }
+    key
+}
+
+/// Graph metadata key recording how node IDs were assigned.
+pub const ID_SCHEME_KEY: &str = "id_scheme";
+
+/// [`ID_SCHEME_KEY`] value of graphs whose node IDs come from [`node_key`].
+pub const PATH_ID_SCHEME: &str = "path";
+
 /// Identifier for edges within the `SourceCodeGraph`.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EdgeId(pub u64);
@@ -188,11 +306,51 @@ pub enum GraphNodeKind {
     Service,
     /// Automated test suites or harnesses.
     Test,
+    /// A definition inside a file (function, type, trait, class).
+    Symbol,
+    /// A repository of a multi-repo workspace.
+    Repository,
     /// Any other kind that does not fit the curated list.
     #[default]
     Other,
 }
 
+impl GraphNodeKind {
+    /// Lowercase name, as used in queries and exports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphNodeKind::Module => "module",
+            GraphNodeKind::File => "file",
+            GraphNodeKind::Directory => "directory",
+            GraphNodeKind::Service => "service",
+            GraphNodeKind::Test => "test",
+            GraphNodeKind::Symbol => "symbol",
+            GraphNodeKind::Repository => "repository",
+            GraphNodeKind::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for GraphNodeKind {
+    type Err = String;
+
+    /// Parse a kind name case-insensitively (`dir` for `directory`, `repo`
+    /// for `repository`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "module" => GraphNodeKind::Module,
+            "file" => GraphNodeKind::File,
+            "directory" | "dir" => GraphNodeKind::Directory,
+            "service" => GraphNodeKind::Service,
+            "test" => GraphNodeKind::Test,
+            "symbol" => GraphNodeKind::Symbol,
+            "repository" | "repo" => GraphNodeKind::Repository,
+            "other" => GraphNodeKind::Other,
+            _ => return Err(format!("unknown node kind '{}'", s)),
+        })
+    }
+}
+
 /// Captures metadata for a node in the graph.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct GraphNode {
@@ -271,19 +429,144 @@ impl SourceCodeGraph {
 
         (graph, id_to_index)
     }
+
+    /// Whether node IDs were derived from node paths (see [`NodeId::from_key`])
+    /// rather than handed out in traversal order.
+    pub fn has_stable_ids(&self) -> bool {
+        self.metadata.get(ID_SCHEME_KEY).map(String::as_str) == Some(PATH_ID_SCHEME)
+    }
+
+    /// Reassign every node ID from its path, as a fresh build would.
+    ///
+    /// Used to migrate graphs persisted before IDs were path-derived. `root`
+    /// is used when the graph does not record its own. Returns the old → new
+    /// mapping so stores keyed by the old IDs can follow.
+    pub fn migrate_to_stable_ids(&mut self, root: &Path) -> HashMap<NodeId, NodeId> {
+        let root = self
+            .metadata
+            .get("root")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| root.to_path_buf());
+
+        let mut ids = NodeIdAllocator::default();
+        let mapping: HashMap<NodeId, NodeId> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let key = match (node.kind, node.metadata.get("file")) {
+                    (GraphNodeKind::Symbol, Some(file)) => {
+                        format!("{}::{}", node_key(Path::new(file), Some(&root)), node.name)
+                    }
+                    _ => match node.metadata.get("path") {
+                        Some(path) => node_key(Path::new(path), Some(&root)),
+                        None => node.name.clone(),
+                    },
+                };
+                (node.id, ids.allocate(&key))
+            })
+            .collect();
+
+        self.remap_node_ids(&mapping);
+        self.metadata
+            .insert("root".to_string(), root.to_string_lossy().to_string());
+        self.metadata
+            .insert(ID_SCHEME_KEY.to_string(), PATH_ID_SCHEME.to_string());
+        mapping
+    }
+
+    /// Rewrite node IDs (and edge endpoints) through `mapping`. IDs missing
+    /// from the mapping are left unchanged.
+    pub fn remap_node_ids(&mut self, mapping: &HashMap<NodeId, NodeId>) {
+        let remap = |id: &mut NodeId| {
+            if let Some(&new_id) = mapping.get(id) {
+                *id = new_id;
+            }
+        };
+        for node in &mut self.nodes {
+            remap(&mut node.id);
+        }
+        for edge in &mut self.edges {
+            remap(&mut edge.from);
+            remap(&mut edge.to);
+        }
+    }
+
+    /// Compare against a newer version of this graph.
+    ///
+    /// Nodes are matched by ID and edges by `(from, to, relationship)`, since
+    /// edge IDs are not preserved when references are re-detected.
+    pub fn diff(&self, newer: &SourceCodeGraph) -> GraphDiff {
+        let old_nodes: HashSet<NodeId> = self.nodes.iter().map(|n| n.id).collect();
+        let new_nodes: HashSet<NodeId> = newer.nodes.iter().map(|n| n.id).collect();
+        let edge_key = |e: &GraphEdge| (e.from, e.to, e.relationship.clone());
+        let old_edges: HashSet<_> = self.edges.iter().map(edge_key).collect();
+        let new_edges: HashSet<_> = newer.edges.iter().map(edge_key).collect();
+
+        GraphDiff {
+            added_nodes: newer
+                .nodes
+                .iter()
+                .filter(|n| !old_nodes.contains(&n.id))
+                .cloned()
+                .collect(),
+            removed_nodes: self
+                .nodes
+                .iter()
+                .filter(|n| !new_nodes.contains(&n.id))
+                .cloned()
+                .collect(),
+            added_edges: newer
+                .edges
+                .iter()
+                .filter(|e| !old_edges.contains(&edge_key(e)))
+                .cloned()
+                .collect(),
+            removed_edges: self
+                .edges
+                .iter()
+                .filter(|e| !new_edges.contains(&edge_key(e)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Structural difference between two versions of a `SourceCodeGraph`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    /// Nodes only present in the newer graph.
+    pub added_nodes: Vec<GraphNode>,
+    /// Nodes only present in the older graph.
+    pub removed_nodes: Vec<GraphNode>,
+    /// Edges only present in the newer graph.
+    pub added_edges: Vec<GraphEdge>,
+    /// Edges only present in the older graph.
+    pub removed_edges: Vec<GraphEdge>,
+}
+
+impl GraphDiff {
+    /// Whether the two graphs are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
 }
 
 /// Types of references detected between source files.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ReferenceKind {
     /// Rust `use` statement
     Uses,
-    /// Python/JS/TS `import` statement
+    /// `import` statement (Python, JS/TS, Go, Java/Kotlin) or C `#include`
     Imports,
     /// Trait or interface implementation
     Implements,
     /// Filesystem hierarchy (parent->child)
     Contains,
+    /// Function or method call between symbols
+    Calls,
 }
 
 impl std::fmt::Display for ReferenceKind {
@@ -293,6 +576,7 @@ impl std::fmt::Display for ReferenceKind {
             ReferenceKind::Imports => write!(f, "imports"),
             ReferenceKind::Implements => write!(f, "implements"),
             ReferenceKind::Contains => write!(f, "contains"),
+            ReferenceKind::Calls => write!(f, "calls"),
         }
     }
 }
@@ -306,6 +590,71 @@ pub struct SourceReference {
     pub kind: ReferenceKind,
     /// Target path (may be partial, resolved later)
     pub target_route: PathBuf,
+    /// The reference as written in source (`crate::store::Store`, `./utils`),
+    /// for resolvers that understand the language's module system. `None`
+    /// when `target_route` is already the best available answer.
+    pub specifier: Option<String>,
+}
+
+/// Kinds of definitions that become `GraphNodeKind::Symbol` nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    /// Free function or method
+    Function,
+    /// Rust struct
+    Struct,
+    /// Rust/TS enum
+    Enum,
+    /// Rust trait or TS interface
+    Trait,
+    /// Python/JS/TS class
+    Class,
+}
+
+impl std::fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymbolKind::Function => write!(f, "function"),
+            SymbolKind::Struct => write!(f, "struct"),
+            SymbolKind::Enum => write!(f, "enum"),
+            SymbolKind::Trait => write!(f, "trait"),
+            SymbolKind::Class => write!(f, "class"),
+        }
+    }
+}
+
+/// A definition found inside a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDefinition {
+    /// Bare name as written at the definition site (`build`)
+    pub name: String,
+    /// Name qualified by its enclosing type, if any (`Builder::build`)
+    pub qualified_name: String,
+    /// What kind of definition this is
+    pub kind: SymbolKind,
+    /// 1-based line of the definition
+    pub line: usize,
+}
+
+/// A symbol-to-symbol reference found inside a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolReference {
+    /// Qualified name of the enclosing definition
+    pub from: String,
+    /// `Calls` or `Implements`
+    pub kind: ReferenceKind,
+    /// Bare name of the referenced symbol (resolved later)
+    pub target: String,
+}
+
+/// Definitions and symbol references detected in one source file.
+#[derive(Debug, Clone, Default)]
+pub struct SourceSymbols {
+    /// Definitions in source order
+    pub definitions: Vec<SymbolDefinition>,
+    /// References between symbols (only populated by the tree-sitter backend)
+    pub references: Vec<SymbolReference>,
 }
 
 /// Builder for constructing a `SourceCodeGraph` from project data.
@@ -314,17 +663,109 @@ pub struct SourceCodeGraphBuilder {
     nodes: Vec<GraphNode>,
     edges: Vec<GraphEdge>,
     path_to_node: HashMap<PathBuf, NodeId>,
-    next_node_id: u64,
+    /// Symbol nodes keyed by (file, qualified name).
+    symbol_to_node: HashMap<(PathBuf, String), NodeId>,
+    /// Symbol nodes keyed by bare name, for cross-file resolution.
+    symbols_by_name: HashMap<String, Vec<(PathBuf, NodeId)>>,
+    /// Workspace root node keys are made relative to.
+    root: Option<PathBuf>,
+    ids: NodeIdAllocator,
+    /// Started from a graph with sequential IDs: its nodes keep them, so
+    /// the build is not stamped as path-derived.
+    legacy_ids: bool,
     next_edge_id: u64,
     metadata: HashMap<String, String>,
 }
 
+/// Hands out path-derived node IDs, probing `<key>#1`, `<key>#2`, ... when
+/// two keys hash to the same ID.
+#[derive(Debug, Default)]
+struct NodeIdAllocator {
+    used: HashSet<NodeId>,
+}
+
+impl NodeIdAllocator {
+    fn allocate(&mut self, key: &str) -> NodeId {
+        let mut id = NodeId::from_key(key);
+        let mut attempt = 0;
+        while !self.used.insert(id) {
+            attempt += 1;
+            id = NodeId::from_key(&format!("{key}#{attempt}"));
+        }
+        id
+    }
+
+    fn release(&mut self, id: NodeId) {
+        self.used.remove(&id);
+    }
+}
+
 impl SourceCodeGraphBuilder {
     /// Create a new builder.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Start from an existing graph, keeping its node and edge IDs.
+    ///
+    /// Path and symbol lookups are rebuilt from node metadata, and the
+    /// workspace root from the graph's `root` metadata, so nodes added
+    /// afterwards get the IDs a fresh build would give them. A graph with
+    /// sequential IDs stays unmarked (see
+    /// [`SourceCodeGraph::has_stable_ids`]) until it is migrated.
+    pub fn from_graph(graph: SourceCodeGraph) -> Self {
+        let mut builder = Self {
+            root: graph.metadata.get("root").map(PathBuf::from),
+            ids: NodeIdAllocator {
+                used: graph.nodes.iter().map(|n| n.id).collect(),
+            },
+            legacy_ids: !graph.has_stable_ids() && !graph.nodes.is_empty(),
+            next_edge_id: graph.edges.iter().map(|e| e.id.0 + 1).max().unwrap_or(0),
+            metadata: graph.metadata,
+            edges: graph.edges,
+            ..Self::default()
+        };
+
+        for node in &graph.nodes {
+            if node.kind == GraphNodeKind::Symbol {
+                if let Some(file) = node.metadata.get("file") {
+                    let file = PathBuf::from(file);
+                    // `Type::method` and `Class.method` are indexed as `method`
+                    let name = node
+                        .name
+                        .rsplit(['.', ':'])
+                        .next()
+                        .unwrap_or(&node.name)
+                        .to_string();
+                    builder
+                        .symbol_to_node
+                        .insert((file.clone(), node.name.clone()), node.id);
+                    builder
+                        .symbols_by_name
+                        .entry(name)
+                        .or_default()
+                        .push((file, node.id));
+                }
+            } else if let Some(path) = node.metadata.get("path") {
+                builder.path_to_node.insert(PathBuf::from(path), node.id);
+            }
+        }
+        builder.nodes = graph.nodes;
+        builder
+    }
+
+    /// Set the workspace root node IDs are derived relative to.
+    ///
+    /// Without a root, IDs are derived from full paths, which still keeps
+    /// them stable across rebuilds of the same checkout.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        self.metadata
+            .insert("root".to_string(), root.to_string_lossy().to_string());
+        self.root = Some(root);
+        self
+    }
+
     /// Set metadata for the graph.
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -337,8 +778,8 @@ impl SourceCodeGraphBuilder {
             return id;
         }
 
-        let id = NodeId(self.next_node_id);
-        self.next_node_id += 1;
+        let key = node_key(path, self.root.as_deref());
+        let id = self.ids.allocate(&key);
 
         let name = path
             .file_name()
@@ -366,8 +807,8 @@ impl SourceCodeGraphBuilder {
             return id;
         }
 
-        let id = NodeId(self.next_node_id);
-        self.next_node_id += 1;
+        let key = node_key(path, self.root.as_deref());
+        let id = self.ids.allocate(&key);
 
         let name = path
             .file_name()
@@ -417,6 +858,107 @@ impl SourceCodeGraphBuilder {
         id
     }
 
+    /// Add a symbol node defined in an existing file node.
+    ///
+    /// The symbol is linked to its file by a `contains` edge. Its `path`
+    /// metadata is `<file>::<qualified name>` so tools can address it like a
+    /// file. Returns `None` if the file has no node.
+    pub fn add_symbol(&mut self, file_path: &Path, symbol: &SymbolDefinition) -> Option<NodeId> {
+        let file_id = self.get_node_id(file_path)?;
+        let key = (file_path.to_path_buf(), symbol.qualified_name.clone());
+        if let Some(&id) = self.symbol_to_node.get(&key) {
+            // Re-detected after an edit: keep the ID, refresh the location
+            self.set_node_metadata(id, "line", symbol.line.to_string());
+            return Some(id);
+        }
+
+        let id = self.ids.allocate(&format!(
+            "{}::{}",
+            node_key(file_path, self.root.as_deref()),
+            symbol.qualified_name
+        ));
+
+        let file = file_path.to_string_lossy().to_string();
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "path".to_string(),
+            format!("{}::{}", file, symbol.qualified_name),
+        );
+        metadata.insert("file".to_string(), file);
+        metadata.insert("symbol_kind".to_string(), symbol.kind.to_string());
+        metadata.insert("line".to_string(), symbol.line.to_string());
+        if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
+            metadata.insert(
+                "language".to_string(),
+                extension_to_language(ext).to_string(),
+            );
+        }
+
+        self.nodes.push(GraphNode {
+            id,
+            name: symbol.qualified_name.clone(),
+            kind: GraphNodeKind::Symbol,
+            metadata,
+        });
+
+        self.symbol_to_node.insert(key, id);
+        self.symbols_by_name
+            .entry(symbol.name.clone())
+            .or_default()
+            .push((file_path.to_path_buf(), id));
+        self.add_edge(file_id, id, ReferenceKind::Contains);
+        Some(id)
+    }
+
+    /// Get NodeId for a symbol by file and qualified name.
+    pub fn get_symbol_id(&self, file_path: &Path, qualified_name: &str) -> Option<NodeId> {
+        self.symbol_to_node
+            .get(&(file_path.to_path_buf(), qualified_name.to_string()))
+            .copied()
+    }
+
+    /// Resolve a bare symbol name referenced from `file_path`.
+    ///
+    /// Definitions in the same file win; otherwise the name must be defined
+    /// exactly once in the graph; ambiguous names are left unresolved rather
+    /// than guessed.
+    pub fn find_symbol(&self, name: &str, file_path: &Path) -> Option<NodeId> {
+        let candidates = self.symbols_by_name.get(name)?;
+        if let Some((_, id)) = candidates.iter().find(|(path, _)| path == file_path) {
+            return Some(*id);
+        }
+        match candidates.as_slice() {
+            [(_, id)] => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Add symbol nodes for every definition detected in one file.
+    ///
+    /// Add definitions for all files before calling
+    /// [`Self::add_symbol_references`] so cross-file targets can resolve.
+    pub fn add_symbols(&mut self, file_path: &Path, symbols: &SourceSymbols) {
+        for definition in &symbols.definitions {
+            self.add_symbol(file_path, definition);
+        }
+    }
+
+    /// Resolve and add `calls`/`implements` edges between symbol nodes.
+    pub fn add_symbol_references(&mut self, file_path: &Path, references: &[SymbolReference]) {
+        let mut seen = std::collections::HashSet::new();
+        for reference in references {
+            let Some(from) = self.get_symbol_id(file_path, &reference.from) else {
+                continue;
+            };
+            let Some(to) = self.find_symbol(&reference.target, file_path) else {
+                continue;
+            };
+            if from != to && seen.insert((from, to, reference.kind.to_string())) {
+                self.add_edge(from, to, reference.kind);
+            }
+        }
+    }
+
     /// Add a hierarchy edge (parent contains child).
     pub fn add_hierarchy_edge(&mut self, parent_path: &Path, child_path: &Path) {
         if let (Some(&parent_id), Some(&child_id)) = (
@@ -429,6 +971,22 @@ impl SourceCodeGraphBuilder {
         }
     }
 
+    /// Test fixture: add a file at `relative` under the builder's root, with
+    /// its parent directory and the hierarchy edge between them.
+    #[cfg(test)]
+    pub(crate) fn add_workspace_file(&mut self, relative: &str) -> NodeId {
+        let path = self
+            .root
+            .as_ref()
+            .expect("workspace fixtures need a root")
+            .join(relative);
+        let parent = path.parent().unwrap();
+        self.add_directory(parent);
+        let id = self.add_file(&path, relative);
+        self.add_hierarchy_edge(parent, &path);
+        id
+    }
+
     /// Add an edge between two nodes.
     pub fn add_edge(&mut self, from: NodeId, to: NodeId, kind: ReferenceKind) {
         let id = EdgeId(self.next_edge_id);
@@ -443,53 +1001,142 @@ impl SourceCodeGraphBuilder {
         });
     }
 
-    /// Get NodeId for a path if it exists.
-    pub fn get_node_id(&self, path: &Path) -> Option<NodeId> {
-        self.path_to_node.get(path).copied()
+    /// Remove a file node together with its symbol nodes and every edge
+    /// touching them. Returns `false` if the file has no node.
+    pub fn remove_file(&mut self, path: &Path) -> bool {
+        let Some(file_id) = self.path_to_node.remove(path) else {
+            return false;
+        };
+        let mut removed: HashSet<NodeId> =
+            self.remove_symbols(path, |_| true).into_iter().collect();
+        removed.insert(file_id);
+        self.ids.release(file_id);
+
+        self.nodes.retain(|n| !removed.contains(&n.id));
+        self.edges
+            .retain(|e| !removed.contains(&e.from) && !removed.contains(&e.to));
+        true
     }
 
-    /// Find a node by matching path suffix (for reference resolution).
-    pub fn find_node_by_path_suffix(&self, route: &Path) -> Option<NodeId> {
-        let route_str = route.to_string_lossy();
+    /// Drop the references a file makes (its outgoing non-`contains` edges and
+    /// those of its symbols) ahead of re-detecting them.
+    ///
+    /// Symbol nodes whose qualified name is still in `definitions` keep
+    /// their IDs (and incoming edges); the others are removed.
+    pub fn clear_file_references(&mut self, path: &Path, definitions: &[SymbolDefinition]) {
+        let Some(file_id) = self.get_node_id(path) else {
+            return;
+        };
+        let kept: HashSet<&str> = definitions
+            .iter()
+            .map(|d| d.qualified_name.as_str())
+            .collect();
+        let removed: HashSet<NodeId> = self
+            .remove_symbols(path, |name| !kept.contains(name))
+            .into_iter()
+            .collect();
+        let sources: HashSet<NodeId> = self
+            .symbol_to_node
+            .iter()
+            .filter(|((file, _), _)| file == path)
+            .map(|(_, &id)| id)
+            .chain([file_id])
+            .collect();
 
-        for (path, &node_id) in &self.path_to_node {
-            let path_str = path.to_string_lossy();
+        let contains = ReferenceKind::Contains.to_string();
+        self.nodes.retain(|n| !removed.contains(&n.id));
+        self.edges.retain(|e| {
+            !removed.contains(&e.from)
+                && !removed.contains(&e.to)
+                && (!sources.contains(&e.from) || e.relationship == contains)
+        });
+    }
 
-            // Strategy 1: Direct suffix match
-            if path_str.ends_with(route_str.as_ref()) {
-                return Some(node_id);
+    /// Remove directory nodes that no longer contain anything, starting at
+    /// `dir` and walking up its ancestors.
+    pub fn prune_empty_directories(&mut self, dir: &Path) {
+        let contains = ReferenceKind::Contains.to_string();
+        for dir in dir.ancestors() {
+            let Some(&id) = self.path_to_node.get(dir) else {
+                break;
+            };
+            let has_children = self
+                .edges
+                .iter()
+                .any(|e| e.from == id && e.relationship == contains);
+            if has_children {
+                break;
             }
+            self.path_to_node.remove(dir);
+            self.ids.release(id);
+            self.nodes.retain(|n| n.id != id);
+            self.edges.retain(|e| e.from != id && e.to != id);
+        }
+    }
 
-            // Strategy 2: Normalized comparison
-            let normalized_path: String = path_str.trim_start_matches("./").replace('\\', "/");
-            let normalized_route: String = route_str.trim_start_matches("./").replace('\\', "/");
-            if normalized_path.ends_with(&normalized_route) {
-                return Some(node_id);
+    /// Unregister symbols of `path` whose qualified name matches `remove`,
+    /// returning their IDs for reuse. Nodes and edges are left to the caller.
+    fn remove_symbols(&mut self, path: &Path, remove: impl Fn(&str) -> bool) -> Vec<NodeId> {
+        let mut removed = Vec::new();
+        self.symbol_to_node.retain(|(file, name), id| {
+            let drop = file == path && remove(name);
+            if drop {
+                removed.push(*id);
             }
+            !drop
+        });
+        for &id in &removed {
+            self.ids.release(id);
+        }
+        for candidates in self.symbols_by_name.values_mut() {
+            candidates.retain(|(_, id)| !removed.contains(id));
+        }
+        self.symbols_by_name
+            .retain(|_, candidates| !candidates.is_empty());
+        removed
+    }
 
-            // Strategy 3: Module path matching (e.g., core/models.rs -> src/core/models.rs)
-            let route_parts: Vec<&str> = normalized_route.split('/').collect();
-            let path_parts: Vec<&str> = normalized_path.split('/').collect();
-            if route_parts.len() <= path_parts.len() {
-                for window in path_parts.windows(route_parts.len()) {
-                    if window == route_parts.as_slice() {
-                        return Some(node_id);
-                    }
-                }
-            }
+    /// Get NodeId for a path if it exists.
+    pub fn get_node_id(&self, path: &Path) -> Option<NodeId> {
+        self.path_to_node.get(path).copied()
+    }
 
-            // Strategy 4: Filename match
-            if let (Some(file_name), Some(route_name)) = (
-                path.file_name().and_then(|n| n.to_str()),
-                route.file_name().and_then(|n| n.to_str()),
-            ) {
-                if file_name == route_name {
-                    return Some(node_id);
-                }
-            }
+    /// Find a node by matching path suffix (for reference resolution).
+    ///
+    /// The route should match whole trailing components, so `models.rs`
+    /// matches `src/models.rs` but not `src/user_models.rs`. Failing that,
+    /// the route's components may appear anywhere in the path; a route whose
+    /// directories exist nowhere never matches on its file name alone. When
+    /// several files match, the shortest path wins, keeping the result
+    /// independent of map order.
+    pub fn find_node_by_path_suffix(&self, route: &Path) -> Option<NodeId> {
+        let route = normalize_path(route);
+        if route.as_os_str().is_empty() {
+            return None;
         }
+        let route_parts: Vec<_> = route.components().collect();
+
+        let suffix = |path: &Path| path.ends_with(&route);
+        let window = |path: &Path| {
+            let parts: Vec<_> = path.components().collect();
+            parts
+                .windows(route_parts.len())
+                .any(|window| window == route_parts.as_slice())
+        };
+        let strategies: [&dyn Fn(&Path) -> bool; 2] = [&suffix, &window];
 
-        None
+        strategies.iter().find_map(|matches| {
+            self.path_to_node
+                .iter()
+                .filter(|(path, _)| matches(path))
+                .min_by(|(a, _), (b, _)| {
+                    a.components()
+                        .count()
+                        .cmp(&b.components().count())
+                        .then_with(|| a.cmp(b))
+                })
+                .map(|(_, &node_id)| node_id)
+        })
     }
 
     /// Set a metadata key on an existing node.
@@ -504,6 +1151,13 @@ impl SourceCodeGraphBuilder {
         }
     }
 
+    /// Remove a metadata key from an existing node.
+    pub fn remove_node_metadata(&mut self, node_id: NodeId, key: &str) {
+        if let Some(node) = self.nodes.iter_mut().find(|n| n.id == node_id) {
+            node.metadata.remove(key);
+        }
+    }
+
     /// Get the current node count.
     pub fn node_count(&self) -> usize {
         self.nodes.len()
@@ -515,7 +1169,11 @@ impl SourceCodeGraphBuilder {
     }
 
     /// Build the final `SourceCodeGraph`.
-    pub fn build(self) -> SourceCodeGraph {
+    pub fn build(mut self) -> SourceCodeGraph {
+        if !self.legacy_ids {
+            self.metadata
+                .insert(ID_SCHEME_KEY.to_string(), PATH_ID_SCHEME.to_string());
+        }
         SourceCodeGraph {
             nodes: self.nodes,
             edges: self.edges,
@@ -527,10 +1185,25 @@ impl SourceCodeGraphBuilder {
 /// Detect references in Rust source code.
 pub fn detect_rust_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
     let mut refs = Vec::new();
+    let mut imported = RustImports::new();
+    let mut implemented = Vec::new();
+    // `#[path = "..."]` applies to the next `mod` declaration
+    let mut path_attribute = None;
 
     for line in content.lines() {
         let trimmed = line.trim();
 
+        // `impl Trait for Type` -> resolved against imports after the scan
+        if let Some(trait_path) = rust_impl_trait(trimmed) {
+            implemented.push(trait_path.to_string());
+            continue;
+        }
+
+        if let Some(path) = rust_path_attribute(trimmed) {
+            path_attribute = Some(path);
+            continue;
+        }
+
         // Handle 'mod' declarations
         if trimmed.starts_with("pub mod ") || trimmed.starts_with("mod ") {
             let mod_part = trimmed
@@ -542,20 +1215,32 @@ pub fn detect_rust_references(content: &str, source_path: &Path) -> Vec<SourceRe
                 .unwrap_or("")
                 .trim();
 
-            if !mod_part.is_empty() && !mod_part.contains('{') {
+            if let Some(path) = path_attribute.take() {
+                if !mod_part.contains('{') {
+                    refs.push(rust_path_module(source_path, path));
+                }
+            } else if !mod_part.is_empty() && !mod_part.contains('{') {
+                let specifier = format!("self::{}", mod_part);
                 refs.push(SourceReference {
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Uses,
                     target_route: PathBuf::from(format!("{}.rs", mod_part)),
+                    specifier: Some(specifier.clone()),
                 });
                 refs.push(SourceReference {
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Uses,
                     target_route: PathBuf::from(format!("{}/mod.rs", mod_part)),
+                    specifier: Some(specifier),
                 });
             }
         }
 
+        // Any other item consumes a pending `#[path]`
+        if !trimmed.starts_with("#") {
+            path_attribute = None;
+        }
+
         if !trimmed.starts_with("use ") {
             continue;
         }
@@ -576,6 +1261,8 @@ pub fn detect_rust_references(content: &str, source_path: &Path) -> Vec<SourceRe
             continue;
         }
 
+        rust_imported_names(trimmed, &mut imported);
+
         // Propose everything that looks like a path.
         // The graph builder will filter out references that don't resolve to actual nodes.
         let module_path = use_part
@@ -590,23 +1277,216 @@ pub fn detect_rust_references(content: &str, source_path: &Path) -> Vec<SourceRe
             .trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_')
             .to_string();
 
+        let specifier = use_part
+            .split(" as ")
+            .next()
+            .unwrap_or(use_part)
+            .trim_end_matches([':', '*'])
+            .trim();
+
         refs.push(SourceReference {
             source_path: source_path.to_path_buf(),
             kind: ReferenceKind::Uses,
             target_route: PathBuf::from(format!("{}.rs", path_str)),
+            specifier: Some(specifier.to_string()),
         });
     }
 
+    for trait_path in implemented {
+        if let Some((route, specifier)) = rust_trait_route(&trait_path, &imported) {
+            push_implements(&mut refs, source_path, route, Some(specifier));
+        }
+    }
+
     refs
 }
 
+/// Names brought into scope by a file's imports, mapped to the route of the
+/// module that defines them. Used to resolve `Implements` targets.
+pub(crate) type ImportedNames = HashMap<String, PathBuf>;
+
+/// Rust names brought into scope by `use`, mapped to their full `::` path.
+pub(crate) type RustImports = HashMap<String, String>;
+
+/// Route of the Rust module file for a `::` path, e.g. `crate::store` ->
+/// `store.rs`. Standard library paths have no file in the workspace.
+pub(crate) fn rust_module_route(segments: &[&str]) -> Option<PathBuf> {
+    let segments: Vec<&str> = segments
+        .iter()
+        .copied()
+        .skip_while(|s| matches!(*s, "crate" | "self" | "super"))
+        .collect();
+    match segments.first() {
+        None | Some(&"std") | Some(&"core") | Some(&"alloc") => None,
+        Some(_) => Some(PathBuf::from(format!("{}.rs", segments.join("/")))),
+    }
+}
+
+/// Route of the module defining a trait named in an `impl`, plus the trait's
+/// full path. Qualified paths name their module directly, bare names go
+/// through the file's imports.
+pub(crate) fn rust_trait_route(
+    trait_path: &str,
+    imported: &RustImports,
+) -> Option<(PathBuf, String)> {
+    // The first segment may itself be imported: `use crate::io; impl io::Read`
+    let (first, rest) = match trait_path.split_once("::") {
+        Some((first, rest)) => (first.trim(), Some(rest)),
+        None => (trait_path, None),
+    };
+    let full = match (imported.get(first), rest) {
+        (Some(path), Some(rest)) => format!("{}::{}", path, rest),
+        (Some(path), None) => path.clone(),
+        (None, Some(_)) => trait_path.to_string(),
+        (None, None) => return None,
+    };
+    let segments: Vec<&str> = full.split("::").map(str::trim).collect();
+    let (_, module) = segments.split_last()?;
+    Some((rust_module_route(module)?, full))
+}
+
+/// Path from a `#[path = "..."]` attribute line.
+pub(crate) fn rust_path_attribute(line: &str) -> Option<&str> {
+    let inner = line.strip_prefix("#[")?.strip_suffix(']')?;
+    let (key, value) = inner.split_once('=')?;
+    if key.trim() != "path" {
+        return None;
+    }
+    Some(value.trim().trim_matches('"'))
+}
+
+/// A `#[path]`-annotated `mod` points at a file relative to the declaring
+/// file's directory, so the route is a concrete path.
+pub(crate) fn rust_path_module(source_path: &Path, path: &str) -> SourceReference {
+    let dir = source_path.parent().unwrap_or(Path::new(""));
+    SourceReference {
+        source_path: source_path.to_path_buf(),
+        kind: ReferenceKind::Uses,
+        target_route: normalize_path(&dir.join(path)),
+        specifier: None,
+    }
+}
+
+/// Lexically normalize a path (drop `.` components, fold `..`).
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Trait path of an `impl Trait for Type` line (`None` for inherent impls).
+fn rust_impl_trait(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("unsafe ").unwrap_or(line);
+    let mut rest = line.strip_prefix("impl")?;
+
+    // Skip the impl's own generics: impl<T: Clone> ...
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        rest = &rest[end + 1..];
+    } else if !rest.starts_with(' ') {
+        return None;
+    }
+
+    let (trait_part, _) = rest.split_once(" for ")?;
+    let trait_path = trait_part.split('<').next()?.trim();
+    if trait_path.is_empty() || trait_path.starts_with('!') {
+        return None;
+    }
+    Some(trait_path)
+}
+
+/// Record the names imported by a single-line `use` statement.
+fn rust_imported_names(line: &str, imported: &mut RustImports) {
+    let Some(body) = line
+        .strip_prefix("pub use ")
+        .or_else(|| line.strip_prefix("use "))
+        .and_then(|s| s.split(';').next())
+    else {
+        return;
+    };
+
+    let (prefix, items): (&str, Vec<&str>) = match body.split_once("::{") {
+        Some((prefix, rest)) => (prefix, rest.trim_end_matches('}').split(',').collect()),
+        None => match body.rsplit_once("::") {
+            Some((prefix, item)) => (prefix, vec![item]),
+            None => return,
+        },
+    };
+
+    let prefix = prefix.trim();
+    for item in items {
+        let (path, name) = match item.trim().split_once(" as ") {
+            Some((path, alias)) => (path.trim(), alias.trim()),
+            None => (item.trim(), item.trim()),
+        };
+        if !name.is_empty() && name != "self" && name != "*" && !name.contains("::") {
+            imported.insert(name.to_string(), format!("{}::{}", prefix, path));
+        }
+    }
+}
+
+/// Push an `Implements` reference unless the same route was already recorded.
+pub(crate) fn push_implements(
+    refs: &mut Vec<SourceReference>,
+    source_path: &Path,
+    route: PathBuf,
+    specifier: Option<String>,
+) {
+    if refs
+        .iter()
+        .any(|r| r.kind == ReferenceKind::Implements && r.target_route == route)
+    {
+        return;
+    }
+    refs.push(SourceReference {
+        source_path: source_path.to_path_buf(),
+        kind: ReferenceKind::Implements,
+        target_route: route,
+        specifier,
+    });
+}
+
 /// Detect references in Python source code.
 pub fn detect_python_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
     let mut refs = Vec::new();
+    let mut imported = ImportedNames::new();
+    let mut bases = Vec::new();
 
     for line in content.lines() {
         let trimmed = line.trim();
 
+        // `class A(B, mod.C):` -> resolved against imports after the scan
+        if let Some(rest) = trimmed.strip_prefix("class ") {
+            if let Some((_, args)) = rest.split_once('(') {
+                let args = args.split(')').next().unwrap_or("");
+                bases.extend(
+                    args.split(',')
+                        .map(str::trim)
+                        .filter(|b| !b.is_empty() && !b.contains('='))
+                        .map(str::to_string),
+                );
+            }
+            continue;
+        }
+
         // Match "import module" statements
         if trimmed.starts_with("import ") && !trimmed.starts_with("import(") {
             let import_part = trimmed
@@ -622,10 +1502,18 @@ pub fn detect_python_references(content: &str, source_path: &Path) -> Vec<Source
 
             if !import_part.is_empty() {
                 let path_str = import_part.replace('.', "/");
+                // `import pkg.models as m` lets `m.Base` name the module
+                if let Some((_, alias)) = trimmed.split_once(" as ") {
+                    imported.insert(
+                        alias.split([',', ' ']).next().unwrap_or("").to_string(),
+                        PathBuf::from(format!("{}.py", path_str)),
+                    );
+                }
                 refs.push(SourceReference {
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Imports,
                     target_route: PathBuf::from(format!("{}.py", path_str)),
+                    specifier: None,
                 });
             }
         }
@@ -638,49 +1526,193 @@ pub fn detect_python_references(content: &str, source_path: &Path) -> Vec<Source
             let module = module_part.trim();
             if !module.is_empty() && module != "." && !module.starts_with("..") {
                 let path_str = module.replace('.', "/");
+                let names = trimmed.split(" import ").nth(1).unwrap_or("");
+                for name in names.trim_matches(['(', ')']).split(',') {
+                    let name = match name.split_once(" as ") {
+                        Some((_, alias)) => alias,
+                        None => name,
+                    }
+                    .trim();
+                    if !name.is_empty() && name != "*" {
+                        imported
+                            .insert(name.to_string(), PathBuf::from(format!("{}.py", path_str)));
+                    }
+                }
                 refs.push(SourceReference {
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Imports,
                     target_route: PathBuf::from(format!("{}.py", path_str)),
+                    specifier: None,
                 });
             }
         }
     }
 
+    for base in bases {
+        if let Some(route) = python_base_route(&base, &imported) {
+            push_implements(&mut refs, source_path, route, None);
+        }
+    }
+
     refs
 }
 
+/// Route of the module defining a Python base class: `m.Base` goes through
+/// the alias `m` (or is taken as a dotted module path), `Base` through the
+/// file's `from ... import` names.
+pub(crate) fn python_base_route(base: &str, imported: &ImportedNames) -> Option<PathBuf> {
+    match base.rsplit_once('.') {
+        Some((module, _)) => imported
+            .get(module)
+            .cloned()
+            .or_else(|| Some(PathBuf::from(format!("{}.py", module.replace('.', "/"))))),
+        None => imported.get(base).cloned(),
+    }
+}
+
 /// Detect references in TypeScript/JavaScript source code.
+///
+/// Covers `import`/`export ... from`, the closing `} from '...'` line of
+/// multi-line imports, `require()` and dynamic `import()`. Bare specifiers
+/// (`@acme/ui`, `~/utils`) are kept for tsconfig- and workspace-aware
+/// resolution in the ops layer.
 pub fn detect_ts_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
     let mut refs = Vec::new();
+    let mut imported = ImportedNames::new();
+    let mut bases = Vec::new();
 
     for line in content.lines() {
         let trimmed = line.trim();
 
-        // Match import statements: import X from 'path' or import 'path'
-        if trimmed.starts_with("import ") {
-            // Extract path from quotes
-            if let Some(path_start) = trimmed.find(['\'', '"']) {
-                let quote_char = trimmed.chars().nth(path_start).unwrap();
-                let rest = &trimmed[path_start + 1..];
-                if let Some(path_end) = rest.find(quote_char) {
-                    let import_path = &rest[..path_end];
-                    // Only track relative imports
-                    if import_path.starts_with('.') {
-                        refs.push(SourceReference {
-                            source_path: source_path.to_path_buf(),
-                            kind: ReferenceKind::Imports,
-                            target_route: PathBuf::from(import_path),
-                        });
-                    }
+        // `class A extends B implements C, D {` / `interface A extends B {`
+        if let Some(heritage) = ts_heritage(trimmed) {
+            bases.extend(heritage);
+            continue;
+        }
+
+        for (start, import_path) in ts_line_specifiers(trimmed) {
+            refs.push(SourceReference {
+                source_path: source_path.to_path_buf(),
+                kind: ReferenceKind::Imports,
+                target_route: PathBuf::from(import_path),
+                specifier: Some(import_path.to_string()),
+            });
+            if trimmed.starts_with("import ") {
+                for name in ts_imported_names(&trimmed[..start]) {
+                    imported.insert(name.to_string(), PathBuf::from(import_path));
                 }
             }
         }
     }
 
+    for base in bases {
+        // `ns.Base` resolves through the namespace import `ns`
+        let root = base.split('.').next().unwrap_or(&base);
+        if let Some(route) = imported.get(root).cloned() {
+            let specifier = route.to_string_lossy().to_string();
+            push_implements(&mut refs, source_path, route, Some(specifier));
+        }
+    }
+
     refs
 }
 
+/// Module specifiers on a TS/JS line, with the byte offset where each
+/// specifier's string literal starts.
+fn ts_line_specifiers(line: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+
+    // `import x from './a'`, `export * from './b'`, `} from './c'`, `import './d'`
+    let is_statement =
+        line.starts_with("import ") || line.starts_with("export ") || line.starts_with('}');
+    if is_statement {
+        let literal = match line.find(" from ") {
+            Some(from) => Some(from + " from ".len()),
+            None if line.starts_with("import ") => Some("import ".len()),
+            None => None,
+        };
+        if let Some(start) = literal {
+            let start = start + (line[start..].len() - line[start..].trim_start().len());
+            if let Some(specifier) = ts_string_literal(&line[start..]) {
+                found.push((start, specifier));
+            }
+        }
+    }
+
+    // `require('./e')`, `import('./f')`
+    for loader in ["require(", "import("] {
+        for (index, _) in line.match_indices(loader) {
+            let start = index + loader.len();
+            let start = start + (line[start..].len() - line[start..].trim_start().len());
+            if let Some(specifier) = ts_string_literal(&line[start..]) {
+                if !found.iter().any(|(s, _)| *s == start) {
+                    found.push((start, specifier));
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Contents of a quoted string at the start of `text`.
+fn ts_string_literal(text: &str) -> Option<&str> {
+    let quote = text
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    let rest = &text[1..];
+    let literal = &rest[..rest.find(quote)?];
+    (!literal.is_empty()).then_some(literal)
+}
+
+/// Names bound by the clause of a TS/JS import (`import A, { B as C } from`).
+fn ts_imported_names(clause: &str) -> Vec<&str> {
+    let clause = clause.trim().trim_start_matches("import").trim();
+    let clause = clause.strip_suffix("from").unwrap_or(clause).trim();
+    let clause = clause.strip_prefix("type ").unwrap_or(clause);
+
+    clause
+        .split([',', '{', '}'])
+        .filter_map(|part| {
+            let part = part.trim().strip_prefix("type ").unwrap_or(part.trim());
+            // `* as ns`, `B as C`
+            let name = match part.split_once(" as ") {
+                Some((_, alias)) => alias.trim(),
+                None => part,
+            };
+            (!name.is_empty() && name != "*").then_some(name)
+        })
+        .collect()
+}
+
+/// Base classes and interfaces named on a TS/JS class or interface line.
+fn ts_heritage(line: &str) -> Option<Vec<String>> {
+    let declaration = ["export ", "default ", "abstract ", "declare "]
+        .iter()
+        .fold(line, |rest, prefix| {
+            rest.strip_prefix(prefix).unwrap_or(rest)
+        });
+    if !declaration.starts_with("class ") && !declaration.starts_with("interface ") {
+        return None;
+    }
+
+    let header = declaration.split('{').next().unwrap_or(declaration);
+    let mut names = Vec::new();
+    for clause in [" extends ", " implements "] {
+        let Some((_, rest)) = header.split_once(clause) else {
+            continue;
+        };
+        let rest = rest.split(" implements ").next().unwrap_or(rest);
+        names.extend(
+            rest.split(',')
+                .map(|n| n.split('<').next().unwrap_or("").trim().to_string())
+                .filter(|n| !n.is_empty()),
+        );
+    }
+    Some(names)
+}
+
 /// Detect references in Lean 4 source code.
 ///
 /// Handles `import` statements (e.g. `import Mathlib.Topology.Basic`)
@@ -710,6 +1742,7 @@ pub fn detect_lean_references(content: &str, source_path: &Path) -> Vec<SourceRe
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Imports,
                     target_route: PathBuf::from(format!("{}.lean", file_path)),
+                    specifier: None,
                 });
             }
             continue;
@@ -730,6 +1763,7 @@ pub fn detect_lean_references(content: &str, source_path: &Path) -> Vec<SourceRe
                     source_path: source_path.to_path_buf(),
                     kind: ReferenceKind::Uses,
                     target_route: PathBuf::from(format!("{}.lean", file_path)),
+                    specifier: None,
                 });
             }
         }
@@ -738,19 +1772,256 @@ pub fn detect_lean_references(content: &str, source_path: &Path) -> Vec<SourceRe
     refs
 }
 
+/// Detect references in Go source code.
+///
+/// Handles single `import "path"` lines and `import ( ... )` blocks, with
+/// or without an alias. Routes are package import paths; the ops layer maps
+/// them to directories through `go.mod` module paths.
+pub fn detect_go_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
+    let mut refs = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.split("//").next().unwrap_or("").trim();
+
+        let spec = if in_block {
+            if trimmed.starts_with(')') {
+                in_block = false;
+                continue;
+            }
+            trimmed
+        } else if let Some(rest) = trimmed
+            .strip_prefix("import")
+            .filter(|r| r.starts_with([' ', '\t', '(', '"']))
+        {
+            let rest = rest.trim_start();
+            match rest.strip_prefix('(') {
+                Some(inline) => {
+                    in_block = !inline.contains(')');
+                    inline
+                }
+                None => rest,
+            }
+        } else {
+            continue;
+        };
+
+        // `"fmt"`, `log "github.com/sirupsen/logrus"`, `_ "embed"`
+        if let Some(import_path) = go_import_path(spec) {
+            refs.push(SourceReference {
+                source_path: source_path.to_path_buf(),
+                kind: ReferenceKind::Imports,
+                target_route: PathBuf::from(import_path),
+                specifier: Some(import_path.to_string()),
+            });
+        }
+    }
+
+    refs
+}
+
+/// The quoted import path of a Go import spec.
+fn go_import_path(spec: &str) -> Option<&str> {
+    let start = spec.find(['"', '`'])?;
+    let quote = spec[start..].chars().next()?;
+    let rest = &spec[start + 1..];
+    let path = &rest[..rest.find(quote)?];
+    (!path.is_empty()).then_some(path)
+}
+
+/// Detect references in Java and Kotlin source code.
+///
+/// `import a.b.C` proposes `a/b/C.java` (or `.kt`), `import a.b.*` the
+/// package directory `a/b`, and `import static a.b.C.m` the class `C`. The
+/// specifier keeps the dotted name for package-aware resolution.
+pub fn detect_java_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
+    let mut refs = Vec::new();
+    let ext = source_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| if e == "kts" { "kt" } else { e })
+        .unwrap_or("java");
+
+    for line in content.lines() {
+        let Some(rest) = line.trim().strip_prefix("import ") else {
+            continue;
+        };
+        let (is_static, rest) = match rest.trim_start().strip_prefix("static ") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        // `a.b.C;` (Java) or `a.b.C as D` (Kotlin)
+        let name = rest
+            .split([';', ' '])
+            .next()
+            .unwrap_or("")
+            .trim()
+            .trim_matches('`');
+        if name.is_empty() || !name.contains('.') {
+            continue;
+        }
+
+        let class_route = |class: &str| format!("{}.{}", class.replace('.', "/"), ext);
+        let (specifier, route) = match name.strip_suffix(".*") {
+            // `import static a.b.C.*` imports the members of class `C`
+            Some(class) if is_static => (class, class_route(class)),
+            // `import a.b.*` imports the whole package
+            Some(package) => (name, package.replace('.', "/")),
+            None if is_static => {
+                let class = name.rsplit_once('.').map_or(name, |(class, _)| class);
+                (class, class_route(class))
+            }
+            None => (name, class_route(name)),
+        };
+
+        refs.push(SourceReference {
+            source_path: source_path.to_path_buf(),
+            kind: ReferenceKind::Imports,
+            target_route: PathBuf::from(route),
+            specifier: Some(specifier.to_string()),
+        });
+    }
+
+    refs
+}
+
+/// Detect references in C and C++ source code.
+///
+/// Only quoted `#include "..."` directives are tracked; angle-bracket
+/// includes name system or third-party headers.
+pub fn detect_c_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
+    let mut refs = Vec::new();
+
+    for line in content.lines() {
+        let Some(directive) = line.trim().strip_prefix('#') else {
+            continue;
+        };
+        let Some(rest) = directive.trim_start().strip_prefix("include") else {
+            continue;
+        };
+        let Some(header) = rest
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|r| r.split('"').next())
+            .filter(|h| !h.is_empty())
+        else {
+            continue;
+        };
+
+        refs.push(SourceReference {
+            source_path: source_path.to_path_buf(),
+            kind: ReferenceKind::Imports,
+            target_route: PathBuf::from(header),
+            specifier: Some(header.to_string()),
+        });
+    }
+
+    refs
+}
+
 /// Detect references based on file extension.
+///
+/// With the `tree-sitter` feature enabled, languages that have a grammar are
+/// parsed into syntax trees (see [`syntax`]); everything else uses the
+/// line-based detectors below.
 pub fn detect_references(content: &str, source_path: &Path) -> Vec<SourceReference> {
+    #[cfg(feature = "tree-sitter")]
+    if let Some(refs) = syntax::detect_references(content, source_path) {
+        return refs;
+    }
+
     match source_path.extension().and_then(|e| e.to_str()) {
         Some("rs") => detect_rust_references(content, source_path),
         Some("py") => detect_python_references(content, source_path),
-        Some("ts") | Some("tsx") | Some("js") | Some("jsx") => {
-            detect_ts_references(content, source_path)
-        }
+        Some("ts") | Some("tsx") | Some("mts") | Some("cts") | Some("js") | Some("jsx")
+        | Some("mjs") | Some("cjs") => detect_ts_references(content, source_path),
         Some("lean") => detect_lean_references(content, source_path),
+        Some("go") => detect_go_references(content, source_path),
+        Some("java") | Some("kt") | Some("kts") => detect_java_references(content, source_path),
+        Some("c") | Some("h") | Some("cc") | Some("cpp") | Some("cxx") | Some("hh")
+        | Some("hpp") | Some("hxx") => detect_c_references(content, source_path),
         _ => Vec::new(),
     }
 }
 
+/// Detect symbol definitions (and, with tree-sitter, calls/implements).
+///
+/// Without the `tree-sitter` feature only top-level definitions found by
+/// line prefix are reported and `references` stays empty.
+pub fn detect_symbols(content: &str, source_path: &Path) -> SourceSymbols {
+    #[cfg(feature = "tree-sitter")]
+    if let Some(symbols) = syntax::detect_symbols(content, source_path) {
+        return symbols;
+    }
+
+    let ext = source_path.extension().and_then(|e| e.to_str());
+    let mut symbols = SourceSymbols::default();
+
+    for (index, line) in content.lines().enumerate() {
+        // Only unindented lines: nested definitions need a real parser.
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let definition = match ext {
+            Some("rs") => {
+                let rest = line
+                    .strip_prefix("pub(crate) ")
+                    .or_else(|| line.strip_prefix("pub "))
+                    .unwrap_or(line);
+                let rest = rest.strip_prefix("async ").unwrap_or(rest);
+                [
+                    ("fn ", SymbolKind::Function),
+                    ("struct ", SymbolKind::Struct),
+                    ("enum ", SymbolKind::Enum),
+                    ("trait ", SymbolKind::Trait),
+                ]
+                .into_iter()
+                .find_map(|(prefix, kind)| rest.strip_prefix(prefix).map(|r| (r, kind)))
+            }
+            Some("py") => [
+                ("def ", SymbolKind::Function),
+                ("async def ", SymbolKind::Function),
+                ("class ", SymbolKind::Class),
+            ]
+            .into_iter()
+            .find_map(|(prefix, kind)| line.strip_prefix(prefix).map(|r| (r, kind))),
+            Some("ts") | Some("tsx") | Some("js") | Some("jsx") => {
+                let rest = line.strip_prefix("export ").unwrap_or(line);
+                let rest = rest.strip_prefix("default ").unwrap_or(rest);
+                let rest = rest.strip_prefix("async ").unwrap_or(rest);
+                [
+                    ("function ", SymbolKind::Function),
+                    ("class ", SymbolKind::Class),
+                    ("interface ", SymbolKind::Trait),
+                    ("enum ", SymbolKind::Enum),
+                ]
+                .into_iter()
+                .find_map(|(prefix, kind)| rest.strip_prefix(prefix).map(|r| (r, kind)))
+            }
+            _ => None,
+        };
+
+        let Some((rest, kind)) = definition else {
+            continue;
+        };
+        let name: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+            .collect();
+        if !name.is_empty() {
+            symbols.definitions.push(SymbolDefinition {
+                qualified_name: name.clone(),
+                name,
+                kind,
+                line: index + 1,
+            });
+        }
+    }
+
+    symbols
+}
+
 /// Map file extension to language name.
 fn extension_to_language(ext: &str) -> &'static str {
     match ext {
@@ -758,13 +2029,14 @@ fn extension_to_language(ext: &str) -> &'static str {
         "py" => "python",
         "js" => "javascript",
         "ts" => "typescript",
-        "tsx" => "typescript",
-        "jsx" => "javascript",
+        "tsx" | "mts" | "cts" => "typescript",
+        "jsx" | "mjs" | "cjs" => "javascript",
         "go" => "go",
         "java" => "java",
+        "kt" | "kts" => "kotlin",
         "lean" => "lean",
         "c" | "h" => "c",
-        "cpp" | "hpp" | "cc" | "cxx" => "cpp",
+        "cpp" | "hpp" | "cc" | "cxx" | "hh" | "hxx" => "cpp",
         "md" => "markdown",
         "json" => "json",
         "yaml" | "yml" => "yaml",
@@ -1581,4 +2853,454 @@ def someDef := sorry
         let refs = detect_lean_references(content, path);
         assert!(refs.is_empty());
     }
+
+    fn import_specifiers(refs: &[SourceReference]) -> Vec<String> {
+        refs.iter().filter_map(|r| r.specifier.clone()).collect()
+    }
+
+    #[test]
+    fn test_detect_go_references() {
+        let content = r#"package main
+
+import "fmt"
+import log "github.com/sirupsen/logrus"
+
+import (
+    "context" // for cancellation
+    store "github.com/acme/svc/internal/store"
+    _ "embed"
+)
+
+func main() {
+    importPath := "not/an/import"
+}
+"#;
+        let refs = detect_references(content, Path::new("cmd/svc/main.go"));
+        assert_eq!(
+            import_specifiers(&refs),
+            vec![
+                "fmt",
+                "github.com/sirupsen/logrus",
+                "context",
+                "github.com/acme/svc/internal/store",
+                "embed",
+            ]
+        );
+        assert!(refs.iter().all(|r| r.kind == ReferenceKind::Imports));
+    }
+
+    #[test]
+    fn test_detect_java_and_kotlin_references() {
+        let java = "package com.acme.app;\n\nimport com.acme.store.Store;\nimport static com.acme.util.Strings.join;\nimport com.acme.model.*;\n";
+        let refs = detect_references(java, Path::new("src/main/java/com/acme/app/App.java"));
+        assert_eq!(
+            import_specifiers(&refs),
+            vec![
+                "com.acme.store.Store",
+                "com.acme.util.Strings",
+                "com.acme.model.*"
+            ]
+        );
+        assert_eq!(
+            refs[0].target_route,
+            PathBuf::from("com/acme/store/Store.java")
+        );
+        assert_eq!(refs[2].target_route, PathBuf::from("com/acme/model"));
+
+        let kotlin = "package com.acme.app\n\nimport com.acme.store.Store as S\nimport kotlinx.coroutines.launch\n";
+        let refs = detect_references(kotlin, Path::new("src/main/kotlin/App.kt"));
+        assert_eq!(
+            import_specifiers(&refs),
+            vec!["com.acme.store.Store", "kotlinx.coroutines.launch"]
+        );
+        assert_eq!(
+            refs[0].target_route,
+            PathBuf::from("com/acme/store/Store.kt")
+        );
+    }
+
+    #[test]
+    fn test_detect_c_references() {
+        let content =
+            "#include <stdio.h>\n#include \"util/strings.h\"\n#  include \"config.h\" // local\n";
+        let refs = detect_references(content, Path::new("src/main.c"));
+        assert_eq!(import_specifiers(&refs), vec!["util/strings.h", "config.h"]);
+
+        let refs = detect_references("#include \"widget.hpp\"\n", Path::new("src/app.cpp"));
+        assert_eq!(import_specifiers(&refs), vec!["widget.hpp"]);
+    }
+
+    #[test]
+    fn test_builder_symbols_and_cross_file_calls() {
+        let mut builder = SourceCodeGraphBuilder::new();
+        let lib = Path::new("/repo/src/lib.rs");
+        let util = Path::new("/repo/src/util.rs");
+        let lib_id = builder.add_file(lib, "src/lib.rs");
+        builder.add_file(util, "src/util.rs");
+
+        let lib_symbols = detect_symbols("pub fn run() {\n    helper();\n}\n", lib);
+        let util_symbols = detect_symbols("pub fn helper() {}\n", util);
+        builder.add_symbols(lib, &lib_symbols);
+        builder.add_symbols(util, &util_symbols);
+        builder.add_symbol_references(
+            lib,
+            &[SymbolReference {
+                from: "run".to_string(),
+                kind: ReferenceKind::Calls,
+                target: "helper".to_string(),
+            }],
+        );
+
+        let run_id = builder.get_symbol_id(lib, "run").unwrap();
+        let helper_id = builder.find_symbol("helper", lib).unwrap();
+        let graph = builder.build();
+
+        let run = graph.nodes.iter().find(|n| n.id == run_id).unwrap();
+        assert_eq!(run.kind, GraphNodeKind::Symbol);
+        assert_eq!(run.metadata["path"], "/repo/src/lib.rs::run");
+        assert_eq!(run.metadata["symbol_kind"], "function");
+        assert_eq!(run.metadata["line"], "1");
+
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.from == lib_id && e.to == run_id && e.relationship == "contains"));
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.from == run_id && e.to == helper_id && e.relationship == "calls"));
+    }
+
+    #[test]
+    fn test_find_symbol_skips_ambiguous_names() {
+        let mut builder = SourceCodeGraphBuilder::new();
+        let def = |name: &str| SymbolDefinition {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind: SymbolKind::Function,
+            line: 1,
+        };
+        for file in ["a.rs", "b.rs", "c.rs"] {
+            builder.add_file(Path::new(file), file);
+        }
+        builder.add_symbol(Path::new("a.rs"), &def("new"));
+        builder.add_symbol(Path::new("b.rs"), &def("new"));
+
+        assert!(builder.find_symbol("new", Path::new("c.rs")).is_none());
+        assert_eq!(
+            builder.find_symbol("new", Path::new("b.rs")),
+            builder.get_symbol_id(Path::new("b.rs"), "new")
+        );
+        assert!(builder
+            .add_symbol(Path::new("missing.rs"), &def("x"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_builder_from_graph_updates_keep_ids_stable() {
+        let def = |name: &str, line: usize| SymbolDefinition {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind: SymbolKind::Function,
+            line,
+        };
+        let (src, lib, util) = (
+            Path::new("/repo/src"),
+            Path::new("/repo/src/lib.rs"),
+            Path::new("/repo/src/util.rs"),
+        );
+        let mut builder = SourceCodeGraphBuilder::new();
+        builder.add_directory(src);
+        let lib_id = builder.add_file(lib, "src/lib.rs");
+        let util_id = builder.add_file(util, "src/util.rs");
+        builder.add_hierarchy_edge(src, lib);
+        builder.add_hierarchy_edge(src, util);
+        builder.add_edge(lib_id, util_id, ReferenceKind::Uses);
+        let run_id = builder.add_symbol(lib, &def("run", 1)).unwrap();
+        let old_id = builder.add_symbol(lib, &def("old", 5)).unwrap();
+        let previous = builder.build();
+
+        // lib.rs was edited: `old` is gone, `run` moved, util.rs is deleted
+        let mut builder = SourceCodeGraphBuilder::from_graph(previous.clone());
+        let kept = [def("run", 3)];
+        builder.clear_file_references(lib, &kept);
+        assert_eq!(builder.add_symbol(lib, &kept[0]), Some(run_id));
+        assert!(builder.remove_file(util));
+        builder.prune_empty_directories(src);
+        let fresh_id = builder.add_file(Path::new("/repo/src/new.rs"), "src/new.rs");
+        let graph = builder.build();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(fresh_id, NodeId::from_key("/repo/src/new.rs"));
+        let run = graph.nodes.iter().find(|n| n.id == run_id).unwrap();
+        assert_eq!(run.metadata["line"], "3");
+
+        let diff = previous.diff(&graph);
+        let ids = |nodes: &[GraphNode]| nodes.iter().map(|n| n.id).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.added_nodes), vec![fresh_id]);
+        assert_eq!(ids(&diff.removed_nodes), vec![util_id, old_id]);
+        assert!(diff.added_edges.is_empty());
+        // src→util, lib→util (uses) and lib→old
+        assert_eq!(diff.removed_edges.len(), 3);
+        assert!(graph.diff(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_node_ids_derive_from_workspace_relative_paths() {
+        let build = |root: &str, files: &[&str]| {
+            let root = Path::new(root);
+            let mut builder = SourceCodeGraphBuilder::new().with_root(root);
+            builder.add_directory(root);
+            for file in files {
+                builder.add_file(&root.join(file), file);
+            }
+            builder.build()
+        };
+        let id_of = |graph: &SourceCodeGraph, name: &str| {
+            graph.nodes.iter().find(|n| n.name == name).unwrap().id
+        };
+
+        let first = build("/a/repo", &["lib.rs", "main.rs"]);
+        // Another checkout location, another traversal order, one more file
+        let second = build("/b/repo", &["new.rs", "main.rs", "lib.rs"]);
+
+        assert!(second.has_stable_ids());
+        assert_eq!(id_of(&first, "lib.rs"), id_of(&second, "lib.rs"));
+        assert_eq!(id_of(&first, "main.rs"), NodeId::from_key("main.rs"));
+        assert_eq!(id_of(&first, "repo"), NodeId::from_key("."));
+        assert_eq!(
+            node_key(
+                Path::new("/a/repo/src/./lib.rs"),
+                Some(Path::new("/a/repo"))
+            ),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            node_key(Path::new("/elsewhere/x.rs"), Some(Path::new("/a"))),
+            "/elsewhere/x.rs"
+        );
+    }
+
+    #[test]
+    fn test_node_id_collisions_are_probed() {
+        let mut ids = NodeIdAllocator::default();
+        let first = ids.allocate("src/lib.rs");
+        let second = ids.allocate("src/lib.rs");
+
+        assert_eq!(first, NodeId::from_key("src/lib.rs"));
+        assert_eq!(second, NodeId::from_key("src/lib.rs#1"));
+        ids.release(first);
+        assert_eq!(ids.allocate("src/lib.rs"), first);
+    }
+
+    #[test]
+    fn test_migrate_to_stable_ids_matches_fresh_build() {
+        let root = Path::new("/repo");
+        let lib = root.join("src/lib.rs");
+        let util = root.join("src/util.rs");
+        let def = SymbolDefinition {
+            name: "run".to_string(),
+            qualified_name: "run".to_string(),
+            kind: SymbolKind::Function,
+            line: 1,
+        };
+        let mut builder = SourceCodeGraphBuilder::new().with_root(root);
+        builder.add_directory(&root.join("src"));
+        let lib_id = builder.add_file(&lib, "src/lib.rs");
+        let util_id = builder.add_file(&util, "src/util.rs");
+        builder.add_edge(lib_id, util_id, ReferenceKind::Uses);
+        builder.add_symbol(&lib, &def);
+        let fresh = builder.build();
+
+        // What an older build persisted: sequential IDs, no root or scheme
+        let mut legacy = fresh.clone();
+        let sequential: HashMap<NodeId, NodeId> = legacy
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id, NodeId(i as u64)))
+            .collect();
+        legacy.remap_node_ids(&sequential);
+        legacy.metadata.clear();
+        assert!(!legacy.has_stable_ids());
+        // Updating it keeps the sequential IDs, so it is not marked as migrated
+        assert!(!SourceCodeGraphBuilder::from_graph(legacy.clone())
+            .build()
+            .has_stable_ids());
+
+        let mapping = legacy.migrate_to_stable_ids(root);
+
+        assert!(legacy.has_stable_ids());
+        assert_eq!(mapping[&NodeId(1)], lib_id);
+        assert!(SourceCodeGraphBuilder::from_graph(legacy.clone())
+            .build()
+            .has_stable_ids());
+        let ids = |g: &SourceCodeGraph| g.nodes.iter().map(|n| n.id).collect::<Vec<_>>();
+        assert_eq!(ids(&legacy), ids(&fresh));
+        assert!(fresh.diff(&legacy).is_empty());
+    }
+
+    #[test]
+    fn test_graph_change_set_from_git_changes() {
+        let change = |path: &str, kind: GitChangeKind| GitFileChange {
+            path: PathBuf::from(path),
+            kind,
+            staged: false,
+        };
+        let snapshot = GitChangeSnapshot {
+            changes: vec![
+                change("a.rs", GitChangeKind::Modified),
+                change("a.rs", GitChangeKind::Modified),
+                change("b.rs", GitChangeKind::Untracked),
+                change("old.rs", GitChangeKind::RenamedFrom),
+                change("new.rs", GitChangeKind::RenamedTo),
+                change("c.rs", GitChangeKind::Deleted),
+            ],
+            captured_at: None,
+        };
+        let changes = GraphChangeSet::from_git_changes(&snapshot);
+
+        assert_eq!(changes.modified, vec![PathBuf::from("a.rs")]);
+        assert_eq!(
+            changes.added,
+            vec![PathBuf::from("b.rs"), PathBuf::from("new.rs")]
+        );
+        assert_eq!(
+            changes.deleted,
+            vec![PathBuf::from("old.rs"), PathBuf::from("c.rs")]
+        );
+        assert_eq!(changes.len(), 5);
+    }
+
+    fn implements_targets(refs: &[SourceReference]) -> Vec<String> {
+        refs.iter()
+            .filter(|r| r.kind == ReferenceKind::Implements)
+            .map(|r| r.target_route.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_rust_implements() {
+        let content = r#"
+use crate::store::{Store, Snapshot as Snap};
+use std::fmt::Display;
+
+impl Store for Builder {}
+impl<T: Clone> Snap<T> for Wrapper<T> {}
+impl crate::sampler::Sampler for Builder {}
+impl Display for Builder {}
+impl Builder {}
+"#;
+        let refs = detect_rust_references(content, Path::new("src/builder.rs"));
+        assert_eq!(
+            implements_targets(&refs),
+            vec!["store.rs".to_string(), "sampler.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_detect_rust_specifiers_and_path_attribute() {
+        let content = r#"
+use serde_json::Value;
+use super::store::{self, Store as S};
+
+#[path = "../shared/util.rs"]
+mod util;
+mod config;
+"#;
+        let refs = detect_rust_references(content, Path::new("src/net/client.rs"));
+        let specifiers: Vec<_> = refs.iter().filter_map(|r| r.specifier.as_deref()).collect();
+        assert!(specifiers.contains(&"serde_json::Value"));
+        assert!(specifiers.contains(&"super::store"));
+        assert!(specifiers.contains(&"self::config"));
+
+        let util: Vec<_> = refs.iter().filter(|r| r.specifier.is_none()).collect();
+        assert_eq!(util.len(), 1);
+        assert_eq!(util[0].target_route, PathBuf::from("src/shared/util.rs"));
+    }
+
+    #[test]
+    fn test_find_node_by_path_suffix_matches_whole_components() {
+        let mut builder = SourceCodeGraphBuilder::new();
+        let models = builder.add_file(Path::new("/repo/src/core/models.rs"), "src/core/models.rs");
+        builder.add_file(Path::new("/repo/src/user_models.rs"), "src/user_models.rs");
+        let nested = builder.add_file(
+            Path::new("/repo/crates/a/src/store.rs"),
+            "crates/a/src/store.rs",
+        );
+        let top = builder.add_file(Path::new("/repo/src/store.rs"), "src/store.rs");
+
+        let find = |route: &str| builder.find_node_by_path_suffix(Path::new(route));
+        assert_eq!(find("core/models.rs"), Some(models));
+        assert_eq!(find("./models.rs"), Some(models));
+        assert_eq!(find("store.rs"), Some(top));
+        assert_eq!(find("a/src/store.rs"), Some(nested));
+        // Fallback: components inside the path, but never the file name alone
+        assert_eq!(find("src/core"), Some(models));
+        assert_eq!(find("other/models.rs"), None);
+        assert_eq!(find("other/missing.rs"), None);
+    }
+
+    #[test]
+    fn test_detect_python_implements() {
+        let content = "from app.core.base import Base, Mixin as M\nimport app.db as db\n\nclass User(Base, M, metaclass=Meta):\n    pass\n\nclass Row(db.Model):\n    pass\n\nclass Local(object):\n    pass\n";
+        let refs = detect_python_references(content, Path::new("app/models.py"));
+        assert_eq!(
+            implements_targets(&refs),
+            vec!["app/core/base.py".to_string(), "app/db.py".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_detect_ts_implements() {
+        let content = r#"
+import Base, { Shape as S } from './base';
+import * as ui from './ui';
+import { Thing } from 'external';
+export class Square extends Base implements S, ui.Widget {
+}
+class Other extends Thing {}
+"#;
+        let refs = detect_ts_references(content, Path::new("src/square.ts"));
+        assert_eq!(
+            implements_targets(&refs),
+            vec![
+                "./base".to_string(),
+                "./ui".to_string(),
+                "external".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_ts_references_specifiers() {
+        let content = r#"
+import React from 'react';
+import { Button } from '@acme/ui';
+import {
+    format,
+    parse,
+} from "~/utils/date";
+import './styles.css';
+export * from './types';
+export { helper } from "../helper";
+export const name = "not from here";
+const fs = require('node:fs');
+const lazy = () => import('./lazy');
+"#;
+        let refs = detect_ts_references(content, Path::new("src/app.tsx"));
+        assert_eq!(
+            import_specifiers(&refs),
+            vec![
+                "react",
+                "@acme/ui",
+                "~/utils/date",
+                "./styles.css",
+                "./types",
+                "../helper",
+                "node:fs",
+                "./lazy",
+            ]
+        );
+    }
 }
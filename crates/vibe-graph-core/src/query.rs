@@ -0,0 +1,1551 @@
+//! Declarative queries over a [`SourceCodeGraph`].
+//!
+//! A small Cypher-like language for questions that would otherwise need a
+//! hand-written traversal:
+//!
+//! ```text
+//! MATCH (f:file)<-[:uses]-(user:file)
+//! WHERE f.relative_path GLOB "crates/*/src/**" AND NOT EXISTS(f.has_tests)
+//! RETURN f.relative_path AS file, count(DISTINCT user) AS users
+//! HAVING users > 5
+//! ORDER BY users DESC
+//! LIMIT 20
+//! ```
+//!
+//! - Node patterns: `(var:kind|kind {key: "value"})`. Kinds are the
+//!   [`GraphNodeKind`] names, case-insensitive (`dir` for `directory`).
+//! - Edge patterns: `-[:rel|rel *min..max]->`, `<-[...]-` or undirected
+//!   `-[...]-`; `-->`, `<--` and `--` match any relationship. Each step binds
+//!   every reachable node once, whatever the number of paths to it.
+//! - Properties: `id`, `name`, `kind` and any node metadata key.
+//! - Predicates: `=`, `!=`, `<`, `<=`, `>`, `>=`, `CONTAINS`,
+//!   `STARTS WITH`, `ENDS WITH`, `GLOB` (`*`, `**`, `?`), `EXISTS(var.key)`,
+//!   combined with `AND`, `OR`, `NOT`. Metadata values compare as numbers
+//!   when both sides are numeric.
+//! - Aggregates: `count(*)`, `count([DISTINCT] x)`, `sum`, `avg`, `min`,
+//!   `max`, `collect`. Non-aggregated `RETURN` items are the grouping keys.
+//!   `HAVING` filters the returned rows and may use their aliases.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+use crate::{GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Bindings a query may produce while matching before it fails, so a
+/// pattern like `MATCH (a), (b), (c)` cannot exhaust memory.
+pub const MAX_BINDINGS: usize = 1_000_000;
+
+/// Error raised while parsing or running a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    /// Byte offset in the query text, when the error points at a token.
+    pub position: Option<usize>,
+    pub message: String,
+}
+
+impl QueryError {
+    fn at(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position: Some(position),
+            message: message.into(),
+        }
+    }
+
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            position: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "query error at {}: {}", position, self.message),
+            None => write!(f, "query error: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A node as returned by a query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryNode {
+    pub id: NodeId,
+    pub name: String,
+    pub kind: GraphNodeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// A single value in a query result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum QueryValue {
+    Null,
+    Bool(bool),
+    Number(#[serde(serialize_with = "serialize_number")] f64),
+    String(String),
+    Node(QueryNode),
+    List(Vec<QueryValue>),
+}
+
+impl fmt::Display for QueryValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryValue::Null => write!(f, "null"),
+            QueryValue::Bool(b) => write!(f, "{}", b),
+            QueryValue::Number(n) if is_integral(*n) => write!(f, "{}", *n as i64),
+            QueryValue::Number(n) => write!(f, "{}", n),
+            QueryValue::String(s) => write!(f, "{}", s),
+            QueryValue::Node(node) => write!(f, "{}", node.path.as_ref().unwrap_or(&node.name)),
+            QueryValue::List(items) => {
+                let items: Vec<String> = items.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+/// Tabular result of a query.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryResult {
+    /// Column names: `AS` aliases, or the item's source text.
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<QueryValue>>,
+}
+
+/// A parsed query, reusable across graphs.
+#[derive(Debug, Clone)]
+pub struct Query {
+    patterns: Vec<Pattern>,
+    filter: Option<Expr>,
+    distinct: bool,
+    returns: Vec<ReturnItem>,
+    having: Option<Expr>,
+    order: Vec<(Expr, bool)>,
+    limit: Option<usize>,
+    /// Variable name → binding slot.
+    vars: Vec<String>,
+    max_bindings: usize,
+}
+
+impl Query {
+    /// Parse query text.
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let tokens = lex(text)?;
+        Parser {
+            text,
+            tokens,
+            pos: 0,
+            vars: Vec::new(),
+        }
+        .query()
+    }
+
+    /// Run the query against a graph.
+    ///
+    /// Fails once matching has produced [`MAX_BINDINGS`] bindings (see
+    /// [`Query::with_max_bindings`]). Without aggregates or `ORDER BY`,
+    /// matching stops as soon as `LIMIT` rows are found.
+    pub fn execute(&self, graph: &SourceCodeGraph) -> Result<QueryResult, QueryError> {
+        let index = GraphIndex::new(graph);
+        let aggregated = self.returns.iter().any(|r| r.expr.has_aggregate());
+        let streaming = !aggregated && self.order.is_empty();
+        let limit = self.limit.unwrap_or(usize::MAX);
+
+        let mut rows = Vec::new();
+        let mut bindings = Vec::new();
+        let mut seen = HashSet::new();
+        let mut budget = self.max_bindings;
+        let root = vec![None; self.vars.len()];
+        self.match_from(&index, 0, &root, &mut budget, &mut |binding| {
+            if let Some(filter) = &self.filter {
+                if !Scope::row(&index, self, &binding).eval(filter)?.is_truthy() {
+                    return Ok(true);
+                }
+            }
+            if !streaming {
+                bindings.push(binding);
+                return Ok(true);
+            }
+            if let Some(row) = self.row(&index, &[&binding])? {
+                if !self.distinct || seen.insert(value_key(&QueryValue::List(row.1.clone()))) {
+                    rows.push(row);
+                }
+            }
+            Ok(rows.len() < limit)
+        })?;
+
+        if !streaming {
+            for group in &self.group(&index, &bindings)? {
+                if let Some(row) = self.row(&index, group)? {
+                    rows.push(row);
+                }
+            }
+            if self.distinct {
+                rows.retain(|(_, row)| seen.insert(value_key(&QueryValue::List(row.clone()))));
+            }
+        }
+        if !self.order.is_empty() {
+            rows.sort_by(|(a, _), (b, _)| {
+                for ((x, y), (_, descending)) in a.iter().zip(b).zip(&self.order) {
+                    let ordering = compare_values(x, y);
+                    if ordering != Ordering::Equal {
+                        return if *descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        };
+                    }
+                }
+                Ordering::Equal
+            });
+        }
+        rows.truncate(limit);
+
+        Ok(QueryResult {
+            columns: self.returns.iter().map(|r| r.alias.clone()).collect(),
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+        })
+    }
+
+    /// Set how many bindings matching may produce before the query fails.
+    pub fn with_max_bindings(mut self, max_bindings: usize) -> Self {
+        self.max_bindings = max_bindings;
+        self
+    }
+
+    /// Extend `binding` with every match of the patterns from `depth` on,
+    /// depth first, handing complete bindings to `emit` until it returns
+    /// `false`. Returns whether to go on.
+    fn match_from(
+        &self,
+        index: &GraphIndex<'_>,
+        depth: usize,
+        binding: &Binding,
+        budget: &mut usize,
+        emit: &mut dyn FnMut(Binding) -> Result<bool, QueryError>,
+    ) -> Result<bool, QueryError> {
+        let Some(pattern) = self.patterns.get(depth) else {
+            return emit(binding.clone());
+        };
+        for next in index.match_pattern(pattern, binding) {
+            *budget = budget.checked_sub(1).ok_or_else(|| {
+                QueryError::new(format!(
+                    "query matches more than {} bindings; narrow its patterns",
+                    self.max_bindings
+                ))
+            })?;
+            if !self.match_from(index, depth + 1, &next, budget, emit)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The sort keys and values of the row for a group of bindings, or
+    /// `None` when `HAVING` rejects it.
+    fn row(&self, index: &GraphIndex<'_>, group: &[&Binding]) -> Result<Option<Row>, QueryError> {
+        let scope = Scope::group(index, self, group);
+        let row: Vec<QueryValue> = self
+            .returns
+            .iter()
+            .map(|item| scope.eval(&item.expr))
+            .collect::<Result<_, _>>()?;
+
+        let scope = scope.with_columns(&self.returns, &row);
+        if let Some(having) = &self.having {
+            if !scope.eval(having)?.is_truthy() {
+                return Ok(None);
+            }
+        }
+        let sort_keys: Vec<QueryValue> = self
+            .order
+            .iter()
+            .map(|(expr, _)| scope.eval(expr))
+            .collect::<Result<_, _>>()?;
+        Ok(Some((sort_keys, row)))
+    }
+
+    /// Split bindings into one group per distinct grouping key. Without
+    /// aggregates every binding is its own group.
+    fn group<'b>(
+        &self,
+        index: &GraphIndex<'_>,
+        bindings: &'b [Binding],
+    ) -> Result<Vec<Vec<&'b Binding>>, QueryError> {
+        if !self.returns.iter().any(|r| r.expr.has_aggregate()) {
+            return Ok(bindings.iter().map(|b| vec![b]).collect());
+        }
+
+        let keys: Vec<&Expr> = self
+            .returns
+            .iter()
+            .filter(|r| !r.expr.has_aggregate())
+            .map(|r| &r.expr)
+            .collect();
+        let mut order = Vec::new();
+        let mut groups: HashMap<String, Vec<&Binding>> = HashMap::new();
+        for binding in bindings {
+            let scope = Scope::row(index, self, binding);
+            let key = keys
+                .iter()
+                .map(|expr| scope.eval(expr).map(|v| value_key(&v)))
+                .collect::<Result<Vec<_>, _>>()?
+                .join("\u{1f}");
+            let group = groups.entry(key.clone()).or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            });
+            group.push(binding);
+        }
+
+        // Aggregating nothing still yields one row (`count(*)` = 0)
+        if order.is_empty() && keys.is_empty() {
+            return Ok(vec![Vec::new()]);
+        }
+        Ok(order
+            .into_iter()
+            .map(|key| groups.remove(&key).unwrap_or_default())
+            .collect())
+    }
+}
+
+impl SourceCodeGraph {
+    /// Parse and run a query (see [`crate::query`]).
+    pub fn query(&self, text: &str) -> Result<QueryResult, QueryError> {
+        Query::parse(text)?.execute(self)
+    }
+}
+
+// =============================================================================
+// Syntax tree
+// =============================================================================
+
+type Binding = Vec<Option<NodeId>>;
+
+/// Sort keys and values of a result row.
+type Row = (Vec<QueryValue>, Vec<QueryValue>);
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    start: NodePattern,
+    steps: Vec<(EdgePattern, NodePattern)>,
+}
+
+#[derive(Debug, Clone)]
+struct NodePattern {
+    slot: usize,
+    kinds: Vec<GraphNodeKind>,
+    properties: Vec<(String, QueryValue)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+#[derive(Debug, Clone)]
+struct EdgePattern {
+    relationships: Vec<String>,
+    direction: Direction,
+    min: usize,
+    /// `None` for unbounded.
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct ReturnItem {
+    expr: Expr,
+    alias: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Glob,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Collect,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(QueryValue),
+    /// A pattern variable, or a `RETURN` alias in `HAVING`/`ORDER BY`.
+    Name(String),
+    Property(String, String),
+    Exists(String, String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    /// `arg` is `None` for `count(*)`.
+    Aggregate {
+        func: Aggregate,
+        distinct: bool,
+        arg: Option<Box<Expr>>,
+    },
+}
+
+impl Expr {
+    fn has_aggregate(&self) -> bool {
+        match self {
+            Expr::Aggregate { .. } => true,
+            Expr::Not(e) => e.has_aggregate(),
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::Compare(a, _, b) => {
+                a.has_aggregate() || b.has_aggregate()
+            }
+            _ => false,
+        }
+    }
+}
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(&'static str),
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const PUNCTUATION: &[&str] = &[
+    "<-", "->", "..", "<=", ">=", "!=", "<>", "(", ")", "[", "]", "{", "}", ":", ",", ".", "|",
+    "*", "-", "<", ">", "=",
+];
+
+fn lex(text: &str) -> Result<Vec<Spanned>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let token = if c == '"' || c == '\'' || c == '`' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => value.push(escaped),
+                        None => return Err(QueryError::at(start, "unterminated string")),
+                    },
+                    Some((_, ch)) if ch == c => break,
+                    Some((_, ch)) => value.push(ch),
+                    None => return Err(QueryError::at(start, "unterminated string")),
+                }
+            }
+            // Backticks quote identifiers (metadata keys with odd characters)
+            if c == '`' {
+                Token::Ident(value)
+            } else {
+                Token::Str(value)
+            }
+        } else if c.is_ascii_digit() {
+            let mut end = eat_digits(&mut chars, start);
+            // `1..3` is a range, not a decimal
+            let mut rest = text[end..].chars();
+            if rest.next() == Some('.') && rest.next().is_some_and(|c| c.is_ascii_digit()) {
+                chars.next();
+                end = eat_digits(&mut chars, end + 1);
+            }
+            let number = text[start..end]
+                .parse()
+                .map_err(|_| QueryError::at(start, "invalid number"))?;
+            Token::Num(number)
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            Token::Ident(text[start..end].to_string())
+        } else {
+            let Some(punct) = PUNCTUATION.iter().find(|p| text[start..].starts_with(**p)) else {
+                return Err(QueryError::at(
+                    start,
+                    format!("unexpected character '{}'", c),
+                ));
+            };
+            // Punctuation is ASCII: one char per byte
+            for _ in 0..punct.len() {
+                chars.next();
+            }
+            Token::Punct(if *punct == "<>" { "!=" } else { punct })
+        };
+
+        let end = chars.peek().map_or(text.len(), |&(i, _)| i);
+        tokens.push(Spanned { token, start, end });
+    }
+    Ok(tokens)
+}
+
+/// Consume ASCII digits, returning the byte offset after the last one.
+fn eat_digits(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>, mut end: usize) -> usize {
+    while let Some(&(i, c)) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        end = i + 1;
+        chars.next();
+    }
+    end
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+    vars: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn query(mut self) -> Result<Query, QueryError> {
+        self.expect_keyword("MATCH")?;
+        let mut patterns = vec![self.pattern()?];
+        while self.eat_punct(",") {
+            patterns.push(self.pattern()?);
+        }
+
+        let filter = if self.eat_keyword("WHERE") {
+            let filter = self.expr()?;
+            if filter.has_aggregate() {
+                return Err(QueryError::new(
+                    "aggregates are not allowed in WHERE (use HAVING)",
+                ));
+            }
+            Some(filter)
+        } else {
+            None
+        };
+
+        self.expect_keyword("RETURN")?;
+        let distinct = self.eat_keyword("DISTINCT");
+        let mut returns = vec![self.return_item()?];
+        while self.eat_punct(",") {
+            returns.push(self.return_item()?);
+        }
+
+        let having = if self.eat_keyword("HAVING") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut order = Vec::new();
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let expr = self.expr()?;
+                let descending = if self.eat_keyword("DESC") {
+                    true
+                } else {
+                    self.eat_keyword("ASC");
+                    false
+                };
+                order.push((expr, descending));
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+        }
+
+        let limit = if self.eat_keyword("LIMIT") {
+            Some(self.count()?)
+        } else {
+            None
+        };
+
+        if let Some(token) = self.tokens.get(self.pos) {
+            return Err(QueryError::at(token.start, "unexpected input after query"));
+        }
+
+        Ok(Query {
+            patterns,
+            filter,
+            distinct,
+            returns,
+            having,
+            order,
+            limit,
+            vars: self.vars,
+            max_bindings: MAX_BINDINGS,
+        })
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, QueryError> {
+        let start = self.node_pattern()?;
+        let mut steps = Vec::new();
+        while self.peek_punct("-") || self.peek_punct("<-") {
+            let edge = self.edge_pattern()?;
+            steps.push((edge, self.node_pattern()?));
+        }
+        Ok(Pattern { start, steps })
+    }
+
+    fn node_pattern(&mut self) -> Result<NodePattern, QueryError> {
+        self.expect_punct("(")?;
+        let name = match self.peek() {
+            Some(Token::Ident(_)) => Some(self.ident()?),
+            _ => None,
+        };
+        let slot = self.slot(name);
+
+        let mut kinds = Vec::new();
+        if self.eat_punct(":") {
+            loop {
+                let position = self.position();
+                let kind = self.ident()?;
+                kinds.push(
+                    kind.parse()
+                        .map_err(|e: String| QueryError::at(position, e))?,
+                );
+                if !self.eat_punct("|") {
+                    break;
+                }
+            }
+        }
+
+        let mut properties = Vec::new();
+        if self.eat_punct("{") {
+            loop {
+                let key = self.ident()?;
+                self.expect_punct(":")?;
+                properties.push((key, self.literal()?));
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+            self.expect_punct("}")?;
+        }
+
+        self.expect_punct(")")?;
+        Ok(NodePattern {
+            slot,
+            kinds,
+            properties,
+        })
+    }
+
+    fn edge_pattern(&mut self) -> Result<EdgePattern, QueryError> {
+        let position = self.position();
+        let incoming = self.eat_punct("<-");
+        if !incoming {
+            self.expect_punct("-")?;
+        }
+
+        let mut relationships = Vec::new();
+        let (mut min, mut max) = (1, Some(1));
+        if self.eat_punct("[") {
+            if self.eat_punct(":") {
+                loop {
+                    relationships.push(self.ident()?);
+                    if !self.eat_punct("|") {
+                        break;
+                    }
+                }
+            }
+            if self.eat_punct("*") {
+                (min, max) = (1, None);
+                if matches!(self.peek(), Some(Token::Num(_))) {
+                    min = self.count()?;
+                    max = Some(min);
+                }
+                if self.eat_punct("..") {
+                    max = match self.peek() {
+                        Some(Token::Num(_)) => Some(self.count()?),
+                        _ => None,
+                    };
+                }
+            }
+            self.expect_punct("]")?;
+        }
+
+        let outgoing = self.eat_punct("->");
+        if !outgoing {
+            self.expect_punct("-")?;
+        }
+        let direction = match (incoming, outgoing) {
+            (true, true) => {
+                return Err(QueryError::at(position, "edge cannot point both ways"));
+            }
+            (true, false) => Direction::Incoming,
+            (false, true) => Direction::Outgoing,
+            (false, false) => Direction::Both,
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(QueryError::at(position, "edge length range is empty"));
+        }
+
+        Ok(EdgePattern {
+            relationships,
+            direction,
+            min,
+            max,
+        })
+    }
+
+    fn return_item(&mut self) -> Result<ReturnItem, QueryError> {
+        let start = self.position();
+        let expr = self.expr()?;
+        let end = self.tokens[self.pos - 1].end;
+        let alias = if self.eat_keyword("AS") {
+            self.ident()?
+        } else {
+            self.text[start..end].to_string()
+        };
+        Ok(ReturnItem { expr, alias })
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            left = Expr::Or(Box::new(left), Box::new(self.and_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            left = Expr::And(Box::new(left), Box::new(self.not_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, QueryError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not_expr()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let left = self.primary()?;
+        let op = if self.eat_punct("=") {
+            CompareOp::Eq
+        } else if self.eat_punct("!=") {
+            CompareOp::Ne
+        } else if self.eat_punct("<=") {
+            CompareOp::Le
+        } else if self.eat_punct(">=") {
+            CompareOp::Ge
+        } else if self.eat_punct("<") {
+            CompareOp::Lt
+        } else if self.eat_punct(">") {
+            CompareOp::Gt
+        } else if self.eat_keyword("CONTAINS") {
+            CompareOp::Contains
+        } else if self.eat_keyword("GLOB") {
+            CompareOp::Glob
+        } else if self.eat_keyword("STARTS") {
+            self.expect_keyword("WITH")?;
+            CompareOp::StartsWith
+        } else if self.eat_keyword("ENDS") {
+            self.expect_keyword("WITH")?;
+            CompareOp::EndsWith
+        } else {
+            return Ok(left);
+        };
+        Ok(Expr::Compare(Box::new(left), op, Box::new(self.primary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        match self.peek() {
+            Some(Token::Punct("(")) => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Some(Token::Str(_) | Token::Num(_)) => Ok(Expr::Literal(self.literal()?)),
+            Some(Token::Ident(word)) => {
+                let upper = word.to_ascii_uppercase();
+                if matches!(upper.as_str(), "TRUE" | "FALSE" | "NULL") {
+                    return Ok(Expr::Literal(self.literal()?));
+                }
+                let name = self.ident()?;
+                if upper == "EXISTS" && self.eat_punct("(") {
+                    let var = self.ident()?;
+                    self.expect_punct(".")?;
+                    let key = self.ident()?;
+                    self.expect_punct(")")?;
+                    return Ok(Expr::Exists(var, key));
+                }
+                if self.eat_punct("(") {
+                    return self.aggregate(position, &upper);
+                }
+                if self.eat_punct(".") {
+                    return Ok(Expr::Property(name, self.ident()?));
+                }
+                Ok(Expr::Name(name))
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    fn aggregate(&mut self, position: usize, name: &str) -> Result<Expr, QueryError> {
+        let func = match name {
+            "COUNT" => Aggregate::Count,
+            "SUM" => Aggregate::Sum,
+            "AVG" => Aggregate::Avg,
+            "MIN" => Aggregate::Min,
+            "MAX" => Aggregate::Max,
+            "COLLECT" => Aggregate::Collect,
+            _ => {
+                return Err(QueryError::at(
+                    position,
+                    format!("unknown function '{}'", name.to_ascii_lowercase()),
+                ))
+            }
+        };
+        let distinct = self.eat_keyword("DISTINCT");
+        let arg = if func == Aggregate::Count && !distinct && self.eat_punct("*") {
+            None
+        } else {
+            let arg = self.expr()?;
+            if arg.has_aggregate() {
+                return Err(QueryError::at(position, "aggregates cannot be nested"));
+            }
+            Some(Box::new(arg))
+        };
+        self.expect_punct(")")?;
+        Ok(Expr::Aggregate {
+            func,
+            distinct,
+            arg,
+        })
+    }
+
+    fn literal(&mut self) -> Result<QueryValue, QueryError> {
+        let value = match self.peek() {
+            Some(Token::Str(s)) => QueryValue::String(s.clone()),
+            Some(Token::Num(n)) => QueryValue::Number(*n),
+            Some(Token::Ident(word)) => match word.to_ascii_uppercase().as_str() {
+                "TRUE" => QueryValue::Bool(true),
+                "FALSE" => QueryValue::Bool(false),
+                "NULL" => QueryValue::Null,
+                _ => return Err(self.unexpected("a literal")),
+            },
+            _ => return Err(self.unexpected("a literal")),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn count(&mut self) -> Result<usize, QueryError> {
+        match self.peek() {
+            Some(Token::Num(n)) if n.fract() == 0.0 && *n >= 0.0 => {
+                let n = *n as usize;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.unexpected("a non-negative integer")),
+        }
+    }
+
+    /// Binding slot for a pattern variable; anonymous nodes get a fresh one.
+    fn slot(&mut self, name: Option<String>) -> usize {
+        if let Some(slot) = name
+            .as_ref()
+            .and_then(|name| self.vars.iter().position(|v| v == name))
+        {
+            return slot;
+        }
+        // `#` cannot start an identifier, so generated names never clash
+        let name = name.unwrap_or_else(|| format!("#{}", self.vars.len()));
+        self.vars.push(name);
+        self.vars.len() - 1
+    }
+
+    fn ident(&mut self) -> Result<String, QueryError> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|t| t.start)
+            .unwrap_or(self.text.len())
+    }
+
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let matched = self.peek_punct(punct);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), QueryError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", punct)))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matched =
+            matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword));
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> QueryError {
+        match self.tokens.get(self.pos) {
+            Some(token) => QueryError::at(
+                token.start,
+                format!(
+                    "expected {}, found '{}'",
+                    expected,
+                    &self.text[token.start..token.end]
+                ),
+            ),
+            None => QueryError::at(
+                self.text.len(),
+                format!("expected {}, found end of query", expected),
+            ),
+        }
+    }
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+struct GraphIndex<'g> {
+    nodes: HashMap<NodeId, &'g GraphNode>,
+    outgoing: HashMap<NodeId, Vec<(NodeId, &'g str)>>,
+    incoming: HashMap<NodeId, Vec<(NodeId, &'g str)>>,
+    order: Vec<NodeId>,
+}
+
+impl<'g> GraphIndex<'g> {
+    fn new(graph: &'g SourceCodeGraph) -> Self {
+        let mut outgoing: HashMap<NodeId, Vec<(NodeId, &str)>> = HashMap::new();
+        let mut incoming: HashMap<NodeId, Vec<(NodeId, &str)>> = HashMap::new();
+        for edge in &graph.edges {
+            outgoing
+                .entry(edge.from)
+                .or_default()
+                .push((edge.to, edge.relationship.as_str()));
+            incoming
+                .entry(edge.to)
+                .or_default()
+                .push((edge.from, edge.relationship.as_str()));
+        }
+        Self {
+            nodes: graph.nodes.iter().map(|n| (n.id, n)).collect(),
+            outgoing,
+            incoming,
+            order: graph.nodes.iter().map(|n| n.id).collect(),
+        }
+    }
+
+    fn match_pattern(&self, pattern: &Pattern, binding: &Binding) -> Vec<Binding> {
+        let starts: Vec<NodeId> = match binding[pattern.start.slot] {
+            Some(id) => vec![id],
+            None => self.order.clone(),
+        };
+        let mut partial: Vec<(Binding, NodeId)> = starts
+            .into_iter()
+            .filter(|id| self.node_matches(*id, &pattern.start))
+            .map(|id| {
+                let mut binding = binding.clone();
+                binding[pattern.start.slot] = Some(id);
+                (binding, id)
+            })
+            .collect();
+
+        for (edge, node) in &pattern.steps {
+            let mut next = Vec::new();
+            for (binding, from) in &partial {
+                for to in self.expand(*from, edge) {
+                    if binding[node.slot].is_some_and(|bound| bound != to)
+                        || !self.node_matches(to, node)
+                    {
+                        continue;
+                    }
+                    let mut binding = binding.clone();
+                    binding[node.slot] = Some(to);
+                    next.push((binding, to));
+                }
+            }
+            partial = next;
+        }
+        partial.into_iter().map(|(binding, _)| binding).collect()
+    }
+
+    fn node_matches(&self, id: NodeId, pattern: &NodePattern) -> bool {
+        let Some(node) = self.nodes.get(&id) else {
+            return false;
+        };
+        (pattern.kinds.is_empty() || pattern.kinds.contains(&node.kind))
+            && pattern
+                .properties
+                .iter()
+                .all(|(key, expected)| compare(&property(node, key), CompareOp::Eq, expected))
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        id: NodeId,
+        edge: &'a EdgePattern,
+    ) -> impl Iterator<Item = NodeId> + 'a {
+        let empty: &[(NodeId, &str)] = &[];
+        let outgoing = match edge.direction {
+            Direction::Outgoing | Direction::Both => self.outgoing.get(&id).map(Vec::as_slice),
+            Direction::Incoming => None,
+        };
+        let incoming = match edge.direction {
+            Direction::Incoming | Direction::Both => self.incoming.get(&id).map(Vec::as_slice),
+            Direction::Outgoing => None,
+        };
+        let relationships = &edge.relationships;
+        outgoing
+            .unwrap_or(empty)
+            .iter()
+            .chain(incoming.unwrap_or(empty))
+            .filter(move |(_, rel)| {
+                relationships.is_empty() || relationships.iter().any(|r| r == rel)
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Nodes reachable from `from` by a walk whose length is within the
+    /// edge's bounds, each listed once.
+    fn expand(&self, from: NodeId, edge: &EdgePattern) -> Vec<NodeId> {
+        let mut found = Vec::new();
+        let mut collected = HashSet::new();
+        let mut frontier = vec![from];
+        let mut depth = 0;
+        loop {
+            if depth >= edge.min {
+                // A node already collected has had its longer walks explored
+                frontier.retain(|id| collected.insert(*id));
+                found.extend(&frontier);
+            }
+            if frontier.is_empty() || edge.max.is_some_and(|max| depth >= max) {
+                break;
+            }
+            let mut next = Vec::new();
+            let mut seen = HashSet::new();
+            for id in &frontier {
+                next.extend(self.neighbors(*id, edge).filter(|n| seen.insert(*n)));
+            }
+            frontier = next;
+            depth += 1;
+        }
+        found
+    }
+}
+
+// =============================================================================
+// Evaluation
+// =============================================================================
+
+/// What an expression can see: one binding (`WHERE`), or a group of them
+/// plus the row computed so far (`HAVING`, `ORDER BY`).
+struct Scope<'a> {
+    index: &'a GraphIndex<'a>,
+    query: &'a Query,
+    group: Vec<&'a Binding>,
+    columns: Vec<(&'a str, &'a QueryValue)>,
+}
+
+impl<'a> Scope<'a> {
+    fn row(index: &'a GraphIndex<'a>, query: &'a Query, binding: &'a Binding) -> Self {
+        Self {
+            index,
+            query,
+            group: vec![binding],
+            columns: Vec::new(),
+        }
+    }
+
+    fn group(index: &'a GraphIndex<'a>, query: &'a Query, group: &[&'a Binding]) -> Self {
+        Self {
+            index,
+            query,
+            group: group.to_vec(),
+            columns: Vec::new(),
+        }
+    }
+
+    fn with_columns(mut self, items: &'a [ReturnItem], row: &'a [QueryValue]) -> Self {
+        self.columns = items.iter().map(|i| i.alias.as_str()).zip(row).collect();
+        self
+    }
+
+    fn eval(&self, expr: &Expr) -> Result<QueryValue, QueryError> {
+        match expr {
+            Expr::Aggregate {
+                func,
+                distinct,
+                arg,
+            } => self.aggregate(*func, *distinct, arg.as_deref()),
+            _ => match self.group.first() {
+                Some(binding) => self.eval_in(expr, binding),
+                // Empty group: only aggregates have a value
+                None => Ok(QueryValue::Null),
+            },
+        }
+    }
+
+    fn eval_in(&self, expr: &Expr, binding: &Binding) -> Result<QueryValue, QueryError> {
+        Ok(match expr {
+            Expr::Literal(value) => value.clone(),
+            Expr::Name(name) => {
+                if let Some((_, value)) = self.columns.iter().find(|(alias, _)| alias == name) {
+                    return Ok((*value).clone());
+                }
+                match self.node(name, binding)? {
+                    Some(node) => QueryValue::Node(QueryNode {
+                        id: node.id,
+                        name: node.name.clone(),
+                        kind: node.kind,
+                        path: node.metadata.get("path").cloned(),
+                    }),
+                    None => QueryValue::Null,
+                }
+            }
+            Expr::Property(var, key) => match self.node(var, binding)? {
+                Some(node) => property(node, key),
+                None => QueryValue::Null,
+            },
+            Expr::Exists(var, key) => QueryValue::Bool(
+                self.node(var, binding)?
+                    .is_some_and(|node| property(node, key) != QueryValue::Null),
+            ),
+            Expr::Not(e) => QueryValue::Bool(!self.eval_in(e, binding)?.is_truthy()),
+            Expr::And(a, b) => QueryValue::Bool(
+                self.eval_in(a, binding)?.is_truthy() && self.eval_in(b, binding)?.is_truthy(),
+            ),
+            Expr::Or(a, b) => QueryValue::Bool(
+                self.eval_in(a, binding)?.is_truthy() || self.eval_in(b, binding)?.is_truthy(),
+            ),
+            Expr::Compare(a, op, b) => {
+                let (a, b) = if a.has_aggregate() || b.has_aggregate() {
+                    (self.eval(a)?, self.eval(b)?)
+                } else {
+                    (self.eval_in(a, binding)?, self.eval_in(b, binding)?)
+                };
+                QueryValue::Bool(compare(&a, *op, &b))
+            }
+            Expr::Aggregate { .. } => self.eval(expr)?,
+        })
+    }
+
+    fn node(&self, var: &str, binding: &Binding) -> Result<Option<&'a GraphNode>, QueryError> {
+        let slot = self
+            .query
+            .vars
+            .iter()
+            .position(|v| v == var)
+            .ok_or_else(|| QueryError::new(format!("unknown variable '{}'", var)))?;
+        Ok(binding[slot].and_then(|id| self.index.nodes.get(&id).copied()))
+    }
+
+    fn aggregate(
+        &self,
+        func: Aggregate,
+        distinct: bool,
+        arg: Option<&Expr>,
+    ) -> Result<QueryValue, QueryError> {
+        let Some(arg) = arg else {
+            return Ok(QueryValue::Number(self.group.len() as f64));
+        };
+
+        let mut values = Vec::with_capacity(self.group.len());
+        let mut seen = HashSet::new();
+        for binding in &self.group {
+            let value = self.eval_in(arg, binding)?;
+            if value == QueryValue::Null || (distinct && !seen.insert(value_key(&value))) {
+                continue;
+            }
+            values.push(value);
+        }
+
+        let numbers = || values.iter().filter_map(as_number);
+        Ok(match func {
+            Aggregate::Count => QueryValue::Number(values.len() as f64),
+            Aggregate::Sum => QueryValue::Number(numbers().sum()),
+            Aggregate::Avg => {
+                let count = numbers().count();
+                if count == 0 {
+                    QueryValue::Null
+                } else {
+                    QueryValue::Number(numbers().sum::<f64>() / count as f64)
+                }
+            }
+            Aggregate::Min => values
+                .into_iter()
+                .min_by(compare_values)
+                .unwrap_or(QueryValue::Null),
+            Aggregate::Max => values
+                .into_iter()
+                .max_by(compare_values)
+                .unwrap_or(QueryValue::Null),
+            Aggregate::Collect => QueryValue::List(values),
+        })
+    }
+}
+
+fn property(node: &GraphNode, key: &str) -> QueryValue {
+    match key {
+        "id" => QueryValue::Number(node.id.0 as f64),
+        "name" => QueryValue::String(node.name.clone()),
+        "kind" => QueryValue::String(node.kind.as_str().to_string()),
+        _ => node
+            .metadata
+            .get(key)
+            .map(|v| QueryValue::String(v.clone()))
+            .unwrap_or(QueryValue::Null),
+    }
+}
+
+impl QueryValue {
+    fn is_truthy(&self) -> bool {
+        match self {
+            QueryValue::Null => false,
+            QueryValue::Bool(b) => *b,
+            QueryValue::Number(n) => *n != 0.0,
+            QueryValue::String(s) => s == "true",
+            QueryValue::Node(_) => true,
+            QueryValue::List(items) => !items.is_empty(),
+        }
+    }
+}
+
+fn as_number(value: &QueryValue) -> Option<f64> {
+    match value {
+        QueryValue::Number(n) => Some(*n),
+        QueryValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &QueryValue) -> Option<String> {
+    match value {
+        QueryValue::Null => None,
+        QueryValue::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Order values: numerically when both are numbers, else as text; nulls
+/// sort first.
+fn compare_values(a: &QueryValue, b: &QueryValue) -> Ordering {
+    if let (Some(x), Some(y)) = (as_number(a), as_number(b)) {
+        return x.partial_cmp(&y).unwrap_or(Ordering::Equal);
+    }
+    as_text(a).cmp(&as_text(b))
+}
+
+fn compare(a: &QueryValue, op: CompareOp, b: &QueryValue) -> bool {
+    if *a == QueryValue::Null || *b == QueryValue::Null {
+        return false;
+    }
+    let (Some(text_a), Some(text_b)) = (as_text(a), as_text(b)) else {
+        return false;
+    };
+    match op {
+        CompareOp::Eq => compare_values(a, b) == Ordering::Equal,
+        CompareOp::Ne => compare_values(a, b) != Ordering::Equal,
+        CompareOp::Lt => compare_values(a, b) == Ordering::Less,
+        CompareOp::Le => compare_values(a, b) != Ordering::Greater,
+        CompareOp::Gt => compare_values(a, b) == Ordering::Greater,
+        CompareOp::Ge => compare_values(a, b) != Ordering::Less,
+        CompareOp::Contains => text_a.contains(&text_b),
+        CompareOp::StartsWith => text_a.starts_with(&text_b),
+        CompareOp::EndsWith => text_a.ends_with(&text_b),
+        CompareOp::Glob => glob_matches(&text_b, &text_a),
+    }
+}
+
+/// Hashable identity of a value, for `DISTINCT` and grouping.
+fn value_key(value: &QueryValue) -> String {
+    match value {
+        QueryValue::Node(node) => format!("node:{}", node.id.0),
+        QueryValue::Number(n) => format!("num:{}", n),
+        QueryValue::List(items) => {
+            let items: Vec<String> = items.iter().map(value_key).collect();
+            format!("list:[{}]", items.join(","))
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// Match `text` against a glob: `**` spans directories, `*` and `?` stay
+/// within one path segment.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_at(&pattern, &text)
+}
+
+fn glob_at(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // `**/` also matches zero directories
+            let rest = &pattern[2..];
+            if rest.first() == Some(&'/') && glob_at(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob_at(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_at(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => text.first().is_some_and(|c| *c != '/') && glob_at(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && glob_at(&pattern[1..], &text[1..]),
+    }
+}
+
+fn is_integral(n: f64) -> bool {
+    n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0
+}
+
+fn serialize_number<S: Serializer>(n: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if is_integral(*n) {
+        serializer.serialize_i64(*n as i64)
+    } else {
+        serializer.serialize_f64(*n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReferenceKind, SourceCodeGraphBuilder};
+
+    /// `crates/core/src/lib.rs` is used by four files, `util.rs` by one.
+    fn sample_graph() -> SourceCodeGraph {
+        let mut builder = SourceCodeGraphBuilder::new().with_root("/ws");
+        let lib = builder.add_workspace_file("crates/core/src/lib.rs");
+        let util = builder.add_workspace_file("crates/core/src/util.rs");
+        builder.set_node_metadata(util, "has_tests", "true");
+        for user in ["a.rs", "b.rs", "c.rs", "d.rs"] {
+            let id = builder.add_workspace_file(&format!("crates/cli/src/{}", user));
+            builder.add_edge(id, lib, ReferenceKind::Uses);
+        }
+        builder.add_edge(lib, util, ReferenceKind::Uses);
+        builder.build()
+    }
+
+    fn column(result: &QueryResult, index: usize) -> Vec<String> {
+        result.rows.iter().map(|r| r[index].to_string()).collect()
+    }
+
+    #[test]
+    fn test_query_aggregates_with_having() {
+        let result = sample_graph()
+            .query(
+                r#"MATCH (f:file)<-[:uses]-(u:file)
+                   WHERE f.relative_path GLOB "crates/*/src/**" AND NOT EXISTS(f.has_tests)
+                   RETURN f.relative_path AS file, count(DISTINCT u) AS users
+                   HAVING users > 3"#,
+            )
+            .unwrap();
+
+        assert_eq!(result.columns, vec!["file", "users"]);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(column(&result, 0), vec!["crates/core/src/lib.rs"]);
+        assert_eq!(result.rows[0][1], QueryValue::Number(4.0));
+    }
+
+    #[test]
+    fn test_query_binding_budget() {
+        let graph = sample_graph();
+        let n = graph.node_count();
+
+        // Without ORDER BY, matching stops at the LIMIT
+        let first = Query::parse("MATCH (a), (b), (c) RETURN a.name LIMIT 1")
+            .unwrap()
+            .with_max_bindings(n);
+        assert_eq!(first.execute(&graph).unwrap().rows.len(), 1);
+
+        let all = Query::parse("MATCH (a), (b), (c) RETURN a.name ORDER BY a.name LIMIT 1")
+            .unwrap()
+            .with_max_bindings(n * n);
+        let error = all.execute(&graph).unwrap_err();
+        assert!(error.message.contains("more than"), "{}", error);
+    }
+
+    #[test]
+    fn test_query_variable_length_paths() {
+        let graph = sample_graph();
+        let reachable = graph
+            .query(
+                r#"MATCH (a {name: "a.rs"})-[:uses*1..2]->(t)
+                   RETURN t.name ORDER BY t.name"#,
+            )
+            .unwrap();
+        assert_eq!(column(&reachable, 0), vec!["lib.rs", "util.rs"]);
+
+        let exact = graph
+            .query(r#"MATCH (a {name: "a.rs"})-[*2]->(t) RETURN t.name"#)
+            .unwrap();
+        assert_eq!(column(&exact, 0), vec!["util.rs"]);
+
+        // Undirected, any relationship, through the directory hierarchy
+        let siblings = graph
+            .query(
+                r#"MATCH (u {name: "util.rs"})-[:contains*2]-(s:file)
+                   WHERE s.name != "util.rs" RETURN DISTINCT s.name"#,
+            )
+            .unwrap();
+        assert_eq!(column(&siblings, 0), vec!["lib.rs"]);
+    }
+
+    #[test]
+    fn test_query_grouping_and_ordering() {
+        let result = sample_graph()
+            .query(
+                "MATCH (d:dir)-[:contains]->(f:file)
+                 RETURN d AS dir, count(*) AS files, collect(f.name) AS names
+                 ORDER BY files DESC LIMIT 1",
+            )
+            .unwrap();
+
+        assert_eq!(column(&result, 0), vec!["/ws/crates/cli/src"]);
+        assert_eq!(result.rows[0][1].to_string(), "4");
+        assert_eq!(result.rows[0][2].to_string(), "[a.rs, b.rs, c.rs, d.rs]");
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["rows"][0][1], serde_json::json!(4));
+    }
+
+    #[test]
+    fn test_query_count_over_nothing() {
+        let result = sample_graph()
+            .query("MATCH (s:symbol) RETURN count(*) AS n")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![QueryValue::Number(0.0)]]);
+    }
+
+    #[test]
+    fn test_query_errors_point_at_input() {
+        let err = Query::parse("MATCH (f:fiel) RETURN f").unwrap_err();
+        assert_eq!(err.position, Some(9));
+        assert!(err.message.contains("unknown node kind"));
+
+        let err = Query::parse("MATCH (f) WHERE count(f) > 1 RETURN f").unwrap_err();
+        assert!(err.message.contains("HAVING"));
+
+        let err = sample_graph().query("MATCH (f) RETURN g.name").unwrap_err();
+        assert_eq!(err.message, "unknown variable 'g'");
+    }
+
+    #[test]
+    fn test_query_non_ascii_input() {
+        let graph = sample_graph();
+        // Positions are byte offsets into the query
+        let text = r#"MATCH (é {name: "lib.rs"}) RETURN é.name AS «name»"#;
+        let err = graph.query(text).unwrap_err();
+        assert_eq!(err.position, text.find('«'));
+
+        let result = graph
+            .query(r#"MATCH (é {name: "lib.rs"}) WHERE é.name != "ü" RETURN é.name"#)
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["lib.rs"]);
+
+        let err = Query::parse("MATCH (n) RETURN n ©").unwrap_err();
+        assert_eq!(err.position, Some(19));
+        assert!(err.message.contains("unexpected character '©'"));
+        assert!(Query::parse("MATCH (n) RETURN n.ü…").is_err());
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("crates/*/src/**", "crates/core/src/a/b.rs"));
+        assert!(!glob_matches("crates/*/src/**", "crates/a/b/src/c.rs"));
+        assert!(glob_matches("**/*.rs", "main.rs"));
+        assert!(glob_matches("src/?.rs", "src/a.rs"));
+        assert!(!glob_matches("*.rs", "src/a.rs"));
+    }
+}
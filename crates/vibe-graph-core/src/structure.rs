@@ -0,0 +1,404 @@
+//! Component-level view of a [`SourceCodeGraph`] and how it changes.
+//!
+//! Files are grouped into components: the nearest enclosing directory with
+//! a package manifest (`Cargo.toml`, `package.json`, `go.mod`, ...). Files
+//! outside every package belong to the root package if there is one, else
+//! to their top-level directory. Dependencies are the
+//! non-`contains` edges, lifted from symbols to their files, so a component
+//! is roughly a crate or package and a cross-component dependency is one
+//! that crosses a crate boundary.
+//!
+//! [`StructuralDiff::between`] compares two graphs by workspace-relative
+//! file path, so graphs built from different checkouts of the same
+//! repository can be compared.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{node_key, GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+/// File names that mark the root of a package.
+pub const MANIFEST_FILES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "Gemfile",
+    "lakefile.lean",
+];
+
+/// Component of files at the workspace root that belong to no package.
+pub const ROOT_COMPONENT: &str = ".";
+
+/// Assigns workspace-relative file paths to components.
+#[derive(Debug, Clone, Default)]
+pub struct Components {
+    /// Package directories below the root, deepest first.
+    packages: Vec<String>,
+    /// Whether the workspace root is itself a package.
+    root_package: bool,
+}
+
+impl Components {
+    /// Find the package directories of a graph from its manifest files.
+    pub fn detect(graph: &SourceCodeGraph) -> Self {
+        let mut packages = BTreeSet::new();
+        let mut root_package = false;
+        for node in &graph.nodes {
+            if !MANIFEST_FILES.contains(&node.name.as_str()) {
+                continue;
+            }
+            match file_key(graph, node)
+                .as_deref()
+                .and_then(|k| k.rsplit_once('/'))
+            {
+                Some((dir, _)) => {
+                    packages.insert(dir.to_string());
+                }
+                None => root_package = true,
+            }
+        }
+        let mut packages: Vec<String> = packages.into_iter().collect();
+        packages.sort_by_key(|dir| std::cmp::Reverse(dir.len()));
+        Self {
+            packages,
+            root_package,
+        }
+    }
+
+    /// Component of a workspace-relative file path.
+    pub fn component_of(&self, file: &str) -> String {
+        let package = self.packages.iter().find(|dir| {
+            file.strip_prefix(dir.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        });
+        match (package, file.split_once('/')) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some((top, _))) if !self.root_package => top.to_string(),
+            _ => ROOT_COMPONENT.to_string(),
+        }
+    }
+}
+
+/// Workspace-relative path of the file a node is or lives in.
+///
+/// `None` for directories, repositories and nodes without a path.
+pub fn file_key(graph: &SourceCodeGraph, node: &GraphNode) -> Option<String> {
+    let path = match node.kind {
+        GraphNodeKind::Directory | GraphNodeKind::Repository => return None,
+        GraphNodeKind::Symbol => node.metadata.get("file")?,
+        _ => node.metadata.get("path")?,
+    };
+    let root = graph.metadata.get("root").map(Path::new);
+    Some(node_key(Path::new(path), root))
+}
+
+/// A file-level dependency.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Depending file.
+    pub from: String,
+    /// File depended upon.
+    pub to: String,
+    /// Edge relationship (`uses`, `imports`, `calls`, ...).
+    pub relationship: String,
+    /// Component of `from`.
+    pub from_component: String,
+    /// Component of `to`.
+    pub to_component: String,
+}
+
+impl Dependency {
+    /// Whether the dependency crosses a component boundary.
+    pub fn is_cross_component(&self) -> bool {
+        self.from_component != self.to_component
+    }
+}
+
+/// Coupling of one component to the others.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Coupling {
+    /// Afferent coupling: components depending on this one.
+    pub afferent: usize,
+    /// Efferent coupling: components this one depends on.
+    pub efferent: usize,
+    /// `efferent / (afferent + efferent)`, 0 for an isolated component.
+    pub instability: f64,
+}
+
+impl Coupling {
+    fn new(afferent: usize, efferent: usize) -> Self {
+        let total = afferent + efferent;
+        Self {
+            afferent,
+            efferent,
+            instability: if total == 0 {
+                0.0
+            } else {
+                efferent as f64 / total as f64
+            },
+        }
+    }
+}
+
+/// Files, dependencies and component coupling of one graph.
+#[derive(Debug, Clone, Default)]
+pub struct Structure {
+    /// Workspace-relative paths of every file.
+    pub files: BTreeSet<String>,
+    /// Distinct file-level dependencies.
+    pub dependencies: BTreeSet<Dependency>,
+    /// Coupling of every component.
+    pub coupling: BTreeMap<String, Coupling>,
+}
+
+impl Structure {
+    /// Extract the structure of a graph.
+    pub fn of(graph: &SourceCodeGraph) -> Self {
+        let components = Components::detect(graph);
+        let files_by_id: HashMap<NodeId, String> = graph
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node.id, file_key(graph, node)?)))
+            .collect();
+
+        let files: BTreeSet<String> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind != GraphNodeKind::Symbol)
+            .filter_map(|node| file_key(graph, node))
+            .collect();
+
+        let dependencies: BTreeSet<Dependency> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.relationship != "contains")
+            .filter_map(|edge| {
+                let from = files_by_id.get(&edge.from)?;
+                let to = files_by_id.get(&edge.to)?;
+                (from != to).then(|| Dependency {
+                    from: from.clone(),
+                    to: to.clone(),
+                    relationship: edge.relationship.clone(),
+                    from_component: components.component_of(from),
+                    to_component: components.component_of(to),
+                })
+            })
+            .collect();
+
+        let mut coupling: BTreeMap<String, Coupling> = files
+            .iter()
+            .map(|file| (components.component_of(file), Coupling::default()))
+            .collect();
+        let cross: BTreeSet<(&str, &str)> = dependencies
+            .iter()
+            .filter(|dep| dep.is_cross_component())
+            .map(|dep| (dep.from_component.as_str(), dep.to_component.as_str()))
+            .collect();
+        for (component, value) in coupling.iter_mut() {
+            let afferent = cross.iter().filter(|(_, to)| to == component).count();
+            let efferent = cross.iter().filter(|(from, _)| from == component).count();
+            *value = Coupling::new(afferent, efferent);
+        }
+
+        Self {
+            files,
+            dependencies,
+            coupling,
+        }
+    }
+}
+
+/// Coupling of a component before and after a change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CouplingChange {
+    /// Component directory (`.` for the workspace root).
+    pub component: String,
+    /// Coupling in the older graph, `None` if the component is new.
+    pub before: Option<Coupling>,
+    /// Coupling in the newer graph, `None` if the component was removed.
+    pub after: Option<Coupling>,
+}
+
+/// How the files, dependencies and component coupling of a codebase
+/// changed between two graphs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuralDiff {
+    /// Files only present in the newer graph.
+    pub added_files: Vec<String>,
+    /// Files only present in the older graph.
+    pub removed_files: Vec<String>,
+    /// File-level dependencies only present in the newer graph.
+    pub added_dependencies: Vec<Dependency>,
+    /// File-level dependencies only present in the older graph.
+    pub removed_dependencies: Vec<Dependency>,
+    /// Components whose coupling changed, appeared or disappeared.
+    pub coupling_changes: Vec<CouplingChange>,
+}
+
+impl StructuralDiff {
+    /// Compare an older graph with a newer one.
+    pub fn between(older: &SourceCodeGraph, newer: &SourceCodeGraph) -> Self {
+        let old = Structure::of(older);
+        let new = Structure::of(newer);
+
+        let components: BTreeSet<&String> =
+            old.coupling.keys().chain(new.coupling.keys()).collect();
+        let coupling_changes = components
+            .into_iter()
+            .filter_map(|component| {
+                let before = old.coupling.get(component).copied();
+                let after = new.coupling.get(component).copied();
+                (before != after).then(|| CouplingChange {
+                    component: component.clone(),
+                    before,
+                    after,
+                })
+            })
+            .collect();
+
+        Self {
+            added_files: new.files.difference(&old.files).cloned().collect(),
+            removed_files: old.files.difference(&new.files).cloned().collect(),
+            added_dependencies: new
+                .dependencies
+                .difference(&old.dependencies)
+                .cloned()
+                .collect(),
+            removed_dependencies: old
+                .dependencies
+                .difference(&new.dependencies)
+                .cloned()
+                .collect(),
+            coupling_changes,
+        }
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.added_dependencies.is_empty()
+            && self.removed_dependencies.is_empty()
+            && self.coupling_changes.is_empty()
+    }
+
+    /// Added dependencies that cross a component boundary.
+    pub fn added_cross_component(&self) -> impl Iterator<Item = &Dependency> {
+        self.added_dependencies
+            .iter()
+            .filter(|dep| dep.is_cross_component())
+    }
+
+    /// Removed dependencies that crossed a component boundary.
+    pub fn removed_cross_component(&self) -> impl Iterator<Item = &Dependency> {
+        self.removed_dependencies
+            .iter()
+            .filter(|dep| dep.is_cross_component())
+    }
+}
+
+/// Test fixture: `files` under a `/ws` root; `edges` are `(from, to)`
+/// relative paths.
+#[cfg(test)]
+pub(crate) fn workspace(files: &[&str], edges: &[(&str, &str)]) -> SourceCodeGraph {
+    use crate::{ReferenceKind, SourceCodeGraphBuilder};
+
+    let root = std::path::PathBuf::from("/ws");
+    let mut builder = SourceCodeGraphBuilder::new().with_root(&root);
+    for file in files {
+        builder.add_file(&root.join(file), file);
+    }
+    for (from, to) in edges {
+        let from = builder.get_node_id(&root.join(from)).unwrap();
+        let to = builder.get_node_id(&root.join(to)).unwrap();
+        builder.add_edge(from, to, ReferenceKind::Uses);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILES: &[&str] = &[
+        "Cargo.toml",
+        "crates/core/Cargo.toml",
+        "crates/core/src/lib.rs",
+        "crates/cli/Cargo.toml",
+        "crates/cli/src/main.rs",
+        "crates/cli/src/args.rs",
+        "xtask/run.rs",
+    ];
+
+    #[test]
+    fn test_components_follow_manifests() {
+        let graph = workspace(FILES, &[]);
+        let components = Components::detect(&graph);
+        assert_eq!(
+            components.component_of("crates/cli/src/main.rs"),
+            "crates/cli"
+        );
+        assert_eq!(
+            components.component_of("crates/core/src/lib.rs"),
+            "crates/core"
+        );
+        assert_eq!(components.component_of("xtask/run.rs"), ".");
+        assert_eq!(components.component_of("crates/cli-extra/x.rs"), ".");
+
+        let graph = workspace(&["src/a.rs", "tests/b.rs", "README.md"], &[]);
+        let components = Components::detect(&graph);
+        assert_eq!(components.component_of("src/a.rs"), "src");
+        assert_eq!(components.component_of("README.md"), ".");
+    }
+
+    #[test]
+    fn test_structural_diff_reports_files_dependencies_and_coupling() {
+        let before = workspace(
+            FILES,
+            &[("crates/cli/src/main.rs", "crates/cli/src/args.rs")],
+        );
+        let mut files = FILES.to_vec();
+        files.retain(|f| *f != "xtask/run.rs");
+        files.push("crates/core/src/model.rs");
+        let after = workspace(
+            &files,
+            &[
+                ("crates/cli/src/main.rs", "crates/cli/src/args.rs"),
+                ("crates/cli/src/main.rs", "crates/core/src/lib.rs"),
+                ("crates/cli/src/args.rs", "crates/core/src/model.rs"),
+                ("crates/core/src/lib.rs", "crates/core/src/model.rs"),
+            ],
+        );
+
+        let diff = StructuralDiff::between(&before, &after);
+        assert_eq!(diff.added_files, vec!["crates/core/src/model.rs"]);
+        assert_eq!(diff.removed_files, vec!["xtask/run.rs"]);
+        assert_eq!(diff.added_dependencies.len(), 3);
+        assert!(diff.removed_dependencies.is_empty());
+        assert_eq!(diff.added_cross_component().count(), 2);
+
+        let cli = diff
+            .coupling_changes
+            .iter()
+            .find(|c| c.component == "crates/cli")
+            .unwrap();
+        assert_eq!(cli.before.unwrap().efferent, 0);
+        let after_cli = cli.after.unwrap();
+        assert_eq!((after_cli.afferent, after_cli.efferent), (0, 1));
+        assert_eq!(after_cli.instability, 1.0);
+        assert!(diff
+            .coupling_changes
+            .iter()
+            .any(|c| c.component == "crates/core" && c.after.unwrap().afferent == 1));
+
+        assert!(StructuralDiff::between(&after, &after).is_empty());
+    }
+}
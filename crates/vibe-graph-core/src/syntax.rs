@@ -0,0 +1,1064 @@
+//! Tree-sitter based reference extraction.
+//!
+//! Produces the same [`SourceReference`] values as the line-based detectors in
+//! the crate root, but walks real syntax trees instead of matching line
+//! prefixes. This picks up constructs the line scanner cannot see:
+//!
+//! - Rust: multi-line `use {...}` groups, nested use trees, `use` inside
+//!   function bodies, and `mod foo;` declarations anywhere in the file.
+//! - Python: relative imports (`from . import x`, `from ..pkg import y`).
+//! - TypeScript/JavaScript: `require()`, dynamic `import()`,
+//!   `export ... from` and `import x = require(...)`.
+//!
+//! The same trees also yield symbol definitions (functions, types, traits,
+//! classes) and the `calls`/`implements` references between them, see
+//! [`detect_symbols`].
+//!
+//! Enabled with the `tree-sitter` cargo feature. Languages without a grammar
+//! (e.g. Lean) keep using the line-based detectors.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::{
+    normalize_path, push_implements, python_base_route, rust_path_attribute, rust_path_module,
+    rust_trait_route, ImportedNames, ReferenceKind, RustImports, SourceReference, SourceSymbols,
+    SymbolDefinition, SymbolKind, SymbolReference,
+};
+
+/// Detect references by parsing `content` with the grammar matching the
+/// file extension of `source_path`.
+///
+/// Returns `None` when no grammar is available for the extension or the
+/// parser fails, so callers can fall back to the line-based detectors.
+pub fn detect_references(content: &str, source_path: &Path) -> Option<Vec<SourceReference>> {
+    match source_path.extension().and_then(|e| e.to_str())? {
+        "rs" => detect_rust_references(content, source_path),
+        "py" => detect_python_references(content, source_path),
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => {
+            detect_ts_references(content, source_path)
+        }
+        _ => None,
+    }
+}
+
+/// Detect references in Rust source code from its syntax tree.
+pub fn detect_rust_references(content: &str, source_path: &Path) -> Option<Vec<SourceReference>> {
+    let tree = parse(content, &tree_sitter_rust::LANGUAGE.into())?;
+    let src = content.as_bytes();
+    let mut refs = Vec::new();
+    let mut imported = RustImports::new();
+    let mut implemented = Vec::new();
+
+    walk(tree.root_node(), &mut |node| match node.kind() {
+        "use_declaration" => {
+            let Some(argument) = node.child_by_field_name("argument") else {
+                return;
+            };
+            let mut paths = Vec::new();
+            collect_use_paths(argument, src, &[], &mut paths);
+
+            for mut segments in paths {
+                // `use foo::{self, ..}` refers to `foo` itself.
+                if segments.last().is_some_and(|s| s == "self") {
+                    segments.pop();
+                }
+                let specifier = segments.join("::");
+                for route in rust_routes(&segments) {
+                    push_ref(
+                        &mut refs,
+                        source_path,
+                        ReferenceKind::Uses,
+                        route,
+                        Some(&specifier),
+                    );
+                }
+                if let Some(name) = segments.last() {
+                    imported.insert(name.clone(), specifier.clone());
+                }
+            }
+        }
+        // `impl Trait for Type` -> resolved against imports after the walk
+        "impl_item" => {
+            let trait_path = node
+                .child_by_field_name("trait")
+                .and_then(|t| match t.kind() {
+                    "generic_type" => t.child_by_field_name("type"),
+                    _ => Some(t),
+                });
+            if let Some(text) = trait_path.and_then(|t| t.utf8_text(src).ok()) {
+                implemented.push(text.to_string());
+            }
+        }
+        // `mod foo;` (no inline body) points at `foo.rs` or `foo/mod.rs`,
+        // unless a `#[path = "..."]` attribute names the file.
+        "mod_item" if node.child_by_field_name("body").is_none() => {
+            if let Some(path) = mod_path_attribute(node, src) {
+                refs.push(rust_path_module(source_path, path));
+            } else if let Some(name) = node
+                .child_by_field_name("name")
+                .and_then(|n| n.utf8_text(src).ok())
+            {
+                let specifier = format!("self::{}", name);
+                push_ref(
+                    &mut refs,
+                    source_path,
+                    ReferenceKind::Uses,
+                    PathBuf::from(format!("{}.rs", name)),
+                    Some(&specifier),
+                );
+                push_ref(
+                    &mut refs,
+                    source_path,
+                    ReferenceKind::Uses,
+                    PathBuf::from(format!("{}/mod.rs", name)),
+                    Some(&specifier),
+                );
+            }
+        }
+        _ => {}
+    });
+
+    for trait_path in implemented {
+        if let Some((route, specifier)) = rust_trait_route(&trait_path, &imported) {
+            push_implements(&mut refs, source_path, route, Some(specifier));
+        }
+    }
+
+    Some(refs)
+}
+
+/// Detect references in Python source code from its syntax tree.
+pub fn detect_python_references(content: &str, source_path: &Path) -> Option<Vec<SourceReference>> {
+    let tree = parse(content, &tree_sitter_python::LANGUAGE.into())?;
+    let src = content.as_bytes();
+    let mut refs = Vec::new();
+    let mut imported = ImportedNames::new();
+    let mut bases = Vec::new();
+
+    walk(tree.root_node(), &mut |node| match node.kind() {
+        "import_statement" => {
+            let mut cursor = node.walk();
+            for name in node.children_by_field_name("name", &mut cursor) {
+                if let Some(module) = imported_module_name(name, src) {
+                    let route = PathBuf::from(format!("{}.py", module.replace('.', "/")));
+                    // `import pkg.models as m` lets `m.Base` name the module
+                    if let Some(alias) = name
+                        .child_by_field_name("alias")
+                        .and_then(|a| a.utf8_text(src).ok())
+                    {
+                        imported.insert(alias.to_string(), route.clone());
+                    }
+                    push_ref(&mut refs, source_path, ReferenceKind::Imports, route, None);
+                }
+            }
+        }
+        // `class A(B, mod.C)` -> resolved against imports after the walk
+        "class_definition" => {
+            if let Some(list) = node.child_by_field_name("superclasses") {
+                let mut cursor = list.walk();
+                for arg in list.named_children(&mut cursor) {
+                    if matches!(arg.kind(), "identifier" | "attribute") {
+                        bases.extend(arg.utf8_text(src).ok().map(str::to_string));
+                    }
+                }
+            }
+        }
+        "import_from_statement" => {
+            let Some(module) = node.child_by_field_name("module_name") else {
+                return;
+            };
+
+            if module.kind() != "relative_import" {
+                if let Ok(text) = module.utf8_text(src) {
+                    let route = PathBuf::from(format!("{}.py", text.replace('.', "/")));
+                    record_python_names(node, src, &route, &mut imported);
+                    push_ref(&mut refs, source_path, ReferenceKind::Imports, route, None);
+                }
+                return;
+            }
+
+            // Relative import: resolve against the importing file's package.
+            let mut level = 0;
+            let mut dotted = None;
+            let mut cursor = module.walk();
+            for child in module.named_children(&mut cursor) {
+                match child.kind() {
+                    "import_prefix" => {
+                        level = child.utf8_text(src).map(|t| t.len()).unwrap_or(0);
+                    }
+                    "dotted_name" => dotted = child.utf8_text(src).ok(),
+                    _ => {}
+                }
+            }
+
+            let Some(base) = python_package_dir(source_path, level) else {
+                return;
+            };
+
+            match dotted {
+                // `from .models import User` -> <pkg>/models.py
+                Some(dotted) => {
+                    let route = base.join(format!("{}.py", dotted.replace('.', "/")));
+                    record_python_names(node, src, &route, &mut imported);
+                    push_ref(&mut refs, source_path, ReferenceKind::Imports, route, None);
+                }
+                // `from . import models` -> <pkg>/models.py for each name
+                None => {
+                    let mut cursor = node.walk();
+                    for name in node.children_by_field_name("name", &mut cursor) {
+                        if let Some(module) = imported_module_name(name, src) {
+                            push_ref(
+                                &mut refs,
+                                source_path,
+                                ReferenceKind::Imports,
+                                base.join(format!("{}.py", module.replace('.', "/"))),
+                                None,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    });
+
+    for base in bases {
+        if let Some(route) = python_base_route(&base, &imported) {
+            push_implements(&mut refs, source_path, route, None);
+        }
+    }
+
+    Some(refs)
+}
+
+/// Detect references in TypeScript/JavaScript source code from its syntax tree.
+///
+/// Like the line-based detector, only relative specifiers are tracked.
+pub fn detect_ts_references(content: &str, source_path: &Path) -> Option<Vec<SourceReference>> {
+    let language: Language = match source_path.extension().and_then(|e| e.to_str()) {
+        Some("ts" | "mts" | "cts") => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Some("tsx") => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        _ => tree_sitter_javascript::LANGUAGE.into(),
+    };
+    let tree = parse(content, &language)?;
+    let src = content.as_bytes();
+    let mut refs = Vec::new();
+    let mut imported = ImportedNames::new();
+    let mut bases = Vec::new();
+
+    walk(tree.root_node(), &mut |node| {
+        // `class A extends B implements C` -> resolved against imports after the walk
+        if matches!(
+            node.kind(),
+            "class_declaration" | "class" | "abstract_class_declaration" | "interface_declaration"
+        ) {
+            bases.extend(supertypes(node, src, first_name));
+            return;
+        }
+
+        let specifier = match node.kind() {
+            // import x from './a'; export * from './b'; import x = require('./c')
+            "import_statement" | "export_statement" | "import_require_clause" => node
+                .child_by_field_name("source")
+                .and_then(|s| string_literal(s, src)),
+            // require('./a') and import('./b')
+            "call_expression" => {
+                let is_loader = node.child_by_field_name("function").is_some_and(|f| {
+                    f.kind() == "import"
+                        || (f.kind() == "identifier" && f.utf8_text(src).ok() == Some("require"))
+                });
+                if is_loader {
+                    node.child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .and_then(|arg| string_literal(arg, src))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        // Bare specifiers (`@acme/ui`, `~/utils`) are kept too: the ops
+        // resolver maps them through tsconfig paths and workspace packages.
+        if let Some(specifier) = specifier {
+            push_ref(
+                &mut refs,
+                source_path,
+                ReferenceKind::Imports,
+                PathBuf::from(specifier),
+                Some(specifier),
+            );
+            if node.kind() == "import_statement" {
+                for name in ts_imported_names(node, src) {
+                    imported.insert(name.to_string(), PathBuf::from(specifier));
+                }
+            }
+        }
+    });
+
+    for base in bases {
+        if let Some(route) = imported.get(base).cloned() {
+            let specifier = route.to_string_lossy().to_string();
+            push_implements(&mut refs, source_path, route, Some(specifier));
+        }
+    }
+
+    Some(refs)
+}
+
+/// Detect symbol definitions and the `calls`/`implements` references between
+/// them.
+///
+/// Methods are qualified by their enclosing type (`Type::method` in Rust,
+/// `Class.method` elsewhere). Calls are attributed to the innermost enclosing
+/// function; calls at module level are ignored. Returns `None` when no
+/// grammar is available, like [`detect_references`].
+pub fn detect_symbols(content: &str, source_path: &Path) -> Option<SourceSymbols> {
+    let ext = source_path.extension().and_then(|e| e.to_str())?;
+    let (language, separator): (Language, &str) = match ext {
+        "rs" => (tree_sitter_rust::LANGUAGE.into(), "::"),
+        "py" => (tree_sitter_python::LANGUAGE.into(), "."),
+        "ts" => (tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), "."),
+        "tsx" => (tree_sitter_typescript::LANGUAGE_TSX.into(), "."),
+        "js" | "jsx" | "mjs" | "cjs" => (tree_sitter_javascript::LANGUAGE.into(), "."),
+        _ => return None,
+    };
+    let tree = parse(content, &language)?;
+    let src = content.as_bytes();
+    let mut symbols = SourceSymbols::default();
+    // Byte ranges of function bodies, used to attribute calls.
+    let mut scopes: Vec<(Range<usize>, String)> = Vec::new();
+
+    walk(tree.root_node(), &mut |node| {
+        // `impl Trait for Type` is not a definition but links two of them.
+        if node.kind() == "impl_item" {
+            let implementor = node
+                .child_by_field_name("type")
+                .and_then(|t| last_name(t, src));
+            let implemented = node
+                .child_by_field_name("trait")
+                .and_then(|t| last_name(t, src));
+            if let (Some(from), Some(target)) = (implementor, implemented) {
+                push_symbol_ref(&mut symbols, from, ReferenceKind::Implements, target);
+            }
+            return;
+        }
+
+        let Some((kind, name)) = symbol_definition(node, src) else {
+            return;
+        };
+        let qualified_name = match enclosing_type(node, src) {
+            Some(owner) if kind == SymbolKind::Function => {
+                format!("{}{}{}", owner, separator, name)
+            }
+            _ => name.to_string(),
+        };
+
+        for base in supertypes(node, src, last_name) {
+            push_symbol_ref(
+                &mut symbols,
+                &qualified_name,
+                ReferenceKind::Implements,
+                base,
+            );
+        }
+        if kind == SymbolKind::Function {
+            scopes.push((node.byte_range(), qualified_name.clone()));
+        }
+
+        symbols.definitions.push(SymbolDefinition {
+            name: name.to_string(),
+            qualified_name,
+            kind,
+            line: node.start_position().row + 1,
+        });
+    });
+
+    walk(tree.root_node(), &mut |node| {
+        let callee = match node.kind() {
+            "call_expression" | "call" => node.child_by_field_name("function"),
+            "new_expression" => node.child_by_field_name("constructor"),
+            _ => None,
+        };
+        let Some(target) = callee.and_then(|c| last_name(c, src)) else {
+            return;
+        };
+
+        // Innermost function containing the call.
+        let caller = scopes
+            .iter()
+            .filter(|(range, _)| range.contains(&node.start_byte()))
+            .min_by_key(|(range, _)| range.len());
+        if let Some((_, from)) = caller {
+            let from = from.clone();
+            push_symbol_ref(&mut symbols, &from, ReferenceKind::Calls, target);
+        }
+    });
+
+    Some(symbols)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse(content: &str, language: &Language) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    parser.parse(content, None)
+}
+
+/// Pre-order traversal of every node in the tree.
+fn walk<'t>(root: Node<'t>, visit: &mut impl FnMut(Node<'t>)) {
+    let mut cursor = root.walk();
+    loop {
+        visit(cursor.node());
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
+/// Push a reference unless an identical one was already recorded.
+fn push_ref(
+    refs: &mut Vec<SourceReference>,
+    source: &Path,
+    kind: ReferenceKind,
+    route: PathBuf,
+    specifier: Option<&str>,
+) {
+    if refs
+        .iter()
+        .any(|r| r.kind == kind && r.target_route == route)
+    {
+        return;
+    }
+    refs.push(SourceReference {
+        source_path: source.to_path_buf(),
+        kind,
+        target_route: route,
+        specifier: specifier.map(str::to_string),
+    });
+}
+
+/// Kind and name of a definition node, for every supported grammar.
+fn symbol_definition<'a>(node: Node<'_>, src: &'a [u8]) -> Option<(SymbolKind, &'a str)> {
+    let kind = match node.kind() {
+        "function_item"
+        | "function_definition"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "method_definition" => SymbolKind::Function,
+        // `const f = () => ...` and `const f = function () ...`
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            if !matches!(value.kind(), "arrow_function" | "function_expression") {
+                return None;
+            }
+            SymbolKind::Function
+        }
+        "struct_item" => SymbolKind::Struct,
+        "enum_item" | "enum_declaration" => SymbolKind::Enum,
+        "trait_item" | "interface_declaration" => SymbolKind::Trait,
+        "class_definition" | "class_declaration" | "abstract_class_declaration" => {
+            SymbolKind::Class
+        }
+        _ => return None,
+    };
+    let name = node.child_by_field_name("name")?;
+    if !matches!(
+        name.kind(),
+        "identifier" | "type_identifier" | "property_identifier"
+    ) {
+        return None;
+    }
+    Some((kind, name.utf8_text(src).ok()?))
+}
+
+/// Name of the type a method is defined in (`impl`/trait body or class).
+///
+/// Stops at the nearest enclosing function so nested helpers stay
+/// unqualified.
+fn enclosing_type<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind() {
+            "impl_item" => return last_name(parent.child_by_field_name("type")?, src),
+            "trait_item"
+            | "class_definition"
+            | "class_declaration"
+            | "class"
+            | "abstract_class_declaration" => {
+                return parent.child_by_field_name("name")?.utf8_text(src).ok()
+            }
+            "function_item"
+            | "function_definition"
+            | "function_declaration"
+            | "method_definition"
+            | "arrow_function" => return None,
+            _ => current = parent.parent(),
+        }
+    }
+    None
+}
+
+/// Base classes, implemented interfaces and supertraits of a type definition.
+///
+/// `name` picks which identifier of a qualified base to report, e.g.
+/// [`last_name`] for the type itself or [`first_name`] for its namespace.
+fn supertypes<'a>(
+    node: Node<'_>,
+    src: &'a [u8],
+    name: fn(Node<'_>, &'a [u8]) -> Option<&'a str>,
+) -> Vec<&'a str> {
+    let mut bases = Vec::new();
+    let mut cursor = node.walk();
+
+    match node.kind() {
+        // class A(B, mod.C, metaclass=M)
+        "class_definition" => {
+            if let Some(list) = node.child_by_field_name("superclasses") {
+                for arg in list.named_children(&mut cursor) {
+                    bases.extend(name(arg, src));
+                }
+            }
+        }
+        // trait A: B + C
+        "trait_item" => {
+            if let Some(bounds) = node.child_by_field_name("bounds") {
+                for bound in bounds.named_children(&mut cursor) {
+                    bases.extend(name(bound, src));
+                }
+            }
+        }
+        // class A extends B implements C, D / interface A extends B
+        _ => {
+            for child in node.named_children(&mut cursor) {
+                if !matches!(child.kind(), "class_heritage" | "extends_type_clause") {
+                    continue;
+                }
+                let mut inner = child.walk();
+                for clause in child.named_children(&mut inner) {
+                    match clause.kind() {
+                        "extends_clause" | "implements_clause" => {
+                            let mut types = clause.walk();
+                            for ty in clause.named_children(&mut types) {
+                                bases.extend(name(ty, src));
+                            }
+                        }
+                        // JavaScript: `extends <expression>` without a clause node
+                        _ => bases.extend(name(clause, src)),
+                    }
+                }
+            }
+        }
+    }
+
+    bases
+}
+
+/// Rightmost identifier of a (possibly qualified or generic) name.
+///
+/// `a::b::c` -> `c`, `self.foo` -> `foo`, `Vec<T>` -> `Vec`.
+fn last_name<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    match node.kind() {
+        "identifier" | "type_identifier" | "field_identifier" | "property_identifier" => {
+            node.utf8_text(src).ok()
+        }
+        "scoped_identifier" | "scoped_type_identifier" | "nested_type_identifier" => {
+            last_name(node.child_by_field_name("name")?, src)
+        }
+        "field_expression" => last_name(node.child_by_field_name("field")?, src),
+        "attribute" => last_name(node.child_by_field_name("attribute")?, src),
+        "member_expression" => last_name(node.child_by_field_name("property")?, src),
+        "generic_type" => last_name(
+            node.child_by_field_name("type")
+                .or_else(|| node.child_by_field_name("name"))?,
+            src,
+        ),
+        "generic_function" => last_name(node.child_by_field_name("function")?, src),
+        _ => None,
+    }
+}
+
+/// Leftmost identifier of a qualified name: `ns.Base` -> `ns`.
+fn first_name<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    match node.kind() {
+        "identifier" | "type_identifier" => node.utf8_text(src).ok(),
+        "member_expression" => first_name(node.child_by_field_name("object")?, src),
+        "nested_type_identifier" => first_name(node.child_by_field_name("module")?, src),
+        "generic_type" => first_name(node.child_by_field_name("name")?, src),
+        _ => None,
+    }
+}
+
+/// Local names bound by a TS/JS import statement (default, named, namespace).
+fn ts_imported_names<'a>(node: Node<'_>, src: &'a [u8]) -> Vec<&'a str> {
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    let Some(clause) = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "import_clause")
+    else {
+        return names;
+    };
+
+    walk(clause, &mut |child| {
+        let bound = match child.kind() {
+            "import_specifier" => child
+                .child_by_field_name("alias")
+                .or_else(|| child.child_by_field_name("name")),
+            "namespace_import" => child.named_child(0),
+            "identifier" if child.parent().is_some_and(|p| p.kind() == "import_clause") => {
+                Some(child)
+            }
+            _ => None,
+        };
+        names.extend(bound.and_then(|b| b.utf8_text(src).ok()));
+    });
+    names
+}
+
+/// Push a symbol reference unless an identical one was already recorded.
+fn push_symbol_ref(symbols: &mut SourceSymbols, from: &str, kind: ReferenceKind, target: &str) {
+    if symbols
+        .references
+        .iter()
+        .any(|r| r.kind == kind && r.from == from && r.target == target)
+    {
+        return;
+    }
+    symbols.references.push(SymbolReference {
+        from: from.to_string(),
+        kind,
+        target: target.to_string(),
+    });
+}
+
+/// Flatten a Rust use tree into its leaf paths (as `::` segments).
+fn collect_use_paths(node: Node<'_>, src: &[u8], prefix: &[String], out: &mut Vec<Vec<String>>) {
+    let with_prefix = |path: Node<'_>| -> Vec<String> {
+        let mut segments = prefix.to_vec();
+        if let Ok(text) = path.utf8_text(src) {
+            segments.extend(text.split("::").map(|s| s.trim().to_string()));
+        }
+        segments
+    };
+
+    match node.kind() {
+        "identifier" | "scoped_identifier" | "crate" | "self" | "super" => {
+            out.push(with_prefix(node));
+        }
+        "use_as_clause" => {
+            if let Some(path) = node.child_by_field_name("path") {
+                out.push(with_prefix(path));
+            }
+        }
+        // `use foo::*` imports from the module itself.
+        "use_wildcard" => match node.named_child(0) {
+            Some(path) => out.push(with_prefix(path)),
+            None => out.push(prefix.to_vec()),
+        },
+        "scoped_use_list" => {
+            let scoped = match node.child_by_field_name("path") {
+                Some(path) => with_prefix(path),
+                None => prefix.to_vec(),
+            };
+            if let Some(list) = node.child_by_field_name("list") {
+                collect_use_paths(list, src, &scoped, out);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                collect_use_paths(child, src, prefix, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Turn a flattened use path into candidate file routes.
+///
+/// The leading `crate`/`self`/`super` segments are stripped like the
+/// line-based detector does. Besides the full path, the parent module is
+/// proposed too, since `use a::b::Item` usually names an item in `a/b.rs`.
+fn rust_routes(segments: &[String]) -> Vec<PathBuf> {
+    let mut segments: Vec<&str> = segments
+        .iter()
+        .map(String::as_str)
+        .skip_while(|s| matches!(*s, "crate" | "self" | "super"))
+        .collect();
+
+    // `use foo::{self, ..}` refers to `foo` itself.
+    if segments.last() == Some(&"self") {
+        segments.pop();
+    }
+
+    if segments.is_empty() {
+        return Vec::new();
+    }
+
+    let mut routes = vec![PathBuf::from(format!("{}.rs", segments.join("/")))];
+    if segments.len() > 1 {
+        let parent = &segments[..segments.len() - 1];
+        routes.push(PathBuf::from(format!("{}.rs", parent.join("/"))));
+    }
+    routes
+}
+
+/// Record the names bound by a `from <module> import a, b as c` statement.
+fn record_python_names(node: Node<'_>, src: &[u8], route: &Path, imported: &mut ImportedNames) {
+    let mut cursor = node.walk();
+    for name in node.children_by_field_name("name", &mut cursor) {
+        let bound = match name.kind() {
+            "aliased_import" => name.child_by_field_name("alias"),
+            _ => Some(name),
+        };
+        if let Some(text) = bound.and_then(|b| b.utf8_text(src).ok()) {
+            imported.insert(text.to_string(), route.to_path_buf());
+        }
+    }
+}
+
+/// Module name from an `import_statement`/`import_from_statement` name node.
+fn imported_module_name<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    let name = match node.kind() {
+        "aliased_import" => node.child_by_field_name("name")?,
+        _ => node,
+    };
+    name.utf8_text(src).ok()
+}
+
+/// Directory a relative Python import with `level` leading dots refers to.
+fn python_package_dir(source_path: &Path, level: usize) -> Option<PathBuf> {
+    let mut dir = source_path.parent()?.to_path_buf();
+    for _ in 1..level {
+        dir = dir.parent()?.to_path_buf();
+    }
+    Some(normalize_path(&dir))
+}
+
+/// Value of a `#[path = "..."]` attribute directly preceding a `mod` item.
+fn mod_path_attribute<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    let mut sibling = node.prev_sibling();
+    while let Some(attribute) = sibling.filter(|s| s.kind() == "attribute_item") {
+        if let Some(path) = attribute.utf8_text(src).ok().and_then(rust_path_attribute) {
+            return Some(path);
+        }
+        sibling = attribute.prev_sibling();
+    }
+    None
+}
+
+/// Unquoted contents of a string literal node.
+fn string_literal<'a>(node: Node<'_>, src: &'a [u8]) -> Option<&'a str> {
+    if node.kind() != "string" {
+        return None;
+    }
+    let text = node.utf8_text(src).ok()?;
+    Some(text.trim_matches(|c| c == '"' || c == '\''))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(refs: &[SourceReference]) -> Vec<String> {
+        refs.iter()
+            .map(|r| r.target_route.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_multiline_use_groups_and_nested_use() {
+        let content = r#"
+use crate::{
+    core::models::User,
+    store::{self, Store},
+};
+
+mod config;
+mod inline {
+    pub fn f() {}
+}
+
+fn run() {
+    use super::helpers::format;
+}
+"#;
+        let refs = detect_rust_references(content, Path::new("src/lib.rs")).unwrap();
+        let targets = targets(&refs);
+
+        assert!(targets.contains(&"core/models/User.rs".to_string()));
+        assert!(targets.contains(&"core/models.rs".to_string()));
+        assert!(targets.contains(&"store.rs".to_string()));
+        assert!(targets.contains(&"store/Store.rs".to_string()));
+        assert!(targets.contains(&"helpers/format.rs".to_string()));
+        assert!(targets.contains(&"helpers.rs".to_string()));
+        assert!(targets.contains(&"config.rs".to_string()));
+        assert!(targets.contains(&"config/mod.rs".to_string()));
+        assert!(!targets.iter().any(|t| t.starts_with("inline")));
+        assert!(refs.iter().all(|r| r.kind == ReferenceKind::Uses));
+    }
+
+    #[test]
+    fn test_python_relative_imports() {
+        let content = "import os.path, json as j\nfrom . import models\nfrom ..core.db import Session\nfrom pkg.util import x\n";
+        let refs = detect_python_references(content, Path::new("/repo/app/api/views.py")).unwrap();
+        let targets = targets(&refs);
+
+        assert_eq!(
+            targets,
+            vec![
+                "os/path.py",
+                "json.py",
+                "/repo/app/api/models.py",
+                "/repo/app/core/db.py",
+                "pkg/util.py",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ts_require_dynamic_import_and_reexports() {
+        let content = r#"
+import React from 'react';
+import { a } from "./a";
+export * from './b';
+export { c } from './c';
+const d = require('./d');
+const e = await import('./e');
+import f = require('./f');
+"#;
+        let refs = detect_ts_references(content, Path::new("src/index.ts")).unwrap();
+        assert_eq!(
+            targets(&refs),
+            vec!["react", "./a", "./b", "./c", "./d", "./e", "./f"]
+        );
+        assert!(refs.iter().all(|r| r.kind == ReferenceKind::Imports));
+    }
+
+    #[test]
+    fn test_unsupported_extension_falls_back() {
+        assert!(detect_references("import Foo", Path::new("Foo.lean")).is_none());
+    }
+
+    fn refs_of(symbols: &SourceSymbols, kind: ReferenceKind) -> Vec<(String, String)> {
+        symbols
+            .references
+            .iter()
+            .filter(|r| r.kind == kind)
+            .map(|r| (r.from.clone(), r.target.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_symbols_calls_and_impls() {
+        let content = r#"
+pub trait Store: Send {}
+
+pub struct Builder;
+
+impl Builder {
+    pub fn build(&self) -> Graph {
+        let g = helpers::empty();
+        self.finish(g)
+    }
+
+    fn finish(&self, g: Graph) -> Graph { g }
+}
+
+impl Store for Builder {}
+
+enum Mode { A }
+"#;
+        let symbols = detect_symbols(content, Path::new("src/lib.rs")).unwrap();
+        let names: Vec<_> = symbols
+            .definitions
+            .iter()
+            .map(|d| (d.qualified_name.as_str(), d.kind))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("Store", SymbolKind::Trait),
+                ("Builder", SymbolKind::Struct),
+                ("Builder::build", SymbolKind::Function),
+                ("Builder::finish", SymbolKind::Function),
+                ("Mode", SymbolKind::Enum),
+            ]
+        );
+        assert_eq!(symbols.definitions[2].name, "build");
+        assert_eq!(symbols.definitions[2].line, 7);
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Calls),
+            vec![
+                ("Builder::build".to_string(), "empty".to_string()),
+                ("Builder::build".to_string(), "finish".to_string()),
+            ]
+        );
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Implements),
+            vec![
+                ("Store".to_string(), "Send".to_string()),
+                ("Builder".to_string(), "Store".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_symbols() {
+        let content = "class Base:\n    pass\n\nclass User(Base, metaclass=Meta):\n    def save(self):\n        validate(self)\n        self.db.commit()\n\ndef validate(x):\n    return x\n\nvalidate(1)\n";
+        let symbols = detect_symbols(content, Path::new("app/models.py")).unwrap();
+        let names: Vec<_> = symbols
+            .definitions
+            .iter()
+            .map(|d| d.qualified_name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["Base", "User", "User.save", "validate"]);
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Calls),
+            vec![
+                ("User.save".to_string(), "validate".to_string()),
+                ("User.save".to_string(), "commit".to_string()),
+            ]
+        );
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Implements),
+            vec![("User".to_string(), "Base".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_ts_symbols() {
+        let content = r#"
+interface Shape { area(): number }
+export class Square extends Base implements Shape {
+    area() { return square(this.side); }
+}
+export const square = (x: number) => x * x;
+function main() { new Square(); }
+"#;
+        let symbols = detect_symbols(content, Path::new("src/shapes.ts")).unwrap();
+        let names: Vec<_> = symbols
+            .definitions
+            .iter()
+            .map(|d| (d.qualified_name.as_str(), d.kind))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("Shape", SymbolKind::Trait),
+                ("Square", SymbolKind::Class),
+                ("Square.area", SymbolKind::Function),
+                ("square", SymbolKind::Function),
+                ("main", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Implements),
+            vec![
+                ("Square".to_string(), "Base".to_string()),
+                ("Square".to_string(), "Shape".to_string()),
+            ]
+        );
+        assert_eq!(
+            refs_of(&symbols, ReferenceKind::Calls),
+            vec![
+                ("Square.area".to_string(), "square".to_string()),
+                ("main".to_string(), "Square".to_string()),
+            ]
+        );
+    }
+
+    fn implements_targets(refs: &[SourceReference]) -> Vec<String> {
+        refs.iter()
+            .filter(|r| r.kind == ReferenceKind::Implements)
+            .map(|r| r.target_route.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_implements_through_imports() {
+        let content = r#"
+use crate::{
+    store::Store,
+    sampler::Sampler,
+};
+
+impl Store for Builder {}
+impl<T> Sampler<T> for Builder {}
+impl crate::rules::Rule for Builder {}
+impl std::fmt::Display for Builder {}
+"#;
+        let refs = detect_rust_references(content, Path::new("src/builder.rs")).unwrap();
+        assert_eq!(
+            implements_targets(&refs),
+            vec!["store.rs", "sampler.rs", "rules.rs"]
+        );
+    }
+
+    #[test]
+    fn test_rust_path_attribute_and_specifiers() {
+        let content = r#"
+use crate::{store::{self, Store}, sampler::*};
+
+#[cfg(unix)]
+#[path = "platform/unix.rs"]
+mod platform;
+mod config;
+"#;
+        let refs = detect_rust_references(content, Path::new("src/lib.rs")).unwrap();
+        let specifiers: Vec<_> = refs.iter().filter_map(|r| r.specifier.as_deref()).collect();
+        assert!(specifiers.contains(&"crate::store"));
+        assert!(specifiers.contains(&"crate::store::Store"));
+        assert!(specifiers.contains(&"crate::sampler"));
+        assert!(specifiers.contains(&"self::config"));
+
+        let platform: Vec<_> = refs.iter().filter(|r| r.specifier.is_none()).collect();
+        assert_eq!(platform.len(), 1);
+        assert_eq!(
+            platform[0].target_route,
+            PathBuf::from("src/platform/unix.rs")
+        );
+        assert!(!targets(&refs).iter().any(|t| t.starts_with("platform.rs")));
+    }
+
+    #[test]
+    fn test_python_implements_relative_base() {
+        let content = "from .base import Base\nimport pkg.mixins as mx\n\nclass User(Base, mx.Audit):\n    pass\n";
+        let refs = detect_python_references(content, Path::new("app/models.py")).unwrap();
+        assert_eq!(
+            implements_targets(&refs),
+            vec!["app/base.py", "pkg/mixins.py"]
+        );
+    }
+
+    #[test]
+    fn test_ts_implements_through_imports() {
+        let content = r#"
+import Base, { Shape as S } from './base';
+import * as ui from './ui';
+export class Square extends Base implements S, ui.Widget {}
+interface Sized extends S {}
+"#;
+        let refs = detect_ts_references(content, Path::new("src/square.ts")).unwrap();
+        assert_eq!(implements_targets(&refs), vec!["./base", "./ui"]);
+    }
+}
@@ -525,6 +525,32 @@ impl McpGateway {
                 output_schema: None,
                 title: None,
             },
+            Tool {
+                name: "query_graph".into(),
+                description: Some(
+                    "Run a declarative graph query (Cypher-like): MATCH node/edge patterns with kinds, relationships and path lengths (e.g. `-[:uses*1..3]->`), WHERE predicates over metadata (=, <, CONTAINS, GLOB, EXISTS), RETURN with count/sum/avg/min/max/collect aggregation, HAVING, ORDER BY and LIMIT."
+                        .into(),
+                ),
+                input_schema: crate::server::schema_to_input_schema::<QueryGraphInput>(),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: None,
+            },
+            Tool {
+                name: "get_collapsed_graph".into(),
+                description: Some(
+                    "Get an aggregated view of the codebase: files collapsed into crates/packages, directories (optionally cut at a depth) or groups sharing a metadata value. Nodes list their member files, edges carry the number of underlying references. Start here on large codebases instead of reading the full graph."
+                        .into(),
+                ),
+                input_schema: crate::server::schema_to_input_schema::<CollapsedGraphInput>(),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: None,
+            },
         ]
     }
 
@@ -758,6 +784,46 @@ impl McpGateway {
                 }
             },
 
+            "query_graph" => match serde_json::from_value::<QueryGraphInput>(args) {
+                Ok(input) => match self.resolve_project(input.project.as_deref()) {
+                    Ok(project) => {
+                        let executor = self.executor_for(&project);
+                        match executor.query_graph(input) {
+                            Ok(output) => {
+                                let text =
+                                    serde_json::to_string_pretty(&output).unwrap_or_default();
+                                CallToolResult::success(vec![Content::text(text)])
+                            }
+                            Err(e) => CallToolResult::error(vec![Content::text(e)]),
+                        }
+                    }
+                    Err(e) => CallToolResult::error(vec![Content::text(e)]),
+                },
+                Err(e) => {
+                    CallToolResult::error(vec![Content::text(format!("Invalid input: {}", e))])
+                }
+            },
+
+            "get_collapsed_graph" => match serde_json::from_value::<CollapsedGraphInput>(args) {
+                Ok(input) => match self.resolve_project(input.project.as_deref()) {
+                    Ok(project) => {
+                        let executor = self.executor_for(&project);
+                        match executor.collapsed_graph(input) {
+                            Ok(output) => {
+                                let text =
+                                    serde_json::to_string_pretty(&output).unwrap_or_default();
+                                CallToolResult::success(vec![Content::text(text)])
+                            }
+                            Err(e) => CallToolResult::error(vec![Content::text(e)]),
+                        }
+                    }
+                    Err(e) => CallToolResult::error(vec![Content::text(e)]),
+                },
+                Err(e) => {
+                    CallToolResult::error(vec![Content::text(format!("Invalid input: {}", e))])
+                }
+            },
+
             "semantic_search" => match serde_json::from_value::<SemanticSearchInput>(args) {
                 Ok(input) => match self.resolve_project(input.project.as_deref()) {
                     Ok(project) => {
@@ -820,7 +886,9 @@ impl ServerHandler for McpGateway {
                  TO FIND CODE BY NAME: Use search_nodes for name/path pattern matching.\n\
                  TO FIND CODE BY MEANING: Use semantic_search for natural-language queries (e.g. 'authentication logic', 'database migrations').\n\
                  TO UNDERSTAND IMPORTS: Use get_dependencies for incoming/outgoing relationships.\n\
-                 TO BROWSE STRUCTURE: Use list_files with filters instead of ls.\n\n\
+                 TO BROWSE STRUCTURE: Use list_files with filters instead of ls.\n\
+                 FOR STRUCTURAL QUESTIONS (fan-in, transitive deps, metadata filters): Use query_graph.\n\
+                 FOR AN OVERVIEW OF LARGE CODEBASES: Use get_collapsed_graph (crate- or directory-level).\n\n\
                  The graph captures structural relationships (uses, contains) and semantic_search adds embedding-based similarity.\n\n\
                  {}",
                 project_count,
@@ -851,6 +919,15 @@ impl ServerHandler for McpGateway {
         request: CallToolRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
+        if request.name == "query_graph" {
+            // Queries can be expensive, so evaluate them on a blocking thread.
+            let handler = self.clone();
+            return tokio::task::spawn_blocking(move || {
+                handler.handle_tool(&request.name, request.arguments)
+            })
+            .await
+            .map_err(|e| ErrorData::internal_error(format!("Query task failed: {}", e), None));
+        }
         Ok(self.handle_tool(&request.name, request.arguments))
     }
 
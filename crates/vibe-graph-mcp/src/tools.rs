@@ -3,7 +3,8 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use vibe_graph_core::{GraphNodeKind, SourceCodeGraph};
+use vibe_graph_core::collapse::{MEMBERS_KEY, MEMBER_COUNT_KEY, WEIGHT_KEY};
+use vibe_graph_core::{CollapseBy, GraphNodeKind, SourceCodeGraph};
 use vibe_graph_git::get_git_changes;
 use vibe_graph_ops::Store;
 
@@ -414,10 +415,13 @@ impl ToolExecutor {
             .nodes
             .iter()
             .filter(|node| {
-                // Only include files (not directories)
+                // Only include files (not directories, nor symbols unless asked for)
                 if matches!(node.kind, GraphNodeKind::Directory) {
                     return false;
                 }
+                if matches!(node.kind, GraphNodeKind::Symbol) && input.kind.is_none() {
+                    return false;
+                }
 
                 // Apply path filter
                 if let Some(path_prefix) = path_filter {
@@ -462,6 +466,85 @@ impl ToolExecutor {
         }
     }
 
+    /// Run a graph query (see `vibe_graph_core::query`).
+    pub fn query_graph(&self, input: QueryGraphInput) -> Result<QueryGraphOutput, String> {
+        let result = self.graph.query(&input.query).map_err(|e| e.to_string())?;
+        let total = result.rows.len();
+        let rows = result
+            .rows
+            .iter()
+            .take(input.limit)
+            .map(|row| {
+                row.iter()
+                    .map(|value| serde_json::to_value(value).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+
+        Ok(QueryGraphOutput {
+            columns: result.columns,
+            rows,
+            total,
+        })
+    }
+
+    /// Summarize the graph with files collapsed into groups
+    /// (see `vibe_graph_core::collapse`).
+    pub fn collapsed_graph(
+        &self,
+        input: CollapsedGraphInput,
+    ) -> Result<CollapsedGraphOutput, String> {
+        let by: CollapseBy = input.by.parse()?;
+        let view = self.graph.collapse(&by);
+        let names: HashMap<_, _> = view.nodes.iter().map(|n| (n.id, n.name.clone())).collect();
+
+        let nodes = view
+            .nodes
+            .iter()
+            .map(|node| CollapsedNodeInfo {
+                name: node.name.clone(),
+                member_count: node
+                    .metadata
+                    .get(MEMBER_COUNT_KEY)
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0),
+                members: node
+                    .metadata
+                    .get(MEMBERS_KEY)
+                    .map(|members| {
+                        members
+                            .lines()
+                            .take(input.max_members)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let mut edges: Vec<CollapsedEdgeInfo> = view
+            .edges
+            .iter()
+            .map(|edge| CollapsedEdgeInfo {
+                from: names.get(&edge.from).cloned().unwrap_or_default(),
+                to: names.get(&edge.to).cloned().unwrap_or_default(),
+                relationship: edge.relationship.clone(),
+                weight: edge
+                    .metadata
+                    .get(WEIGHT_KEY)
+                    .and_then(|weight| weight.parse().ok())
+                    .unwrap_or(1),
+            })
+            .collect();
+        edges.sort_by(|a, b| b.weight.cmp(&a.weight));
+
+        Ok(CollapsedGraphOutput {
+            by: by.to_string(),
+            nodes,
+            edges,
+        })
+    }
+
     /// Perform a semantic (embedding-based) search over the indexed graph.
     #[cfg(feature = "semantic")]
     pub fn semantic_search(&self, input: SemanticSearchInput) -> SemanticSearchOutput {
@@ -581,6 +664,8 @@ fn kind_to_string(kind: &GraphNodeKind) -> String {
         GraphNodeKind::Directory => "directory".to_string(),
         GraphNodeKind::Service => "service".to_string(),
         GraphNodeKind::Test => "test".to_string(),
+        GraphNodeKind::Symbol => "symbol".to_string(),
+        GraphNodeKind::Repository => "repository".to_string(),
         GraphNodeKind::Other => "other".to_string(),
     }
 }
@@ -188,7 +188,7 @@ pub struct SearchNodesInput {
     /// Search query (matches against node name and path).
     pub query: String,
 
-    /// Filter by node kind: "file", "directory", "module", "test", "service".
+    /// Filter by node kind: "file", "directory", "module", "test", "service", "symbol".
     #[serde(default)]
     pub kind: Option<String>,
 
@@ -212,7 +212,8 @@ pub struct GetDependenciesInput {
     #[serde(default)]
     pub project: Option<String>,
 
-    /// Path or name of the node to query.
+    /// Path or name of the node to query. Symbol nodes (graphs built with
+    /// `vg graph --symbols`) are addressed as `<file>::<Type::fn>`.
     pub node_path: String,
 
     /// Include incoming dependencies (nodes that depend on this one).
@@ -329,6 +330,51 @@ fn default_semantic_top_k() -> usize {
     10
 }
 
+/// Input for the `query_graph` tool.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct QueryGraphInput {
+    /// Project name to query. Required if multiple projects are registered.
+    #[serde(default)]
+    pub project: Option<String>,
+
+    /// Graph query, e.g. `MATCH (f:file)<-[:uses]-(u) RETURN f.relative_path AS file,
+    /// count(u) AS users ORDER BY users DESC LIMIT 10`.
+    pub query: String,
+
+    /// Maximum number of rows to return.
+    #[serde(default = "default_query_limit")]
+    pub limit: usize,
+}
+
+fn default_query_limit() -> usize {
+    100
+}
+
+/// Input for the `get_collapsed_graph` tool.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CollapsedGraphInput {
+    /// Project name to summarize. Required if multiple projects are registered.
+    #[serde(default)]
+    pub project: Option<String>,
+
+    /// Grouping: "crate" (crate/package), "directory", "directory:<depth>"
+    /// or "metadata:<key>".
+    #[serde(default = "default_collapse_by")]
+    pub by: String,
+
+    /// Maximum member files listed per group (0 for counts only).
+    #[serde(default = "default_max_members")]
+    pub max_members: usize,
+}
+
+fn default_collapse_by() -> String {
+    "crate".to_string()
+}
+
+fn default_max_members() -> usize {
+    20
+}
+
 // =============================================================================
 // Tool Output Types
 // =============================================================================
@@ -487,6 +533,61 @@ pub struct ListFilesOutput {
     pub path: Option<String>,
 }
 
+/// Output for the `query_graph` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct QueryGraphOutput {
+    /// Column names, in `RETURN` order.
+    pub columns: Vec<String>,
+
+    /// Result rows (truncated to the requested limit).
+    pub rows: Vec<Vec<serde_json::Value>>,
+
+    /// Total number of rows before truncation.
+    pub total: usize,
+}
+
+/// A group of files in a collapsed graph.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CollapsedNodeInfo {
+    /// Group name: a directory, crate path or metadata value.
+    pub name: String,
+
+    /// Number of member files.
+    pub member_count: usize,
+
+    /// Member file paths (truncated to `max_members`).
+    pub members: Vec<String>,
+}
+
+/// Aggregated references between two groups.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CollapsedEdgeInfo {
+    /// Source group name.
+    pub from: String,
+
+    /// Target group name.
+    pub to: String,
+
+    /// Relationship type: "uses", "imports", "implements", "calls".
+    pub relationship: String,
+
+    /// Number of underlying references.
+    pub weight: usize,
+}
+
+/// Output for the `get_collapsed_graph` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CollapsedGraphOutput {
+    /// Grouping that was applied.
+    pub by: String,
+
+    /// Groups, by name.
+    pub nodes: Vec<CollapsedNodeInfo>,
+
+    /// References between groups, heaviest first.
+    pub edges: Vec<CollapsedEdgeInfo>,
+}
+
 /// A single hit from semantic search.
 #[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SemanticSearchHit {
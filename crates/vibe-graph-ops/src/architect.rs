@@ -2,8 +2,10 @@ use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use vibe_graph_core::structure::file_key;
 use vibe_graph_core::{
-    GraphNodeKind, LayoutStrategy, NodeId, ReferenceKind, SourceCodeGraph, SourceCodeGraphBuilder,
+    GraphNodeKind, LayoutStrategy, ModuleClustering, NodeId, ReferenceKind, SourceCodeGraph,
+    SourceCodeGraphBuilder,
 };
 
 /// Transforms a raw logical graph into a deployable filesystem graph.
@@ -32,9 +34,9 @@ impl ArchitectFactory {
             LayoutStrategy::Preserve | LayoutStrategy::Direct => Box::new(PreserveArchitect {
                 root_dir: root_dir.to_path_buf(),
             }),
-            _ => Box::new(FlatArchitect {
+            LayoutStrategy::Modular => Box::new(ModularArchitect {
                 root_dir: root_dir.to_path_buf(),
-            }), // Default to flat
+            }),
         }
     }
 }
@@ -287,3 +289,212 @@ impl GraphArchitect for LatticeArchitect {
         Ok(builder.build())
     }
 }
+
+/// Groups files into one directory per inferred module.
+///
+/// Modules come from [`ModuleClustering`]: communities of files that
+/// depend on each other. Files without dependencies keep their current
+/// relative path. Module directories carry `inferred_module` metadata.
+pub struct ModularArchitect {
+    pub root_dir: PathBuf,
+}
+
+impl GraphArchitect for ModularArchitect {
+    fn architect(&self, graph: &SourceCodeGraph) -> Result<SourceCodeGraph> {
+        let clustering = ModuleClustering::detect(graph);
+        let module_of = clustering.module_of();
+
+        let mut builder = SourceCodeGraphBuilder::new().with_root(&self.root_dir);
+        let root_id = builder.add_directory(&self.root_dir);
+        let mut module_dirs: HashMap<&str, NodeId> = HashMap::new();
+        for module in &clustering.modules {
+            let dir = self.root_dir.join(&module.name);
+            let dir_id = builder.add_directory(&dir);
+            builder.add_edge(root_id, dir_id, ReferenceKind::Contains);
+            builder.set_node_metadata(dir_id, "inferred_module", &module.name);
+            builder.set_node_metadata(dir_id, "current_directory", &module.directory);
+            module_dirs.insert(module.name.as_str(), dir_id);
+        }
+
+        let mut id_map: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+        for node in &graph.nodes {
+            if matches!(node.kind, GraphNodeKind::Directory | GraphNodeKind::Symbol) {
+                continue;
+            }
+            let Some(key) = file_key(graph, node) else {
+                continue;
+            };
+            let current = Path::new(&key);
+
+            let new_id = match module_of.get(key.as_str()) {
+                Some(module) => {
+                    let dir = self.root_dir.join(&module.name);
+                    // Same-named files from different directories keep
+                    // their old parent as a prefix
+                    let mut path = dir.join(&node.name);
+                    if taken.contains(&path) {
+                        let parent = current
+                            .parent()
+                            .and_then(|p| p.file_name())
+                            .and_then(OsStr::to_str)
+                            .unwrap_or("root");
+                        path = dir.join(format!("{}_{}", parent, node.name));
+                    }
+                    let path = claim(&mut taken, path);
+                    let relative = path.strip_prefix(&self.root_dir).unwrap_or(&path);
+                    let id = builder.add_file(&path, &relative.to_string_lossy());
+                    builder.add_edge(
+                        module_dirs[module.name.as_str()],
+                        id,
+                        ReferenceKind::Contains,
+                    );
+                    id
+                }
+                None => {
+                    let relative = if current.is_absolute() {
+                        PathBuf::from(&node.name)
+                    } else {
+                        current.to_path_buf()
+                    };
+                    let path = claim(&mut taken, self.root_dir.join(&relative));
+                    let relative = path.strip_prefix(&self.root_dir).unwrap_or(&path);
+                    let id = builder.add_file(&path, &relative.to_string_lossy());
+                    let mut child = path.clone();
+                    let mut child_id = id;
+                    while let Some(parent) = child.parent() {
+                        if !parent.starts_with(&self.root_dir) {
+                            break;
+                        }
+                        let existed = builder.get_node_id(parent).is_some();
+                        let parent_id = builder.add_directory(parent);
+                        builder.add_edge(parent_id, child_id, ReferenceKind::Contains);
+                        if existed || parent == self.root_dir {
+                            break;
+                        }
+                        child = parent.to_path_buf();
+                        child_id = parent_id;
+                    }
+                    id
+                }
+            };
+            id_map.insert(node.id, new_id);
+        }
+
+        for edge in &graph.edges {
+            if edge.relationship == "contains" {
+                continue;
+            }
+            if let (Some(&from), Some(&to)) = (id_map.get(&edge.from), id_map.get(&edge.to)) {
+                let kind = match edge.relationship.as_str() {
+                    "imports" => ReferenceKind::Imports,
+                    "implements" => ReferenceKind::Implements,
+                    _ => ReferenceKind::Uses,
+                };
+                builder.add_edge(from, to, kind);
+            }
+        }
+
+        Ok(builder.build())
+    }
+}
+
+/// Reserve `path`, or the first free `stem_N.ext` next to it.
+fn claim(taken: &mut HashSet<PathBuf>, path: PathBuf) -> PathBuf {
+    if taken.insert(path.clone()) {
+        return path;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| taken.insert(candidate.clone()))
+        .expect("unbounded suffixes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modular_architect_groups_by_inferred_module() {
+        let root = PathBuf::from("/ws");
+        let mut builder = SourceCodeGraphBuilder::new().with_root(&root);
+        for file in ["a/x.rs", "b/y.rs", "b/z.rs", "c/w.rs", "docs/notes.md"] {
+            builder.add_file(&root.join(file), file);
+        }
+        for (from, to) in [("a/x.rs", "b/y.rs"), ("b/z.rs", "c/w.rs")] {
+            let from = builder.get_node_id(&root.join(from)).unwrap();
+            let to = builder.get_node_id(&root.join(to)).unwrap();
+            builder.add_edge(from, to, ReferenceKind::Uses);
+        }
+        let graph = builder.build();
+
+        let out = PathBuf::from("/out");
+        let proposed = ArchitectFactory::create(LayoutStrategy::Modular, &out)
+            .architect(&graph)
+            .unwrap();
+
+        let mut files: Vec<String> = proposed
+            .nodes
+            .iter()
+            .filter(|n| n.kind != GraphNodeKind::Directory)
+            .map(|n| n.metadata["relative_path"].clone())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["a/x.rs", "a/y.rs", "b/w.rs", "b/z.rs", "docs/notes.md"]
+        );
+        let uses = proposed
+            .edges
+            .iter()
+            .filter(|e| e.relationship == "uses")
+            .count();
+        assert_eq!(uses, 2);
+    }
+
+    #[test]
+    fn test_modular_architect_keeps_same_named_files() {
+        let root = PathBuf::from("/ws");
+        let mut builder = SourceCodeGraphBuilder::new().with_root(&root);
+        let files = ["a/src/lib.rs", "b/src/lib.rs", "c/src/lib.rs"];
+        for file in files {
+            builder.add_file(&root.join(file), file);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+            let from = builder.get_node_id(&root.join(files[from])).unwrap();
+            let to = builder.get_node_id(&root.join(files[to])).unwrap();
+            builder.add_edge(from, to, ReferenceKind::Uses);
+        }
+        let graph = builder.build();
+
+        let proposed = ArchitectFactory::create(LayoutStrategy::Modular, Path::new("/out"))
+            .architect(&graph)
+            .unwrap();
+
+        let mut files: Vec<String> = proposed
+            .nodes
+            .iter()
+            .filter(|n| n.kind != GraphNodeKind::Directory)
+            .map(|n| n.metadata["relative_path"].clone())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["src/lib.rs", "src/src_lib.rs", "src/src_lib_2.rs"]
+        );
+        let uses = proposed
+            .edges
+            .iter()
+            .filter(|e| e.relationship == "uses")
+            .count();
+        assert_eq!(uses, 3);
+    }
+}
@@ -3,24 +3,34 @@
 //! The OpsContext holds configuration and provides methods for all vibe-graph
 //! operations. It can be used by CLI, REST API, or any other consumer.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use git2::{Cred, RemoteCallbacks};
 use octocrab::Octocrab;
+use rayon::prelude::*;
 use tracing::{debug, info, warn};
 use vibe_graph_core::{
-    detect_references, GitChangeSnapshot, SourceCodeGraph, SourceCodeGraphBuilder,
+    detect_references, detect_symbols, GitChangeSnapshot, GraphChangeSet, GraphDiff, GraphEdge,
+    GraphNode, GraphNodeKind, NodeId, ReferenceKind, SourceCodeGraph, SourceCodeGraphBuilder,
+    SourceReference, SourceSymbols, StructuralDiff,
 };
 use vibe_graph_git::get_git_changes;
 
 use crate::config::Config;
 use crate::error::{OpsError, OpsResult};
-use crate::project::{Project, ProjectSource, Repository};
+use crate::packages::{self, RepositoryPackages};
+use crate::project::{Project, ProjectSource, Repository, Source};
 use crate::requests::*;
+use crate::resolve::{
+    self, CachedManifests, ManifestCache, ManifestSource, ReferenceResolver, Resolution,
+};
 use crate::responses::*;
+use crate::revision::Revision;
 use crate::scan::scan_directory;
-use crate::store::Store;
+use crate::store::{GraphSnapshotInfo, Store};
 use crate::workspace::{SyncSource, WorkspaceInfo, WorkspaceKind};
 
 /// The main operations context.
@@ -31,12 +41,18 @@ use crate::workspace::{SyncSource, WorkspaceInfo, WorkspaceKind};
 pub struct OpsContext {
     /// Configuration for operations.
     pub config: Config,
+
+    /// Manifest contents shared by graph builds and updates.
+    manifests: Arc<ManifestCache>,
 }
 
 impl OpsContext {
     /// Create a new OpsContext with the given configuration.
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            manifests: Arc::default(),
+        }
     }
 
     /// Create a new OpsContext with default configuration.
@@ -148,20 +164,24 @@ impl OpsContext {
             repositories: vec![],
         };
 
-        for repo_path in &workspace.repo_paths {
-            let repo_name = repo_path
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_else(|| "repo".to_string());
-
-            let mut repo = Repository::new(
-                &repo_name,
-                repo_path.display().to_string(),
-                repo_path.clone(),
-            );
-            scan_directory(&mut repo, repo_path)?;
-            project.repositories.push(repo);
-        }
+        project.repositories = workspace
+            .repo_paths
+            .par_iter()
+            .map(|repo_path| {
+                let repo_name = repo_path
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "repo".to_string());
+
+                let mut repo = Repository::new(
+                    &repo_name,
+                    repo_path.display().to_string(),
+                    repo_path.clone(),
+                );
+                scan_directory(&mut repo, repo_path)?;
+                Ok(repo)
+            })
+            .collect::<OpsResult<_>>()?;
 
         Ok(project)
     }
@@ -230,40 +250,49 @@ impl OpsContext {
             repositories: vec![],
         };
 
-        for repo in &all_repos {
-            let repo_name = &repo.name;
-
-            // Skip if in ignore list
-            if request.ignore.iter().any(|s| s == repo_name) {
-                debug!(repo = %repo_name, "Skipping ignored repository");
-                continue;
-            }
-
-            let clone_url = repo
-                .clone_url
-                .as_ref()
-                .map(|u| u.to_string())
-                .unwrap_or_else(|| format!("https://github.com/{}/{}.git", org, repo_name));
-
-            let repo_path = org_dir.join(repo_name);
-
-            // Clone or update
-            if needs_clone(&repo_path) {
-                if repo_path.exists() {
-                    std::fs::remove_dir_all(&repo_path)?;
+        let selected: Vec<_> = all_repos
+            .iter()
+            .filter(|repo| {
+                let ignored = request.ignore.iter().any(|s| s == &repo.name);
+                if ignored {
+                    debug!(repo = %repo.name, "Skipping ignored repository");
                 }
+                !ignored
+            })
+            .collect();
+
+        // Clone and scan repositories concurrently; order follows the listing
+        let repositories: Vec<Option<Repository>> = selected
+            .par_iter()
+            .map(|repo| {
+                let repo_name = &repo.name;
+                let clone_url = repo
+                    .clone_url
+                    .as_ref()
+                    .map(|u| u.to_string())
+                    .unwrap_or_else(|| format!("https://github.com/{}/{}.git", org, repo_name));
+
+                let repo_path = org_dir.join(repo_name);
+
+                // Clone or update
+                if needs_clone(&repo_path) {
+                    if repo_path.exists() {
+                        std::fs::remove_dir_all(&repo_path)?;
+                    }
 
-                if let Err(e) = clone_repository(&clone_url, &repo_path, &username, &token) {
-                    warn!(repo = %repo_name, error = %e, "Failed to clone repository");
-                    continue;
+                    if let Err(e) = clone_repository(&clone_url, &repo_path, &username, &token) {
+                        warn!(repo = %repo_name, error = %e, "Failed to clone repository");
+                        return Ok(None);
+                    }
                 }
-            }
 
-            // Scan the repository
-            let mut repository = Repository::new(repo_name, &clone_url, repo_path.clone());
-            scan_directory(&mut repository, &repo_path)?;
-            project.repositories.push(repository);
-        }
+                // Scan the repository
+                let mut repository = Repository::new(repo_name, &clone_url, repo_path.clone());
+                scan_directory(&mut repository, &repo_path)?;
+                Ok(Some(repository))
+            })
+            .collect::<OpsResult<_>>()?;
+        project.repositories = repositories.into_iter().flatten().collect();
 
         // Expand content
         let max_size = self.config.max_content_size_kb * 1024;
@@ -390,18 +419,32 @@ impl OpsContext {
             .unwrap_or_else(|_| request.path.clone());
         let store = Store::new(&path);
 
+        if let Some(rev) = &request.rev {
+            return self.graph_at_revision(&store, rev, request.clone());
+        }
+
         if !store.exists() {
             return Err(OpsError::StoreNotFound { path });
         }
 
-        // Try to load cached graph first (unless force rebuild)
+        // Try to load cached graph first (unless force rebuild). The cached
+        // graph must have symbols exactly when the request asks for them.
         if !request.force {
-            if let Some(graph) = store.load_graph()? {
+            if let Some(graph) = store
+                .load_graph()?
+                .filter(|g| g.metadata.contains_key("symbols") == request.symbols)
+            {
+                let snapshot_created = if request.snapshot {
+                    Some(store.snapshot_graph(&graph)?)
+                } else {
+                    None
+                };
                 return Ok(GraphResponse {
                     graph,
-                    saved_path: store.self_dir().join("graph.json"),
+                    saved_path: store.location(),
                     output_path: request.output,
                     from_cache: true,
+                    snapshot_created,
                 });
             }
         }
@@ -409,7 +452,7 @@ impl OpsContext {
         // Load project and build graph
         let project = store.load()?.ok_or(OpsError::ProjectNotFound)?;
 
-        let graph = self.build_source_graph(&project)?;
+        let graph = self.build_source_graph_with_options(&project, (&request).into())?;
 
         // Save graph
         let saved_path = store.save_graph(&graph)?;
@@ -420,27 +463,221 @@ impl OpsContext {
             std::fs::write(output_path, &json)?;
         }
 
+        let snapshot_created = if request.snapshot {
+            Some(store.snapshot_graph(&graph)?)
+        } else {
+            None
+        };
+
         Ok(GraphResponse {
             graph,
             saved_path,
             output_path: request.output,
             from_cache: false,
+            snapshot_created,
+        })
+    }
+
+    /// Build the graph of a workspace at a git revision, without touching
+    /// the working tree or the stored working-tree graph.
+    fn graph_at_revision(
+        &self,
+        store: &Store,
+        rev: &str,
+        request: GraphRequest,
+    ) -> OpsResult<GraphResponse> {
+        let revision = Revision::resolve(store.root(), rev)?;
+        let (graph, from_cache) =
+            self.revision_graph(store, &revision, (&request).into(), request.force)?;
+
+        if let Some(ref output_path) = request.output {
+            let json = serde_json::to_string_pretty(&graph)?;
+            std::fs::write(output_path, &json)?;
+        }
+
+        let snapshot_created = if request.snapshot {
+            Some(store.snapshot_graph_at(&graph, Some(revision.commit.clone()))?)
+        } else {
+            None
+        };
+
+        Ok(GraphResponse {
+            graph,
+            saved_path: store.self_dir().to_path_buf(),
+            output_path: request.output,
+            from_cache,
+            snapshot_created,
+        })
+    }
+
+    /// The graph of a revision, from the per-tree cache unless `force`.
+    /// Returns whether it came from the cache.
+    fn revision_graph(
+        &self,
+        store: &Store,
+        revision: &Revision,
+        options: GraphBuildOptions,
+        force: bool,
+    ) -> OpsResult<(SourceCodeGraph, bool)> {
+        let mut key = revision.tree_id();
+        if options.symbols {
+            key.push_str("-symbols");
+        }
+        if !force {
+            if let Some(graph) = store.load_revision_graph(&key)? {
+                debug!(commit = %revision.commit, tree = %key, "Loaded cached revision graph");
+                return Ok((graph, true));
+            }
+        }
+
+        let project = revision.project(self.config.max_content_size_kb * 1024)?;
+        let mut graph = self.build_graph(&project, options, revision)?;
+        graph
+            .metadata
+            .insert("commit".to_string(), revision.commit.clone());
+        store.save_revision_graph(&key, &graph)?;
+        Ok((graph, false))
+    }
+
+    /// A graph to compare: a stored snapshot, or else the graph built from
+    /// the git revision the reference names.
+    fn diff_side(
+        &self,
+        store: &Store,
+        reference: &str,
+    ) -> OpsResult<(GraphSnapshotInfo, SourceCodeGraph)> {
+        match store.resolve_graph_snapshot(reference) {
+            Ok(info) => {
+                let graph = store.load_graph_snapshot(&info.id)?;
+                Ok((info, graph))
+            }
+            Err(OpsError::SnapshotNotFound { id }) => {
+                let Ok(revision) = Revision::resolve(store.root(), reference) else {
+                    return Err(OpsError::SnapshotNotFound { id });
+                };
+                let (graph, _) =
+                    self.revision_graph(store, &revision, GraphBuildOptions::default(), false)?;
+                let info = GraphSnapshotInfo::new(&graph, revision.time, Some(revision.commit));
+                Ok((info, graph))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Compare a graph snapshot with another one or with the current graph.
+    pub async fn graph_diff(&self, request: GraphDiffRequest) -> OpsResult<GraphDiffResponse> {
+        let path = request
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| request.path.clone());
+        let store = Store::new(&path);
+
+        if !store.exists() && request.to.is_none() {
+            return Err(OpsError::StoreNotFound { path });
+        }
+
+        let (from, older) = self.diff_side(&store, &request.from)?;
+        let (to, newer) = match &request.to {
+            Some(reference) => {
+                let (to, graph) = self.diff_side(&store, reference)?;
+                (Some(to), graph)
+            }
+            None => (None, store.load_graph()?.ok_or(OpsError::GraphNotFound)?),
+        };
+
+        Ok(GraphDiffResponse {
+            from,
+            to,
+            diff: StructuralDiff::between(&older, &newer),
+        })
+    }
+
+    /// List the graph snapshots of a workspace.
+    pub async fn graph_snapshots(
+        &self,
+        request: GraphSnapshotsRequest,
+    ) -> OpsResult<GraphSnapshotsResponse> {
+        let path = request
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| request.path.clone());
+        let store = Store::new(&path);
+
+        Ok(GraphSnapshotsResponse {
+            snapshots: store.list_graph_snapshots()?,
+        })
+    }
+
+    /// Find the packages each repository of a synced workspace publishes
+    /// and the dependencies between repositories they imply.
+    pub async fn repo_dependencies(
+        &self,
+        request: RepoDependenciesRequest,
+    ) -> OpsResult<RepoDependenciesResponse> {
+        let path = request
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| request.path.clone());
+        let store = Store::new(&path);
+        if !store.exists() {
+            return Err(OpsError::StoreNotFound { path });
+        }
+        let project = store.load()?.ok_or(OpsError::ProjectNotFound)?;
+
+        let repositories: Vec<RepositoryPackages> = project
+            .repositories
+            .iter()
+            .map(RepositoryPackages::discover)
+            .collect();
+        let dependencies = packages::cross_repo_dependencies(&repositories);
+        Ok(RepoDependenciesResponse {
+            repositories,
+            dependencies,
         })
     }
 
     /// Build a SourceCodeGraph from a Project.
     pub fn build_source_graph(&self, project: &Project) -> OpsResult<SourceCodeGraph> {
+        self.build_source_graph_with_options(project, GraphBuildOptions::default())
+    }
+
+    /// Build a SourceCodeGraph from a Project with explicit build options.
+    ///
+    /// With `options.symbols`, every parsed file also gets `Symbol` child
+    /// nodes for its definitions, plus `calls`/`implements` edges between
+    /// them (see [`vibe_graph_core::detect_symbols`]).
+    pub fn build_source_graph_with_options(
+        &self,
+        project: &Project,
+        options: GraphBuildOptions,
+    ) -> OpsResult<SourceCodeGraph> {
+        let manifests = CachedManifests::new(&self.manifests, project);
+        self.build_graph(project, options, &manifests)
+    }
+
+    /// Build a graph, reading the manifests the resolver needs from
+    /// `manifests` (the working tree or a git tree).
+    fn build_graph(
+        &self,
+        project: &Project,
+        options: GraphBuildOptions,
+        manifests: &dyn ManifestSource,
+    ) -> OpsResult<SourceCodeGraph> {
         let mut builder = SourceCodeGraphBuilder::new()
             .with_metadata("name", &project.name)
             .with_metadata("type", "source_code_graph");
+        if options.symbols {
+            builder = builder.with_metadata("symbols", "true");
+        }
 
         // Track all directories
         let mut all_dirs: HashSet<PathBuf> = HashSet::new();
 
-        // Find workspace root
+        // Find workspace root (node IDs are derived from paths relative to it)
         let workspace_root = find_workspace_root(&project.repositories);
         if let Some(ref root) = workspace_root {
             all_dirs.insert(root.clone());
+            builder = builder.with_root(root);
         }
 
         // Collect directories and add file nodes
@@ -501,52 +738,301 @@ impl OpsContext {
         }
 
         // Detect and add reference edges
-        let max_size = self.config.max_content_size_kb * 1024;
+        let mut symbol_references = Vec::new();
+        let mut references = Vec::new();
+        let mut resolver = ReferenceResolver::with_manifests(
+            project
+                .repositories
+                .iter()
+                .flat_map(|repo| repo.sources.iter().map(|s| s.path.clone())),
+            manifests,
+        );
 
-        for repo in &project.repositories {
-            for source in &repo.sources {
-                if !source.is_text() || source.size.map(|s| s > max_size).unwrap_or(true) {
-                    continue;
-                }
+        // Files are read and analyzed in parallel, then recorded in order
+        let sources: Vec<&Source> = project
+            .repositories
+            .iter()
+            .flat_map(|repo| &repo.sources)
+            .collect();
+        let analyses: Vec<(&Source, SourceAnalysis)> = sources
+            .par_iter()
+            .filter_map(|&source| {
+                let content = self.read_source(source)?;
+                Some((
+                    source,
+                    analyze_source(&source.path, &content, options.symbols),
+                ))
+            })
+            .collect();
+
+        for (source, analysis) in analyses {
+            let symbols = record_analysis(
+                &mut builder,
+                &mut resolver,
+                &source.path,
+                analysis,
+                &mut references,
+            );
+            if let Some(symbols) = symbols {
+                builder.add_symbols(&source.path, &symbols);
+                symbol_references.push((source.path.clone(), symbols.references));
+            }
+        }
 
-                let content = match &source.content {
-                    Some(c) => c.clone(),
-                    None => match std::fs::read_to_string(&source.path) {
-                        Ok(c) => c,
-                        Err(_) => continue,
-                    },
-                };
+        // References are resolved once every file has been seen (Java/Kotlin
+        // imports resolve through the packages other files declare).
+        link_references(&mut builder, &resolver, &references);
 
-                // Detect inline tests and mark the node
-                if let Some(node_id) = builder.get_node_id(&source.path) {
-                    if has_inline_tests(&content, &source.path) {
-                        builder.set_node_metadata(node_id, "has_tests", "true");
-                    }
-                }
+        // Symbol edges are resolved once every file's definitions exist
+        for (path, references) in &symbol_references {
+            builder.add_symbol_references(path, references);
+        }
 
-                let refs = detect_references(&content, &source.path);
-
-                for reference in refs {
-                    if let Some(source_id) = builder.get_node_id(&reference.source_path) {
-                        if let Some(target_id) =
-                            builder.find_node_by_path_suffix(&reference.target_route)
-                        {
-                            if source_id != target_id {
-                                builder.add_edge(source_id, target_id, reference.kind);
-                            }
-                        }
-                    }
+        let mut graph = builder.build();
+        if project.repositories.len() > 1 {
+            packages::annotate(&mut graph, &project.repositories, manifests);
+        }
+
+        info!(
+            nodes = graph.node_count(),
+            edges = graph.edge_count(),
+            "Built SourceCodeGraph"
+        );
+
+        Ok(graph)
+    }
+
+    /// Update the stored graph of a workspace for a set of changed files.
+    ///
+    /// The project's source list is updated for added and deleted files and
+    /// saved alongside the new graph. Modified files whose content hash is
+    /// unchanged are not re-analyzed. Without a stored graph this falls back
+    /// to a full build, reported as a diff where everything was added.
+    pub async fn update_graph(
+        &self,
+        request: GraphUpdateRequest,
+    ) -> OpsResult<GraphUpdateResponse> {
+        let path = request
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| request.path.clone());
+        let store = Store::new(&path);
+
+        if !store.exists() {
+            return Err(OpsError::StoreNotFound { path });
+        }
+
+        let mut project = store.load()?.ok_or(OpsError::ProjectNotFound)?;
+        let changes = project.apply_changes(&request.changes)?;
+
+        let (graph, diff) = match store.load_graph()? {
+            Some(previous) => self.update_source_graph(&project, previous, &changes)?,
+            None => {
+                let graph = self.build_source_graph(&project)?;
+                let diff = SourceCodeGraph::default().diff(&graph);
+                (graph, diff)
+            }
+        };
+
+        store.save_project(&project)?;
+        let saved_path = store.save_graph(&graph)?;
+
+        Ok(GraphUpdateResponse {
+            graph,
+            diff,
+            saved_path,
+        })
+    }
+
+    /// Incrementally update a graph built by
+    /// [`Self::build_source_graph_with_options`], returning the new graph
+    /// and what changed in it.
+    ///
+    /// `project` must already reflect the changes (added files present,
+    /// deleted ones gone). Added and modified files are re-read, along with
+    /// the unchanged files whose references may now resolve differently:
+    /// those referencing a deleted file or a neighbour of an added one, and
+    /// those with unresolved references an added file could satisfy. Every
+    /// other node keeps its ID and edges. Whether symbols are emitted
+    /// follows the previous graph.
+    ///
+    /// Changes that can affect any file fall back to a full rebuild: an
+    /// edited manifest, or, with symbols, a change in which symbols a file
+    /// defines (symbol references resolve by name across the workspace).
+    pub fn update_source_graph(
+        &self,
+        project: &Project,
+        previous: SourceCodeGraph,
+        changes: &GraphChangeSet,
+    ) -> OpsResult<(SourceCodeGraph, GraphDiff)> {
+        let options = GraphBuildOptions {
+            symbols: previous.metadata.contains_key("symbols"),
+        };
+        let manifests = CachedManifests::new(&self.manifests, project);
+        let touched = || {
+            changes
+                .added
+                .iter()
+                .chain(&changes.modified)
+                .chain(&changes.deleted)
+        };
+        if touched().any(|path| resolve::is_manifest(path) || packages::is_manifest(path)) {
+            return self.rebuild_source_graph(project, &previous, options);
+        }
+
+        let sources: HashMap<&Path, (&Repository, &Source)> = project
+            .repositories
+            .iter()
+            .flat_map(|repo| {
+                repo.sources
+                    .iter()
+                    .map(move |source| (source.path.as_path(), (repo, source)))
+            })
+            .collect();
+
+        // Deleted files, and changed files the project no longer includes
+        let removed: Vec<&PathBuf> = touched()
+            .filter(|path| !sources.contains_key(path.as_path()))
+            .collect();
+
+        // Changed files, then unchanged ones the change may affect
+        let importers = affected_importers(&previous, changes);
+        let mut seen = HashSet::new();
+        let changed: Vec<(&Repository, &Source)> = changes
+            .added
+            .iter()
+            .chain(&changes.modified)
+            .chain(&importers)
+            .filter_map(|path| sources.get(path.as_path()).copied())
+            .filter(|(_, source)| seen.insert(&source.path))
+            .collect();
+
+        let analyses: Vec<Option<SourceAnalysis>> = changed
+            .par_iter()
+            .map(|(_, source)| {
+                self.read_source(source)
+                    .map(|content| analyze_source(&source.path, &content, options.symbols))
+            })
+            .collect();
+
+        if options.symbols && definitions_changed(&previous, &removed, &changed, &analyses) {
+            return self.rebuild_source_graph(project, &previous, options);
+        }
+
+        // Unchanged Java/Kotlin files contribute their package from metadata
+        let changed_paths: HashSet<&Path> = changed.iter().map(|(_, s)| s.path.as_path()).collect();
+        let mut resolver =
+            ReferenceResolver::with_manifests(sources.keys().map(|p| p.to_path_buf()), &manifests);
+        for node in &previous.nodes {
+            let (Some(path), Some(package)) =
+                (node.metadata.get("path"), node.metadata.get("package"))
+            else {
+                continue;
+            };
+            let path = Path::new(path);
+            if sources.contains_key(path) && !changed_paths.contains(path) {
+                resolver.add_package(path, package);
+            }
+        }
+
+        let region = Region::capture(
+            &previous,
+            removed
+                .iter()
+                .map(|p| p.as_path())
+                .chain(changed_paths.iter().copied()),
+            removed.iter().map(|p| p.as_path()),
+        );
+        let mut builder = SourceCodeGraphBuilder::from_graph(previous);
+
+        for path in &removed {
+            if builder.remove_file(path) {
+                if let Some(parent) = path.parent() {
+                    builder.prune_empty_directories(parent);
                 }
             }
         }
+        for (repo, source) in &changed {
+            if builder.get_node_id(&source.path).is_none() {
+                add_source_node(&mut builder, repo, source);
+            }
+        }
 
+        let mut references = Vec::new();
+        let mut symbol_references = Vec::new();
+        for ((_, source), analysis) in changed.iter().zip(analyses) {
+            let definitions = analysis
+                .as_ref()
+                .and_then(|a| a.symbols.as_ref())
+                .map(|s| s.definitions.as_slice())
+                .unwrap_or_default();
+            builder.clear_file_references(&source.path, definitions);
+            if let Some(node_id) = builder.get_node_id(&source.path) {
+                builder.remove_node_metadata(node_id, "has_tests");
+                builder.remove_node_metadata(node_id, "package");
+                builder.remove_node_metadata(node_id, UNRESOLVED_METADATA);
+            }
+
+            let Some(analysis) = analysis else {
+                continue;
+            };
+            let detected = record_analysis(
+                &mut builder,
+                &mut resolver,
+                &source.path,
+                analysis,
+                &mut references,
+            );
+            if let Some(detected) = detected {
+                builder.add_symbols(&source.path, &detected);
+                symbol_references.push((source.path.clone(), detected.references));
+            }
+        }
+
+        link_references(&mut builder, &resolver, &references);
+        for (path, references) in &symbol_references {
+            builder.add_symbol_references(path, references);
+        }
+
+        let mut graph = builder.build();
+        if project.repositories.len() > 1 {
+            packages::annotate(&mut graph, &project.repositories, &manifests);
+        }
+        let diff = region.diff(&graph);
         info!(
-            nodes = builder.node_count(),
-            edges = builder.edge_count(),
-            "Built SourceCodeGraph"
+            changed = changed.len(),
+            added_nodes = diff.added_nodes.len(),
+            removed_nodes = diff.removed_nodes.len(),
+            added_edges = diff.added_edges.len(),
+            removed_edges = diff.removed_edges.len(),
+            "Updated SourceCodeGraph"
         );
 
-        Ok(builder.build())
+        Ok((graph, diff))
+    }
+
+    /// Rebuild a graph from scratch with the options it was built with.
+    fn rebuild_source_graph(
+        &self,
+        project: &Project,
+        previous: &SourceCodeGraph,
+        options: GraphBuildOptions,
+    ) -> OpsResult<(SourceCodeGraph, GraphDiff)> {
+        let graph = self.build_source_graph_with_options(project, options)?;
+        let diff = previous.diff(&graph);
+        Ok((graph, diff))
+    }
+
+    /// Read a text source within the configured size limit.
+    fn read_source(&self, source: &Source) -> Option<String> {
+        let max_size = self.config.max_content_size_kb * 1024;
+        if !source.is_text() || source.size.map(|s| s > max_size).unwrap_or(true) {
+            return None;
+        }
+        match &source.content {
+            Some(c) => Some(c.clone()),
+            None => std::fs::read_to_string(&source.path).ok(),
+        }
     }
 
     // =========================================================================
@@ -712,6 +1198,312 @@ fn has_inline_tests(content: &str, path: &Path) -> bool {
     }
 }
 
+/// Add the node of a file that was not in the graph yet, with any missing
+/// directories between it and its repository root.
+fn add_source_node(builder: &mut SourceCodeGraphBuilder, repo: &Repository, source: &Source) {
+    let mut child = source.path.as_path();
+    builder.add_file(child, &source.relative_path);
+    while let Some(dir) = child.parent() {
+        let exists = builder.get_node_id(dir).is_some();
+        builder.add_directory(dir);
+        builder.add_hierarchy_edge(dir, child);
+        if exists || dir == repo.local_path {
+            break;
+        }
+        child = dir;
+    }
+}
+
+/// What one file declares, derived from its content alone so files can be
+/// analyzed in parallel.
+struct SourceAnalysis {
+    has_tests: bool,
+    package: Option<String>,
+    references: Vec<SourceReference>,
+    symbols: Option<SourceSymbols>,
+}
+
+/// Analyze one file: inline tests, its Java/Kotlin package, the references
+/// it makes and, with `symbols`, its symbol definitions.
+fn analyze_source(path: &Path, content: &str, symbols: bool) -> SourceAnalysis {
+    SourceAnalysis {
+        has_tests: has_inline_tests(content, path),
+        package: ReferenceResolver::declared_package(path, content),
+        references: detect_references(content, path),
+        symbols: symbols.then(|| detect_symbols(content, path)),
+    }
+}
+
+/// Record an analysis on the graph and resolver. References are collected
+/// for [`link_references`]; detected symbols are handed back.
+fn record_analysis(
+    builder: &mut SourceCodeGraphBuilder,
+    resolver: &mut ReferenceResolver,
+    path: &Path,
+    analysis: SourceAnalysis,
+    references: &mut Vec<SourceReference>,
+) -> Option<SourceSymbols> {
+    if let Some(package) = &analysis.package {
+        resolver.add_package(path, package);
+    }
+    if let Some(node_id) = builder.get_node_id(path) {
+        if analysis.has_tests {
+            builder.set_node_metadata(node_id, "has_tests", "true");
+        }
+        if let Some(package) = analysis.package {
+            builder.set_node_metadata(node_id, "package", package);
+        }
+    }
+    references.extend(analysis.references);
+    analysis.symbols
+}
+
+/// File metadata listing the file stems of references that matched no
+/// project file, so graph updates can tell which files an added file may
+/// satisfy.
+const UNRESOLVED_METADATA: &str = "unresolved";
+
+/// Resolve references and add their edges.
+///
+/// Several candidate routes of one reference may hit the same file, so
+/// edges are deduplicated per (source, target, kind). References that
+/// match no project file are recorded under [`UNRESOLVED_METADATA`].
+fn link_references(
+    builder: &mut SourceCodeGraphBuilder,
+    resolver: &ReferenceResolver,
+    references: &[SourceReference],
+) {
+    let mut seen_edges = HashSet::new();
+    let mut unresolved: HashMap<NodeId, BTreeSet<&str>> = HashMap::new();
+    for reference in references {
+        let Some(source_id) = builder.get_node_id(&reference.source_path) else {
+            continue;
+        };
+        let targets: Vec<NodeId> = match resolver.resolve(reference) {
+            Resolution::File(path) => builder.get_node_id(&path).into_iter().collect(),
+            Resolution::Package(paths) => paths
+                .iter()
+                .filter_map(|path| builder.get_node_id(path))
+                .collect(),
+            Resolution::External => continue,
+            Resolution::Unhandled => builder
+                .find_node_by_path_suffix(&reference.target_route)
+                .into_iter()
+                .collect(),
+        };
+        if targets.is_empty() {
+            if let Some(stem) = reference.target_route.file_stem().and_then(OsStr::to_str) {
+                unresolved.entry(source_id).or_default().insert(stem);
+            }
+        }
+        for target_id in targets {
+            if source_id != target_id && seen_edges.insert((source_id, target_id, reference.kind)) {
+                builder.add_edge(source_id, target_id, reference.kind);
+            }
+        }
+    }
+    for (node_id, stems) in unresolved {
+        let stems: Vec<&str> = stems.into_iter().collect();
+        builder.set_node_metadata(node_id, UNRESOLVED_METADATA, stems.join(","));
+    }
+}
+
+/// Unchanged files whose references may resolve differently after
+/// `changes`: those referencing a deleted file, a changed Java/Kotlin file
+/// (its package may have moved) or a neighbour or namesake of an added
+/// file, and those with unresolved references an added file could satisfy.
+fn affected_importers(graph: &SourceCodeGraph, changes: &GraphChangeSet) -> BTreeSet<PathBuf> {
+    let added_dirs: HashSet<&Path> = changes.added.iter().filter_map(|p| p.parent()).collect();
+    let added_names: HashSet<&OsStr> = changes.added.iter().filter_map(|p| p.file_name()).collect();
+    // Unresolved references are recorded by file stem; `./utils` may also
+    // be satisfied by `utils/index.ts`
+    let added_stems: HashSet<&str> = changes
+        .added
+        .iter()
+        .flat_map(|p| [p.file_stem(), p.parent().and_then(Path::file_name)])
+        .flatten()
+        .filter_map(OsStr::to_str)
+        .collect();
+
+    let mut files: HashMap<NodeId, &Path> = HashMap::new();
+    let mut targets: HashSet<NodeId> = HashSet::new();
+    let mut importers = BTreeSet::new();
+    for node in &graph.nodes {
+        if matches!(
+            node.kind,
+            GraphNodeKind::Directory | GraphNodeKind::Symbol | GraphNodeKind::Repository
+        ) {
+            continue;
+        }
+        let Some(path) = node.metadata.get("path").map(Path::new) else {
+            continue;
+        };
+        files.insert(node.id, path);
+
+        let jvm = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("java" | "kt" | "kts")
+        );
+        if changes.deleted.iter().any(|p| p == path)
+            || (jvm && changes.modified.iter().any(|p| p == path))
+            || path.parent().is_some_and(|dir| added_dirs.contains(dir))
+            || path.file_name().is_some_and(|n| added_names.contains(n))
+        {
+            targets.insert(node.id);
+        }
+        let unresolved = node.metadata.get(UNRESOLVED_METADATA);
+        if unresolved.is_some_and(|stems| stems.split(',').any(|s| added_stems.contains(s))) {
+            importers.insert(path.to_path_buf());
+        }
+    }
+
+    let contains = ReferenceKind::Contains.to_string();
+    for edge in &graph.edges {
+        if edge.relationship != contains && targets.contains(&edge.to) {
+            if let Some(path) = files.get(&edge.from) {
+                importers.insert(path.to_path_buf());
+            }
+        }
+    }
+    importers
+}
+
+/// Whether the re-read and removed files define other symbols than they
+/// did in `graph`.
+fn definitions_changed(
+    graph: &SourceCodeGraph,
+    removed: &[&PathBuf],
+    changed: &[(&Repository, &Source)],
+    analyses: &[Option<SourceAnalysis>],
+) -> bool {
+    let mut before: HashMap<&Path, BTreeSet<&str>> = HashMap::new();
+    for node in &graph.nodes {
+        if node.kind != GraphNodeKind::Symbol {
+            continue;
+        }
+        if let Some(file) = node.metadata.get("file") {
+            before
+                .entry(Path::new(file))
+                .or_default()
+                .insert(&node.name);
+        }
+    }
+
+    removed
+        .iter()
+        .any(|path| before.contains_key(path.as_path()))
+        || changed.iter().zip(analyses).any(|((_, source), analysis)| {
+            let after: BTreeSet<&str> = analysis
+                .iter()
+                .filter_map(|a| a.symbols.as_ref())
+                .flat_map(|s| &s.definitions)
+                .map(|d| d.qualified_name.as_str())
+                .collect();
+            before.remove(source.path.as_path()).unwrap_or_default() != after
+        })
+}
+
+/// The part of a graph an incremental update can change: the nodes of
+/// re-read and removed files with their symbols, the directories above
+/// removed files, and every edge touching them. Diffing only this region
+/// spares comparing both graphs whole.
+struct Region {
+    /// Every node ID of the graph, to tell added nodes apart.
+    all: HashSet<NodeId>,
+    ids: HashSet<NodeId>,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+impl Region {
+    fn capture<'a>(
+        graph: &SourceCodeGraph,
+        files: impl IntoIterator<Item = &'a Path>,
+        removed: impl IntoIterator<Item = &'a Path>,
+    ) -> Self {
+        let files: HashSet<&Path> = files.into_iter().collect();
+        let dirs: HashSet<&Path> = removed
+            .into_iter()
+            .flat_map(|p| p.ancestors().skip(1))
+            .collect();
+        let in_region = |node: &GraphNode| {
+            let key = if node.kind == GraphNodeKind::Symbol {
+                "file"
+            } else {
+                "path"
+            };
+            node.metadata.get(key).is_some_and(|path| {
+                let path = Path::new(path);
+                match node.kind {
+                    GraphNodeKind::Directory => dirs.contains(path),
+                    _ => files.contains(path),
+                }
+            })
+        };
+
+        let nodes: Vec<GraphNode> = graph
+            .nodes
+            .iter()
+            .filter(|n| in_region(n))
+            .cloned()
+            .collect();
+        let ids: HashSet<NodeId> = nodes.iter().map(|n| n.id).collect();
+        let edges = graph
+            .edges
+            .iter()
+            .filter(|e| ids.contains(&e.from) || ids.contains(&e.to))
+            .cloned()
+            .collect();
+        Self {
+            all: graph.nodes.iter().map(|n| n.id).collect(),
+            ids,
+            nodes,
+            edges,
+        }
+    }
+
+    /// What changed in the region, and what was added around it, in `graph`.
+    fn diff(self, graph: &SourceCodeGraph) -> GraphDiff {
+        let current: HashSet<NodeId> = graph.nodes.iter().map(|n| n.id).collect();
+        let added_nodes: Vec<GraphNode> = graph
+            .nodes
+            .iter()
+            .filter(|n| !self.all.contains(&n.id))
+            .cloned()
+            .collect();
+        let mut ids = self.ids;
+        ids.extend(added_nodes.iter().map(|n| n.id));
+
+        let key = |e: &GraphEdge| (e.from, e.to, e.relationship.clone());
+        let edges: Vec<&GraphEdge> = graph
+            .edges
+            .iter()
+            .filter(|e| ids.contains(&e.from) || ids.contains(&e.to))
+            .collect();
+        let before: HashSet<_> = self.edges.iter().map(key).collect();
+        let after: HashSet<_> = edges.iter().map(|e| key(e)).collect();
+
+        GraphDiff {
+            added_nodes,
+            removed_nodes: self
+                .nodes
+                .into_iter()
+                .filter(|n| !current.contains(&n.id))
+                .collect(),
+            added_edges: edges
+                .into_iter()
+                .filter(|e| !before.contains(&key(e)))
+                .cloned()
+                .collect(),
+            removed_edges: self
+                .edges
+                .into_iter()
+                .filter(|e| !after.contains(&key(e)))
+                .collect(),
+        }
+    }
+}
+
 fn find_workspace_root(repositories: &[Repository]) -> Option<PathBuf> {
     if repositories.is_empty() {
         return None;
@@ -787,3 +1579,304 @@ fn get_single_repo_changes(path: &Path) -> GitChangeSnapshot {
         Err(_) => GitChangeSnapshot::default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::write;
+
+    /// Edges as `(from path, to path, relationship)`, comparable across builds.
+    fn edge_paths(graph: &SourceCodeGraph) -> Vec<(String, String, String)> {
+        let path = |id: NodeId| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.id == id)
+                .map(|n| n.metadata["path"].clone())
+                .unwrap()
+        };
+        let mut edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (path(e.from), path(e.to), e.relationship.clone()))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// A single-repository project over `paths`.
+    fn local_project(root: &Path, paths: &[&PathBuf]) -> Project {
+        let root = root.to_path_buf();
+        let mut repo = Repository::new("app", root.to_string_lossy(), root.clone());
+        for path in paths {
+            repo.sources
+                .push(Source::from_path(path.to_path_buf(), &root).unwrap());
+        }
+        let mut project = Project::local(root);
+        project.repositories.push(repo);
+        project
+    }
+
+    /// Apply `changes`, update `previous` for them and check the result,
+    /// and its diff, against a full rebuild.
+    fn assert_update_matches_rebuild(
+        ctx: &OpsContext,
+        project: &mut Project,
+        previous: SourceCodeGraph,
+        changes: &GraphChangeSet,
+    ) -> SourceCodeGraph {
+        project.apply_changes(changes).unwrap();
+        let (graph, diff) = ctx
+            .update_source_graph(project, previous.clone(), changes)
+            .unwrap();
+        let rebuilt = ctx.build_source_graph(project).unwrap();
+
+        let nodes = |graph: &SourceCodeGraph| {
+            let mut nodes: Vec<_> = graph
+                .nodes
+                .iter()
+                .map(|n| {
+                    let mut metadata: Vec<_> = n.metadata.iter().collect();
+                    metadata.sort();
+                    (n.id.0, format!("{:?}", metadata))
+                })
+                .collect();
+            nodes.sort();
+            nodes
+        };
+        assert_eq!(nodes(&graph), nodes(&rebuilt));
+        assert_eq!(edge_paths(&graph), edge_paths(&rebuilt));
+
+        let ids = |nodes: &[GraphNode]| {
+            let mut ids: Vec<_> = nodes.iter().map(|n| n.id.0).collect();
+            ids.sort();
+            ids
+        };
+        let edges = |edges: &[GraphEdge]| {
+            let mut edges: Vec<_> = edges
+                .iter()
+                .map(|e| (e.from.0, e.to.0, e.relationship.clone()))
+                .collect();
+            edges.sort();
+            edges
+        };
+        let expected = previous.diff(&rebuilt);
+        assert_eq!(ids(&diff.added_nodes), ids(&expected.added_nodes));
+        assert_eq!(ids(&diff.removed_nodes), ids(&expected.removed_nodes));
+        assert_eq!(edges(&diff.added_edges), edges(&expected.added_edges));
+        assert_eq!(edges(&diff.removed_edges), edges(&expected.removed_edges));
+        graph
+    }
+
+    #[test]
+    fn test_update_source_graph_matches_full_rebuild() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let lib = write(&root, "src/lib.rs", "mod util;\nmod store;\n");
+        let util = write(&root, "src/util.rs", "pub fn helper() {}\n");
+        let store = write(&root, "src/store.rs", "use crate::util::helper;\n");
+        let mut project = local_project(&root, &[&lib, &util, &store]);
+
+        let ctx = OpsContext::new(Config::default());
+        let previous = ctx.build_source_graph(&project).unwrap();
+        let util_id = previous
+            .nodes
+            .iter()
+            .find(|n| n.metadata.get("path") == Some(&util.to_string_lossy().to_string()))
+            .unwrap()
+            .id;
+
+        std::fs::remove_file(&store).unwrap();
+        write(&root, "src/lib.rs", "mod util;\nmod cache;\n");
+        let cache = write(&root, "src/cache/mod.rs", "use crate::util::helper;\n");
+        let changes = GraphChangeSet {
+            added: vec![cache.clone()],
+            modified: vec![lib.clone()],
+            deleted: vec![store.clone()],
+        };
+        project.apply_changes(&changes).unwrap();
+
+        let (graph, diff) = ctx
+            .update_source_graph(&project, previous, &changes)
+            .unwrap();
+        let rebuilt = ctx.build_source_graph(&project).unwrap();
+
+        assert!(graph.nodes.iter().any(|n| n.id == util_id));
+        assert_eq!(graph.node_count(), rebuilt.node_count());
+        assert_eq!(edge_paths(&graph), edge_paths(&rebuilt));
+
+        let paths = |nodes: &[GraphNode]| {
+            let mut paths: Vec<_> = nodes.iter().map(|n| n.metadata["path"].clone()).collect();
+            paths.sort();
+            paths
+        };
+        let cache_dir = cache.parent().unwrap();
+        assert_eq!(
+            paths(&diff.added_nodes),
+            vec![
+                cache_dir.to_string_lossy().to_string(),
+                cache.to_string_lossy().to_string()
+            ]
+        );
+        assert_eq!(
+            paths(&diff.removed_nodes),
+            vec![store.to_string_lossy().to_string()]
+        );
+    }
+
+    #[test]
+    fn test_update_source_graph_relinks_unchanged_importers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let manifest = write(&root, "Cargo.toml", "[package]\nname = \"app\"\n");
+        let lib = write(&root, "src/lib.rs", "pub mod util;\npub mod store;\n");
+        let store = write(&root, "src/store.rs", "use crate::util::helper;\n");
+        let test = write(&root, "tests/it.rs", "use app::util::helper;\n");
+        let mut project = local_project(&root, &[&manifest, &lib, &store, &test]);
+        let ctx = OpsContext::new(Config::default());
+        let graph = ctx.build_source_graph(&project).unwrap();
+
+        let edge = |graph: &SourceCodeGraph, from: &Path, to: &Path| {
+            let (from, to) = (from.to_string_lossy(), to.to_string_lossy());
+            edge_paths(graph)
+                .iter()
+                .any(|(f, t, _)| *f == from && *t == to)
+        };
+
+        // Unchanged files referencing the added file link to it
+        let util = write(&root, "src/util.rs", "pub fn helper() {}\n");
+        let added = GraphChangeSet {
+            added: vec![util.clone()],
+            ..Default::default()
+        };
+        let graph = assert_update_matches_rebuild(&ctx, &mut project, graph, &added);
+        assert!(edge(&graph, &store, &util));
+        assert!(edge(&graph, &test, &util));
+
+        // ...and lose the link when it goes away again
+        std::fs::remove_file(&util).unwrap();
+        let deleted = GraphChangeSet {
+            deleted: vec![util.clone()],
+            ..Default::default()
+        };
+        let graph = assert_update_matches_rebuild(&ctx, &mut project, graph, &deleted);
+        assert!(!edge(&graph, &store, &util));
+
+        // A renamed crate changes how the integration test resolves
+        write(&root, "src/util.rs", "pub fn helper() {}\n");
+        let graph = assert_update_matches_rebuild(&ctx, &mut project, graph, &added);
+        assert!(edge(&graph, &test, &util));
+        write(&root, "Cargo.toml", "[package]\nname = \"renamed\"\n");
+        let edited = GraphChangeSet {
+            modified: vec![manifest.clone()],
+            ..Default::default()
+        };
+        let graph = assert_update_matches_rebuild(&ctx, &mut project, graph, &edited);
+        assert!(!edge(&graph, &test, &util));
+    }
+
+    #[tokio::test]
+    async fn test_graph_cache_matches_symbols_request() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("lib.rs"), "pub fn helper() {}\n").unwrap();
+
+        let ctx = OpsContext::new(Config::default());
+        ctx.sync(SyncRequest::local(&root)).await.unwrap();
+        let has_symbols =
+            |graph: &SourceCodeGraph| graph.nodes.iter().any(|n| n.kind == GraphNodeKind::Symbol);
+
+        let symbols = ctx
+            .graph(GraphRequest::new(&root).with_symbols())
+            .await
+            .unwrap();
+        assert!(has_symbols(&symbols.graph));
+
+        let plain = ctx.graph(GraphRequest::new(&root)).await.unwrap();
+        assert!(!plain.from_cache);
+        assert!(!has_symbols(&plain.graph));
+
+        let cached = ctx.graph(GraphRequest::new(&root)).await.unwrap();
+        assert!(cached.from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_graph_at_revision_reads_git_objects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let git = git2::Repository::init(&root).unwrap();
+        let commit = |message: &str| {
+            let mut index = git.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("dev", "dev@example.com").unwrap();
+            let parent = git.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            git.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+            .to_string()
+        };
+
+        write(&root, "Cargo.toml", "[package]\nname = \"app\"\n");
+        write(&root, "src/lib.rs", "mod util;\n");
+        write(&root, "src/util.rs", "pub fn helper() {}\n");
+        let first = commit("first");
+        write(&root, "src/lib.rs", "mod util;\nmod store;\n");
+        write(&root, "src/store.rs", "use crate::util::helper;\n");
+        commit("second");
+        // Uncommitted changes must not leak into revision graphs
+        std::fs::remove_file(root.join("src/util.rs")).unwrap();
+
+        let ctx = OpsContext::new(Config::default());
+        let has_file = |graph: &SourceCodeGraph, rel: &str| {
+            let path = root.join(rel).to_string_lossy().to_string();
+            graph
+                .nodes
+                .iter()
+                .any(|n| n.metadata.get("path") == Some(&path))
+        };
+
+        let response = ctx
+            .graph(GraphRequest::new(&root).at_rev("HEAD~1"))
+            .await
+            .unwrap();
+        assert!(!response.from_cache);
+        assert_eq!(response.graph.metadata["commit"], first);
+        assert!(has_file(&response.graph, "src/util.rs"));
+        assert!(!has_file(&response.graph, "src/store.rs"));
+
+        let cached = ctx
+            .graph(GraphRequest::new(&root).at_rev(&first))
+            .await
+            .unwrap();
+        assert!(cached.from_cache);
+
+        let head = ctx
+            .graph(GraphRequest::new(&root).at_rev("HEAD"))
+            .await
+            .unwrap();
+        let store = root.join("src/store.rs").to_string_lossy().to_string();
+        let util = root.join("src/util.rs").to_string_lossy().to_string();
+        assert!(edge_paths(&head.graph)
+            .iter()
+            .any(|(from, to, rel)| *from == store && *to == util && rel != "contains"));
+        assert!(!root.join("src/util.rs").exists());
+
+        let diff = ctx
+            .graph_diff(GraphDiffRequest::new(&root, "HEAD~1").to("HEAD"))
+            .await
+            .unwrap();
+        assert_eq!(diff.diff.added_files, vec!["src/store.rs".to_string()]);
+        assert!(diff.diff.removed_files.is_empty());
+    }
+}
@@ -49,6 +49,21 @@ pub enum OpsError {
     #[error("Git error: {0}")]
     Git(#[from] git2::Error),
 
+    /// SQLite store error.
+    #[error("Store database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    /// No snapshot with the given ID.
+    #[error("Snapshot not found: {id}")]
+    SnapshotNotFound { id: String },
+
+    /// A snapshot reference matches several snapshots.
+    #[error("Snapshot reference '{reference}' is ambiguous: {}", matches.join(", "))]
+    AmbiguousSnapshot {
+        reference: String,
+        matches: Vec<String>,
+    },
+
     /// Path resolution error.
     #[error("Failed to resolve path {path}: {message}")]
     PathResolution { path: PathBuf, message: String },
@@ -33,20 +33,36 @@ mod architect;
 mod config;
 mod context;
 mod error;
+mod packages;
 mod project;
 mod requests;
+mod resolve;
 mod responses;
+mod revision;
 mod scan;
 mod store;
 mod workspace;
 
 // Re-export public API
-pub use architect::{ArchitectFactory, FlatArchitect, GraphArchitect, LatticeArchitect};
+pub use architect::{
+    ArchitectFactory, FlatArchitect, GraphArchitect, LatticeArchitect, ModularArchitect,
+};
 pub use config::Config;
 pub use context::OpsContext;
 pub use error::{OpsError, OpsResult};
+pub use packages::{
+    cross_repo_dependencies, CrossRepoDependency, DeclaredDependency, DependencyKind, Ecosystem,
+    Package, RepositoryPackages, DEPENDS_ON,
+};
 pub use project::{Project, ProjectSource, Repository, Source};
 pub use requests::*;
 pub use responses::*;
-pub use store::{has_store, Manifest, Store, StoreStats};
+pub use scan::{
+    scan_directory, scan_directory_with_options, stream_directory, Exclusion, ExclusionReason,
+    ScanEntry, ScanOptions,
+};
+pub use store::{
+    has_store, GraphSnapshotInfo, JsonBackend, Manifest, SqliteBackend, StorageBackend, Store,
+    StoreBackendKind, StoreStats, STORE_BACKEND_ENV,
+};
 pub use workspace::{SyncSource, WorkspaceInfo, WorkspaceKind};
@@ -0,0 +1,766 @@
+//! Cross-repository dependencies declared in package manifests.
+//!
+//! In a multi-repo workspace, file references only resolve inside the
+//! combined tree. Repositories that consume each other as published
+//! packages (Cargo crates, npm packages, Python distributions, Go modules)
+//! are connected here instead, at manifest level: each repository's
+//! manifests name the packages it publishes and the ones it depends on, and
+//! a dependency on a package published by a sibling repository becomes a
+//! [`CrossRepoDependency`] carrying its version requirement.
+//!
+//! [`annotate`] adds the result to a graph as [`GraphNodeKind::Repository`]
+//! nodes with `depends_on` edges between them.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use vibe_graph_core::structure::MANIFEST_FILES;
+use vibe_graph_core::{EdgeId, GraphEdge, GraphNode, GraphNodeKind, NodeId, SourceCodeGraph};
+
+use crate::project::Repository;
+use crate::resolve::{
+    go_mod_directives, normalize_crate_name, read_manifest, Disk, ManifestSource,
+};
+
+/// Relationship of the edges between repository nodes.
+pub const DEPENDS_ON: &str = "depends_on";
+
+/// Whether `path` is a package manifest: one [`annotate`] reads, or one
+/// marking a component root (see [`MANIFEST_FILES`]).
+pub(crate) fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| MANIFEST_FILES.contains(&name))
+}
+
+/// Package ecosystem a manifest belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    /// `Cargo.toml`
+    Cargo,
+    /// `package.json`
+    Npm,
+    /// `pyproject.toml` (PEP 621 or Poetry)
+    Python,
+    /// `go.mod`
+    Go,
+}
+
+impl Ecosystem {
+    /// The ecosystem whose manifest has this file name, among the
+    /// [`MANIFEST_FILES`] of the ecosystems read here.
+    fn of_manifest(file_name: &str) -> Option<Self> {
+        match file_name {
+            "Cargo.toml" => Some(Ecosystem::Cargo),
+            "package.json" => Some(Ecosystem::Npm),
+            "pyproject.toml" => Some(Ecosystem::Python),
+            "go.mod" => Some(Ecosystem::Go),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Python => "python",
+            Ecosystem::Go => "go",
+        }
+    }
+
+    /// Name under which registries consider two packages the same: crates.io
+    /// folds `-`/`_` and case, PyPI normalizes as in PEP 503.
+    fn normalize(&self, name: &str) -> String {
+        match self {
+            Ecosystem::Cargo => normalize_crate_name(&name.to_ascii_lowercase()),
+            Ecosystem::Python => {
+                let mut out = String::with_capacity(name.len());
+                for c in name.chars() {
+                    if matches!(c, '-' | '_' | '.') {
+                        if !out.ends_with('-') {
+                            out.push('-');
+                        }
+                    } else {
+                        out.push(c.to_ascii_lowercase());
+                    }
+                }
+                out
+            }
+            Ecosystem::Npm | Ecosystem::Go => name.to_string(),
+        }
+    }
+}
+
+/// Which section of a manifest declares a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// Needed at runtime.
+    Normal,
+    /// Development or test only.
+    Dev,
+    /// Build scripts only.
+    Build,
+    /// Behind a feature or extra.
+    Optional,
+}
+
+impl DependencyKind {
+    /// Lowercase name, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Dev => "dev",
+            DependencyKind::Build => "build",
+            DependencyKind::Optional => "optional",
+        }
+    }
+}
+
+/// A dependency as declared in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    /// Package depended upon.
+    pub name: String,
+    /// Version requirement (`^1.2`, `>=2.0`, `v1.4.0`), when one is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirement: Option<String>,
+    /// Manifest section the dependency comes from.
+    pub kind: DependencyKind,
+}
+
+/// A package published by a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Package {
+    /// Package, distribution or module path.
+    pub name: String,
+    /// Declared version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Ecosystem of the manifest.
+    pub ecosystem: Ecosystem,
+    /// Manifest path relative to the repository root.
+    pub manifest: String,
+    /// Dependencies the manifest declares.
+    pub dependencies: Vec<DeclaredDependency>,
+}
+
+/// The packages found in one repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryPackages {
+    /// Repository name.
+    pub repository: String,
+    /// Packages, by manifest path.
+    pub packages: Vec<Package>,
+}
+
+impl RepositoryPackages {
+    /// Read the manifests among a repository's sources from disk.
+    pub fn discover(repo: &Repository) -> Self {
+        Self::discover_with(repo, &Disk)
+    }
+
+    /// Read the manifests among a repository's sources from `manifests`.
+    pub(crate) fn discover_with(repo: &Repository, manifests: &dyn ManifestSource) -> Self {
+        let mut files: Vec<(&Path, &str, Ecosystem)> = repo
+            .sources
+            .iter()
+            .filter(|s| !s.relative_path.split('/').any(|c| c == "node_modules"))
+            .filter_map(|s| {
+                let name = s.path.file_name()?.to_str()?;
+                Some((
+                    s.path.as_path(),
+                    s.relative_path.as_str(),
+                    Ecosystem::of_manifest(name)?,
+                ))
+            })
+            .collect();
+        files.sort_by_key(|&(_, relative, _)| relative);
+
+        // Cargo workspace manifests provide inherited versions and dependencies
+        let cargo: Vec<(&Path, toml::Table)> = files
+            .iter()
+            .filter(|(_, _, ecosystem)| *ecosystem == Ecosystem::Cargo)
+            .filter_map(|&(path, _, _)| Some((path, read_manifest(path, manifests)?)))
+            .collect();
+        let workspaces: Vec<(&Path, &toml::Table)> = cargo
+            .iter()
+            .filter_map(|(path, table)| Some((path.parent()?, table.get("workspace")?.as_table()?)))
+            .collect();
+
+        let mut packages = Vec::new();
+        for (path, relative, ecosystem) in files {
+            let package = match ecosystem {
+                Ecosystem::Cargo => {
+                    cargo
+                        .iter()
+                        .find(|(p, _)| *p == path)
+                        .and_then(|(_, table)| {
+                            let dir = path.parent().unwrap_or(Path::new(""));
+                            let workspace = workspaces
+                                .iter()
+                                .filter(|(root, _)| dir.starts_with(root))
+                                .max_by_key(|(root, _)| root.as_os_str().len())
+                                .map(|&(_, table)| table);
+                            cargo_package(table, workspace)
+                        })
+                }
+                Ecosystem::Npm => manifests.read(path).and_then(|c| npm_package(path, &c)),
+                Ecosystem::Python => {
+                    read_manifest(path, manifests).and_then(|t| python_package(&t))
+                }
+                Ecosystem::Go => manifests.read(path).and_then(|c| go_package(&c)),
+            };
+            if let Some((name, version, dependencies)) = package {
+                packages.push(Package {
+                    name,
+                    version,
+                    ecosystem,
+                    manifest: relative.to_string(),
+                    dependencies,
+                });
+            }
+        }
+
+        debug!(
+            repository = %repo.name,
+            packages = packages.len(),
+            "Discovered repository packages"
+        );
+        Self {
+            repository: repo.name.clone(),
+            packages,
+        }
+    }
+}
+
+/// A repository depending on a package published by another repository.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrossRepoDependency {
+    /// Depending repository.
+    pub from: String,
+    /// Repository publishing the package.
+    pub to: String,
+    /// Package depended upon.
+    pub package: String,
+    /// Ecosystem of the package.
+    pub ecosystem: Ecosystem,
+    /// Version requirement, when one is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirement: Option<String>,
+    /// Manifest section the dependency comes from.
+    pub kind: DependencyKind,
+    /// Declaring manifest, relative to the depending repository.
+    pub manifest: String,
+}
+
+/// Match every declared dependency against the packages the other
+/// repositories publish. A package published by several repositories (a
+/// fork, a vendored copy) yields a dependency on each.
+pub fn cross_repo_dependencies(repositories: &[RepositoryPackages]) -> Vec<CrossRepoDependency> {
+    let mut publishers: HashMap<(Ecosystem, String), Vec<(&str, &str)>> = HashMap::new();
+    for repo in repositories {
+        for package in &repo.packages {
+            publishers
+                .entry((
+                    package.ecosystem,
+                    package.ecosystem.normalize(&package.name),
+                ))
+                .or_default()
+                .push((repo.repository.as_str(), package.name.as_str()));
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    for repo in repositories {
+        for package in &repo.packages {
+            for dependency in &package.dependencies {
+                let key = (
+                    package.ecosystem,
+                    package.ecosystem.normalize(&dependency.name),
+                );
+                for &(to, published) in publishers.get(&key).into_iter().flatten() {
+                    if to == repo.repository {
+                        continue;
+                    }
+                    dependencies.push(CrossRepoDependency {
+                        from: repo.repository.clone(),
+                        to: to.to_string(),
+                        package: published.to_string(),
+                        ecosystem: package.ecosystem,
+                        requirement: dependency.requirement.clone(),
+                        kind: dependency.kind,
+                        manifest: package.manifest.clone(),
+                    });
+                }
+            }
+        }
+    }
+    dependencies.sort();
+    dependencies.dedup();
+    dependencies
+}
+
+/// Add a [`GraphNodeKind::Repository`] node per repository and one
+/// `depends_on` edge per pair of repositories with cross-repo dependencies.
+///
+/// Repository nodes carry `url`, `directory` (relative to the graph root)
+/// and `packages`; edges carry `packages`, one `<package> <requirement>`
+/// line per dependency (`*` without a requirement, ` (dev)` and the like
+/// for non-runtime ones). Repository nodes from an earlier call are
+/// replaced.
+pub(crate) fn annotate(
+    graph: &mut SourceCodeGraph,
+    repositories: &[Repository],
+    manifests: &dyn ManifestSource,
+) {
+    let previous: HashSet<NodeId> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == GraphNodeKind::Repository)
+        .map(|node| node.id)
+        .collect();
+    graph.nodes.retain(|node| !previous.contains(&node.id));
+    graph
+        .edges
+        .retain(|edge| !previous.contains(&edge.from) && !previous.contains(&edge.to));
+
+    let found: Vec<RepositoryPackages> = repositories
+        .iter()
+        .map(|repo| RepositoryPackages::discover_with(repo, manifests))
+        .collect();
+    let root = graph.metadata.get("root").map(PathBuf::from);
+    let repository_id = |name: &str| NodeId::from_key(&format!("repository:{}", name));
+
+    for (repo, packages) in repositories.iter().zip(&found) {
+        let directory = match root.as_deref().map(|r| repo.local_path.strip_prefix(r)) {
+            Some(Ok(relative)) if relative.as_os_str().is_empty() => ".".to_string(),
+            Some(Ok(relative)) => relative.to_string_lossy().replace('\\', "/"),
+            _ => repo.local_path.to_string_lossy().to_string(),
+        };
+        let names: BTreeSet<&str> = packages.packages.iter().map(|p| p.name.as_str()).collect();
+        let mut metadata = HashMap::new();
+        metadata.insert("url".to_string(), repo.url.clone());
+        metadata.insert("directory".to_string(), directory);
+        metadata.insert(
+            "packages".to_string(),
+            names.into_iter().collect::<Vec<_>>().join("\n"),
+        );
+        graph.nodes.push(GraphNode {
+            id: repository_id(&repo.name),
+            name: repo.name.clone(),
+            kind: GraphNodeKind::Repository,
+            metadata,
+        });
+    }
+
+    let mut edges: BTreeMap<(&str, &str), BTreeSet<String>> = BTreeMap::new();
+    let dependencies = cross_repo_dependencies(&found);
+    for dependency in &dependencies {
+        let mut line = format!(
+            "{} {}",
+            dependency.package,
+            dependency.requirement.as_deref().unwrap_or("*")
+        );
+        if dependency.kind != DependencyKind::Normal {
+            line.push_str(&format!(" ({})", dependency.kind.as_str()));
+        }
+        edges
+            .entry((dependency.from.as_str(), dependency.to.as_str()))
+            .or_default()
+            .insert(line);
+    }
+
+    let first_edge = graph.edges.iter().map(|e| e.id.0 + 1).max().unwrap_or(0);
+    for (id, ((from, to), packages)) in (first_edge..).zip(edges) {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "packages".to_string(),
+            packages.into_iter().collect::<Vec<_>>().join("\n"),
+        );
+        graph.edges.push(GraphEdge {
+            id: EdgeId(id),
+            from: repository_id(from),
+            to: repository_id(to),
+            relationship: DEPENDS_ON.to_string(),
+            metadata,
+        });
+    }
+}
+
+/// Name, version and dependencies read from a manifest.
+type ParsedPackage = (String, Option<String>, Vec<DeclaredDependency>);
+
+/// A `[package]` manifest. `workspace = true` versions and dependencies
+/// come from the `[workspace]` table of the enclosing workspace.
+fn cargo_package(table: &toml::Table, workspace: Option<&toml::Table>) -> Option<ParsedPackage> {
+    let package = table.get("package")?.as_table()?;
+    let name = package.get("name")?.as_str()?.to_string();
+    let version = match package.get("version") {
+        Some(toml::Value::String(version)) => Some(version.clone()),
+        Some(toml::Value::Table(t))
+            if t.get("workspace").and_then(|w| w.as_bool()) == Some(true) =>
+        {
+            workspace
+                .and_then(|w| w.get("package")?.get("version")?.as_str())
+                .map(str::to_string)
+        }
+        _ => None,
+    };
+    let inherited = workspace
+        .and_then(|w| w.get("dependencies")?.as_table())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut sections: Vec<&toml::Table> = vec![table];
+    if let Some(targets) = table.get("target").and_then(|t| t.as_table()) {
+        sections.extend(targets.values().filter_map(|t| t.as_table()));
+    }
+    let mut dependencies = Vec::new();
+    for section in sections {
+        for (key, kind) in [
+            ("dependencies", DependencyKind::Normal),
+            ("dev-dependencies", DependencyKind::Dev),
+            ("build-dependencies", DependencyKind::Build),
+        ] {
+            let Some(deps) = section.get(key).and_then(|d| d.as_table()) else {
+                continue;
+            };
+            for (key, value) in deps {
+                let from_workspace = value.get("workspace").and_then(|w| w.as_bool()) == Some(true);
+                let declared = if from_workspace {
+                    inherited.get(key).unwrap_or(value)
+                } else {
+                    value
+                };
+                let optional = value.get("optional").and_then(|o| o.as_bool()) == Some(true);
+                let (name, requirement) = match declared {
+                    toml::Value::String(version) => (key.clone(), Some(version.clone())),
+                    value => (
+                        value
+                            .get("package")
+                            .and_then(|p| p.as_str())
+                            .unwrap_or(key)
+                            .to_string(),
+                        value
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string),
+                    ),
+                };
+                dependencies.push(DeclaredDependency {
+                    name,
+                    requirement,
+                    kind: if optional {
+                        DependencyKind::Optional
+                    } else {
+                        kind
+                    },
+                });
+            }
+        }
+    }
+    Some((name, version, dependencies))
+}
+
+fn npm_package(path: &Path, content: &str) -> Option<ParsedPackage> {
+    let json: serde_json::Value = match serde_json::from_str(content) {
+        Ok(json) => json,
+        Err(e) => {
+            debug!(path = %path.display(), error = %e, "Skipping unparsable package.json");
+            return None;
+        }
+    };
+    let name = json.get("name")?.as_str()?.to_string();
+    let version = json
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("dependencies", DependencyKind::Normal),
+        ("peerDependencies", DependencyKind::Normal),
+        ("devDependencies", DependencyKind::Dev),
+        ("optionalDependencies", DependencyKind::Optional),
+    ] {
+        let Some(deps) = json.get(key).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (name, requirement) in deps {
+            dependencies.push(DeclaredDependency {
+                name: name.clone(),
+                requirement: requirement.as_str().map(str::to_string),
+                kind,
+            });
+        }
+    }
+    Some((name, version, dependencies))
+}
+
+/// A PEP 621 `[project]` table, or a Poetry `[tool.poetry]` one.
+fn python_package(table: &toml::Table) -> Option<ParsedPackage> {
+    if let Some(project) = table.get("project").and_then(|p| p.as_table()) {
+        let name = project.get("name")?.as_str()?.to_string();
+        let version = project
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let mut dependencies: Vec<DeclaredDependency> = project
+            .get("dependencies")
+            .and_then(|d| d.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|d| pep508(d.as_str()?, DependencyKind::Normal))
+            .collect();
+        let extras = project
+            .get("optional-dependencies")
+            .and_then(|d| d.as_table());
+        for requirements in extras.into_iter().flat_map(|e| e.values()) {
+            dependencies.extend(
+                requirements
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|d| pep508(d.as_str()?, DependencyKind::Optional)),
+            );
+        }
+        return Some((name, version, dependencies));
+    }
+
+    let poetry = table.get("tool")?.get("poetry")?.as_table()?;
+    let name = poetry.get("name")?.as_str()?.to_string();
+    let version = poetry
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let mut sections = vec![
+        (poetry.get("dependencies"), DependencyKind::Normal),
+        (poetry.get("dev-dependencies"), DependencyKind::Dev),
+    ];
+    if let Some(groups) = poetry.get("group").and_then(|g| g.as_table()) {
+        sections.extend(
+            groups
+                .values()
+                .map(|group| (group.get("dependencies"), DependencyKind::Dev)),
+        );
+    }
+    let mut dependencies = Vec::new();
+    for (deps, kind) in sections {
+        let Some(deps) = deps.and_then(|d| d.as_table()) else {
+            continue;
+        };
+        for (name, value) in deps.iter().filter(|(name, _)| *name != "python") {
+            let requirement = match value {
+                toml::Value::String(version) => Some(version.clone()),
+                value => value
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+            };
+            dependencies.push(DeclaredDependency {
+                name: name.clone(),
+                requirement,
+                kind,
+            });
+        }
+    }
+    Some((name, version, dependencies))
+}
+
+/// Parse a PEP 508 requirement (`name[extra] (>=1.0); python_version < "3.11"`).
+/// Direct references (`name @ https://...`) have no version requirement.
+fn pep508(requirement: &str, kind: DependencyKind) -> Option<DeclaredDependency> {
+    let requirement = requirement.split(';').next()?.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let (name, mut rest) = requirement.split_at(end);
+    if name.is_empty() {
+        return None;
+    }
+    rest = rest.trim_start();
+    if let Some(after_extras) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+        rest = after_extras.1.trim_start();
+    }
+    let specifier = rest.trim_start_matches('(').trim_end_matches(')').trim();
+    Some(DeclaredDependency {
+        name: name.to_string(),
+        requirement: (!specifier.is_empty() && !specifier.starts_with('@'))
+            .then(|| specifier.to_string()),
+        kind,
+    })
+}
+
+/// The module path of a `go.mod` and its `require` directives.
+fn go_package(content: &str) -> Option<ParsedPackage> {
+    let mut module = None;
+    let mut dependencies = Vec::new();
+    for (verb, arguments) in go_mod_directives(content) {
+        match verb {
+            "module" => module = Some(arguments.trim_matches('"').to_string()),
+            "require" => {
+                let mut parts = arguments.split_whitespace();
+                if let Some(path) = parts.next() {
+                    dependencies.push(DeclaredDependency {
+                        name: path.trim_matches('"').to_string(),
+                        requirement: parts.next().map(str::to_string),
+                        kind: DependencyKind::Normal,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Some((module?, None, dependencies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Source;
+
+    fn repository(root: &Path, name: &str, files: &[(&str, &str)]) -> Repository {
+        let dir = root.join(name);
+        let mut repo = Repository::new(name, dir.display().to_string(), dir.clone());
+        for (relative, content) in files {
+            let path = dir.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            repo.sources.push(Source::from_path(path, &dir).unwrap());
+        }
+        repo
+    }
+
+    fn workspace(root: &Path) -> Vec<Repository> {
+        vec![
+            repository(
+                root,
+                "core",
+                &[
+                    (
+                        "Cargo.toml",
+                        "[workspace]\nmembers = [\"crates/*\"]\n[workspace.package]\nversion = \"1.4.0\"\n",
+                    ),
+                    (
+                        "crates/core-model/Cargo.toml",
+                        "[package]\nname = \"core-model\"\nversion.workspace = true\n",
+                    ),
+                    (
+                        "sdk/pyproject.toml",
+                        "[project]\nname = \"Core_SDK\"\nversion = \"0.3.0\"\n",
+                    ),
+                ],
+            ),
+            repository(
+                root,
+                "api",
+                &[
+                    (
+                        "Cargo.toml",
+                        "[package]\nname = \"api\"\n[dependencies]\ncore_model = { version = \"^1.4\", features = [\"serde\"] }\nserde = \"1\"\n",
+                    ),
+                    (
+                        "client/package.json",
+                        r#"{"name": "@org/api-client", "version": "2.0.0"}"#,
+                    ),
+                ],
+            ),
+            repository(
+                root,
+                "web",
+                &[
+                    (
+                        "package.json",
+                        r#"{"name": "web", "devDependencies": {"@org/api-client": "^2.0.0"}}"#,
+                    ),
+                    (
+                        "scripts/pyproject.toml",
+                        "[project]\nname = \"web-scripts\"\ndependencies = [\"core-sdk[cli] >=0.3; python_version >= '3.10'\"]\n",
+                    ),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_cross_repo_dependencies_match_published_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let repositories = workspace(dir.path());
+        let found: Vec<RepositoryPackages> = repositories
+            .iter()
+            .map(RepositoryPackages::discover)
+            .collect();
+
+        let core = &found[0].packages;
+        assert_eq!(core.len(), 2);
+        assert_eq!(core[0].name, "core-model");
+        assert_eq!(core[0].version.as_deref(), Some("1.4.0"));
+
+        let dependencies: Vec<String> = cross_repo_dependencies(&found)
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} → {}: {} {:?} {:?}",
+                    d.from, d.to, d.package, d.requirement, d.kind
+                )
+            })
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![
+                r#"api → core: core-model Some("^1.4") Normal"#,
+                r#"web → api: @org/api-client Some("^2.0.0") Dev"#,
+                r#"web → core: Core_SDK Some(">=0.3") Normal"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotate_adds_repository_nodes_and_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let repositories = workspace(dir.path());
+        let mut graph = SourceCodeGraph::empty();
+        graph
+            .metadata
+            .insert("root".to_string(), dir.path().display().to_string());
+
+        annotate(&mut graph, &repositories, &Disk);
+        annotate(&mut graph, &repositories, &Disk);
+
+        assert_eq!(graph.nodes.len(), 3);
+        let api = graph.nodes.iter().find(|n| n.name == "api").unwrap();
+        assert_eq!(api.kind, GraphNodeKind::Repository);
+        assert_eq!(api.metadata["directory"], "api");
+        assert_eq!(api.metadata["packages"], "@org/api-client\napi");
+
+        let names: HashMap<NodeId, &str> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id, n.name.as_str()))
+            .collect();
+        let edges: Vec<(&str, &str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| {
+                (
+                    names[&e.from],
+                    names[&e.to],
+                    e.metadata["packages"].as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("api", "core", "core-model ^1.4"),
+                ("web", "api", "@org/api-client ^2.0.0 (dev)"),
+                ("web", "core", "Core_SDK >=0.3"),
+            ]
+        );
+        assert!(graph.edges.iter().all(|e| e.relationship == DEPENDS_ON));
+    }
+}
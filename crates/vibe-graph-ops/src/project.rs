@@ -1,12 +1,16 @@
 //! Domain types for projects, repositories, and sources.
 
-use std::path::PathBuf;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
 use file_format::FileFormat;
 use humansize::{format_size, DECIMAL};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use vibe_graph_core::GraphChangeSet;
 
 use crate::error::OpsResult;
+use crate::scan::{Exclusion, ScanFilter, ScanOptions};
 
 /// A project represents a collection of repositories.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,19 +85,105 @@ impl Project {
         }
     }
 
-    /// Expand content for sources matching the filter predicate.
+    /// Expand content for sources matching the filter predicate. Files are
+    /// read in parallel.
     pub fn expand_content<F>(&mut self, filter_fn: F) -> OpsResult<()>
     where
-        F: Fn(&Source) -> bool,
+        F: Fn(&Source) -> bool + Sync,
     {
+        self.repositories
+            .par_iter_mut()
+            .flat_map(|repo| repo.sources.par_iter_mut())
+            .filter(|source| filter_fn(source))
+            .for_each(|source| {
+                source.content = std::fs::read_to_string(&source.path).ok();
+            });
+        Ok(())
+    }
+
+    /// Bring the source list in line with a set of changed files.
+    ///
+    /// Deleted files are dropped, added files join the repository that
+    /// contains them, and modified files get their size, format and hash
+    /// refreshed. Added paths the scanner would exclude, and paths outside
+    /// every repository, are ignored.
+    ///
+    /// Returns the changes that actually apply: modified files whose content
+    /// hash did not change, and ignored additions, are left out.
+    pub fn apply_changes(&mut self, changes: &GraphChangeSet) -> OpsResult<GraphChangeSet> {
+        let mut applied = GraphChangeSet {
+            deleted: changes.deleted.clone(),
+            ..Default::default()
+        };
         for repo in &mut self.repositories {
+            repo.sources
+                .retain(|source| !changes.deleted.contains(&source.path));
             for source in &mut repo.sources {
-                if filter_fn(source) {
-                    source.content = std::fs::read_to_string(&source.path).ok();
+                if changes.modified.contains(&source.path) {
+                    let refreshed = Source::from_path(source.path.clone(), &repo.local_path)?;
+                    if source.hash.is_none() || refreshed.hash != source.hash {
+                        applied.modified.push(source.path.clone());
+                    }
+                    *source = refreshed;
                 }
             }
         }
-        Ok(())
+
+        for path in &changes.added {
+            if !path.is_file() {
+                continue;
+            }
+            let repo = self
+                .repositories
+                .iter_mut()
+                .filter(|repo| path.starts_with(&repo.local_path))
+                .max_by_key(|repo| repo.local_path.components().count());
+            if let Some(repo) = repo {
+                let options = ScanOptions::default();
+                if ScanFilter::new(&repo.local_path, &options)
+                    .exclusion(path)
+                    .is_some()
+                {
+                    continue;
+                }
+                let source = Source::from_path(path.clone(), &repo.local_path)?;
+                match repo.sources.iter_mut().find(|s| &s.path == path) {
+                    // Already known: only a content change matters
+                    Some(existing) => {
+                        if existing.hash.is_none() || existing.hash != source.hash {
+                            applied.modified.push(path.clone());
+                        }
+                        *existing = source;
+                    }
+                    None => {
+                        repo.sources.push(source);
+                        applied.added.push(path.clone());
+                    }
+                }
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Digest over every source's relative path and content hash, or `None`
+    /// when some source has no hash (e.g. loaded from an older store).
+    pub fn content_hash(&self) -> Option<String> {
+        let mut entries: Vec<(&str, &str, &str)> = Vec::new();
+        for repo in &self.repositories {
+            for source in &repo.sources {
+                entries.push((&repo.name, &source.relative_path, source.hash.as_deref()?));
+            }
+        }
+        entries.sort_unstable();
+
+        let mut hasher = blake3::Hasher::new();
+        for (repo, path, hash) in entries {
+            for part in [repo, path, hash] {
+                hasher.update(part.as_bytes());
+                hasher.update(&[0]);
+            }
+        }
+        Some(hasher.finalize().to_hex().to_string())
     }
 
     /// Get total count of all sources across repositories.
@@ -130,6 +220,10 @@ pub struct Repository {
 
     /// All source files in this repository.
     pub sources: Vec<Source>,
+
+    /// Paths the last scan left out, with the reason for each.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<Exclusion>,
 }
 
 impl Repository {
@@ -140,6 +234,7 @@ impl Repository {
             url: url.into(),
             local_path,
             sources: vec![],
+            excluded: vec![],
         }
     }
 
@@ -170,6 +265,11 @@ pub struct Source {
     /// File size in bytes.
     pub size: Option<u64>,
 
+    /// BLAKE3 hash of the file content (hex), used to tell real changes
+    /// from touched files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+
     /// File content (populated on demand).
     pub content: Option<String>,
 }
@@ -184,16 +284,37 @@ impl Source {
 
         let format = FileFormat::from_file(&path).unwrap_or(FileFormat::ArbitraryBinaryData);
         let size = std::fs::metadata(&path).ok().map(|m| m.len());
+        let hash = hash_file(&path);
 
         Ok(Self {
             path,
             relative_path,
             format,
             size,
+            hash,
             content: None,
         })
     }
 
+    /// Create a source from file content that is not on disk, such as a
+    /// blob of a git tree. `path` is where the file would live in the
+    /// working tree.
+    pub fn from_bytes(path: PathBuf, repo_root: &Path, bytes: &[u8]) -> Self {
+        let relative_path = path
+            .strip_prefix(repo_root)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string_lossy().to_string());
+
+        Self {
+            path,
+            relative_path,
+            format: FileFormat::from_bytes(bytes),
+            size: Some(bytes.len() as u64),
+            hash: Some(blake3::hash(bytes).to_hex().to_string()),
+            content: None,
+        }
+    }
+
     /// Get human-readable size.
     pub fn human_size(&self) -> String {
         self.size
@@ -215,6 +336,15 @@ impl Source {
     }
 }
 
+/// BLAKE3 hash of a file's content, streamed so large files are not loaded
+/// whole.
+fn hash_file(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(file).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
 /// Custom serde for FileFormat.
 mod file_format_serde {
     use file_format::FileFormat;
@@ -6,6 +6,7 @@
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use vibe_graph_core::GraphChangeSet;
 
 use crate::workspace::SyncSource;
 
@@ -129,6 +130,19 @@ pub struct GraphRequest {
     /// Whether to rebuild even if cached graph exists.
     #[serde(default)]
     pub force: bool,
+
+    /// Also emit symbol nodes (functions, types, traits, classes) below files.
+    #[serde(default)]
+    pub symbols: bool,
+
+    /// Keep a snapshot of the graph for later comparison.
+    #[serde(default)]
+    pub snapshot: bool,
+
+    /// Build the graph at this git revision (branch, tag, commit) instead
+    /// of the working tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
 }
 
 impl GraphRequest {
@@ -138,6 +152,9 @@ impl GraphRequest {
             path: path.into(),
             output: None,
             force: false,
+            symbols: false,
+            snapshot: false,
+            rev: None,
         }
     }
 
@@ -152,6 +169,122 @@ impl GraphRequest {
         self.force = true;
         self
     }
+
+    /// Include symbol-level nodes and `calls`/`implements` edges.
+    pub fn with_symbols(mut self) -> Self {
+        self.symbols = true;
+        self
+    }
+
+    /// Snapshot the graph after building or loading it.
+    pub fn with_snapshot(mut self) -> Self {
+        self.snapshot = true;
+        self
+    }
+
+    /// Build the graph at a git revision.
+    pub fn at_rev(mut self, rev: impl Into<String>) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+}
+
+/// Request to compare two graph snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDiffRequest {
+    /// Path to the workspace.
+    pub path: PathBuf,
+
+    /// Older graph: a snapshot ID, ID prefix or git revision. Revisions
+    /// without a snapshot are built from their git tree.
+    pub from: String,
+
+    /// Newer graph, named like `from`; the current graph when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+impl GraphDiffRequest {
+    /// Compare a snapshot with the current graph.
+    pub fn new(path: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            from: from.into(),
+            to: None,
+        }
+    }
+
+    /// Compare with another snapshot instead of the current graph.
+    pub fn to(mut self, to: impl Into<String>) -> Self {
+        self.to = Some(to.into());
+        self
+    }
+}
+
+/// Request to list graph snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphSnapshotsRequest {
+    /// Path to the workspace.
+    pub path: PathBuf,
+}
+
+impl GraphSnapshotsRequest {
+    /// Create a request for a path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Request to list the dependencies between the repositories of a
+/// workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoDependenciesRequest {
+    /// Path to the workspace.
+    pub path: PathBuf,
+}
+
+impl RepoDependenciesRequest {
+    /// Create a request for a path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Request to update a stored graph for a set of changed files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphUpdateRequest {
+    /// Path to the workspace.
+    pub path: PathBuf,
+
+    /// Files added, modified or deleted since the graph was built.
+    #[serde(default)]
+    pub changes: GraphChangeSet,
+}
+
+impl GraphUpdateRequest {
+    /// Create an update request for a path.
+    pub fn new(path: impl Into<PathBuf>, changes: GraphChangeSet) -> Self {
+        Self {
+            path: path.into(),
+            changes,
+        }
+    }
+}
+
+/// Options controlling how a `SourceCodeGraph` is built.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct GraphBuildOptions {
+    /// Emit symbol nodes linked to their file by `contains` edges.
+    #[serde(default)]
+    pub symbols: bool,
+}
+
+impl From<&GraphRequest> for GraphBuildOptions {
+    fn from(request: &GraphRequest) -> Self {
+        Self {
+            symbols: request.symbols,
+        }
+    }
 }
 
 /// Request to get workspace status.
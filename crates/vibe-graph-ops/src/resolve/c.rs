@@ -0,0 +1,174 @@
+//! C/C++ `#include "..."` resolution through include directories.
+//!
+//! A quoted include is looked up next to the including file first, then in
+//! the project's include directories: every directory named `include`, plus
+//! `-I`/`-iquote` flags from `compile_commands.json` when one is present.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::{normalize_path, ManifestSource, Resolution};
+
+/// Extensions of C and C++ sources and headers.
+const C_FAMILY: &[&str] = &["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"];
+
+pub(super) fn is_c_family(ext: &str) -> bool {
+    C_FAMILY.contains(&ext)
+}
+
+/// Include search path of a project.
+#[derive(Debug, Default)]
+pub(super) struct IncludeDirs {
+    dirs: Vec<PathBuf>,
+}
+
+impl IncludeDirs {
+    /// Collect include directories from the project's layout and any
+    /// `compile_commands.json` among `files`.
+    pub fn discover(files: &HashSet<PathBuf>, source: &dyn ManifestSource) -> Self {
+        let mut dirs: HashSet<PathBuf> = HashSet::new();
+
+        for file in files {
+            if file
+                .file_name()
+                .is_some_and(|n| n == "compile_commands.json")
+            {
+                dirs.extend(compile_commands_includes(file, source));
+            }
+            dirs.extend(
+                file.ancestors()
+                    .skip(1)
+                    .find(|dir| dir.file_name().is_some_and(|n| n == "include"))
+                    .map(Path::to_path_buf),
+            );
+        }
+
+        let mut dirs: Vec<PathBuf> = dirs.into_iter().collect();
+        dirs.sort();
+        debug!(dirs = dirs.len(), "Discovered include directories");
+        Self { dirs }
+    }
+
+    /// Resolve a quoted include written in `source`.
+    pub fn resolve(&self, source: &Path, header: &str, files: &HashSet<PathBuf>) -> Resolution {
+        let local = source.parent().map(|dir| normalize_path(&dir.join(header)));
+        if let Some(local) = local.filter(|p| files.contains(p)) {
+            return Resolution::File(local);
+        }
+
+        // Prefer include directories close to the including file, so each
+        // component of a multi-project tree finds its own headers.
+        let found = self
+            .dirs
+            .iter()
+            .map(|dir| normalize_path(&dir.join(header)))
+            .filter(|candidate| files.contains(candidate))
+            .max_by_key(|candidate| shared_components(candidate, source));
+
+        match found {
+            Some(path) => Resolution::File(path),
+            None => Resolution::Unhandled,
+        }
+    }
+}
+
+fn shared_components(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+/// `-I` and `-iquote` directories from a compilation database.
+fn compile_commands_includes(path: &Path, source: &dyn ManifestSource) -> Vec<PathBuf> {
+    let entries: Vec<Value> = match source
+        .read(path)
+        .and_then(|content| serde_json::from_str(&content).ok())
+    {
+        Some(entries) => entries,
+        None => {
+            debug!(path = %path.display(), "Skipping unreadable compile_commands.json");
+            return Vec::new();
+        }
+    };
+
+    let mut dirs = Vec::new();
+    for entry in &entries {
+        let directory = entry
+            .get("directory")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| path.parent().unwrap_or(Path::new("")).to_path_buf());
+        let args: Vec<String> = match entry.get("arguments").and_then(Value::as_array) {
+            Some(args) => args
+                .iter()
+                .filter_map(|a| a.as_str().map(str::to_string))
+                .collect(),
+            None => entry
+                .get("command")
+                .and_then(Value::as_str)
+                .map(|c| c.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let dir = match arg.as_str() {
+                "-I" | "-iquote" => args.next().map(String::as_str),
+                other => other.strip_prefix("-I"),
+            };
+            if let Some(dir) = dir.filter(|d| !d.is_empty()) {
+                dirs.push(normalize_path(&directory.join(dir)));
+            }
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::{write, Disk};
+
+    #[test]
+    fn test_includes_resolve_locally_then_through_include_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let commands = format!(
+            r#"[{{"directory": "{}", "command": "cc -Ithird_party/json -c src/main.c"}}]"#,
+            root.join("engine").display()
+        );
+        let files: HashSet<PathBuf> = [
+            write(root, "engine/compile_commands.json", &commands),
+            write(root, "engine/src/main.c", ""),
+            write(root, "engine/src/config.h", ""),
+            write(root, "engine/include/engine/api.h", ""),
+            write(root, "engine/third_party/json/json.h", ""),
+            write(root, "tools/include/engine/api.h", ""),
+        ]
+        .into_iter()
+        .collect();
+        let includes = IncludeDirs::discover(&files, &Disk);
+        let main = root.join("engine/src/main.c");
+
+        assert_eq!(
+            includes.resolve(&main, "config.h", &files),
+            Resolution::File(root.join("engine/src/config.h"))
+        );
+        assert_eq!(
+            includes.resolve(&main, "engine/api.h", &files),
+            Resolution::File(root.join("engine/include/engine/api.h"))
+        );
+        assert_eq!(
+            includes.resolve(&main, "json.h", &files),
+            Resolution::File(root.join("engine/third_party/json/json.h"))
+        );
+        assert_eq!(
+            includes.resolve(&main, "missing.h", &files),
+            Resolution::Unhandled
+        );
+    }
+}
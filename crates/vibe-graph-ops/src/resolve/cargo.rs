@@ -0,0 +1,542 @@
+//! Rust path resolution through Cargo manifests.
+//!
+//! Each `Cargo.toml` with a `[package]` section becomes a crate. Paths in
+//! `use` and `mod` items are resolved against the crate of the referencing
+//! file: `crate::`, `self::` and `super::` walk its module tree, and a
+//! leading dependency name (with `-` normalized to `_`) jumps to the library
+//! root of the matching workspace crate.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use super::{expand_members, normalize_path, ManifestSource, Resolution};
+
+/// Crates that ship with the toolchain and never live in the workspace.
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// Directories whose top-level `.rs` files are crate roots of their own.
+const TARGET_DIRS: &[&str] = &["src/bin", "tests", "examples", "benches"];
+
+/// A dependency as declared in `[dependencies]` (or dev/build/target ones).
+#[derive(Debug, Clone, Default)]
+struct Dependency {
+    /// Package name, which differs from the key when renamed via `package =`.
+    package: String,
+    /// Manifest directory for `path` dependencies.
+    path: Option<PathBuf>,
+}
+
+/// A package found in the workspace.
+#[derive(Debug)]
+struct CargoCrate {
+    /// Package name from `[package] name`.
+    package: String,
+    /// Directory containing the crate's `Cargo.toml`.
+    dir: PathBuf,
+    /// Name used in paths: `[lib] name`, or the package name with `-` → `_`.
+    lib_name: String,
+    /// Library root, `src/lib.rs` unless `[lib] path` says otherwise.
+    lib_root: PathBuf,
+    /// Dependencies keyed by the (normalized) name they are used under.
+    dependencies: HashMap<String, Dependency>,
+}
+
+/// Shared `[workspace.dependencies]` of a workspace manifest.
+#[derive(Debug)]
+struct WorkspaceManifest {
+    dir: PathBuf,
+    dependencies: HashMap<String, Dependency>,
+}
+
+/// A module tree within a crate: the crate root file and the directory its
+/// child modules live in.
+#[derive(Debug, Clone)]
+struct ModuleTree {
+    /// `None` for modules shared by several roots (`tests/common/mod.rs`).
+    root_file: Option<PathBuf>,
+    dir: PathBuf,
+}
+
+/// Where a file sits within its crate.
+#[derive(Debug)]
+struct ModuleContext {
+    tree: ModuleTree,
+    /// Module path from the crate root, e.g. `["store", "disk"]`.
+    path: Vec<String>,
+}
+
+/// All Cargo packages of a project.
+#[derive(Debug, Default)]
+pub(super) struct CargoWorkspace {
+    crates: Vec<CargoCrate>,
+}
+
+impl CargoWorkspace {
+    /// Read every `Cargo.toml` in `files`, plus manifests of workspace
+    /// members that were not part of the scan.
+    pub fn discover(files: &HashSet<PathBuf>, source: &dyn ManifestSource) -> Self {
+        let mut manifests: Vec<PathBuf> = files
+            .iter()
+            .filter(|p| p.file_name().is_some_and(|n| n == "Cargo.toml"))
+            .cloned()
+            .collect();
+        manifests.sort();
+
+        let mut parsed: Vec<(PathBuf, toml::Table)> = Vec::new();
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        while let Some(manifest) = manifests.pop() {
+            if !seen.insert(manifest.clone()) {
+                continue;
+            }
+            let Some(table) = read_manifest(&manifest, source) else {
+                continue;
+            };
+            let dir = manifest.parent().unwrap_or(Path::new("")).to_path_buf();
+            if let Some(members) = table
+                .get("workspace")
+                .and_then(|w| w.get("members"))
+                .and_then(|m| m.as_array())
+            {
+                for pattern in members.iter().filter_map(|m| m.as_str()) {
+                    for member in expand_members(&dir, pattern, "Cargo.toml", source) {
+                        manifests.push(member.join("Cargo.toml"));
+                    }
+                }
+            }
+            parsed.push((dir, table));
+        }
+
+        let workspaces: Vec<WorkspaceManifest> = parsed
+            .iter()
+            .filter_map(|(dir, table)| {
+                let deps = table.get("workspace")?.get("dependencies")?.as_table()?;
+                Some(WorkspaceManifest {
+                    dir: dir.clone(),
+                    dependencies: dependency_table(dir, deps, None),
+                })
+            })
+            .collect();
+
+        let mut crates: Vec<CargoCrate> = parsed
+            .iter()
+            .filter_map(|(dir, table)| CargoCrate::from_manifest(dir, table, &workspaces))
+            .collect();
+        crates.sort_by(|a, b| a.dir.cmp(&b.dir));
+
+        debug!(crates = crates.len(), "Discovered Cargo crates");
+        Self { crates }
+    }
+
+    /// Resolve a Rust path written in `source` (`crate::store::Store`,
+    /// `vibe_graph_core::SourceCodeGraph`, `self::config`).
+    pub fn resolve(&self, source: &Path, specifier: &str, files: &HashSet<PathBuf>) -> Resolution {
+        let Some(krate) = self.crate_for(source) else {
+            return Resolution::Unhandled;
+        };
+        let Some(context) = krate.module_context(source, files) else {
+            return Resolution::Unhandled;
+        };
+
+        let segments: Vec<&str> = specifier
+            .split("::")
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        let Some(&first) = segments.first() else {
+            return Resolution::Unhandled;
+        };
+
+        let (tree, base, rest): (ModuleTree, Vec<String>, &[&str]) = match first {
+            "crate" => (context.tree, Vec::new(), &segments[1..]),
+            "self" => (context.tree, context.path, &segments[1..]),
+            "super" => {
+                let supers = segments.iter().take_while(|s| **s == "super").count();
+                if supers > context.path.len() {
+                    return Resolution::External;
+                }
+                let base = context.path[..context.path.len() - supers].to_vec();
+                (context.tree, base, &segments[supers..])
+            }
+            name if SYSROOT_CRATES.contains(&name) => return Resolution::External,
+            name => match self.extern_crate(krate, name) {
+                Some(Some(target)) if files.contains(&target.lib_root) => {
+                    (target.lib_tree(), Vec::new(), &segments[1..])
+                }
+                Some(_) => return Resolution::External,
+                // Not a crate: a path relative to the current module.
+                None => (context.tree, context.path, &segments[..]),
+            },
+        };
+
+        let mut full = base.clone();
+        full.extend(rest.iter().map(|s| s.to_string()));
+        match tree.longest_module(&full, base.len(), files) {
+            Some(path) => Resolution::File(path),
+            None => Resolution::External,
+        }
+    }
+
+    /// The crate whose manifest directory is the closest ancestor of `path`.
+    fn crate_for(&self, path: &Path) -> Option<&CargoCrate> {
+        self.crates
+            .iter()
+            .filter(|c| path.starts_with(&c.dir))
+            .max_by_key(|c| c.dir.components().count())
+    }
+
+    /// Look up a leading path segment as a crate name.
+    ///
+    /// Returns `None` when `name` is not a crate, `Some(None)` for crates
+    /// outside the workspace and `Some(Some(_))` for workspace crates.
+    fn extern_crate<'a>(
+        &'a self,
+        krate: &'a CargoCrate,
+        name: &str,
+    ) -> Option<Option<&'a CargoCrate>> {
+        if name == krate.lib_name {
+            return Some(Some(krate));
+        }
+        let dependency = krate.dependencies.get(name)?;
+        let target = match &dependency.path {
+            Some(dir) => self.crates.iter().find(|c| &c.dir == dir),
+            None => self.crates.iter().find(|c| c.package == dependency.package),
+        };
+        Some(target)
+    }
+}
+
+impl CargoCrate {
+    fn from_manifest(
+        dir: &Path,
+        table: &toml::Table,
+        workspaces: &[WorkspaceManifest],
+    ) -> Option<Self> {
+        let package = table.get("package")?.get("name")?.as_str()?.to_string();
+        let lib = table.get("lib");
+        let lib_name = lib
+            .and_then(|l| l.get("name"))
+            .and_then(|n| n.as_str())
+            .map(normalize_crate_name)
+            .unwrap_or_else(|| normalize_crate_name(&package));
+        let lib_root = lib
+            .and_then(|l| l.get("path"))
+            .and_then(|p| p.as_str())
+            .map(|p| normalize_path(&dir.join(p)))
+            .unwrap_or_else(|| dir.join("src").join("lib.rs"));
+
+        let workspace = workspaces
+            .iter()
+            .filter(|w| dir.starts_with(&w.dir))
+            .max_by_key(|w| w.dir.components().count());
+
+        let mut dependencies = HashMap::new();
+        let mut tables = vec![table];
+        if let Some(targets) = table.get("target").and_then(|t| t.as_table()) {
+            tables.extend(targets.values().filter_map(|t| t.as_table()));
+        }
+        for table in tables {
+            for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                if let Some(deps) = table.get(section).and_then(|d| d.as_table()) {
+                    dependencies.extend(dependency_table(dir, deps, workspace));
+                }
+            }
+        }
+
+        Some(Self {
+            package,
+            dir: dir.to_path_buf(),
+            lib_name,
+            lib_root,
+            dependencies,
+        })
+    }
+
+    fn lib_tree(&self) -> ModuleTree {
+        ModuleTree {
+            root_file: Some(self.lib_root.clone()),
+            dir: self.lib_root.parent().unwrap_or(&self.dir).to_path_buf(),
+        }
+    }
+
+    /// Work out which module tree `file` belongs to and its module path.
+    fn module_context(&self, file: &Path, files: &HashSet<PathBuf>) -> Option<ModuleContext> {
+        if self.is_crate_root(file) {
+            return Some(ModuleContext {
+                tree: ModuleTree {
+                    root_file: Some(file.to_path_buf()),
+                    dir: file.parent()?.to_path_buf(),
+                },
+                path: Vec::new(),
+            });
+        }
+
+        // Walk up until a directory that hosts a crate root's modules.
+        let lib_dir = self.lib_root.parent();
+        let tree = file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.dir))
+            .find_map(|dir| {
+                if Some(dir) == lib_dir && files.contains(&self.lib_root) {
+                    return Some(self.lib_tree());
+                }
+                let main = dir.join("main.rs");
+                if files.contains(&main) {
+                    return Some(ModuleTree {
+                        root_file: Some(main),
+                        dir: dir.to_path_buf(),
+                    });
+                }
+                TARGET_DIRS
+                    .iter()
+                    .any(|t| dir == self.dir.join(t))
+                    .then(|| ModuleTree {
+                        root_file: None,
+                        dir: dir.to_path_buf(),
+                    })
+            })?;
+
+        let relative = file.strip_prefix(&tree.dir).ok()?.with_extension("");
+        let mut path: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if path.last().is_some_and(|last| last == "mod") {
+            path.pop();
+        }
+        Some(ModuleContext { tree, path })
+    }
+
+    fn is_crate_root(&self, file: &Path) -> bool {
+        if file == self.lib_root || file == self.dir.join("build.rs") {
+            return true;
+        }
+        let Some(parent) = file.parent() else {
+            return false;
+        };
+        if parent == self.dir.join("src") && file.file_name().is_some_and(|n| n == "main.rs") {
+            return true;
+        }
+        // `src/bin/tool.rs` and `src/bin/tool/main.rs`
+        TARGET_DIRS.iter().any(|t| {
+            let dir = self.dir.join(t);
+            parent == dir
+                || (file.file_name().is_some_and(|n| n == "main.rs")
+                    && parent.parent() == Some(dir.as_path()))
+        })
+    }
+}
+
+impl ModuleTree {
+    /// The file defining the longest prefix of `segments` that exists,
+    /// trying no fewer than `min` segments.
+    fn longest_module(
+        &self,
+        segments: &[String],
+        min: usize,
+        files: &HashSet<PathBuf>,
+    ) -> Option<PathBuf> {
+        (min..=segments.len())
+            .rev()
+            .find_map(|len| self.module_file(&segments[..len], files))
+    }
+
+    /// `foo::bar` → `<dir>/foo/bar.rs` or `<dir>/foo/bar/mod.rs`.
+    fn module_file(&self, segments: &[String], files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        if segments.is_empty() {
+            return self.root_file.clone().filter(|root| files.contains(root));
+        }
+        let module_dir: PathBuf = segments.iter().collect();
+        [
+            self.dir.join(&module_dir).with_extension("rs"),
+            self.dir.join(&module_dir).join("mod.rs"),
+        ]
+        .into_iter()
+        .find(|candidate| files.contains(candidate))
+    }
+}
+
+/// Read and parse a TOML manifest, skipping it when it does not parse.
+pub(crate) fn read_manifest(path: &Path, source: &dyn ManifestSource) -> Option<toml::Table> {
+    let content = source.read(path)?;
+    match content.parse::<toml::Table>() {
+        Ok(table) => Some(table),
+        Err(e) => {
+            debug!(path = %path.display(), error = %e, "Skipping unparsable manifest");
+            None
+        }
+    }
+}
+
+/// Parse a dependency table. `workspace = true` entries inherit the package
+/// and path of the matching `[workspace.dependencies]` entry.
+fn dependency_table(
+    dir: &Path,
+    deps: &toml::Table,
+    workspace: Option<&WorkspaceManifest>,
+) -> HashMap<String, Dependency> {
+    deps.iter()
+        .map(|(key, value)| {
+            let inherited = value
+                .get("workspace")
+                .and_then(|w| w.as_bool())
+                .unwrap_or(false)
+                .then(|| workspace.and_then(|w| w.dependencies.get(&normalize_crate_name(key))))
+                .flatten();
+            let dependency = match inherited {
+                Some(dependency) => dependency.clone(),
+                None => Dependency {
+                    package: value
+                        .get("package")
+                        .and_then(|p| p.as_str())
+                        .unwrap_or(key)
+                        .to_string(),
+                    path: value
+                        .get("path")
+                        .and_then(|p| p.as_str())
+                        .map(|p| normalize_path(&dir.join(p))),
+                },
+            };
+            (normalize_crate_name(key), dependency)
+        })
+        .collect()
+}
+
+/// Crate name as written in paths: `-` becomes `_`.
+pub(crate) fn normalize_crate_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::{write, Disk};
+
+    /// A workspace whose scanned files include only the root manifest, so
+    /// member manifests are found through `[workspace] members`.
+    fn fixture() -> (tempfile::TempDir, HashSet<PathBuf>) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let manifest = write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nshared-core = { path = \"crates/core\" }\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"shared-core\"\n",
+        );
+        write(
+            root,
+            "crates/app/Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\nshared-core.workspace = true\nstore = { package = \"shared-core\", path = \"../core\" }\nserde = \"1\"\n",
+        );
+        let files = [
+            "crates/core/src/lib.rs",
+            "crates/core/src/store.rs",
+            "crates/core/src/store/disk.rs",
+            "crates/core/src/graph/mod.rs",
+            "crates/app/src/lib.rs",
+            "crates/app/src/main.rs",
+            "crates/app/src/store.rs",
+            "crates/app/src/net/mod.rs",
+            "crates/app/src/net/client.rs",
+            "crates/app/src/bin/tool.rs",
+            "crates/app/tests/common/mod.rs",
+        ];
+        let mut files: HashSet<PathBuf> = files.iter().map(|f| write(root, f, "")).collect();
+        files.insert(manifest);
+        (tmp, files)
+    }
+
+    fn resolve(root: &Path, files: &HashSet<PathBuf>, source: &str, spec: &str) -> Resolution {
+        CargoWorkspace::discover(files, &Disk).resolve(&root.join(source), spec, files)
+    }
+
+    #[test]
+    fn test_workspace_crate_paths_resolve_to_lib_root() {
+        let (tmp, files) = fixture();
+        let root = tmp.path();
+        let file = |p: &str| Resolution::File(root.join(p));
+
+        let app = "crates/app/src/net/client.rs";
+        assert_eq!(
+            resolve(root, &files, app, "shared_core::Graph"),
+            file("crates/core/src/lib.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, app, "shared_core::store::disk::Disk"),
+            file("crates/core/src/store/disk.rs")
+        );
+        // Renamed dependency
+        assert_eq!(
+            resolve(root, &files, app, "store::graph"),
+            file("crates/core/src/graph/mod.rs")
+        );
+        // Own library from a binary target
+        assert_eq!(
+            resolve(
+                root,
+                &files,
+                "crates/app/src/bin/tool.rs",
+                "app::store::Store"
+            ),
+            file("crates/app/src/store.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, app, "serde::Serialize"),
+            Resolution::External
+        );
+        assert_eq!(
+            resolve(root, &files, app, "std::fmt::Display"),
+            Resolution::External
+        );
+    }
+
+    #[test]
+    fn test_relative_module_paths() {
+        let (tmp, files) = fixture();
+        let root = tmp.path();
+        let file = |p: &str| Resolution::File(root.join(p));
+
+        let client = "crates/app/src/net/client.rs";
+        assert_eq!(
+            resolve(root, &files, client, "super::Config"),
+            file("crates/app/src/net/mod.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, client, "super::super::store::Store"),
+            file("crates/app/src/store.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, client, "crate::store"),
+            file("crates/app/src/store.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, "crates/app/src/net/mod.rs", "self::client"),
+            file("crates/app/src/net/client.rs")
+        );
+        // `mod disk;` inside `store.rs` lives in `store/`
+        assert_eq!(
+            resolve(root, &files, "crates/core/src/store.rs", "self::disk"),
+            file("crates/core/src/store/disk.rs")
+        );
+        // `crate::` stays in its own crate when a sibling has a same-named module
+        assert_eq!(
+            resolve(
+                root,
+                &files,
+                "crates/core/src/lib.rs",
+                "crate::store::Store"
+            ),
+            file("crates/core/src/store.rs")
+        );
+        assert_eq!(
+            resolve(root, &files, "crates/app/tests/common/mod.rs", "super::x"),
+            Resolution::External
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Go import resolution through `go.mod` module paths.
+//!
+//! An import path `<module>/<rest>` maps to the package directory
+//! `<go.mod dir>/<rest>`, and resolves to every non-test `.go` file in it.
+//! Local `replace` directives (`replace example.com/x => ../x`) add modules
+//! that live elsewhere in the project.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use super::{normalize_path, ManifestSource, Resolution};
+
+/// A module declared by `go.mod`, or brought in by a local `replace`.
+#[derive(Debug)]
+struct GoModule {
+    path: String,
+    dir: PathBuf,
+}
+
+/// Go modules and packages of a project.
+#[derive(Debug, Default)]
+pub(super) struct GoModules {
+    modules: Vec<GoModule>,
+    /// Package directory → non-test `.go` files.
+    packages: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl GoModules {
+    /// Read every `go.mod` in `files` and group `.go` files by directory.
+    pub fn discover(files: &HashSet<PathBuf>, source: &dyn ManifestSource) -> Self {
+        let mut modules = Vec::new();
+        let mut packages: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+        for file in files {
+            let Some(name) = file.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if name == "go.mod" {
+                let dir = file.parent().unwrap_or(Path::new(""));
+                match source.read(file) {
+                    Some(content) => modules.extend(parse_go_mod(dir, &content)),
+                    None => debug!(path = %file.display(), "Skipping unreadable go.mod"),
+                }
+            } else if name.ends_with(".go") && !name.ends_with("_test.go") {
+                if let Some(dir) = file.parent() {
+                    packages
+                        .entry(dir.to_path_buf())
+                        .or_default()
+                        .push(file.clone());
+                }
+            }
+        }
+
+        for package in packages.values_mut() {
+            package.sort();
+        }
+        debug!(modules = modules.len(), "Discovered Go modules");
+        Self { modules, packages }
+    }
+
+    /// Resolve an import path to the files of its package.
+    pub fn resolve(&self, import_path: &str) -> Resolution {
+        let module = self
+            .modules
+            .iter()
+            .filter(|m| {
+                import_path == m.path
+                    || import_path
+                        .strip_prefix(&m.path)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|m| m.path.len());
+
+        // Standard library and third-party modules
+        let Some(module) = module else {
+            return Resolution::External;
+        };
+
+        let rest = import_path[module.path.len()..].trim_start_matches('/');
+        let dir = normalize_path(&module.dir.join(rest));
+        match self.packages.get(&dir) {
+            Some(files) => Resolution::Package(files.clone()),
+            None => Resolution::External,
+        }
+    }
+}
+
+/// Modules defined by a `go.mod`: its own `module` line plus local
+/// `replace` targets.
+fn parse_go_mod(dir: &Path, content: &str) -> Vec<GoModule> {
+    let mut modules = Vec::new();
+    for (verb, arguments) in go_mod_directives(content) {
+        match verb {
+            "module" => modules.push(GoModule {
+                path: arguments.trim_matches('"').to_string(),
+                dir: dir.to_path_buf(),
+            }),
+            // `example.com/x [v1.2.3] => ../x`
+            "replace" => {
+                let Some((from, to)) = arguments.split_once("=>") else {
+                    continue;
+                };
+                let target = to.split_whitespace().next().unwrap_or("");
+                if target.starts_with('.') || target.starts_with('/') {
+                    let path = from.split_whitespace().next().unwrap_or("");
+                    modules.push(GoModule {
+                        path: path.to_string(),
+                        dir: normalize_path(&dir.join(target)),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    modules
+}
+
+/// The directives of a `go.mod` as `(verb, arguments)`, without comments.
+/// A block (`require ( ... )`) yields one directive per line.
+pub(crate) fn go_mod_directives(content: &str) -> Vec<(&str, &str)> {
+    let mut directives = Vec::new();
+    let mut block = None;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(verb) = block {
+            if line.starts_with(')') {
+                block = None;
+            } else {
+                directives.push((verb, line));
+            }
+            continue;
+        }
+        let split = line
+            .find(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or(line.len());
+        let (verb, arguments) = (&line[..split], line[split..].trim());
+        if arguments == "(" {
+            block = Some(verb);
+        } else {
+            directives.push((verb, arguments));
+        }
+    }
+    directives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::{write, Disk};
+
+    #[test]
+    fn test_go_imports_resolve_through_module_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let files: HashSet<PathBuf> = [
+            write(
+                root,
+                "svc/go.mod",
+                "module github.com/acme/svc\n\ngo 1.22\n\nreplace (\n    github.com/acme/shared => ../shared\n    github.com/other/x v1.0.0 => github.com/fork/x v1.0.1\n)\n",
+            ),
+            write(root, "svc/cmd/main.go", ""),
+            write(root, "svc/internal/store/store.go", ""),
+            write(root, "svc/internal/store/cache.go", ""),
+            write(root, "svc/internal/store/store_test.go", ""),
+            write(root, "shared/log/log.go", ""),
+        ]
+        .into_iter()
+        .collect();
+        let modules = GoModules::discover(&files, &Disk);
+
+        assert_eq!(
+            modules.resolve("github.com/acme/svc/internal/store"),
+            Resolution::Package(vec![
+                root.join("svc/internal/store/cache.go"),
+                root.join("svc/internal/store/store.go"),
+            ])
+        );
+        assert_eq!(
+            modules.resolve("github.com/acme/shared/log"),
+            Resolution::Package(vec![root.join("shared/log/log.go")])
+        );
+        assert_eq!(modules.resolve("fmt"), Resolution::External);
+        assert_eq!(modules.resolve("github.com/other/x"), Resolution::External);
+        // A module path prefix must end at a path separator
+        assert_eq!(
+            modules.resolve("github.com/acme/svcx/store"),
+            Resolution::External
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Java and Kotlin import resolution through `package` declarations.
+//!
+//! Source roots (`src/main/java`, `src/main/kotlin`, ...) are whatever is
+//! left of a file's path once its package directories are removed, so
+//! rather than guessing them the resolver indexes each file by the package
+//! it declares. This also covers Kotlin files whose directory does not
+//! mirror their package.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::Resolution;
+
+/// Java/Kotlin files grouped by declared package.
+#[derive(Debug, Default)]
+pub(super) struct JvmPackages {
+    packages: HashMap<String, Vec<PathBuf>>,
+}
+
+impl JvmPackages {
+    /// Index a source file by its declared package.
+    pub fn add_package(&mut self, path: &Path, package: &str) {
+        self.packages
+            .entry(package.to_string())
+            .or_default()
+            .push(path.to_path_buf());
+    }
+
+    /// Resolve a dotted import (`com.acme.store.Store`, `com.acme.model.*`).
+    pub fn resolve(&self, name: &str) -> Resolution {
+        if let Some(package) = name.strip_suffix(".*") {
+            return match self.packages.get(package) {
+                Some(files) => Resolution::Package(files.clone()),
+                None => Resolution::External,
+            };
+        }
+
+        // The longest known package prefix; the next segment is the class
+        // (`com.acme.Outer.Inner` lives in `Outer.java`).
+        let segments: Vec<&str> = name.split('.').collect();
+        for len in (1..segments.len()).rev() {
+            let Some(files) = self.packages.get(&segments[..len].join(".")) else {
+                continue;
+            };
+            let class = segments[len];
+            let file = files
+                .iter()
+                .find(|f| f.file_stem().and_then(|s| s.to_str()) == Some(class));
+            return match file {
+                Some(file) => Resolution::File(file.clone()),
+                // Kotlin top-level declarations live in arbitrarily named files
+                None => Resolution::Package(files.clone()),
+            };
+        }
+
+        Resolution::External
+    }
+}
+
+/// The package a Java or Kotlin source declares; `None` for other files.
+pub(super) fn source_package(path: &Path, content: &str) -> Option<String> {
+    let is_jvm = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("java" | "kt" | "kts")
+    );
+    if !is_jvm {
+        return None;
+    }
+    declared_package(content)
+}
+
+/// The `package a.b.c` declaration of a Java or Kotlin file.
+fn declared_package(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let name = line.trim().strip_prefix("package ")?;
+        let name = name.trim().trim_end_matches(';').trim().replace('`', "");
+        (!name.is_empty()).then_some(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jvm_imports_resolve_through_declared_packages() {
+        let mut packages = JvmPackages::default();
+        let store = PathBuf::from("/repo/store/src/main/java/com/acme/store/Store.java");
+        let model = PathBuf::from("/repo/app/src/main/kotlin/model/Models.kt");
+        let dates = PathBuf::from("/repo/app/src/main/kotlin/util/Dates.kt");
+        let readme = PathBuf::from("/repo/README.md");
+        let sources = [
+            (&store, "package com.acme.store;\n\npublic class Store {}\n"),
+            (
+                &model,
+                "package com.acme.model\n\ndata class User(val id: Int)\n",
+            ),
+            (&dates, "package com.acme.util\n\nfun today() = 0\n"),
+            (&readme, "package com.acme.docs"),
+        ];
+        for (path, content) in sources {
+            if let Some(package) = source_package(path, content) {
+                packages.add_package(path, &package);
+            }
+        }
+
+        assert_eq!(
+            packages.resolve("com.acme.store.Store"),
+            Resolution::File(store.clone())
+        );
+        assert_eq!(
+            packages.resolve("com.acme.store.Store.Entry"),
+            Resolution::File(store)
+        );
+        assert_eq!(
+            packages.resolve("com.acme.model.*"),
+            Resolution::Package(vec![model.clone()])
+        );
+        assert_eq!(
+            packages.resolve("com.acme.model.User"),
+            Resolution::Package(vec![model])
+        );
+        assert_eq!(
+            packages.resolve("com.acme.util.Dates"),
+            Resolution::File(dates)
+        );
+        assert_eq!(packages.resolve("java.util.List"), Resolution::External);
+        assert_eq!(packages.resolve("com.acme.docs.X"), Resolution::External);
+    }
+}
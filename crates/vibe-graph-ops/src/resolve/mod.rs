@@ -0,0 +1,262 @@
+//! Workspace-aware resolution of detected references.
+//!
+//! Reference detection in `vibe-graph-core` looks at one file at a time, so it
+//! can only propose candidate routes (`store.rs`, `./utils`) alongside the
+//! reference as written in source. The resolver combines those with the
+//! project's manifests to pick the exact file a reference points at:
+//!
+//! - Rust: `Cargo.toml` workspaces and module trees ([`cargo`])
+//! - Go: `go.mod` module paths ([`go`])
+//! - Java/Kotlin: `package` declarations ([`jvm`])
+//! - C/C++: include directories ([`c`])
+//! - TypeScript/JavaScript: `tsconfig.json` and `package.json` ([`ts`])
+//!
+//! Manifests are read through a [`ManifestSource`], so the same resolution
+//! works for a working tree on disk and for a git tree object.
+
+mod c;
+mod cargo;
+mod go;
+mod jvm;
+mod ts;
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use vibe_graph_core::{normalize_path, SourceReference};
+
+use crate::project::Project;
+
+use c::IncludeDirs;
+use cargo::CargoWorkspace;
+pub(crate) use cargo::{normalize_crate_name, read_manifest};
+pub(crate) use go::go_mod_directives;
+use go::GoModules;
+use jvm::JvmPackages;
+use ts::TsProject;
+
+/// Outcome of resolving a single reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Resolution {
+    /// The reference points at this project file.
+    File(PathBuf),
+    /// The reference points at a package spread over several files (Go
+    /// import paths, Java wildcard imports).
+    Package(Vec<PathBuf>),
+    /// The reference points outside the project (std, registry crates,
+    /// npm packages).
+    External,
+    /// No resolver understands the reference; callers fall back to
+    /// suffix matching on the candidate route.
+    Unhandled,
+}
+
+/// Resolves references against the files and manifests of a project.
+pub(crate) struct ReferenceResolver {
+    files: HashSet<PathBuf>,
+    cargo: CargoWorkspace,
+    go: GoModules,
+    jvm: JvmPackages,
+    includes: IncludeDirs,
+    ts: TsProject,
+}
+
+impl ReferenceResolver {
+    /// Create a resolver over the given project files.
+    ///
+    /// Manifests among the files (and the workspace members they declare)
+    /// are read from `manifests`.
+    pub fn with_manifests(
+        files: impl IntoIterator<Item = PathBuf>,
+        manifests: &dyn ManifestSource,
+    ) -> Self {
+        let files: HashSet<PathBuf> = files.into_iter().collect();
+        Self {
+            cargo: CargoWorkspace::discover(&files, manifests),
+            go: GoModules::discover(&files, manifests),
+            jvm: JvmPackages::default(),
+            includes: IncludeDirs::discover(&files, manifests),
+            ts: TsProject::discover(&files, manifests),
+            files,
+        }
+    }
+
+    /// What a source file declares about itself (its Java/Kotlin
+    /// `package`). Needs no resolver state, so files can be read in
+    /// parallel; record the result with [`Self::add_package`] before
+    /// resolving.
+    pub fn declared_package(path: &Path, content: &str) -> Option<String> {
+        jvm::source_package(path, content)
+    }
+
+    /// Record the Java/Kotlin package of a file.
+    pub fn add_package(&mut self, path: &Path, package: &str) {
+        self.jvm.add_package(path, package);
+    }
+
+    /// Resolve a reference to the file it points at.
+    pub fn resolve(&self, reference: &SourceReference) -> Resolution {
+        // Routes computed relative to the source file (`#[path]`, Python
+        // relative imports) already name the file.
+        if self.files.contains(&reference.target_route) {
+            return Resolution::File(reference.target_route.clone());
+        }
+
+        let Some(specifier) = &reference.specifier else {
+            return Resolution::Unhandled;
+        };
+        let source = &reference.source_path;
+        match source.extension().and_then(|e| e.to_str()) {
+            Some("rs") => self.cargo.resolve(source, specifier, &self.files),
+            Some("go") => self.go.resolve(specifier),
+            Some("java" | "kt" | "kts") => self.jvm.resolve(specifier),
+            Some(ext) if c::is_c_family(ext) => {
+                self.includes.resolve(source, specifier, &self.files)
+            }
+            Some(ext) if ts::is_ts_family(ext) => self.ts.resolve(source, specifier, &self.files),
+            _ => Resolution::Unhandled,
+        }
+    }
+}
+
+/// Whether the resolver reads `path` as a manifest, so that changing it can
+/// change how any reference in the project resolves.
+pub(crate) fn is_manifest(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    match name {
+        "Cargo.toml"
+        | "go.mod"
+        | "package.json"
+        | "pnpm-workspace.yaml"
+        | "compile_commands.json" => true,
+        // `extends` chains commonly go through `tsconfig.base.json`
+        _ => {
+            (name.starts_with("tsconfig") || name.starts_with("jsconfig"))
+                && name.ends_with(".json")
+        }
+    }
+}
+
+/// Where manifests and the directories around them are read from.
+pub(crate) trait ManifestSource {
+    /// Text content of the file at `path`, if there is one.
+    fn read(&self, path: &Path) -> Option<String>;
+
+    /// Immediate subdirectories of `dir`.
+    fn subdirs(&self, dir: &Path) -> Vec<PathBuf>;
+
+    /// Whether `path` is a file.
+    fn is_file(&self, path: &Path) -> bool;
+}
+
+/// Manifests of a working tree on disk.
+pub(crate) struct Disk;
+
+impl ManifestSource for Disk {
+    fn read(&self, path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn subdirs(&self, dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+}
+
+/// Manifest contents kept across builds, keyed by path and content hash.
+#[derive(Debug, Default)]
+pub(crate) struct ManifestCache {
+    entries: Mutex<HashMap<PathBuf, (String, String)>>,
+}
+
+/// Manifests of a working tree on disk, read through a [`ManifestCache`]:
+/// project files whose content hash matches the cached entry are not read
+/// again.
+pub(crate) struct CachedManifests<'a> {
+    cache: &'a ManifestCache,
+    hashes: HashMap<&'a Path, &'a str>,
+}
+
+impl<'a> CachedManifests<'a> {
+    /// Serve `cache` for the files of `project` that have a content hash.
+    pub fn new(cache: &'a ManifestCache, project: &'a Project) -> Self {
+        let hashes = project
+            .repositories
+            .iter()
+            .flat_map(|repo| &repo.sources)
+            .filter_map(|source| Some((source.path.as_path(), source.hash.as_deref()?)))
+            .collect();
+        Self { cache, hashes }
+    }
+}
+
+impl ManifestSource for CachedManifests<'_> {
+    fn read(&self, path: &Path) -> Option<String> {
+        let Some(&hash) = self.hashes.get(path) else {
+            return Disk.read(path);
+        };
+        let mut entries = self.cache.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached, content)) = entries.get(path) {
+            if cached == hash {
+                return Some(content.clone());
+            }
+        }
+        let content = Disk.read(path)?;
+        entries.insert(path.to_path_buf(), (hash.to_string(), content.clone()));
+        Some(content)
+    }
+
+    fn subdirs(&self, dir: &Path) -> Vec<PathBuf> {
+        Disk.subdirs(dir)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        Disk.is_file(path)
+    }
+}
+
+/// Expand a workspace member pattern (`crates/*`, `packages/ui`) relative
+/// to `dir`. Only a trailing `*` is supported, which covers the common
+/// layouts; wildcard matches must contain `manifest`.
+fn expand_members(
+    dir: &Path,
+    pattern: &str,
+    manifest: &str,
+    manifests: &dyn ManifestSource,
+) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./");
+    match pattern
+        .strip_suffix("/**")
+        .or_else(|| pattern.strip_suffix("/*"))
+    {
+        Some(parent) => manifests
+            .subdirs(&dir.join(parent))
+            .into_iter()
+            .filter(|p| manifests.is_file(&p.join(manifest)))
+            .collect(),
+        None => vec![normalize_path(&dir.join(pattern))],
+    }
+}
+
+/// Write `content` to `path` under `root`, creating its directories.
+#[cfg(test)]
+pub(crate) fn write(root: &Path, path: &str, content: &str) -> PathBuf {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    path
+}
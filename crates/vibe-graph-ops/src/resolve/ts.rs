@@ -0,0 +1,609 @@
+//! TypeScript/JavaScript module resolution.
+//!
+//! Follows the parts of the TypeScript and Node resolution algorithms that
+//! matter inside a repository:
+//!
+//! - relative specifiers with extension probing (`.ts`, `.tsx`, `.d.ts`,
+//!   `.js`, ...) and `/index.*` directories
+//! - `compilerOptions.paths` and `baseUrl` from the nearest `tsconfig.json`
+//!   (or `jsconfig.json`), including relative `extends` chains
+//! - imports of workspace packages (`@acme/ui`, `@acme/ui/button`) through
+//!   their `package.json` `exports` and entry fields
+//!
+//! Workspace packages are every `package.json` in the scan plus members
+//! declared by `workspaces` or `pnpm-workspace.yaml`. Entry points that point
+//! at build output (`./dist/index.js`) are mapped back to sources under
+//! `src/` when the build output is not part of the project.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::{expand_members, normalize_path, ManifestSource, Resolution};
+
+/// Source extensions, in TypeScript's probing order.
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Directories that usually hold build output rather than sources.
+const BUILD_DIRS: &[&str] = &["dist", "build", "lib", "out"];
+
+/// Export conditions, most source-like first.
+const CONDITIONS: &[&str] = &[
+    "source", "types", "typings", "import", "module", "default", "require", "node", "browser",
+];
+
+/// Entry fields of a `package.json`, most source-like first.
+const ENTRY_FIELDS: &[&str] = &["source", "types", "typings", "module", "main"];
+
+pub(super) fn is_ts_family(ext: &str) -> bool {
+    matches!(
+        ext,
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs"
+    )
+}
+
+/// Effective module options of a `tsconfig.json`.
+#[derive(Debug, Default)]
+struct TsConfig {
+    dir: PathBuf,
+    base_url: Option<PathBuf>,
+    /// `paths` patterns with their substitutions.
+    paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` substitutions are relative to.
+    paths_base: PathBuf,
+}
+
+/// A workspace package.
+#[derive(Debug)]
+struct Package {
+    name: String,
+    dir: PathBuf,
+    manifest: Value,
+}
+
+/// tsconfig files and workspace packages of a project.
+#[derive(Debug, Default)]
+pub(super) struct TsProject {
+    configs: Vec<TsConfig>,
+    packages: Vec<Package>,
+}
+
+impl TsProject {
+    /// Read every `tsconfig.json`/`jsconfig.json` and `package.json` in
+    /// `files`, following workspace declarations to members outside it.
+    pub fn discover(files: &HashSet<PathBuf>, source: &dyn ManifestSource) -> Self {
+        let mut configs = Vec::new();
+        let mut manifests: Vec<PathBuf> = Vec::new();
+
+        let mut sorted: Vec<&PathBuf> = files.iter().collect();
+        sorted.sort();
+        for file in sorted {
+            let dir = file.parent().unwrap_or(Path::new(""));
+            match file.file_name().and_then(|n| n.to_str()) {
+                Some("tsconfig.json" | "jsconfig.json") => {
+                    if let Some(config) = TsConfig::load(file, source) {
+                        configs.push(config);
+                    }
+                }
+                Some("package.json") => manifests.push(file.clone()),
+                Some("pnpm-workspace.yaml") => {
+                    let content = source.read(file).unwrap_or_default();
+                    for pattern in pnpm_workspace_packages(&content) {
+                        for member in expand_members(dir, &pattern, "package.json", source) {
+                            manifests.push(member.join("package.json"));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut packages = Vec::new();
+        let mut seen = HashSet::new();
+        while let Some(manifest) = manifests.pop() {
+            if !seen.insert(manifest.clone()) {
+                continue;
+            }
+            let Some(value) = read_json(&manifest, source) else {
+                continue;
+            };
+            let dir = manifest.parent().unwrap_or(Path::new("")).to_path_buf();
+            // `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+            let workspaces = value.get("workspaces").and_then(|w| {
+                w.as_array()
+                    .or_else(|| w.get("packages").and_then(Value::as_array))
+            });
+            for pattern in workspaces.into_iter().flatten().filter_map(Value::as_str) {
+                for member in expand_members(&dir, pattern, "package.json", source) {
+                    manifests.push(member.join("package.json"));
+                }
+            }
+            if let Some(name) = value.get("name").and_then(Value::as_str) {
+                packages.push(Package {
+                    name: name.to_string(),
+                    dir,
+                    manifest: value,
+                });
+            }
+        }
+
+        debug!(
+            configs = configs.len(),
+            packages = packages.len(),
+            "Discovered TypeScript project files"
+        );
+        Self { configs, packages }
+    }
+
+    /// Resolve a module specifier written in `source`.
+    pub fn resolve(&self, source: &Path, specifier: &str, files: &HashSet<PathBuf>) -> Resolution {
+        let found = if is_relative(specifier) {
+            source
+                .parent()
+                .and_then(|dir| probe(&normalize_path(&dir.join(specifier)), files))
+        } else if specifier.starts_with("node:") {
+            None
+        } else {
+            self.config_for(source)
+                .and_then(|config| config.resolve(specifier, files))
+                .or_else(|| self.resolve_package(specifier, files))
+        };
+
+        match found {
+            Some(path) => Resolution::File(path),
+            None => Resolution::External,
+        }
+    }
+
+    /// The tsconfig closest to `source`.
+    fn config_for(&self, source: &Path) -> Option<&TsConfig> {
+        self.configs
+            .iter()
+            .filter(|c| source.starts_with(&c.dir))
+            .max_by_key(|c| c.dir.components().count())
+    }
+
+    /// `@acme/ui` or `@acme/ui/button` through a workspace package.
+    fn resolve_package(&self, specifier: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        let package = self
+            .packages
+            .iter()
+            .filter(|p| {
+                specifier == p.name
+                    || specifier
+                        .strip_prefix(&p.name)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|p| p.name.len())?;
+        let subpath = format!(".{}", &specifier[package.name.len()..]);
+        package.resolve(&subpath, files)
+    }
+}
+
+impl TsConfig {
+    /// Load a tsconfig, merging relative `extends` parents.
+    fn load(path: &Path, source: &dyn ManifestSource) -> Option<Self> {
+        let mut config = Self {
+            dir: path.parent()?.to_path_buf(),
+            ..Self::default()
+        };
+        let mut paths_set = false;
+
+        // Walk the chain child-first; the first definition of an option wins.
+        let mut current = Some(path.to_path_buf());
+        let mut visited = HashSet::new();
+        while let Some(file) = current.take() {
+            if !visited.insert(file.clone()) {
+                break;
+            }
+            let Some(value) = read_json(&file, source) else {
+                break;
+            };
+            let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+            let options = value.get("compilerOptions");
+
+            if config.base_url.is_none() {
+                if let Some(base) = options
+                    .and_then(|o| o.get("baseUrl"))
+                    .and_then(Value::as_str)
+                {
+                    config.base_url = Some(normalize_path(&dir.join(base)));
+                }
+            }
+            if !paths_set {
+                if let Some(paths) = options
+                    .and_then(|o| o.get("paths"))
+                    .and_then(Value::as_object)
+                {
+                    config.paths = paths
+                        .iter()
+                        .map(|(pattern, targets)| {
+                            let targets = targets
+                                .as_array()
+                                .into_iter()
+                                .flatten()
+                                .filter_map(Value::as_str)
+                                .map(str::to_string)
+                                .collect();
+                            (pattern.clone(), targets)
+                        })
+                        .collect();
+                    config.paths_base = dir.clone();
+                    paths_set = true;
+                }
+            }
+
+            current = value
+                .get("extends")
+                .and_then(Value::as_str)
+                .filter(|parent| is_relative(parent))
+                .map(|parent| {
+                    let parent = normalize_path(&dir.join(parent));
+                    if parent.extension().is_some_and(|e| e == "json") {
+                        parent
+                    } else {
+                        parent.with_extension("json")
+                    }
+                });
+        }
+
+        // `paths` are relative to `baseUrl` when one is set
+        if let Some(base) = &config.base_url {
+            config.paths_base = base.clone();
+        }
+        Some(config)
+    }
+
+    /// Resolve through `paths`, then `baseUrl`.
+    fn resolve(&self, specifier: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        // The pattern with the longest prefix before `*` wins
+        let matched = self
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let captured = match pattern.split_once('*') {
+                    Some((prefix, suffix)) => specifier
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(suffix))?,
+                    None if pattern == specifier => "",
+                    None => return None,
+                };
+                let prefix_len = pattern.split('*').next().map_or(0, str::len);
+                Some((prefix_len, captured, targets))
+            })
+            .max_by_key(|(prefix_len, _, _)| *prefix_len);
+
+        if let Some((_, captured, targets)) = matched {
+            let found = targets.iter().find_map(|target| {
+                let target = target.replacen('*', captured, 1);
+                probe(&normalize_path(&self.paths_base.join(target)), files)
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        self.base_url
+            .as_ref()
+            .and_then(|base| probe(&normalize_path(&base.join(specifier)), files))
+    }
+}
+
+impl Package {
+    /// Resolve a subpath (`.`, `./button`) of this package.
+    fn resolve(&self, subpath: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        if let Some(exports) = self.manifest.get("exports") {
+            let targets = export_targets(exports, subpath);
+            if let Some(found) = targets
+                .iter()
+                .find_map(|target| probe_source(&self.dir, target, files))
+            {
+                return Some(found);
+            }
+        }
+
+        if subpath == "." {
+            ENTRY_FIELDS
+                .iter()
+                .filter_map(|field| self.manifest.get(*field).and_then(Value::as_str))
+                .chain(["index", "src/index"])
+                .find_map(|entry| probe_source(&self.dir, entry, files))
+        } else {
+            probe_source(&self.dir, subpath, files)
+                .or_else(|| probe_source(&self.dir.join("src"), subpath, files))
+        }
+    }
+}
+
+/// Candidate targets of `exports` for a subpath, most source-like first.
+fn export_targets(exports: &Value, subpath: &str) -> Vec<String> {
+    let is_subpath_map = exports
+        .as_object()
+        .is_some_and(|map| map.keys().any(|k| k.starts_with('.')));
+    if !is_subpath_map {
+        return if subpath == "." {
+            condition_targets(exports)
+        } else {
+            Vec::new()
+        };
+    }
+
+    let map = exports.as_object().into_iter().flatten();
+    let mut targets = Vec::new();
+    for (key, value) in map {
+        if key == subpath {
+            targets.extend(condition_targets(value));
+        } else if let Some((prefix, suffix)) = key.split_once('*') {
+            // `"./*": "./src/*.ts"`
+            if let Some(captured) = subpath
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix(suffix))
+            {
+                targets.extend(
+                    condition_targets(value)
+                        .into_iter()
+                        .map(|t| t.replacen('*', captured, 1)),
+                );
+            }
+        }
+    }
+    targets
+}
+
+/// Leaf targets of a conditional export, in [`CONDITIONS`] order.
+fn condition_targets(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(target) => vec![target.clone()],
+        Value::Array(items) => items.iter().flat_map(condition_targets).collect(),
+        Value::Object(map) => {
+            let known = CONDITIONS.iter().filter_map(|c| map.get(*c));
+            let other = map
+                .iter()
+                .filter(|(k, _)| !CONDITIONS.contains(&k.as_str()))
+                .map(|(_, v)| v);
+            known.chain(other).flat_map(condition_targets).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Probe a package entry, falling back from build output to `src/`.
+fn probe_source(dir: &Path, target: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let path = normalize_path(&dir.join(target));
+    if let Some(found) = probe(&path, files) {
+        return Some(found);
+    }
+
+    // `./dist/index.js` or `./dist/index.d.ts` → `./src/index.ts`
+    let relative = path.strip_prefix(dir).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    if !BUILD_DIRS.contains(&first) {
+        return None;
+    }
+    let rest = components.as_path().to_string_lossy();
+    let rest = rest.strip_suffix(".d.ts").unwrap_or(&rest);
+    probe(&dir.join("src").join(rest), files)
+}
+
+/// The file a module path refers to, with extension and index probing.
+fn probe(path: &Path, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    if files.contains(path) {
+        return Some(path.to_path_buf());
+    }
+
+    let raw = path.to_string_lossy();
+    // ESM-style `./util.js` written for `./util.ts`
+    for (js, ts) in [
+        (".js", ".ts"),
+        (".jsx", ".tsx"),
+        (".mjs", ".mts"),
+        (".cjs", ".cts"),
+    ] {
+        if let Some(stem) = raw.strip_suffix(js) {
+            for candidate in [format!("{}{}", stem, ts), format!("{}.tsx", stem)] {
+                let candidate = PathBuf::from(candidate);
+                if files.contains(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    // Extensions are appended, not substituted: `./user.service` → `user.service.ts`
+    EXTENSIONS
+        .iter()
+        .map(|ext| PathBuf::from(format!("{}.{}", raw, ext)))
+        .chain(
+            EXTENSIONS
+                .iter()
+                .map(|ext| path.join(format!("index.{}", ext))),
+        )
+        .find(|candidate| files.contains(candidate))
+}
+
+fn is_relative(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+}
+
+/// `packages:` entries of a `pnpm-workspace.yaml`.
+fn pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with([' ', '\t', '-']) && !trimmed.is_empty() {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.trim().trim_matches(['\'', '"']);
+            // Negated patterns exclude members; there is nothing to add
+            if !item.starts_with('!') && !item.is_empty() {
+                patterns.push(item.to_string());
+            }
+        }
+    }
+    patterns
+}
+
+/// Read a JSON file that may contain comments and trailing commas, as
+/// `tsconfig.json` files commonly do.
+fn read_json(path: &Path, source: &dyn ManifestSource) -> Option<Value> {
+    let content = source.read(path)?;
+    match serde_json::from_str(&strip_jsonc(&content)) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            debug!(path = %path.display(), error = %e, "Skipping unparsable JSON");
+            None
+        }
+    }
+}
+
+/// Remove `//` and `/* */` comments and trailing commas outside strings.
+fn strip_jsonc(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            ']' | '}' => {
+                // Drop a trailing comma before the closing bracket
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::{write, Disk};
+
+    #[test]
+    fn test_ts_resolution_through_tsconfig_and_workspace_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut files: HashSet<PathBuf> = [
+            (
+                "tsconfig.base.json",
+                r#"{
+                    // shared options
+                    "compilerOptions": {
+                        "baseUrl": ".",
+                        "paths": { "~/*": ["apps/web/src/*"], },
+                    },
+                }"#,
+            ),
+            (
+                "apps/web/tsconfig.json",
+                r#"{ "extends": "../../tsconfig.base.json" }"#,
+            ),
+            ("package.json", r#"{ "private": true, "workspaces": ["packages/*"] }"#),
+            ("apps/web/src/app.tsx", ""),
+            ("apps/web/src/utils/date.ts", ""),
+            ("apps/web/src/components/index.tsx", ""),
+            ("apps/web/src/types.d.ts", ""),
+            ("apps/web/src/esm.ts", ""),
+            (
+                "packages/ui/package.json",
+                r#"{ "name": "@acme/ui", "exports": { ".": { "types": "./dist/index.d.ts", "import": "./dist/index.js" }, "./*": "./src/*.tsx" } }"#,
+            ),
+            ("packages/ui/src/index.ts", ""),
+            ("packages/ui/src/button.tsx", ""),
+        ]
+        .into_iter()
+        .map(|(path, content)| write(root, path, content))
+        .collect();
+        // A member only reachable through `workspaces`
+        write(
+            root,
+            "packages/config/package.json",
+            r#"{ "name": "@acme/config", "main": "index.js" }"#,
+        );
+        files.insert(write(root, "packages/config/index.js", ""));
+        files.remove(&root.join("packages/ui/package.json"));
+
+        let project = TsProject::discover(&files, &Disk);
+        let app = root.join("apps/web/src/app.tsx");
+        let resolve = |spec: &str| project.resolve(&app, spec, &files);
+        let file = |p: &str| Resolution::File(root.join(p));
+
+        assert_eq!(resolve("./utils/date"), file("apps/web/src/utils/date.ts"));
+        assert_eq!(
+            resolve("./components"),
+            file("apps/web/src/components/index.tsx")
+        );
+        assert_eq!(resolve("./types"), file("apps/web/src/types.d.ts"));
+        assert_eq!(resolve("./esm.js"), file("apps/web/src/esm.ts"));
+        assert_eq!(resolve("~/utils/date"), file("apps/web/src/utils/date.ts"));
+        assert_eq!(resolve("apps/web/src/esm"), file("apps/web/src/esm.ts"));
+        assert_eq!(resolve("@acme/ui"), file("packages/ui/src/index.ts"));
+        assert_eq!(
+            resolve("@acme/ui/button"),
+            file("packages/ui/src/button.tsx")
+        );
+        assert_eq!(resolve("@acme/config"), file("packages/config/index.js"));
+        assert_eq!(resolve("@acme/ui-kit"), Resolution::External);
+        assert_eq!(resolve("react"), Resolution::External);
+        assert_eq!(resolve("node:fs"), Resolution::External);
+        assert_eq!(resolve("./missing"), Resolution::External);
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let input = "{\n  // comment\n  \"a\": \"http://x\", /* block */\n  \"b\": [1, 2,],\n}";
+        let value: Value = serde_json::from_str(&strip_jsonc(input)).unwrap();
+        assert_eq!(value["a"], "http://x");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_pnpm_workspace_packages() {
+        let content = "packages:\n  - 'packages/*'\n  - \"apps/web\"\n  - '!**/test/**'\ncatalog:\n  - react\n";
+        assert_eq!(
+            pnpm_workspace_packages(content),
+            vec!["packages/*".to_string(), "apps/web".to_string()]
+        );
+    }
+}
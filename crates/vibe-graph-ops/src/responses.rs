@@ -7,10 +7,11 @@ use std::path::PathBuf;
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
-use vibe_graph_core::{GitChangeSnapshot, SourceCodeGraph};
+use vibe_graph_core::{GitChangeSnapshot, GraphDiff, SourceCodeGraph, StructuralDiff};
 
+use crate::packages::{CrossRepoDependency, RepositoryPackages};
 use crate::project::Project;
-use crate::store::Manifest;
+use crate::store::{GraphSnapshotInfo, Manifest};
 use crate::workspace::WorkspaceInfo;
 
 /// Response from a sync operation.
@@ -25,7 +26,7 @@ pub struct SyncResponse {
     /// Path where the project was saved or cloned.
     pub path: PathBuf,
 
-    /// Whether a new snapshot was created.
+    /// Where the snapshot created by this sync was written, if any.
     pub snapshot_created: Option<PathBuf>,
 
     /// Detected git remote (for single repos).
@@ -60,6 +61,10 @@ pub struct GraphResponse {
 
     /// Whether the graph was loaded from cache.
     pub from_cache: bool,
+
+    /// Graph snapshot taken by this request, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_created: Option<GraphSnapshotInfo>,
 }
 
 impl GraphResponse {
@@ -74,6 +79,49 @@ impl GraphResponse {
     }
 }
 
+/// Response from a graph update operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphUpdateResponse {
+    /// The updated graph.
+    pub graph: SourceCodeGraph,
+
+    /// Nodes and edges added or removed by the update.
+    pub diff: GraphDiff,
+
+    /// Path where the graph was saved.
+    pub saved_path: PathBuf,
+}
+
+/// Response from a graph diff operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDiffResponse {
+    /// The older snapshot.
+    pub from: GraphSnapshotInfo,
+
+    /// The newer snapshot, `None` for the current graph.
+    pub to: Option<GraphSnapshotInfo>,
+
+    /// What changed between them.
+    pub diff: StructuralDiff,
+}
+
+/// Response listing graph snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphSnapshotsResponse {
+    /// Graph snapshots, newest first.
+    pub snapshots: Vec<GraphSnapshotInfo>,
+}
+
+/// Response listing repository packages and cross-repo dependencies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoDependenciesResponse {
+    /// Packages found in each repository, in workspace order.
+    pub repositories: Vec<RepositoryPackages>,
+
+    /// Dependencies on packages published by another repository.
+    pub dependencies: Vec<CrossRepoDependency>,
+}
+
 /// Response from a status operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusResponse {
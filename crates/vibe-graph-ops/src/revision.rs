@@ -0,0 +1,186 @@
+//! Reading a workspace as it was at a git revision.
+//!
+//! A revision (`main`, `v1.2`, `HEAD~3`, a merge-base SHA) is resolved to a
+//! commit whose tree is read straight from the object database, so the
+//! working tree is never touched. Files are placed at the paths they would
+//! have in a checkout, which keeps node IDs comparable with the graph of the
+//! working tree.
+
+use std::path::{Path, PathBuf};
+
+use git2::{Object, ObjectType, Oid, Tree};
+use tracing::debug;
+
+use crate::error::{OpsError, OpsResult};
+use crate::project::{Project, Repository, Source};
+use crate::resolve::ManifestSource;
+use crate::scan::{Exclusion, ScanFilter, ScanOptions};
+
+/// Git file mode of symbolic links, which are not followed.
+const LINK_MODE: i32 = 0o120000;
+
+/// A workspace at one commit of the git repository containing it.
+pub(crate) struct Revision {
+    repo: git2::Repository,
+    /// Workspace root in the working tree.
+    root: PathBuf,
+    /// Tree of the workspace directory at the commit.
+    tree_id: Oid,
+    /// Full commit ID.
+    pub commit: String,
+    /// Commit time, in seconds since the Unix epoch.
+    pub time: u64,
+}
+
+impl Revision {
+    /// Resolve `rev` in the repository containing the workspace at `root`.
+    pub fn resolve(root: &Path, rev: &str) -> OpsResult<Self> {
+        let repo = git2::Repository::discover(root)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| OpsError::path_resolution(root, "bare repositories have no workspace"))?
+            .canonicalize()?;
+        let prefix = root
+            .strip_prefix(&workdir)
+            .map_err(|_| OpsError::path_resolution(root, "workspace is outside the repository"))?
+            .to_path_buf();
+
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        let tree = commit.tree()?;
+        let tree_id = if prefix.as_os_str().is_empty() {
+            tree.id()
+        } else {
+            let entry = tree.get_path(&prefix)?;
+            if entry.kind() != Some(ObjectType::Tree) {
+                return Err(OpsError::path_resolution(
+                    root,
+                    format!("not a directory at {}", rev),
+                ));
+            }
+            entry.id()
+        };
+        let commit_id = commit.id().to_string();
+        let time = commit.time().seconds().max(0) as u64;
+        drop(tree);
+        drop(commit);
+
+        debug!(rev, commit = %commit_id, tree = %tree_id, "Resolved revision");
+        Ok(Self {
+            repo,
+            root: root.to_path_buf(),
+            tree_id,
+            commit: commit_id,
+            time,
+        })
+    }
+
+    /// ID of the workspace's tree at this revision. Equal trees produce
+    /// equal graphs, so this is the cache key for revision graphs.
+    pub fn tree_id(&self) -> String {
+        self.tree_id.to_string()
+    }
+
+    /// The workspace's files at this revision, filtered like a scan (minus
+    /// git ignores). Text files up to `max_content_size` bytes carry their
+    /// content.
+    pub fn project(&self, max_content_size: u64) -> OpsResult<Project> {
+        let options = ScanOptions::default();
+        let vg_toml = self.read(&self.root.join("vg.toml"));
+        let filter = ScanFilter::for_tree(&self.root, &options, vg_toml.as_deref());
+
+        let mut project = Project::local(self.root.clone());
+        let mut repo = Repository::new(
+            &project.name,
+            self.root.display().to_string(),
+            self.root.clone(),
+        );
+        let tree = self.repo.find_tree(self.tree_id)?;
+        self.collect(&tree, &self.root, &filter, max_content_size, &mut repo)?;
+
+        repo.sources.sort_by(|a, b| a.path.cmp(&b.path));
+        repo.excluded.sort_by(|a, b| a.path.cmp(&b.path));
+        debug!(
+            commit = %self.commit,
+            files = repo.sources.len(),
+            "Read revision tree"
+        );
+        project.repositories.push(repo);
+        Ok(project)
+    }
+
+    fn collect(
+        &self,
+        tree: &Tree<'_>,
+        dir: &Path,
+        filter: &ScanFilter<'_>,
+        max_content_size: u64,
+        repo: &mut Repository,
+    ) -> OpsResult<()> {
+        for entry in tree.iter() {
+            let Some(name) = entry.name() else {
+                continue;
+            };
+            let path = dir.join(name);
+            // Submodules (commit entries) and symlinks are not followed
+            let is_dir = match entry.kind() {
+                Some(ObjectType::Tree) => true,
+                Some(ObjectType::Blob) if entry.filemode() != LINK_MODE => false,
+                _ => continue,
+            };
+
+            if let Some(reason) = filter.tree_exclusion(&path, is_dir) {
+                repo.excluded.push(Exclusion {
+                    path: path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf(),
+                    is_dir,
+                    reason,
+                });
+                continue;
+            }
+
+            if is_dir {
+                let subtree = self.repo.find_tree(entry.id())?;
+                self.collect(&subtree, &path, filter, max_content_size, repo)?;
+            } else {
+                let blob = self.repo.find_blob(entry.id())?;
+                let mut source = Source::from_bytes(path, &self.root, blob.content());
+                if source.is_text() && blob.size() as u64 <= max_content_size {
+                    source.content = Some(String::from_utf8_lossy(blob.content()).into_owned());
+                }
+                repo.sources.push(source);
+            }
+        }
+        Ok(())
+    }
+
+    /// The object at a working-tree `path`, looked up in this revision.
+    fn object(&self, path: &Path) -> Option<Object<'_>> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let tree = self.repo.find_tree(self.tree_id).ok()?;
+        if relative.as_os_str().is_empty() {
+            return Some(tree.into_object());
+        }
+        tree.get_path(relative).ok()?.to_object(&self.repo).ok()
+    }
+}
+
+impl ManifestSource for Revision {
+    fn read(&self, path: &Path) -> Option<String> {
+        let blob = self.object(path)?.into_blob().ok()?;
+        String::from_utf8(blob.content().to_vec()).ok()
+    }
+
+    fn subdirs(&self, dir: &Path) -> Vec<PathBuf> {
+        let Some(tree) = self.object(dir).and_then(|o| o.into_tree().ok()) else {
+            return Vec::new();
+        };
+        tree.iter()
+            .filter(|entry| entry.kind() == Some(ObjectType::Tree))
+            .filter_map(|entry| entry.name().map(|name| dir.join(name)))
+            .collect()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.object(path)
+            .is_some_and(|o| o.kind() == Some(ObjectType::Blob))
+    }
+}
@@ -1,68 +1,133 @@
 //! Directory scanning utilities.
+//!
+//! A path is left out of a scan when it is hidden, one of the built-in
+//! build/dependency directories, listed in the `[ignore]` section of
+//! `vg.toml` or in [`ScanOptions::exclude`], or ignored by git rules:
+//! `.gitignore` and `.ignore` files at any depth (negations included),
+//! `.git/info/exclude` and the global excludes file. Every exclusion is
+//! recorded on the [`Repository`] together with its reason.
+//!
+//! Directories are walked and files are read and hashed on the rayon
+//! thread pool. [`stream_directory`] yields records as they are produced;
+//! [`scan_directory`] collects them into a repository in path order.
+//!
+//! The same rules, minus git ignores, select the files of a git tree when
+//! a graph is built at a revision.
 
-use std::path::Path;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
 
-use walkdir::WalkDir;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use rayon::Scope;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::error::OpsResult;
 use crate::project::{Repository, Source};
 
-/// Scan a directory and populate repository sources.
-pub fn scan_directory(repo: &mut Repository, path: &Path) -> OpsResult<()> {
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e) && !is_blacklisted(e))
-        .filter_map(|e| e.ok())
-    {
-        if entry.path().is_file() {
-            if let Ok(source) = Source::from_path(entry.path().to_path_buf(), &repo.local_path) {
-                repo.sources.push(source);
+/// Directories skipped in every scan.
+const BLACKLIST: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "__pycache__",
+    ".venv",
+    "venv",
+    ".git",
+    "vendor",
+    ".next",
+    "coverage",
+    ".turbo",
+    "pkg",
+    ".cargo",
+];
+
+/// Why a path was left out of a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum ExclusionReason {
+    /// Name starts with `.`.
+    Hidden,
+    /// Built-in build or dependency directory (`node_modules`, `target`, ...).
+    Builtin { name: String },
+    /// Listed in `[ignore] directories` of `vg.toml`.
+    ConfigDirectory { name: String },
+    /// Matches a glob in `[ignore] patterns` of `vg.toml`.
+    ConfigPattern { pattern: String },
+    /// Matches a [`ScanOptions::exclude`] pattern.
+    ScanOption { pattern: String },
+    /// Matches a git ignore rule. `source` is the file defining it.
+    Gitignore {
+        pattern: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<PathBuf>,
+    },
+    /// Larger than [`ScanOptions::max_size`].
+    TooLarge { size: u64 },
+    /// Extension not in [`ScanOptions::extensions`].
+    Extension,
+}
+
+impl fmt::Display for ExclusionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExclusionReason::Hidden => write!(f, "hidden"),
+            ExclusionReason::Builtin { name } => write!(f, "built-in ignore '{}'", name),
+            ExclusionReason::ConfigDirectory { name } => {
+                write!(f, "vg.toml ignore directory '{}'", name)
+            }
+            ExclusionReason::ConfigPattern { pattern } => {
+                write!(f, "vg.toml ignore pattern '{}'", pattern)
             }
+            ExclusionReason::ScanOption { pattern } => write!(f, "exclude option '{}'", pattern),
+            ExclusionReason::Gitignore {
+                pattern,
+                source: Some(source),
+            } => write!(f, "'{}' in {}", pattern, source.display()),
+            ExclusionReason::Gitignore {
+                pattern,
+                source: None,
+            } => write!(f, "git ignore '{}'", pattern),
+            ExclusionReason::TooLarge { size } => write!(f, "larger than limit ({} bytes)", size),
+            ExclusionReason::Extension => write!(f, "extension not selected"),
         }
     }
-    Ok(())
 }
 
-/// Check if entry is hidden (starts with .).
-fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with('.'))
-        .unwrap_or(false)
-}
-
-/// Check if entry is in a blacklisted directory.
-fn is_blacklisted(entry: &walkdir::DirEntry) -> bool {
-    const BLACKLIST: &[&str] = &[
-        "node_modules",
-        "target",
-        "dist",
-        "build",
-        "__pycache__",
-        ".venv",
-        "venv",
-        ".git",
-        "vendor",
-        ".next",
-        "coverage",
-        ".turbo",
-        "pkg",
-        ".cargo",
-    ];
-
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| BLACKLIST.contains(&s))
-        .unwrap_or(false)
+/// A path left out of a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exclusion {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// Whether the path is a directory (its contents were not visited).
+    #[serde(default)]
+    pub is_dir: bool,
+    #[serde(flatten)]
+    pub reason: ExclusionReason,
+}
+
+/// One record produced by [`stream_directory`].
+#[derive(Debug, Clone)]
+pub enum ScanEntry {
+    /// A file included in the scan.
+    Source(Source),
+    /// A path left out of the scan.
+    Excluded(Exclusion),
+}
+
+/// Scan a directory and populate repository sources.
+pub fn scan_directory(repo: &mut Repository, path: &Path) -> OpsResult<()> {
+    scan_directory_with_options(repo, path, &ScanOptions::default())
 }
 
 /// Customizable scan options.
 #[derive(Debug, Clone, Default)]
-#[allow(dead_code)]
 pub struct ScanOptions {
-    /// Additional paths to exclude.
+    /// Additional paths to exclude (gitignore syntax, relative to the root).
     pub exclude: Vec<String>,
     /// Maximum file size to include (in bytes).
     pub max_size: Option<u64>,
@@ -72,7 +137,6 @@ pub struct ScanOptions {
     pub include_hidden: bool,
 }
 
-#[allow(dead_code)]
 impl ScanOptions {
     /// Create scan options with default settings.
     pub fn new() -> Self {
@@ -105,61 +169,502 @@ impl ScanOptions {
 }
 
 /// Advanced scan with options.
-#[allow(dead_code)]
+///
+/// Sources and exclusions are appended sorted by path, so repeated scans of
+/// an unchanged tree produce identical repositories.
 pub fn scan_directory_with_options(
     repo: &mut Repository,
     path: &Path,
     options: &ScanOptions,
 ) -> OpsResult<()> {
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_entry(|e| {
-            // Check hidden
-            if !options.include_hidden && is_hidden(e) {
-                return false;
-            }
-            // Check blacklist
-            if is_blacklisted(e) {
-                return false;
+    // Scoped rather than streamed: this may itself run on a rayon worker
+    // (one task per repository), where blocking on a channel could starve
+    // the pool.
+    let (tx, rx) = mpsc::channel();
+    let filter = ScanFilter::new(path, options);
+    rayon::scope(|scope| filter.walk(scope, path.to_path_buf(), Vec::new(), tx));
+
+    let mut sources = Vec::new();
+    let mut excluded = Vec::new();
+    for entry in rx {
+        match entry {
+            ScanEntry::Source(source) => sources.push(source),
+            ScanEntry::Excluded(exclusion) => excluded.push(exclusion),
+        }
+    }
+    sources.sort_by(|a, b| a.path.cmp(&b.path));
+    excluded.sort_by(|a, b| a.path.cmp(&b.path));
+    repo.sources.extend(sources);
+    repo.excluded.extend(excluded);
+    Ok(())
+}
+
+/// Scan a directory in the background, yielding records as they are
+/// produced.
+///
+/// The walk runs on the rayon thread pool, so records arrive in no
+/// particular order. Sources carry their content hash; relative paths are
+/// relative to `path`. Do not drain the iterator from inside a rayon task;
+/// use [`scan_directory_with_options`] there.
+pub fn stream_directory(path: &Path, options: ScanOptions) -> impl Iterator<Item = ScanEntry> {
+    let (tx, rx) = mpsc::channel();
+    let root = path.to_path_buf();
+    rayon::spawn(move || {
+        let filter = ScanFilter::new(&root, &options);
+        rayon::scope(|scope| filter.walk(scope, root.clone(), Vec::new(), tx));
+    });
+    rx.into_iter()
+}
+
+// =============================================================================
+// Ignore rules
+// =============================================================================
+
+/// The `[ignore]` section of `vg.toml` (same shape as the automaton's
+/// `ProjectConfig`).
+#[derive(Debug, Default, Deserialize)]
+struct IgnoreConfig {
+    #[serde(default)]
+    directories: Vec<String>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct VgToml {
+    #[serde(default)]
+    ignore: IgnoreConfig,
+}
+
+impl IgnoreConfig {
+    fn load(root: &Path) -> Self {
+        let path = root.join("vg.toml");
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content, &path),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn parse(content: &str, path: &Path) -> Self {
+        match toml::from_str::<VgToml>(content) {
+            Ok(config) => config.ignore,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable vg.toml");
+                Self::default()
             }
-            // Check custom exclude
-            if let Some(name) = e.file_name().to_str() {
-                if options.exclude.iter().any(|ex| name == ex) {
-                    return false;
+        }
+    }
+}
+
+/// Decides which paths under a root are scanned.
+pub(crate) struct ScanFilter<'a> {
+    root: PathBuf,
+    options: &'a ScanOptions,
+    config_directories: Vec<String>,
+    config_patterns: Gitignore,
+    option_patterns: Gitignore,
+    /// `.git/info/exclude`, then the global excludes file.
+    repo_rules: Vec<Gitignore>,
+}
+
+impl<'a> ScanFilter<'a> {
+    pub(crate) fn new(root: &Path, options: &'a ScanOptions) -> Self {
+        let mut repo_rules = Vec::new();
+        let info_exclude = root.join(".git").join("info").join("exclude");
+        if info_exclude.is_file() {
+            repo_rules.push(ignore_file(root, &[info_exclude]));
+        }
+        let (global, _) = Gitignore::global();
+        repo_rules.push(global);
+
+        Self::with_config(root, options, IgnoreConfig::load(root), repo_rules)
+    }
+
+    /// Filter for the files of a git tree rooted at `root`. `vg_toml` is
+    /// the tree's own `vg.toml`; git ignore rules do not apply, as tracked
+    /// files are never ignored.
+    pub(crate) fn for_tree(root: &Path, options: &'a ScanOptions, vg_toml: Option<&str>) -> Self {
+        let config = vg_toml
+            .map(|content| IgnoreConfig::parse(content, &root.join("vg.toml")))
+            .unwrap_or_default();
+        Self::with_config(root, options, config, Vec::new())
+    }
+
+    fn with_config(
+        root: &Path,
+        options: &'a ScanOptions,
+        config: IgnoreConfig,
+        repo_rules: Vec<Gitignore>,
+    ) -> Self {
+        let lines = |patterns: &[String]| {
+            let mut builder = GitignoreBuilder::new(root);
+            for pattern in patterns {
+                if let Err(e) = builder.add_line(None, pattern) {
+                    warn!(pattern = %pattern, error = %e, "Invalid ignore pattern");
                 }
             }
-            true
-        })
-        .filter_map(|e| e.ok())
-    {
-        if !entry.path().is_file() {
-            continue;
-        }
-
-        // Check extensions filter
-        if !options.extensions.is_empty() {
-            let ext = entry
-                .path()
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("");
-            if !options.extensions.iter().any(|e| e == ext) {
+            builder.build().unwrap_or_else(|_| Gitignore::empty())
+        };
+
+        Self {
+            root: root.to_path_buf(),
+            options,
+            config_patterns: lines(&config.patterns),
+            config_directories: config.directories,
+            option_patterns: lines(&options.exclude),
+            repo_rules,
+        }
+    }
+
+    /// Why `path` (anywhere under the root) would be excluded, checking it
+    /// and every parent directory. Used for paths that arrive outside a walk.
+    pub(crate) fn exclusion(&self, path: &Path) -> Option<ExclusionReason> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut stack = Vec::new();
+        let mut current = self.root.clone();
+        let components: Vec<_> = relative.components().collect();
+        for (i, component) in components.iter().enumerate() {
+            stack.push(directory_rules(&current));
+            current.push(component);
+            let is_dir = i + 1 < components.len() || current.is_dir();
+            if let Some(reason) = self.check(&current, is_dir, &stack) {
+                return Some(reason);
+            }
+        }
+        None
+    }
+
+    /// Why a path of a git tree is excluded, without consulting ignore
+    /// files. The caller walks the tree and checks parents first.
+    pub(crate) fn tree_exclusion(&self, path: &Path, is_dir: bool) -> Option<ExclusionReason> {
+        self.check(path, is_dir, &[])
+    }
+
+    /// Walk `dir`, spawning a task per subdirectory and per included file.
+    /// `stack` holds the ignore rules of every ancestor of `dir`.
+    fn walk<'s>(
+        &'s self,
+        scope: &Scope<'s>,
+        dir: PathBuf,
+        mut stack: Vec<Gitignore>,
+        tx: Sender<ScanEntry>,
+    ) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        stack.push(directory_rules(&dir));
+        for entry in entries {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // Symlinked directories are not followed
+            let is_dir = file_type.is_dir();
+            if !is_dir && !path.is_file() {
+                continue;
+            }
+
+            let reason = self.check(&path, is_dir, &stack).or_else(|| {
+                if is_dir {
+                    None
+                } else {
+                    self.file_limits(&path)
+                }
+            });
+            if let Some(reason) = reason {
+                let _ = tx.send(ScanEntry::Excluded(Exclusion {
+                    path: path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf(),
+                    is_dir,
+                    reason,
+                }));
                 continue;
             }
+
+            let tx = tx.clone();
+            if is_dir {
+                let stack = stack.clone();
+                scope.spawn(move |scope| self.walk(scope, path, stack, tx));
+            } else {
+                scope.spawn(move |_| {
+                    if let Ok(source) = Source::from_path(path, &self.root) {
+                        let _ = tx.send(ScanEntry::Source(source));
+                    }
+                });
+            }
         }
+    }
+
+    /// Rules that apply to any path, in precedence order. `stack` holds the
+    /// `.gitignore`/`.ignore` rules of each ancestor directory, outermost
+    /// first.
+    fn check(&self, path: &Path, is_dir: bool, stack: &[Gitignore]) -> Option<ExclusionReason> {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
-        // Check size limit
-        if let Some(max_size) = options.max_size {
-            if let Ok(meta) = entry.metadata() {
-                if meta.len() > max_size {
-                    continue;
+        if BLACKLIST.contains(&name) {
+            return Some(ExclusionReason::Builtin {
+                name: name.to_string(),
+            });
+        }
+        if !self.options.include_hidden && name.starts_with('.') {
+            return Some(ExclusionReason::Hidden);
+        }
+        if is_dir && self.config_directories.iter().any(|d| d == name) {
+            return Some(ExclusionReason::ConfigDirectory {
+                name: name.to_string(),
+            });
+        }
+        if let Match::Ignore(glob) = self.config_patterns.matched(path, is_dir) {
+            return Some(ExclusionReason::ConfigPattern {
+                pattern: glob.original().to_string(),
+            });
+        }
+        if let Match::Ignore(glob) = self.option_patterns.matched(path, is_dir) {
+            return Some(ExclusionReason::ScanOption {
+                pattern: glob.original().to_string(),
+            });
+        }
+
+        // Deeper rules override shallower ones; the first decisive match wins
+        for rules in stack.iter().rev().chain(&self.repo_rules) {
+            match rules.matched(path, is_dir) {
+                Match::None => continue,
+                Match::Whitelist(_) => return None,
+                Match::Ignore(glob) => {
+                    return Some(ExclusionReason::Gitignore {
+                        pattern: glob.original().to_string(),
+                        source: glob.from().map(Path::to_path_buf),
+                    })
                 }
             }
         }
+        None
+    }
 
-        if let Ok(source) = Source::from_path(entry.path().to_path_buf(), &repo.local_path) {
-            repo.sources.push(source);
+    fn file_limits(&self, path: &Path) -> Option<ExclusionReason> {
+        if !self.options.extensions.is_empty() {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !self.options.extensions.iter().any(|e| e == ext) {
+                return Some(ExclusionReason::Extension);
+            }
         }
+        if let Some(max_size) = self.options.max_size {
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if size > max_size {
+                return Some(ExclusionReason::TooLarge { size });
+            }
+        }
+        None
+    }
+}
+
+/// `.gitignore` and `.ignore` of one directory; `.ignore` takes precedence.
+fn directory_rules(dir: &Path) -> Gitignore {
+    let files: Vec<PathBuf> = [".gitignore", ".ignore"]
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file())
+        .collect();
+    if files.is_empty() {
+        return Gitignore::empty();
+    }
+    ignore_file(dir, &files)
+}
+
+fn ignore_file(root: &Path, files: &[PathBuf]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for file in files {
+        if let Some(e) = builder.add(file) {
+            warn!(path = %file.display(), error = %e, "Failed to read ignore file");
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn scan(root: &Path, options: &ScanOptions) -> Repository {
+        let mut repo = Repository::new("test", "", root.to_path_buf());
+        scan_directory_with_options(&mut repo, root, options).unwrap();
+        repo
+    }
+
+    fn reason_for<'r>(repo: &'r Repository, rel: &str) -> Option<&'r ExclusionReason> {
+        repo.excluded
+            .iter()
+            .find(|e| e.path == Path::new(rel))
+            .map(|e| &e.reason)
+    }
+
+    #[test]
+    fn test_scan_honours_nested_gitignore_and_negation() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(root, ".gitignore", "*.pb.rs\ngenerated/\n");
+        write(root, "src/main.rs", "");
+        write(root, "src/api.pb.rs", "");
+        write(root, "src/generated/out.rs", "");
+        write(root, "proto/.gitignore", "!keep.pb.rs\n");
+        write(root, "proto/keep.pb.rs", "");
+        write(root, "proto/drop.pb.rs", "");
+        write(root, "third_party/.ignore", "*\n");
+        write(root, "third_party/lib.c", "");
+
+        let repo = scan(root, &ScanOptions::default());
+        let mut files: Vec<_> = repo
+            .sources
+            .iter()
+            .map(|s| s.relative_path.clone())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["proto/keep.pb.rs", "src/main.rs"]);
+
+        assert_eq!(
+            reason_for(&repo, "src/api.pb.rs"),
+            Some(&ExclusionReason::Gitignore {
+                pattern: "*.pb.rs".to_string(),
+                source: Some(root.join(".gitignore")),
+            })
+        );
+        let generated = repo
+            .excluded
+            .iter()
+            .find(|e| e.path == Path::new("src/generated"))
+            .unwrap();
+        assert!(generated.is_dir);
+        assert!(matches!(
+            reason_for(&repo, "third_party/lib.c"),
+            Some(ExclusionReason::Gitignore { pattern, .. }) if pattern == "*"
+        ));
+        assert_eq!(
+            reason_for(&repo, ".gitignore"),
+            Some(&ExclusionReason::Hidden)
+        );
+    }
+
+    #[test]
+    fn test_scan_applies_vg_toml_and_options() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "vg.toml",
+            "[ignore]\ndirectories = [\"fixtures\"]\npatterns = [\"docs/**/*.md\"]\n",
+        );
+        write(root, "src/lib.rs", "");
+        write(root, "src/fixtures/big.json", "");
+        write(root, "docs/guide/intro.md", "");
+        write(root, "scratch/tmp.rs", "");
+        write(root, "target/debug/out", "");
+
+        let repo = scan(root, &ScanOptions::new().exclude(["scratch"]));
+        let mut files: Vec<_> = repo
+            .sources
+            .iter()
+            .map(|s| s.relative_path.clone())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["src/lib.rs", "vg.toml"]);
+
+        assert_eq!(
+            reason_for(&repo, "src/fixtures"),
+            Some(&ExclusionReason::ConfigDirectory {
+                name: "fixtures".to_string()
+            })
+        );
+        assert_eq!(
+            reason_for(&repo, "docs/guide/intro.md"),
+            Some(&ExclusionReason::ConfigPattern {
+                pattern: "docs/**/*.md".to_string()
+            })
+        );
+        assert_eq!(
+            reason_for(&repo, "scratch"),
+            Some(&ExclusionReason::ScanOption {
+                pattern: "scratch".to_string()
+            })
+        );
+        assert_eq!(
+            reason_for(&repo, "target"),
+            Some(&ExclusionReason::Builtin {
+                name: "target".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_filter_checks_parents_of_single_paths() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(root, ".gitignore", "gen/\n");
+        write(root, "gen/a/b.rs", "");
+        write(root, "src/c.rs", "");
+
+        let options = ScanOptions::default();
+        let filter = ScanFilter::new(root, &options);
+        assert!(matches!(
+            filter.exclusion(&root.join("gen/a/b.rs")),
+            Some(ExclusionReason::Gitignore { .. })
+        ));
+        assert_eq!(filter.exclusion(&root.join("src/c.rs")), None);
+    }
+
+    #[test]
+    fn test_scan_hashes_sources_and_detects_real_changes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for i in 0..40 {
+            write(
+                root,
+                &format!("src/m{}/lib.rs", i % 7),
+                &format!("// {}", i),
+            );
+            write(root, &format!("src/f{:02}.rs", i), "fn main() {}\n");
+        }
+
+        let repo = scan(root, &ScanOptions::default());
+        let paths: Vec<_> = repo.sources.iter().map(|s| s.path.clone()).collect();
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(paths, sorted);
+        assert_eq!(repo.sources.len(), 47);
+        assert_eq!(
+            repo.sources[0].hash, repo.sources[1].hash,
+            "identical content hashes identically"
+        );
+        assert_eq!(repo.sources[0].hash.as_ref().unwrap().len(), 64);
+
+        let streamed = stream_directory(root, ScanOptions::default())
+            .filter(|e| matches!(e, ScanEntry::Source(_)))
+            .count();
+        assert_eq!(streamed, 47);
+
+        let mut project = crate::project::Project::local(root.to_path_buf());
+        project.repositories.push(repo);
+        let before = project.content_hash().unwrap();
+
+        let touched = root.join("src/f00.rs");
+        let edited = root.join("src/f01.rs");
+        write(root, "src/f00.rs", "fn main() {}\n");
+        write(root, "src/f01.rs", "fn main() { run() }\n");
+        let applied = project
+            .apply_changes(&vibe_graph_core::GraphChangeSet {
+                modified: vec![touched, edited.clone()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(applied.modified, vec![edited]);
+        assert_ne!(project.content_hash().unwrap(), before);
     }
-    Ok(())
 }
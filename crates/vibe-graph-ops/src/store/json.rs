@@ -0,0 +1,234 @@
+//! The original storage format: one pretty-printed JSON file per item.
+//!
+//! ```text
+//! .self/
+//! ├── project.json
+//! ├── graph.json
+//! ├── snapshots/
+//! │   ├── <timestamp>.json
+//! │   └── graphs/
+//! │       ├── index.json
+//! │       └── <id>.json
+//! └── revisions/
+//!     └── <tree>.json
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use vibe_graph_core::SourceCodeGraph;
+
+use super::{GraphSnapshotInfo, StorageBackend, StoreBackendKind};
+use crate::error::{OpsError, OpsResult};
+use crate::project::Project;
+
+pub(super) const PROJECT_FILE: &str = "project.json";
+pub(super) const GRAPH_FILE: &str = "graph.json";
+pub(super) const SNAPSHOTS_DIR: &str = "snapshots";
+const GRAPH_SNAPSHOTS_DIR: &str = "graphs";
+const GRAPH_SNAPSHOT_INDEX: &str = "index.json";
+const REVISIONS_DIR: &str = "revisions";
+
+/// JSON files under `.self`.
+#[derive(Debug, Clone)]
+pub struct JsonBackend {
+    self_dir: PathBuf,
+}
+
+impl JsonBackend {
+    /// Create a backend over a `.self` directory.
+    pub fn new(self_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            self_dir: self_dir.into(),
+        }
+    }
+
+    /// Whether any JSON store files exist in `self_dir`.
+    pub(super) fn has_data(self_dir: &Path) -> bool {
+        self_dir.join(PROJECT_FILE).exists()
+            || self_dir.join(GRAPH_FILE).exists()
+            || !Self::new(self_dir)
+                .list_snapshots()
+                .unwrap_or_default()
+                .is_empty()
+            || self_dir
+                .join(SNAPSHOTS_DIR)
+                .join(GRAPH_SNAPSHOTS_DIR)
+                .exists()
+            || self_dir.join(REVISIONS_DIR).exists()
+    }
+
+    /// Remove the files of this backend, keeping the directory.
+    pub(super) fn remove_files(&self) -> OpsResult<()> {
+        for file in [PROJECT_FILE, GRAPH_FILE] {
+            let path = self.self_dir.join(file);
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        for id in self.list_snapshots()? {
+            std::fs::remove_file(self.snapshot_path(&id))?;
+        }
+        for dir in [
+            self.graph_snapshots_dir(),
+            self.self_dir.join(REVISIONS_DIR),
+        ] {
+            if dir.exists() {
+                std::fs::remove_dir_all(dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn graph_snapshots_dir(&self) -> PathBuf {
+        self.self_dir.join(SNAPSHOTS_DIR).join(GRAPH_SNAPSHOTS_DIR)
+    }
+
+    fn save_graph_snapshot_index(&self, index: &[GraphSnapshotInfo]) -> OpsResult<()> {
+        let json = serde_json::to_string_pretty(index)?;
+        std::fs::write(self.graph_snapshots_dir().join(GRAPH_SNAPSHOT_INDEX), json)?;
+        Ok(())
+    }
+
+    fn snapshot_path(&self, id: &str) -> PathBuf {
+        self.self_dir
+            .join(SNAPSHOTS_DIR)
+            .join(format!("{}.json", id))
+    }
+}
+
+impl StorageBackend for JsonBackend {
+    fn kind(&self) -> StoreBackendKind {
+        StoreBackendKind::Json
+    }
+
+    fn location(&self) -> PathBuf {
+        self.self_dir.clone()
+    }
+
+    fn save_project(&self, project: &Project) -> OpsResult<()> {
+        let json = serde_json::to_string_pretty(project)?;
+        std::fs::write(self.self_dir.join(PROJECT_FILE), json)?;
+        Ok(())
+    }
+
+    fn load_project(&self) -> OpsResult<Option<Project>> {
+        let path = self.self_dir.join(PROJECT_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    fn save_graph(&self, graph: &SourceCodeGraph) -> OpsResult<PathBuf> {
+        let path = self.self_dir.join(GRAPH_FILE);
+        let json = serde_json::to_string_pretty(graph)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    fn load_graph(&self) -> OpsResult<Option<SourceCodeGraph>> {
+        let path = self.self_dir.join(GRAPH_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    fn has_graph(&self) -> bool {
+        self.self_dir.join(GRAPH_FILE).exists()
+    }
+
+    fn save_snapshot(&self, id: &str, project: &Project) -> OpsResult<PathBuf> {
+        let dir = self.self_dir.join(SNAPSHOTS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let path = self.snapshot_path(id);
+        let json = serde_json::to_string_pretty(project)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    fn list_snapshots(&self) -> OpsResult<Vec<String>> {
+        let dir = self.self_dir.join(SNAPSHOTS_DIR);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut ids: Vec<String> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+
+        // Newest (largest timestamp) first
+        ids.sort_by(|a, b| b.cmp(a));
+        Ok(ids)
+    }
+
+    fn load_snapshot(&self, id: &str) -> OpsResult<Project> {
+        let path = self.snapshot_path(id);
+        if !path.exists() {
+            return Err(OpsError::SnapshotNotFound { id: id.to_string() });
+        }
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save_graph_snapshot(
+        &self,
+        info: &GraphSnapshotInfo,
+        graph: &SourceCodeGraph,
+    ) -> OpsResult<()> {
+        let dir = self.graph_snapshots_dir();
+        std::fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string(graph)?;
+        std::fs::write(dir.join(format!("{}.json", info.id)), json)?;
+
+        let mut index = self.list_graph_snapshots()?;
+        index.retain(|s| s.id != info.id);
+        index.insert(0, info.clone());
+        self.save_graph_snapshot_index(&index)
+    }
+
+    fn list_graph_snapshots(&self) -> OpsResult<Vec<GraphSnapshotInfo>> {
+        let path = self.graph_snapshots_dir().join(GRAPH_SNAPSHOT_INDEX);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let json = std::fs::read_to_string(path)?;
+        let mut index: Vec<GraphSnapshotInfo> = serde_json::from_str(&json)?;
+        index.sort_by(|a, b| b.created.cmp(&a.created));
+        Ok(index)
+    }
+
+    fn load_graph_snapshot(&self, id: &str) -> OpsResult<SourceCodeGraph> {
+        let path = self.graph_snapshots_dir().join(format!("{}.json", id));
+        if !path.exists() {
+            return Err(OpsError::SnapshotNotFound { id: id.to_string() });
+        }
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save_revision_graph(&self, key: &str, graph: &SourceCodeGraph) -> OpsResult<()> {
+        let dir = self.self_dir.join(REVISIONS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string(graph)?;
+        std::fs::write(dir.join(format!("{}.json", key)), json)?;
+        Ok(())
+    }
+
+    fn load_revision_graph(&self, key: &str) -> OpsResult<Option<SourceCodeGraph>> {
+        let path = self
+            .self_dir
+            .join(REVISIONS_DIR)
+            .join(format!("{}.json", key));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+}
@@ -0,0 +1,763 @@
+//! Persistence layer using `.self` folder.
+//!
+//! [`Store`] keeps the small manifest as `manifest.json` and delegates the
+//! project, graph and snapshots to a [`StorageBackend`]:
+//!
+//! - [`SqliteBackend`] (default): indexed tables in `.self/store.db`,
+//!   supporting partial loads such as [`StorageBackend::load_subgraph`]
+//! - [`JsonBackend`]: the original pretty-printed JSON files
+//!
+//! Graph snapshots record the graph at a git commit (or a point in time)
+//! so builds can be compared later with
+//! [`StructuralDiff`](vibe_graph_core::StructuralDiff).
+//!
+//! Graphs built at git revisions are cached by tree ID, so building the
+//! same tree again (another branch, a rebased commit) is a lookup.
+//!
+//! The backend is picked with the `VG_STORE_BACKEND` environment variable
+//! (`sqlite` or `json`). A JSON store opened with the SQLite backend is
+//! imported into the database on the first write (or
+//! [`SqliteBackend::migrate`]) and its files removed; reads before that
+//! leave it untouched.
+
+mod json;
+mod sqlite;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use vibe_graph_core::{NodeId, SourceCodeGraph, ID_SCHEME_KEY, PATH_ID_SCHEME};
+use walkdir::WalkDir;
+
+use crate::error::{OpsError, OpsResult};
+use crate::project::Project;
+use crate::workspace::WorkspaceKind;
+
+pub use json::JsonBackend;
+pub use sqlite::SqliteBackend;
+
+/// Name of the persistence folder.
+pub const SELF_DIR: &str = ".self";
+
+/// Environment variable selecting the storage backend.
+pub const STORE_BACKEND_ENV: &str = "VG_STORE_BACKEND";
+
+const MANIFEST_FILE: &str = "manifest.json";
+const ID_MIGRATION_FILE: &str = "id_migration.json";
+
+/// Length of the abbreviated commit IDs used as graph snapshot IDs.
+const SHORT_COMMIT_LEN: usize = 12;
+
+/// Workspace manifest containing metadata about the persisted state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Version of the manifest format.
+    pub version: u32,
+
+    /// Name of the workspace.
+    pub name: String,
+
+    /// Root path of the workspace.
+    pub root: PathBuf,
+
+    /// Detected workspace kind.
+    pub kind: String,
+
+    /// Timestamp of last sync.
+    pub last_sync: SystemTime,
+
+    /// Number of repositories.
+    pub repo_count: usize,
+
+    /// Total number of source files.
+    pub source_count: usize,
+
+    /// Total size in bytes.
+    pub total_size: u64,
+
+    /// Remote URL or GitHub organization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    /// Digest of every source's content hash (see [`Project::content_hash`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+impl Manifest {
+    /// Create a new manifest from a project.
+    pub fn from_project(
+        project: &Project,
+        root: &Path,
+        kind: &WorkspaceKind,
+        remote: Option<String>,
+    ) -> Self {
+        Self {
+            version: 1,
+            name: project.name.clone(),
+            root: root.to_path_buf(),
+            kind: kind.to_string(),
+            last_sync: SystemTime::now(),
+            repo_count: project.repositories.len(),
+            source_count: project.total_sources(),
+            total_size: project.total_size(),
+            remote,
+            content_hash: project.content_hash(),
+        }
+    }
+}
+
+/// Description of a stored graph snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphSnapshotInfo {
+    /// Snapshot ID: the abbreviated commit, or a timestamp outside git.
+    pub id: String,
+
+    /// When the snapshot was taken (seconds since the Unix epoch).
+    pub created: u64,
+
+    /// Full ID of the workspace's HEAD commit when the snapshot was taken.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Number of nodes in the graph.
+    pub node_count: usize,
+
+    /// Number of edges in the graph.
+    pub edge_count: usize,
+}
+
+impl GraphSnapshotInfo {
+    /// Describe `graph`, identified by the abbreviated `commit` when there
+    /// is one and by `created` otherwise.
+    pub fn new(graph: &SourceCodeGraph, created: u64, commit: Option<String>) -> Self {
+        let id = match &commit {
+            Some(commit) => commit[..SHORT_COMMIT_LEN.min(commit.len())].to_string(),
+            None => created.to_string(),
+        };
+        Self {
+            id,
+            created,
+            commit,
+            node_count: graph.node_count(),
+            edge_count: graph.edge_count(),
+        }
+    }
+}
+
+/// Available storage backends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackendKind {
+    /// Pretty-printed JSON files.
+    Json,
+    /// Embedded SQLite database.
+    #[default]
+    Sqlite,
+}
+
+impl StoreBackendKind {
+    /// Backend named by `VG_STORE_BACKEND`, or the default.
+    pub fn from_env() -> Self {
+        match std::env::var(STORE_BACKEND_ENV) {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                warn!(error = %e, "Falling back to the default store backend");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+impl fmt::Display for StoreBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreBackendKind::Json => write!(f, "json"),
+            StoreBackendKind::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl FromStr for StoreBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(StoreBackendKind::Json),
+            "sqlite" | "sqlite3" => Ok(StoreBackendKind::Sqlite),
+            other => Err(format!(
+                "unknown store backend '{}' (expected json or sqlite)",
+                other
+            )),
+        }
+    }
+}
+
+/// Storage for the bulky parts of a `.self` store: the project, the graph
+/// and project snapshots. Content is stripped from projects before they
+/// reach a backend.
+pub trait StorageBackend: fmt::Debug + Send + Sync {
+    /// Which backend this is.
+    fn kind(&self) -> StoreBackendKind;
+
+    /// File or directory holding the data, for display.
+    fn location(&self) -> PathBuf;
+
+    /// Replace the stored project.
+    fn save_project(&self, project: &Project) -> OpsResult<()>;
+
+    /// Load the stored project.
+    fn load_project(&self) -> OpsResult<Option<Project>>;
+
+    /// Replace the stored graph, returning where it was written.
+    fn save_graph(&self, graph: &SourceCodeGraph) -> OpsResult<PathBuf>;
+
+    /// Load the whole stored graph.
+    fn load_graph(&self) -> OpsResult<Option<SourceCodeGraph>>;
+
+    /// Whether a graph is stored.
+    fn has_graph(&self) -> bool;
+
+    /// Metadata of the stored graph.
+    ///
+    /// The default reads the whole graph; indexed backends override it.
+    fn graph_metadata(&self) -> OpsResult<Option<HashMap<String, String>>> {
+        Ok(self.load_graph()?.map(|graph| graph.metadata))
+    }
+
+    /// Load the nodes whose `path` is `prefix` or lies under it, with the
+    /// edges between them and the graph metadata.
+    ///
+    /// The default filters a full load; indexed backends override it.
+    fn load_subgraph(&self, prefix: &Path) -> OpsResult<Option<SourceCodeGraph>> {
+        Ok(self
+            .load_graph()?
+            .map(|graph| filter_subgraph(graph, prefix)))
+    }
+
+    /// Store a project snapshot under `id`, returning where it was written.
+    fn save_snapshot(&self, id: &str, project: &Project) -> OpsResult<PathBuf>;
+
+    /// Snapshot IDs, newest first.
+    fn list_snapshots(&self) -> OpsResult<Vec<String>>;
+
+    /// Load a snapshot by ID.
+    fn load_snapshot(&self, id: &str) -> OpsResult<Project>;
+
+    /// Store a graph snapshot, replacing any with the same ID.
+    fn save_graph_snapshot(
+        &self,
+        info: &GraphSnapshotInfo,
+        graph: &SourceCodeGraph,
+    ) -> OpsResult<()>;
+
+    /// Graph snapshots, newest first.
+    fn list_graph_snapshots(&self) -> OpsResult<Vec<GraphSnapshotInfo>>;
+
+    /// Load a graph snapshot by ID.
+    fn load_graph_snapshot(&self, id: &str) -> OpsResult<SourceCodeGraph>;
+
+    /// Cache the graph built from a git tree under `key`.
+    fn save_revision_graph(&self, key: &str, graph: &SourceCodeGraph) -> OpsResult<()>;
+
+    /// Load a cached git tree graph.
+    fn load_revision_graph(&self, key: &str) -> OpsResult<Option<SourceCodeGraph>>;
+}
+
+/// Store manages the `.self` folder and persistence operations.
+#[derive(Debug, Clone)]
+pub struct Store {
+    /// Root path of the workspace.
+    root: PathBuf,
+
+    /// Path to the `.self` directory.
+    self_dir: PathBuf,
+
+    /// Where the project, graph and snapshots live.
+    backend: Arc<dyn StorageBackend>,
+}
+
+impl Store {
+    /// Create a new store for the given workspace root, using the backend
+    /// selected by `VG_STORE_BACKEND`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self::with_backend(root, StoreBackendKind::from_env())
+    }
+
+    /// Create a store with an explicit backend.
+    pub fn with_backend(root: impl AsRef<Path>, kind: StoreBackendKind) -> Self {
+        let root = root.as_ref().to_path_buf();
+        let self_dir = root.join(SELF_DIR);
+        let backend: Arc<dyn StorageBackend> = match kind {
+            StoreBackendKind::Json => Arc::new(JsonBackend::new(&self_dir)),
+            StoreBackendKind::Sqlite => Arc::new(SqliteBackend::new(&self_dir)),
+        };
+        Self {
+            root,
+            self_dir,
+            backend,
+        }
+    }
+
+    /// The backend in use.
+    pub fn backend_kind(&self) -> StoreBackendKind {
+        self.backend.kind()
+    }
+
+    /// Get the root path.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the backend keeps the project and graph.
+    pub fn location(&self) -> PathBuf {
+        self.backend.location()
+    }
+
+    /// Get the path to the `.self` directory.
+    pub fn self_dir(&self) -> &Path {
+        &self.self_dir
+    }
+
+    /// Check if the `.self` directory exists.
+    pub fn exists(&self) -> bool {
+        self.self_dir.exists()
+    }
+
+    /// Initialize the `.self` directory structure.
+    pub fn init(&self) -> OpsResult<()> {
+        if !self.self_dir.exists() {
+            std::fs::create_dir_all(&self.self_dir)?;
+            debug!(path = %self.self_dir.display(), "Created .self directory");
+        }
+
+        Ok(())
+    }
+
+    /// Save a project to the store.
+    pub fn save(
+        &self,
+        project: &Project,
+        kind: &WorkspaceKind,
+        remote: Option<String>,
+    ) -> OpsResult<()> {
+        self.init()?;
+
+        // Strip content for storage (it can be re-read from disk)
+        self.backend.save_project(&strip_content(project))?;
+
+        // Save manifest
+        let manifest = Manifest::from_project(project, &self.root, kind, remote);
+        self.save_manifest(&manifest)?;
+
+        info!(
+            path = %self.self_dir.display(),
+            repos = project.repositories.len(),
+            files = project.total_sources(),
+            "Saved project to .self"
+        );
+
+        Ok(())
+    }
+
+    /// Save only the project's source list, keeping the manifest of the
+    /// last sync.
+    pub fn save_project(&self, project: &Project) -> OpsResult<()> {
+        self.init()?;
+        self.backend.save_project(&strip_content(project))?;
+
+        debug!(path = %self.backend.location().display(), "Saved project");
+        Ok(())
+    }
+
+    /// Save the manifest to the store.
+    pub fn save_manifest(&self, manifest: &Manifest) -> OpsResult<()> {
+        self.init()?;
+
+        let manifest_path = self.self_dir.join(MANIFEST_FILE);
+        let manifest_json = serde_json::to_string_pretty(manifest)?;
+        std::fs::write(&manifest_path, &manifest_json)?;
+
+        debug!(path = %manifest_path.display(), "Saved manifest");
+        Ok(())
+    }
+
+    /// Create a timestamped snapshot, returning where it was written.
+    pub fn snapshot(&self, project: &Project) -> OpsResult<PathBuf> {
+        self.init()?;
+
+        let id = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string();
+        let path = self.backend.save_snapshot(&id, &strip_content(project))?;
+
+        info!(id = %id, path = %path.display(), "Created snapshot");
+        Ok(path)
+    }
+
+    /// Load the project from the store.
+    pub fn load(&self) -> OpsResult<Option<Project>> {
+        if !self.exists() {
+            return Ok(None);
+        }
+        let Some(project) = self.backend.load_project()? else {
+            return Ok(None);
+        };
+
+        info!(
+            path = %self.backend.location().display(),
+            repos = project.repositories.len(),
+            "Loaded project from .self"
+        );
+
+        Ok(Some(project))
+    }
+
+    /// Load the manifest from the store.
+    pub fn load_manifest(&self) -> OpsResult<Option<Manifest>> {
+        let manifest_path = self.self_dir.join(MANIFEST_FILE);
+
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&manifest_path)?;
+        let manifest: Manifest = serde_json::from_str(&json)?;
+
+        Ok(Some(manifest))
+    }
+
+    /// Save a SourceCodeGraph to the store.
+    ///
+    /// Replacing a graph saved before node IDs were derived from paths
+    /// records the old → new ID mapping (see [`Self::load_id_migration`]).
+    pub fn save_graph(&self, graph: &SourceCodeGraph) -> OpsResult<PathBuf> {
+        self.init()?;
+
+        if graph.has_stable_ids() && self.has_legacy_graph()? {
+            if let Some(mut legacy) = self.backend.load_graph()? {
+                let mapping = legacy.migrate_to_stable_ids(&self.canonical_root());
+                self.save_id_migration(&mapping)?;
+                info!(
+                    nodes = mapping.len(),
+                    "Replaced graph with path-derived node IDs"
+                );
+            }
+        }
+
+        let graph_path = self.backend.save_graph(graph)?;
+
+        info!(
+            path = %graph_path.display(),
+            nodes = graph.node_count(),
+            edges = graph.edge_count(),
+            "Saved graph to .self"
+        );
+
+        Ok(graph_path)
+    }
+
+    /// Load a SourceCodeGraph from the store.
+    ///
+    /// Graphs saved before node IDs were derived from paths are migrated in
+    /// memory only: nothing is written, so stores keyed by the old IDs stay
+    /// consistent until [`Self::migrate_graph`] moves them all.
+    pub fn load_graph(&self) -> OpsResult<Option<SourceCodeGraph>> {
+        if !self.exists() {
+            return Ok(None);
+        }
+        let Some(mut graph) = self.backend.load_graph()? else {
+            return Ok(None);
+        };
+
+        if !graph.has_stable_ids() {
+            graph.migrate_to_stable_ids(&self.canonical_root());
+        }
+
+        info!(
+            path = %self.backend.location().display(),
+            nodes = graph.node_count(),
+            edges = graph.edge_count(),
+            "Loaded graph from .self"
+        );
+
+        Ok(Some(graph))
+    }
+
+    /// Persist the migration of a graph saved with sequential node IDs,
+    /// recording the old → new mapping for other stores to follow. Returns
+    /// whether the stored graph was migrated.
+    pub fn migrate_graph(&self) -> OpsResult<bool> {
+        if !self.has_legacy_graph()? {
+            return Ok(false);
+        }
+        let Some(graph) = self.load_graph()? else {
+            return Ok(false);
+        };
+        self.save_graph(&graph)?;
+        info!(
+            nodes = graph.node_count(),
+            "Migrated graph to path-derived node IDs"
+        );
+        Ok(true)
+    }
+
+    /// Whether the stored graph still uses sequential node IDs.
+    fn has_legacy_graph(&self) -> OpsResult<bool> {
+        if !self.has_graph() {
+            return Ok(false);
+        }
+        Ok(self.backend.graph_metadata()?.is_some_and(|metadata| {
+            metadata.get(ID_SCHEME_KEY).map(String::as_str) != Some(PATH_ID_SCHEME)
+        }))
+    }
+
+    fn canonical_root(&self) -> PathBuf {
+        self.root
+            .canonicalize()
+            .unwrap_or_else(|_| self.root.clone())
+    }
+
+    /// Check if a graph exists in the store.
+    pub fn has_graph(&self) -> bool {
+        self.exists() && self.backend.has_graph()
+    }
+
+    /// Old → new node IDs recorded by the last graph migration, for stores
+    /// keyed by node ID (automaton state, semantic index) to follow.
+    pub fn load_id_migration(&self) -> OpsResult<Option<HashMap<NodeId, NodeId>>> {
+        let path = self.self_dir.join(ID_MIGRATION_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&path)?;
+        let pairs: Vec<(NodeId, NodeId)> = serde_json::from_str(&json)?;
+        Ok(Some(pairs.into_iter().collect()))
+    }
+
+    /// Forget the ID migration once every dependent store has applied it.
+    pub fn clear_id_migration(&self) -> OpsResult<()> {
+        let path = self.self_dir.join(ID_MIGRATION_FILE);
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn save_id_migration(&self, mapping: &HashMap<NodeId, NodeId>) -> OpsResult<()> {
+        self.init()?;
+
+        let mut pairs: Vec<(NodeId, NodeId)> = mapping.iter().map(|(&a, &b)| (a, b)).collect();
+        pairs.sort_by_key(|(old, _)| old.0);
+        let json = serde_json::to_string(&pairs)?;
+        std::fs::write(self.self_dir.join(ID_MIGRATION_FILE), json)?;
+        Ok(())
+    }
+
+    /// List available snapshot IDs, newest first.
+    pub fn list_snapshots(&self) -> OpsResult<Vec<String>> {
+        if !self.exists() {
+            return Ok(vec![]);
+        }
+        self.backend.list_snapshots()
+    }
+
+    /// Load a specific snapshot.
+    pub fn load_snapshot(&self, id: &str) -> OpsResult<Project> {
+        self.backend.load_snapshot(id)
+    }
+
+    /// Snapshot a graph, keyed by the workspace's HEAD commit when it is a
+    /// git repository and by timestamp otherwise. Snapshotting again at the
+    /// same commit replaces the earlier snapshot.
+    pub fn snapshot_graph(&self, graph: &SourceCodeGraph) -> OpsResult<GraphSnapshotInfo> {
+        self.snapshot_graph_at(graph, head_commit(&self.root))
+    }
+
+    /// Snapshot a graph built at `commit` (for example from a git tree
+    /// rather than the working tree).
+    pub fn snapshot_graph_at(
+        &self,
+        graph: &SourceCodeGraph,
+        commit: Option<String>,
+    ) -> OpsResult<GraphSnapshotInfo> {
+        self.init()?;
+
+        let created = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let info = GraphSnapshotInfo::new(graph, created, commit);
+        self.backend.save_graph_snapshot(&info, graph)?;
+
+        info!(id = %info.id, nodes = info.node_count, "Created graph snapshot");
+        Ok(info)
+    }
+
+    /// List graph snapshots, newest first.
+    pub fn list_graph_snapshots(&self) -> OpsResult<Vec<GraphSnapshotInfo>> {
+        if !self.exists() {
+            return Ok(vec![]);
+        }
+        self.backend.list_graph_snapshots()
+    }
+
+    /// Load a graph snapshot by ID.
+    pub fn load_graph_snapshot(&self, id: &str) -> OpsResult<SourceCodeGraph> {
+        self.backend.load_graph_snapshot(id)
+    }
+
+    /// Find the graph snapshot a user-supplied reference names: a snapshot
+    /// ID, an unambiguous ID prefix, or a git revision (`main`, `HEAD~3`)
+    /// of the workspace whose commit was snapshotted.
+    pub fn resolve_graph_snapshot(&self, reference: &str) -> OpsResult<GraphSnapshotInfo> {
+        let snapshots = self.list_graph_snapshots()?;
+        if let Some(info) = snapshots.iter().find(|s| s.id == reference) {
+            return Ok(info.clone());
+        }
+
+        let matching: Vec<&GraphSnapshotInfo> = snapshots
+            .iter()
+            .filter(|s| s.id.starts_with(reference))
+            .collect();
+        match matching.as_slice() {
+            [info] => return Ok((*info).clone()),
+            [] => {}
+            _ => {
+                return Err(OpsError::AmbiguousSnapshot {
+                    reference: reference.to_string(),
+                    matches: matching.iter().map(|s| s.id.clone()).collect(),
+                })
+            }
+        }
+
+        let commit = git2::Repository::open(&self.root)
+            .and_then(|repo| Ok(repo.revparse_single(reference)?.peel_to_commit()?.id()))
+            .ok()
+            .map(|id| id.to_string());
+        commit
+            .and_then(|commit| {
+                snapshots
+                    .into_iter()
+                    .find(|s| s.commit.as_deref() == Some(commit.as_str()))
+            })
+            .ok_or_else(|| OpsError::SnapshotNotFound {
+                id: reference.to_string(),
+            })
+    }
+
+    /// Cache the graph built from a git tree.
+    pub fn save_revision_graph(&self, key: &str, graph: &SourceCodeGraph) -> OpsResult<()> {
+        self.init()?;
+        self.backend.save_revision_graph(key, graph)
+    }
+
+    /// Load a graph cached by [`Self::save_revision_graph`].
+    pub fn load_revision_graph(&self, key: &str) -> OpsResult<Option<SourceCodeGraph>> {
+        if !self.exists() {
+            return Ok(None);
+        }
+        self.backend.load_revision_graph(key)
+    }
+
+    /// Clean up the `.self` directory.
+    pub fn clean(&self) -> OpsResult<()> {
+        if self.self_dir.exists() {
+            std::fs::remove_dir_all(&self.self_dir)?;
+            info!(path = %self.self_dir.display(), "Removed .self directory");
+        }
+        Ok(())
+    }
+
+    /// Get storage statistics.
+    pub fn stats(&self) -> OpsResult<StoreStats> {
+        if !self.exists() {
+            return Ok(StoreStats::default());
+        }
+
+        let manifest = self.load_manifest()?;
+        let snapshots = self.list_snapshots()?;
+
+        // Calculate total size of .self directory
+        let total_size = WalkDir::new(&self.self_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| e.metadata().ok())
+            .map(|m| m.len())
+            .sum();
+
+        Ok(StoreStats {
+            exists: true,
+            backend: self.backend_kind(),
+            manifest,
+            snapshot_count: snapshots.len(),
+            graph_snapshot_count: self.list_graph_snapshots()?.len(),
+            total_size,
+        })
+    }
+}
+
+/// Statistics about the store.
+#[derive(Debug, Default, Clone)]
+pub struct StoreStats {
+    /// Whether the store exists.
+    pub exists: bool,
+    /// Backend holding the project and graph.
+    pub backend: StoreBackendKind,
+    /// Loaded manifest if available.
+    pub manifest: Option<Manifest>,
+    /// Number of snapshots.
+    pub snapshot_count: usize,
+    /// Number of graph snapshots.
+    pub graph_snapshot_count: usize,
+    /// Total size of .self directory in bytes.
+    pub total_size: u64,
+}
+
+/// Full ID of the HEAD commit of the repository at `root`, if any.
+fn head_commit(root: &Path) -> Option<String> {
+    let repo = git2::Repository::open(root).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+/// Keep the nodes at or under `prefix` and the edges between them.
+fn filter_subgraph(mut graph: SourceCodeGraph, prefix: &Path) -> SourceCodeGraph {
+    graph.nodes.retain(|node| {
+        node.metadata
+            .get("path")
+            .is_some_and(|path| Path::new(path).starts_with(prefix))
+    });
+    let kept: HashSet<NodeId> = graph.nodes.iter().map(|n| n.id).collect();
+    graph
+        .edges
+        .retain(|edge| kept.contains(&edge.from) && kept.contains(&edge.to));
+    graph
+}
+
+/// Strip content from project for storage.
+fn strip_content(project: &Project) -> Project {
+    let mut stripped = project.clone();
+    for repo in &mut stripped.repositories {
+        for source in &mut repo.sources {
+            source.content = None;
+        }
+    }
+    stripped
+}
+
+/// Check if a `.self` directory exists at the given path.
+pub fn has_store(path: &Path) -> bool {
+    path.join(SELF_DIR).exists()
+}
@@ -0,0 +1,738 @@
+//! Embedded SQLite storage in `.self/store.db`.
+//!
+//! Nodes, edges and sources get one row each, with the columns used for
+//! lookups (`path`, node IDs) indexed and the full record kept as compact
+//! JSON. Loading a directory's part of the graph therefore reads only the
+//! matching rows.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use vibe_graph_core::{GraphEdge, GraphNode, SourceCodeGraph};
+
+use super::json::JsonBackend;
+use super::{GraphSnapshotInfo, StorageBackend, StoreBackendKind};
+use crate::error::{OpsError, OpsResult};
+use crate::project::{Project, ProjectSource, Repository, Source};
+use crate::scan::Exclusion;
+
+const DB_FILE: &str = "store.db";
+
+const SCHEMA_VERSION: &str = "1";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS repositories (
+    idx INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    local_path TEXT NOT NULL,
+    excluded TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sources (
+    seq INTEGER PRIMARY KEY,
+    repo_idx INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sources_path ON sources(path);
+CREATE TABLE IF NOT EXISTS nodes (
+    seq INTEGER PRIMARY KEY,
+    id INTEGER NOT NULL,
+    path TEXT,
+    kind TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS nodes_id ON nodes(id);
+CREATE INDEX IF NOT EXISTS nodes_path ON nodes(path);
+CREATE TABLE IF NOT EXISTS edges (
+    seq INTEGER PRIMARY KEY,
+    from_id INTEGER NOT NULL,
+    to_id INTEGER NOT NULL,
+    relationship TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS edges_from ON edges(from_id);
+CREATE INDEX IF NOT EXISTS edges_to ON edges(to_id);
+CREATE TABLE IF NOT EXISTS snapshots (
+    id TEXT PRIMARY KEY,
+    created INTEGER NOT NULL,
+    project TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS graph_snapshots (
+    id TEXT PRIMARY KEY,
+    created INTEGER NOT NULL,
+    commit_id TEXT,
+    node_count INTEGER NOT NULL,
+    edge_count INTEGER NOT NULL,
+    graph TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS revision_graphs (
+    key TEXT PRIMARY KEY,
+    graph TEXT NOT NULL
+);
+";
+
+/// Project fields that are not repositories.
+#[derive(Serialize, Deserialize)]
+struct ProjectHeader {
+    name: String,
+    source: ProjectSource,
+}
+
+/// SQLite database under `.self`.
+#[derive(Debug, Clone)]
+pub struct SqliteBackend {
+    self_dir: PathBuf,
+}
+
+impl SqliteBackend {
+    /// Create a backend over a `.self` directory. The database is created
+    /// on first write.
+    pub fn new(self_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            self_dir: self_dir.into(),
+        }
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.self_dir.join(DB_FILE)
+    }
+
+    /// Move a JSON store found next to the database into it, returning
+    /// whether there was one. Writes do this on their own.
+    pub fn migrate(&self) -> OpsResult<bool> {
+        let legacy = JsonBackend::has_data(&self.self_dir);
+        self.connect(true)?;
+        Ok(legacy)
+    }
+
+    /// The JSON store next to the database, while it is not migrated.
+    /// Reads go to it directly until the first write moves it in.
+    fn unmigrated(&self) -> Option<JsonBackend> {
+        JsonBackend::has_data(&self.self_dir).then(|| JsonBackend::new(&self.self_dir))
+    }
+
+    /// Open the database. With `create`, the database is created if needed
+    /// and a JSON store found next to it is moved in; without, returns
+    /// `None` when there is no database.
+    fn connect(&self, create: bool) -> OpsResult<Option<Connection>> {
+        if !create && !self.db_path().exists() {
+            return Ok(None);
+        }
+
+        let mut conn = Connection::open(self.db_path())?;
+        conn.busy_timeout(Duration::from_secs(10))?;
+        conn.execute_batch(SCHEMA)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO meta (key, value) VALUES ('schema_version', ?1)",
+            params![SCHEMA_VERSION],
+        )?;
+
+        if create && JsonBackend::has_data(&self.self_dir) {
+            self.import_json(&mut conn)?;
+        }
+        Ok(Some(conn))
+    }
+
+    /// Move a JSON store into the database, then delete its files.
+    /// Cached revision graphs are dropped rather than moved; they are
+    /// rebuilt on demand.
+    fn import_json(&self, conn: &mut Connection) -> OpsResult<()> {
+        let json = JsonBackend::new(&self.self_dir);
+        let tx = conn.transaction()?;
+        if let Some(project) = json.load_project()? {
+            write_project(&tx, &project)?;
+        }
+        if let Some(graph) = json.load_graph()? {
+            write_graph(&tx, &graph)?;
+        }
+        for id in json.list_snapshots()? {
+            write_snapshot(&tx, &id, &json.load_snapshot(&id)?)?;
+        }
+        for info in json.list_graph_snapshots()? {
+            write_graph_snapshot(&tx, &info, &json.load_graph_snapshot(&info.id)?)?;
+        }
+        tx.commit()?;
+
+        JsonBackend::new(&self.self_dir).remove_files()?;
+        info!(path = %self.db_path().display(), "Migrated JSON store to SQLite");
+        Ok(())
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn kind(&self) -> StoreBackendKind {
+        StoreBackendKind::Sqlite
+    }
+
+    fn location(&self) -> PathBuf {
+        self.db_path()
+    }
+
+    fn save_project(&self, project: &Project) -> OpsResult<()> {
+        let mut conn = self.connect(true)?.expect("created on demand");
+        let tx = conn.transaction()?;
+        write_project(&tx, project)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_project(&self) -> OpsResult<Option<Project>> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_project();
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(None);
+        };
+        let Some(header) = read_meta(&conn, "project")? else {
+            return Ok(None);
+        };
+        let header: ProjectHeader = serde_json::from_str(&header)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT idx, name, url, local_path, excluded FROM repositories ORDER BY idx",
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut repositories = Vec::new();
+        let mut index_of = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let excluded: String = row.get(4)?;
+            let mut repo = Repository::new(
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                PathBuf::from(row.get::<_, String>(3)?),
+            );
+            repo.excluded = serde_json::from_str::<Vec<Exclusion>>(&excluded)?;
+            index_of.insert(row.get::<_, i64>(0)?, repositories.len());
+            repositories.push(repo);
+        }
+
+        let mut stmt = conn.prepare("SELECT repo_idx, data FROM sources ORDER BY seq")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let Some(&i) = index_of.get(&row.get::<_, i64>(0)?) else {
+                continue;
+            };
+            let source: Source = serde_json::from_str(&row.get::<_, String>(1)?)?;
+            repositories[i].sources.push(source);
+        }
+
+        Ok(Some(Project {
+            name: header.name,
+            source: header.source,
+            repositories,
+        }))
+    }
+
+    fn save_graph(&self, graph: &SourceCodeGraph) -> OpsResult<PathBuf> {
+        let mut conn = self.connect(true)?.expect("created on demand");
+        let tx = conn.transaction()?;
+        write_graph(&tx, graph)?;
+        tx.commit()?;
+        Ok(self.db_path())
+    }
+
+    fn load_graph(&self) -> OpsResult<Option<SourceCodeGraph>> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_graph();
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(None);
+        };
+        let Some(metadata) = read_meta(&conn, "graph")? else {
+            return Ok(None);
+        };
+        Ok(Some(SourceCodeGraph {
+            nodes: read_records(&conn, "SELECT data FROM nodes ORDER BY seq", [])?,
+            edges: read_records(&conn, "SELECT data FROM edges ORDER BY seq", [])?,
+            metadata: serde_json::from_str(&metadata)?,
+        }))
+    }
+
+    fn has_graph(&self) -> bool {
+        if let Some(json) = self.unmigrated() {
+            return json.has_graph();
+        }
+        matches!(
+            self.connect(false)
+                .and_then(|conn| conn.map(|c| read_meta(&c, "graph")).transpose()),
+            Ok(Some(Some(_)))
+        )
+    }
+
+    fn graph_metadata(&self) -> OpsResult<Option<HashMap<String, String>>> {
+        if let Some(json) = self.unmigrated() {
+            return json.graph_metadata();
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(None);
+        };
+        read_meta(&conn, "graph")?
+            .map(|metadata| Ok(serde_json::from_str(&metadata)?))
+            .transpose()
+    }
+
+    fn load_subgraph(&self, prefix: &Path) -> OpsResult<Option<SourceCodeGraph>> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_subgraph(prefix);
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(None);
+        };
+        let Some(metadata) = read_meta(&conn, "graph")? else {
+            return Ok(None);
+        };
+
+        // Paths under `prefix` sort between "prefix/" and "prefix0" ('0'
+        // follows '/'), which lets the path index answer the query
+        let prefix = prefix.to_string_lossy();
+        let prefix = prefix.trim_end_matches('/');
+        let range = params![prefix, format!("{}/", prefix), format!("{}0", prefix)];
+        let nodes: Vec<GraphNode> = read_records(
+            &conn,
+            "SELECT data FROM nodes
+             WHERE path = ?1 OR (path >= ?2 AND path < ?3)
+             ORDER BY seq",
+            range,
+        )?;
+        let edges: Vec<GraphEdge> = read_records(
+            &conn,
+            "WITH selected AS (
+                 SELECT id FROM nodes WHERE path = ?1 OR (path >= ?2 AND path < ?3)
+             )
+             SELECT data FROM edges
+             WHERE from_id IN selected AND to_id IN selected
+             ORDER BY seq",
+            range,
+        )?;
+
+        Ok(Some(SourceCodeGraph {
+            nodes,
+            edges,
+            metadata: serde_json::from_str(&metadata)?,
+        }))
+    }
+
+    fn save_snapshot(&self, id: &str, project: &Project) -> OpsResult<PathBuf> {
+        let conn = self.connect(true)?.expect("created on demand");
+        write_snapshot(&conn, id, project)?;
+        Ok(self.db_path())
+    }
+
+    fn list_snapshots(&self) -> OpsResult<Vec<String>> {
+        if let Some(json) = self.unmigrated() {
+            return json.list_snapshots();
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(vec![]);
+        };
+        let mut stmt = conn.prepare("SELECT id FROM snapshots ORDER BY created DESC, id DESC")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+
+    fn load_snapshot(&self, id: &str) -> OpsResult<Project> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_snapshot(id);
+        }
+        let not_found = || OpsError::SnapshotNotFound { id: id.to_string() };
+        let conn = self.connect(false)?.ok_or_else(not_found)?;
+        let json: String = conn
+            .query_row(
+                "SELECT project FROM snapshots WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(not_found)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save_graph_snapshot(
+        &self,
+        info: &GraphSnapshotInfo,
+        graph: &SourceCodeGraph,
+    ) -> OpsResult<()> {
+        let conn = self.connect(true)?.expect("created on demand");
+        write_graph_snapshot(&conn, info, graph)
+    }
+
+    fn list_graph_snapshots(&self) -> OpsResult<Vec<GraphSnapshotInfo>> {
+        if let Some(json) = self.unmigrated() {
+            return json.list_graph_snapshots();
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(vec![]);
+        };
+        let mut stmt = conn.prepare(
+            "SELECT id, created, commit_id, node_count, edge_count FROM graph_snapshots
+             ORDER BY created DESC, id DESC",
+        )?;
+        let snapshots = stmt
+            .query_map([], |row| {
+                Ok(GraphSnapshotInfo {
+                    id: row.get(0)?,
+                    created: row.get::<_, i64>(1)? as u64,
+                    commit: row.get(2)?,
+                    node_count: row.get::<_, i64>(3)? as usize,
+                    edge_count: row.get::<_, i64>(4)? as usize,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(snapshots)
+    }
+
+    fn load_graph_snapshot(&self, id: &str) -> OpsResult<SourceCodeGraph> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_graph_snapshot(id);
+        }
+        let not_found = || OpsError::SnapshotNotFound { id: id.to_string() };
+        let conn = self.connect(false)?.ok_or_else(not_found)?;
+        let json: String = conn
+            .query_row(
+                "SELECT graph FROM graph_snapshots WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(not_found)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save_revision_graph(&self, key: &str, graph: &SourceCodeGraph) -> OpsResult<()> {
+        let conn = self.connect(true)?.expect("created on demand");
+        conn.execute(
+            "INSERT OR REPLACE INTO revision_graphs (key, graph) VALUES (?1, ?2)",
+            params![key, serde_json::to_string(graph)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_revision_graph(&self, key: &str) -> OpsResult<Option<SourceCodeGraph>> {
+        if let Some(json) = self.unmigrated() {
+            return json.load_revision_graph(key);
+        }
+        let Some(conn) = self.connect(false)? else {
+            return Ok(None);
+        };
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT graph FROM revision_graphs WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+}
+
+fn read_meta(conn: &Connection, key: &str) -> OpsResult<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn write_meta(conn: &Connection, key: &str, value: &str) -> OpsResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Deserialize the JSON `data` column of every row a query returns.
+fn read_records<T: serde::de::DeserializeOwned>(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> OpsResult<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query(params)?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next()? {
+        records.push(serde_json::from_str(&row.get::<_, String>(0)?)?);
+    }
+    Ok(records)
+}
+
+fn write_project(conn: &Connection, project: &Project) -> OpsResult<()> {
+    conn.execute_batch("DELETE FROM repositories; DELETE FROM sources;")?;
+    let header = ProjectHeader {
+        name: project.name.clone(),
+        source: project.source.clone(),
+    };
+    write_meta(conn, "project", &serde_json::to_string(&header)?)?;
+
+    let mut insert_repo = conn.prepare(
+        "INSERT INTO repositories (idx, name, url, local_path, excluded)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let mut insert_source =
+        conn.prepare("INSERT INTO sources (repo_idx, path, data) VALUES (?1, ?2, ?3)")?;
+    for (idx, repo) in project.repositories.iter().enumerate() {
+        insert_repo.execute(params![
+            idx as i64,
+            repo.name,
+            repo.url,
+            repo.local_path.to_string_lossy(),
+            serde_json::to_string(&repo.excluded)?,
+        ])?;
+        for source in &repo.sources {
+            insert_source.execute(params![
+                idx as i64,
+                source.path.to_string_lossy(),
+                serde_json::to_string(source)?,
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn write_graph(conn: &Connection, graph: &SourceCodeGraph) -> OpsResult<()> {
+    conn.execute_batch("DELETE FROM nodes; DELETE FROM edges;")?;
+    write_meta(conn, "graph", &serde_json::to_string(&graph.metadata)?)?;
+
+    let mut insert_node =
+        conn.prepare("INSERT INTO nodes (id, path, kind, data) VALUES (?1, ?2, ?3, ?4)")?;
+    for node in &graph.nodes {
+        insert_node.execute(params![
+            node.id.0 as i64,
+            node.metadata.get("path"),
+            node.kind.as_str(),
+            serde_json::to_string(node)?,
+        ])?;
+    }
+
+    let mut insert_edge = conn.prepare(
+        "INSERT INTO edges (from_id, to_id, relationship, data) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for edge in &graph.edges {
+        insert_edge.execute(params![
+            edge.from.0 as i64,
+            edge.to.0 as i64,
+            edge.relationship,
+            serde_json::to_string(edge)?,
+        ])?;
+    }
+    Ok(())
+}
+
+fn write_snapshot(conn: &Connection, id: &str, project: &Project) -> OpsResult<()> {
+    let created: i64 = id.parse().unwrap_or_default();
+    conn.execute(
+        "INSERT OR REPLACE INTO snapshots (id, created, project) VALUES (?1, ?2, ?3)",
+        params![id, created, serde_json::to_string(project)?],
+    )?;
+    Ok(())
+}
+
+fn write_graph_snapshot(
+    conn: &Connection,
+    info: &GraphSnapshotInfo,
+    graph: &SourceCodeGraph,
+) -> OpsResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO graph_snapshots
+         (id, created, commit_id, node_count, edge_count, graph)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            info.id,
+            info.created as i64,
+            info.commit,
+            info.node_count as i64,
+            info.edge_count as i64,
+            serde_json::to_string(graph)?,
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use vibe_graph_core::{GraphNodeKind, NodeId};
+
+    fn node(id: u64, path: &str) -> GraphNode {
+        GraphNode {
+            id: NodeId(id),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            kind: GraphNodeKind::File,
+            metadata: HashMap::from([("path".to_string(), path.to_string())]),
+        }
+    }
+
+    fn edge(from: u64, to: u64) -> GraphEdge {
+        GraphEdge {
+            from: NodeId(from),
+            to: NodeId(to),
+            relationship: "uses".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sample_graph() -> SourceCodeGraph {
+        let mut graph = SourceCodeGraph {
+            nodes: vec![
+                node(1, "/ws/src/a.rs"),
+                node(2, "/ws/src/b.rs"),
+                node(3, "/ws/src-gen/c.rs"),
+                node(4, "/ws/tests/d.rs"),
+            ],
+            edges: vec![edge(1, 2), edge(2, 3), edge(4, 1)],
+            metadata: HashMap::new(),
+        };
+        graph.metadata.insert("name".to_string(), "ws".to_string());
+        graph
+    }
+
+    #[test]
+    fn test_sqlite_round_trip_and_prefix_load() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Store::with_backend(temp.path(), StoreBackendKind::Sqlite);
+        let mut project = Project::local(temp.path().to_path_buf());
+        let mut repo = Repository::new("ws", "ws", temp.path().to_path_buf());
+        let file = temp.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        repo.sources
+            .push(Source::from_path(file, &temp.path().to_path_buf()).unwrap());
+        project.repositories.push(repo);
+
+        assert!(store.load().unwrap().is_none());
+        store.save_project(&project).unwrap();
+        store.save_graph(&sample_graph()).unwrap();
+
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.repositories[0].sources.len(), 1);
+        assert_eq!(
+            loaded.repositories[0].sources[0].hash,
+            project.repositories[0].sources[0].hash
+        );
+        let graph = store.backend.load_graph().unwrap().unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.metadata["name"], "ws");
+
+        let sub = store
+            .backend
+            .load_subgraph(Path::new("/ws/src"))
+            .unwrap()
+            .unwrap();
+        let ids: Vec<u64> = sub.nodes.iter().map(|n| n.id.0).collect();
+        assert_eq!(ids, vec![1, 2], "src-gen is not under src");
+        assert_eq!(sub.edge_count(), 1);
+
+        assert_eq!(store.snapshot(&project).unwrap(), store.location());
+        let id = store.list_snapshots().unwrap().remove(0);
+        assert_eq!(store.load_snapshot(&id).unwrap().name, project.name);
+        let info = store.snapshot_graph(&sample_graph()).unwrap();
+        assert_eq!(info.commit, None, "not a git repository");
+        assert_eq!(info.edge_count, 3);
+        assert_eq!(store.list_graph_snapshots().unwrap(), vec![info.clone()]);
+        let resolved = store.resolve_graph_snapshot(&info.id[..4]).unwrap();
+        assert_eq!(resolved, info);
+        let graph = store.load_graph_snapshot(&info.id).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert!(matches!(
+            store.resolve_graph_snapshot("nope"),
+            Err(OpsError::SnapshotNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_json_store_migrates_to_sqlite() {
+        let temp = tempfile::tempdir().unwrap();
+        let json = Store::with_backend(temp.path(), StoreBackendKind::Json);
+        let project = Project::local(temp.path().to_path_buf());
+        json.save_project(&project).unwrap();
+        json.save_graph(&sample_graph()).unwrap();
+        json.snapshot(&project).unwrap();
+        let id = json.list_snapshots().unwrap().remove(0);
+        let graph_snapshot = json.snapshot_graph(&sample_graph()).unwrap();
+        assert!(temp.path().join(".self/graph.json").exists());
+
+        // Reads see the JSON store without moving it
+        let store = Store::with_backend(temp.path(), StoreBackendKind::Sqlite);
+        assert!(store.has_graph());
+        assert_eq!(store.load().unwrap().unwrap().name, project.name);
+        assert_eq!(store.list_snapshots().unwrap(), vec![id.clone()]);
+        assert!(temp.path().join(".self/graph.json").exists());
+        assert!(!temp.path().join(".self/store.db").exists());
+
+        assert!(SqliteBackend::new(temp.path().join(".self"))
+            .migrate()
+            .unwrap());
+        assert!(!temp.path().join(".self/graph.json").exists());
+        assert!(!temp.path().join(".self/project.json").exists());
+        assert!(temp.path().join(".self/store.db").exists());
+
+        assert_eq!(store.load().unwrap().unwrap().name, project.name);
+        assert_eq!(store.backend.load_graph().unwrap().unwrap().node_count(), 4);
+        assert_eq!(store.list_snapshots().unwrap(), vec![id]);
+        assert_eq!(store.list_graph_snapshots().unwrap(), vec![graph_snapshot]);
+        assert!(!temp.path().join(".self/snapshots/graphs").exists());
+    }
+
+    #[test]
+    fn test_replacing_legacy_graph_records_id_migration() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Store::with_backend(temp.path(), StoreBackendKind::Sqlite);
+        let mut legacy = sample_graph();
+        legacy
+            .metadata
+            .insert("root".to_string(), "/ws".to_string());
+        store.save_graph(&legacy).unwrap();
+
+        // Loading migrates in memory only
+        let loaded = store.load_graph().unwrap().unwrap();
+        assert!(loaded.has_stable_ids());
+        assert!(store.load_id_migration().unwrap().is_none());
+        assert!(!store
+            .backend
+            .load_graph()
+            .unwrap()
+            .unwrap()
+            .has_stable_ids());
+
+        // A rebuild replacing the legacy graph records the mapping
+        let mut rebuilt = legacy.clone();
+        let expected = rebuilt.migrate_to_stable_ids(temp.path());
+        store.save_graph(&rebuilt).unwrap();
+        assert_eq!(store.load_id_migration().unwrap(), Some(expected));
+        assert!(!store.migrate_graph().unwrap());
+
+        // Replacing a path-keyed graph records nothing new
+        store.clear_id_migration().unwrap();
+        store.save_graph(&rebuilt).unwrap();
+        assert!(store.load_id_migration().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_migrate_graph_persists_legacy_graph() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Store::with_backend(temp.path(), StoreBackendKind::Sqlite);
+        store.save_graph(&sample_graph()).unwrap();
+
+        assert!(store.migrate_graph().unwrap());
+        let mapping = store.load_id_migration().unwrap().unwrap();
+        assert_eq!(mapping.len(), 4);
+        let stored = store.backend.load_graph().unwrap().unwrap();
+        assert!(stored.has_stable_ids());
+        assert!(stored.nodes.iter().any(|n| n.id == mapping[&NodeId(1)]));
+        assert!(!store.migrate_graph().unwrap());
+    }
+}
@@ -4,6 +4,8 @@
 //! at dimension 384).  A pluggable ANN backend (e.g. `instant-distance`) can
 //! be added later behind a feature flag without changing the public API.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use vibe_graph_core::NodeId;
 
@@ -76,6 +78,19 @@ impl VectorIndex {
         self.entries.len() != before
     }
 
+    /// Rewrite node IDs through an old → new mapping, dropping entries of
+    /// nodes the mapping no longer knows.
+    pub fn remap_node_ids(&mut self, mapping: &HashMap<NodeId, NodeId>) {
+        self.entries
+            .retain_mut(|entry| match mapping.get(&entry.node_id) {
+                Some(&id) => {
+                    entry.node_id = id;
+                    true
+                }
+                None => false,
+            });
+    }
+
     /// Find the `top_k` most similar entries to `query`.
     /// Returns results sorted by descending cosine similarity.
     pub fn search(&self, query: &Embedding, top_k: usize) -> Vec<SearchHit> {
@@ -190,6 +205,16 @@ mod tests {
         assert!(!idx.remove(NodeId(1)));
     }
 
+    #[test]
+    fn test_remap_node_ids() {
+        let mut idx = VectorIndex::new(2);
+        idx.upsert(NodeId(1), vec![1.0, 0.0]);
+        idx.upsert(NodeId(2), vec![0.0, 1.0]);
+        idx.remap_node_ids(&[(NodeId(1), NodeId(42))].into());
+        assert_eq!(idx.len(), 1);
+        assert_eq!(idx.get(NodeId(42)).unwrap(), &vec![1.0, 0.0]);
+    }
+
     #[test]
     fn test_search_above_threshold() {
         let mut idx = VectorIndex::new(4);